@@ -0,0 +1,51 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Evaluation of 16 parameter vectors with a cost function which takes 50 ms: a serial loop over
+//! `apply` compared to `bulk_apply` on a pool of 8 threads.
+//!
+//! Run with `cargo bench --features rayon --bench bulk`.
+
+use argmin::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct SlowSum {}
+
+impl ArgminOp for SlowSum {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+
+    fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+        std::thread::sleep(Duration::from_millis(50));
+        Ok(p.iter().sum())
+    }
+}
+
+fn main() {
+    let params: Vec<Vec<f64>> = (0..16).map(|i| vec![i as f64, 1.0]).collect();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(8)
+        .build()
+        .unwrap();
+
+    let mut serial = OpWrapper::new(&SlowSum {});
+    let start = Instant::now();
+    for p in params.iter() {
+        serial.apply(p).unwrap();
+    }
+    let serial_time = start.elapsed();
+
+    let mut bulk = OpWrapper::new(&SlowSum {});
+    let start = Instant::now();
+    pool.install(|| bulk.bulk_apply(&params)).unwrap();
+    let bulk_time = start.elapsed();
+
+    println!("serial: {:?}, bulk_apply: {:?}", serial_time, bulk_time);
+}
@@ -0,0 +1,60 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Initialization of a Nelder-Mead simplex in 7 dimensions with a cost function which takes
+//! 20 ms: a serial loop over the 8 vertices compared to `init` on a pool of 8 threads, which
+//! evaluates the vertices in bulk.
+//!
+//! Run with `cargo bench --features rayon --bench neldermead`.
+
+use argmin::prelude::*;
+use argmin::solver::neldermead::{NelderMead, SimplexStep};
+use argmin::testfunctions::rosenbrock;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct SlowRosenbrock {}
+
+impl ArgminOp for SlowRosenbrock {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+
+    fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+        std::thread::sleep(Duration::from_millis(20));
+        Ok(rosenbrock(p, 1.0, 100.0))
+    }
+}
+
+fn main() {
+    let x0 = vec![-1.2, 1.0, 0.5, 2.0, 1.0, 0.0, 3.0];
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(8)
+        .build()
+        .unwrap();
+    let mut solver = NelderMead::from_point(x0, SimplexStep::default()).unwrap();
+
+    let problem = SlowRosenbrock {};
+    let start = Instant::now();
+    for (vertex, _) in solver.simplex() {
+        problem.apply(vertex).unwrap();
+    }
+    let serial_time = start.elapsed();
+
+    let mut op = OpWrapper::new(&problem);
+    let state = IterState::new(vec![]);
+    let start = Instant::now();
+    pool.install(|| solver.init(&mut op, &state).map(|_| ()))
+        .unwrap();
+    let parallel_time = start.elapsed();
+
+    println!(
+        "{} vertices, serial: {:?}, init: {:?}",
+        op.cost_func_count, serial_time, parallel_time
+    );
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Dot product of two vectors with 10^6 elements: scalar loop compared to the kernel of the
+//! `simd` feature.
+//!
+//! Run with `cargo bench --features simd --bench simd`.
+
+use argmin::math::simd::dot;
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+use std::time::Instant;
+
+fn main() {
+    let mut rng = XorShiftRng::seed_from_u64(3);
+    let a: Vec<f64> = (0..1_000_000).map(|_| rng.gen_range(-1e3, 1e3)).collect();
+    let b: Vec<f64> = (0..1_000_000).map(|_| rng.gen_range(-1e3, 1e3)).collect();
+    let (mut scalar, mut vectorized) = (0.0f64, 0.0f64);
+
+    let start = Instant::now();
+    for _ in 0..100 {
+        scalar += a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f64>();
+    }
+    let scalar_time = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..100 {
+        vectorized += dot(&a, &b);
+    }
+    let vectorized_time = start.elapsed();
+
+    println!(
+        "scalar: {:?} ({}), simd: {:?} ({})",
+        scalar_time, scalar, vectorized_time, vectorized
+    );
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Cost of explicit inversion compared to a direct solve for the Newton step on a 500
+//! dimensional quadratic. Both need exactly one gradient and one Hessian evaluation.
+//!
+//! Run with `cargo bench --features ndarrayl --bench solve`.
+
+use argmin::math::ArgminSolve;
+use argmin::prelude::*;
+use ndarray::{Array1, Array2};
+use std::time::Instant;
+
+fn main() {
+    let n = 500;
+    let a = Array2::from_shape_fn((n, n), |(i, j)| {
+        if i == j {
+            4.0
+        } else if (i as i64 - j as i64).abs() == 1 {
+            -1.0
+        } else {
+            0.0
+        }
+    });
+    let b = Array1::from_shape_fn(n, |i| (i as f64).sin());
+
+    let t = Instant::now();
+    let _: Array1<f64> = ArgminDot::dot(&a.inv().unwrap(), &b);
+    let t_inv = t.elapsed();
+
+    let t = Instant::now();
+    ArgminSolve::solve(&a, &b).unwrap();
+    let t_solve = t.elapsed();
+
+    println!("inverse: {:?}, solve: {:?}", t_inv, t_solve);
+}
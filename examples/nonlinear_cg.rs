@@ -45,7 +45,7 @@ fn run() -> Result<(), Error> {
     let solver = NonlinearConjugateGradient::new(linesearch, beta_method)?
         // Set the number of iterations when a restart should be performed
         // This allows the algorithm to "forget" previous information which may not be helpful anymore.
-        .restart_iters(10)
+        .restart_iters(10)?
         // Set the value for the orthogonality measure.
         // Setting this parameter leads to a restart of the algorithm (setting beta = 0) after two
         // consecutive search directions are not orthogonal anymore. In other words, if this condition
@@ -54,7 +54,7 @@ fn run() -> Result<(), Error> {
         // `|\nabla f_k^T * \nabla f_{k-1}| / | \nabla f_k ||^2 >= v`
         //
         // A typical value for `v` is 0.1.
-        .restart_orthogonality(0.1);
+        .restart_orthogonality(0.1)?;
 
     // Run solver
     let res = Executor::new(operator, solver, init_param)
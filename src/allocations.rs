@@ -0,0 +1,62 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Counting of allocations in tests
+//!
+//! The test binary uses a global allocator which counts the allocations of each thread, such that
+//! tests can check how often and how much a piece of code allocates.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations of the current thread
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<Allocations> = Cell::new(Allocations::default());
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|a| {
+            let Allocations { count, bytes } = a.get();
+            a.set(Allocations {
+                count: count + 1,
+                bytes: bytes + layout.size(),
+            })
+        });
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of allocations and allocated bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Allocations {
+    /// number of allocations (a reallocation counts as an allocation)
+    pub count: usize,
+    /// total number of allocated bytes
+    pub bytes: usize,
+}
+
+/// Allocations performed by `f` on the current thread and the output of `f`
+pub fn allocations<T, F: FnOnce() -> T>(f: F) -> (Allocations, T) {
+    let before = ALLOCATIONS.with(|a| a.get());
+    let out = f();
+    let after = ALLOCATIONS.with(|a| a.get());
+    let allocated = Allocations {
+        count: after.count - before.count,
+        bytes: after.bytes - before.bytes,
+    };
+    (allocated, out)
+}
@@ -0,0 +1,1076 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Run specifications
+//!
+//! A `RunConfig` describes a run: the solver and its hyperparameters, the termination criteria of
+//! the `Executor`, checkpoints and observers. It is read from TOML or JSON, for instance
+//!
+//! ```toml
+//! max_iters = 500
+//! target_cost = 1e-10
+//!
+//! [solver]
+//! name = "nelder_mead"
+//! alpha = 1.0
+//! tol_f = 1e-12
+//!
+//! [checkpoint]
+//! dir = ".checkpoints"
+//! name = "run"
+//! every = 20
+//!
+//! [observers]
+//! logger = "term"
+//! writer = { dir = "params", prefix = "param", every = 10 }
+//! ```
+//!
+//! and turned into a ready `Executor` with `configure_executor`. All keys except `solver.name`
+//! are optional; hyperparameters which are not given keep the defaults of the solver.
+//!
+//! The following solvers are available (with their hyperparameters):
+//!
+//! * `steepest_descent`: `c` (Armijo condition), `rho` (backtracking factor)
+//! * `nelder_mead`: `step` (absolute step of the initial simplex, relative by default), `alpha`,
+//!   `gamma`, `rho`, `sigma`, `tol_f`, `tol_x`
+//! * `simulated_annealing`: `init_temp` (required), `seed`, `temp_func` (`"fast"`,
+//!   `"boltzmann"` or `{ exponential = x }`), `stall_accepted`, `stall_best`, `reanneal_fixed`,
+//!   `reanneal_accepted`, `reanneal_best`
+//! * `lbfgsb`: `m` (default: 10), `pg_tol`, `lower`, `upper`
+//!
+//! Unknown keys, values of the wrong type and invalid hyperparameters are reported as
+//! `ConfigError`, which contains the path of the offending key (for instance `solver.alpha`).
+//!
+//! ## Choosing the solver at runtime
+//!
+//! `ConfiguredSolver` implements `Solver`, so a solver which is only known at runtime, for
+//! instance from user input, can be passed to `Executor::new` without matching on its type:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! # use argmin::config::ConfiguredSolver;
+//! # use argmin::testfunctions::problem::TestProblem;
+//! # fn main() -> Result<(), Error> {
+//! let params = serde_json::json!({ "alpha": 1.0, "tol_f": 1e-12 });
+//! let init = vec![-1.2, 1.0];
+//! let solver = ConfiguredSolver::from_name("nelder_mead", params, &init)?;
+//! let res = Executor::new(TestProblem::rosenbrock(2), solver, init)
+//!     .max_iters(500)
+//!     .run_fast()?;
+//! # assert!(res.cost < 1e-6);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::operator::Bounds;
+use crate::prelude::*;
+use crate::solver::gradientdescent::SteepestDescent;
+use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+use crate::solver::neldermead::{NelderMead, SimplexStep};
+use crate::solver::quasinewton::LBFGSB;
+use crate::solver::simulatedannealing::{SATempFunc, SimulatedAnnealing};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+/// Names of the solvers which can be configured
+pub const SOLVERS: &[&str] = &[
+    "steepest_descent",
+    "nelder_mead",
+    "simulated_annealing",
+    "lbfgsb",
+];
+
+/// Invalid run specification
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    /// path of the offending key (empty for syntax errors)
+    path: String,
+    /// description of the problem
+    message: String,
+}
+
+impl ConfigError {
+    /// Constructor
+    pub fn new(path: &str, message: &str) -> Self {
+        ConfigError {
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Path of the offending key, for instance `solver.alpha`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Description of the problem
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "Invalid run specification: {}", self.message)
+        } else {
+            write!(
+                f,
+                "Invalid run specification: {}: {}",
+                self.path, self.message
+            )
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn config_error(path: &str, message: &str) -> Error {
+    ConfigError::new(path, message).into()
+}
+
+/// Entries of a table which have not been read yet. Entries left over in `finish` are unknown.
+struct Table {
+    /// path of the table (empty for the root)
+    path: String,
+    /// remaining entries
+    entries: Map<String, Value>,
+}
+
+impl Table {
+    fn new(path: String, value: Value) -> Result<Self, Error> {
+        match value {
+            Value::Object(entries) => Ok(Table { path, entries }),
+            _ => Err(config_error(&path, "expected a table")),
+        }
+    }
+
+    fn path(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        }
+    }
+
+    fn take<T, F>(&mut self, key: &str, expected: &str, f: F) -> Result<Option<T>, Error>
+    where
+        F: FnOnce(&Value) -> Option<T>,
+    {
+        match self.entries.remove(key) {
+            None => Ok(None),
+            Some(value) => f(&value)
+                .map(Some)
+                .ok_or_else(|| config_error(&self.path(key), &format!("expected {}", expected))),
+        }
+    }
+
+    fn f64(&mut self, key: &str) -> Result<Option<f64>, Error> {
+        self.take(key, "a number", Value::as_f64)
+    }
+
+    fn u64(&mut self, key: &str) -> Result<Option<u64>, Error> {
+        self.take(key, "a non-negative integer", Value::as_u64)
+    }
+
+    fn positive_u64(&mut self, key: &str) -> Result<Option<u64>, Error> {
+        self.take(key, "a positive integer", |v| v.as_u64().filter(|&x| x > 0))
+    }
+
+    fn usize(&mut self, key: &str) -> Result<Option<usize>, Error> {
+        let expected = format!("a non-negative integer of at most {}", std::usize::MAX);
+        self.take(key, &expected, |v| {
+            v.as_u64().and_then(|x| usize::try_from(x).ok())
+        })
+    }
+
+    fn string(&mut self, key: &str) -> Result<Option<String>, Error> {
+        self.take(key, "a string", |v| v.as_str().map(String::from))
+    }
+
+    fn f64s(&mut self, key: &str) -> Result<Option<Vec<f64>>, Error> {
+        self.take(key, "an array of numbers", |v| {
+            v.as_array()?.iter().map(Value::as_f64).collect()
+        })
+    }
+
+    fn table(&mut self, key: &str) -> Result<Option<Table>, Error> {
+        let path = self.path(key);
+        self.entries
+            .remove(key)
+            .map(|value| Table::new(path, value))
+            .transpose()
+    }
+
+    fn required<T>(&self, key: &str, value: Option<T>) -> Result<T, Error> {
+        value.ok_or_else(|| config_error(&self.path(key), "missing"))
+    }
+
+    /// Fails if there are unread entries
+    fn finish(self) -> Result<(), Error> {
+        match self.entries.keys().next() {
+            Some(key) => Err(config_error(&self.path(key), "unknown key")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Solver and its hyperparameters. Hyperparameters which are `None` keep the solver's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolverConfig {
+    /// `SteepestDescent` with a backtracking line search and the Armijo condition
+    SteepestDescent {
+        /// parameter of the Armijo condition
+        c: Option<f64>,
+        /// backtracking factor
+        rho: Option<f64>,
+    },
+    /// `NelderMead` with an initial simplex around the initial parameter vector
+    NelderMead {
+        /// absolute step of the initial simplex (relative steps by default)
+        step: Option<f64>,
+        /// reflection coefficient
+        alpha: Option<f64>,
+        /// expansion coefficient
+        gamma: Option<f64>,
+        /// contraction coefficient
+        rho: Option<f64>,
+        /// shrink coefficient
+        sigma: Option<f64>,
+        /// tolerance for the standard deviation of the costs of the vertices
+        tol_f: Option<f64>,
+        /// tolerance for the diameter of the simplex
+        tol_x: Option<f64>,
+    },
+    /// `SimulatedAnnealing`
+    SimulatedAnnealing {
+        /// initial temperature
+        init_temp: f64,
+        /// seed of the random number generator
+        seed: Option<u64>,
+        /// temperature function
+        temp_func: Option<SATempFunc>,
+        /// stop after this number of iterations without accepted solution
+        stall_accepted: Option<u64>,
+        /// stop after this number of iterations without new best solution
+        stall_best: Option<u64>,
+        /// reanneal after this number of iterations
+        reanneal_fixed: Option<u64>,
+        /// reanneal after this number of iterations without accepted solution
+        reanneal_accepted: Option<u64>,
+        /// reanneal after this number of iterations without new best solution
+        reanneal_best: Option<u64>,
+    },
+    /// `LBFGSB`, unbounded unless `lower` or `upper` are given
+    LBFGSB {
+        /// number of stored correction pairs
+        m: usize,
+        /// tolerance for the infinity norm of the projected gradient
+        pg_tol: Option<f64>,
+        /// lower bounds
+        lower: Option<Vec<f64>>,
+        /// upper bounds
+        upper: Option<Vec<f64>>,
+    },
+}
+
+impl SolverConfig {
+    fn parse(mut table: Table) -> Result<Self, Error> {
+        let name = table.string("name")?;
+        let name = table.required("name", name)?;
+        let config = match name.as_ref() {
+            "steepest_descent" => SolverConfig::SteepestDescent {
+                c: table.f64("c")?,
+                rho: table.f64("rho")?,
+            },
+            "nelder_mead" => SolverConfig::NelderMead {
+                step: table.f64("step")?,
+                alpha: table.f64("alpha")?,
+                gamma: table.f64("gamma")?,
+                rho: table.f64("rho")?,
+                sigma: table.f64("sigma")?,
+                tol_f: table.f64("tol_f")?,
+                tol_x: table.f64("tol_x")?,
+            },
+            "simulated_annealing" => {
+                let init_temp = table.f64("init_temp")?;
+                SolverConfig::SimulatedAnnealing {
+                    init_temp: table.required("init_temp", init_temp)?,
+                    seed: table.u64("seed")?,
+                    temp_func: parse_temp_func(&mut table)?,
+                    stall_accepted: table.u64("stall_accepted")?,
+                    stall_best: table.u64("stall_best")?,
+                    reanneal_fixed: table.u64("reanneal_fixed")?,
+                    reanneal_accepted: table.u64("reanneal_accepted")?,
+                    reanneal_best: table.u64("reanneal_best")?,
+                }
+            }
+            "lbfgsb" => SolverConfig::LBFGSB {
+                m: table.usize("m")?.unwrap_or(10),
+                pg_tol: table.f64("pg_tol")?,
+                lower: table.f64s("lower")?,
+                upper: table.f64s("upper")?,
+            },
+            _ => {
+                return Err(config_error(
+                    &table.path("name"),
+                    &format!(
+                        "unknown solver `{}` (expected one of {})",
+                        name,
+                        SOLVERS.join(", ")
+                    ),
+                ))
+            }
+        };
+        table.finish()?;
+        Ok(config)
+    }
+
+    /// Solver `name` with the hyperparameters `params`, which is a JSON object or `null` (the
+    /// defaults of the solver)
+    pub fn from_name(name: &str, params: Value) -> Result<Self, Error> {
+        let mut table = match params {
+            Value::Null => Table::new("solver".to_string(), Value::Object(Map::new()))?,
+            params => Table::new("solver".to_string(), params)?,
+        };
+        if table.entries.contains_key("name") {
+            return Err(config_error(
+                &table.path("name"),
+                "must not be given with the hyperparameters",
+            ));
+        }
+        table
+            .entries
+            .insert("name".to_string(), Value::String(name.to_string()));
+        SolverConfig::parse(table)
+    }
+
+    /// Name of the solver
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverConfig::SteepestDescent { .. } => "steepest_descent",
+            SolverConfig::NelderMead { .. } => "nelder_mead",
+            SolverConfig::SimulatedAnnealing { .. } => "simulated_annealing",
+            SolverConfig::LBFGSB { .. } => "lbfgsb",
+        }
+    }
+
+    /// Constructs the solver. Invalid hyperparameters are reported with their path.
+    pub fn build(&self, init_param: &[f64]) -> Result<ConfiguredSolver, Error> {
+        /// Attaches the path of the hyperparameter to the error of a setter
+        fn at<T>(key: &str, res: Result<T, Error>) -> Result<T, Error> {
+            res.map_err(|e| config_error(&format!("solver.{}", key), &e.to_string()))
+        }
+
+        Ok(match *self {
+            SolverConfig::SteepestDescent { c, rho } => {
+                let condition = at("c", ArmijoCondition::new(c.unwrap_or(1e-4)))?;
+                let mut linesearch = BacktrackingLineSearch::new(condition);
+                if let Some(rho) = rho {
+                    linesearch = at("rho", linesearch.rho(rho))?;
+                }
+                ConfiguredSolver::SteepestDescent(SteepestDescent::new(linesearch)?)
+            }
+            SolverConfig::NelderMead {
+                step,
+                alpha,
+                gamma,
+                rho,
+                sigma,
+                tol_f,
+                tol_x,
+            } => {
+                let step = step.map_or_else(SimplexStep::default, SimplexStep::Absolute);
+                let mut solver = at("step", NelderMead::from_point(init_param.to_vec(), step))?;
+                if let Some(alpha) = alpha {
+                    solver = at("alpha", solver.alpha(alpha))?;
+                }
+                if let Some(gamma) = gamma {
+                    solver = at("gamma", solver.gamma(gamma))?;
+                }
+                if let Some(rho) = rho {
+                    solver = at("rho", solver.rho(rho))?;
+                }
+                if let Some(sigma) = sigma {
+                    solver = at("sigma", solver.sigma(sigma))?;
+                }
+                if let Some(tol_f) = tol_f {
+                    solver = at("tol_f", solver.tol_f(tol_f))?;
+                }
+                if let Some(tol_x) = tol_x {
+                    solver = at("tol_x", solver.tol_x(tol_x))?;
+                }
+                ConfiguredSolver::NelderMead(solver)
+            }
+            SolverConfig::SimulatedAnnealing {
+                init_temp,
+                seed,
+                temp_func,
+                stall_accepted,
+                stall_best,
+                reanneal_fixed,
+                reanneal_accepted,
+                reanneal_best,
+            } => {
+                let mut solver = at("init_temp", SimulatedAnnealing::new(init_temp))?;
+                if let Some(seed) = seed {
+                    solver = solver.seed(seed);
+                }
+                if let Some(temp_func) = temp_func {
+                    solver = solver.temp_func(temp_func);
+                }
+                if let Some(iter) = stall_accepted {
+                    solver = solver.stall_accepted(iter);
+                }
+                if let Some(iter) = stall_best {
+                    solver = solver.stall_best(iter);
+                }
+                if let Some(iter) = reanneal_fixed {
+                    solver = solver.reannealing_fixed(iter);
+                }
+                if let Some(iter) = reanneal_accepted {
+                    solver = solver.reannealing_accepted(iter);
+                }
+                if let Some(iter) = reanneal_best {
+                    solver = solver.reannealing_best(iter);
+                }
+                ConfiguredSolver::SimulatedAnnealing(solver)
+            }
+            SolverConfig::LBFGSB {
+                m,
+                pg_tol,
+                ref lower,
+                ref upper,
+            } => {
+                // `from_options` only fails if both bounds are given, and it checks the upper
+                // bound against the lower one
+                let bounds = at("upper", Bounds::from_options(lower.clone(), upper.clone()))?;
+                let mut solver = at("m", LBFGSB::new(bounds, m))?;
+                if let Some(pg_tol) = pg_tol {
+                    solver = at("pg_tol", solver.pg_tol(pg_tol))?;
+                }
+                ConfiguredSolver::LBFGSB(solver)
+            }
+        })
+    }
+}
+
+fn parse_temp_func(table: &mut Table) -> Result<Option<SATempFunc>, Error> {
+    let path = table.path("temp_func");
+    let value = match table.entries.remove("temp_func") {
+        None => return Ok(None),
+        Some(value) => value,
+    };
+    match value {
+        Value::String(ref name) if name == "fast" => Ok(Some(SATempFunc::TemperatureFast)),
+        Value::String(ref name) if name == "boltzmann" => Ok(Some(SATempFunc::Boltzmann)),
+        Value::Object(_) => {
+            let mut exponential = Table::new(path, value)?;
+            let x = exponential.f64("exponential")?;
+            let x = exponential.required("exponential", x)?;
+            exponential.finish()?;
+            Ok(Some(SATempFunc::Exponential(x)))
+        }
+        _ => Err(config_error(
+            &path,
+            "expected \"fast\", \"boltzmann\" or { exponential = x }",
+        )),
+    }
+}
+
+/// Checkpoint settings
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckpointConfig {
+    /// directory of the checkpoints
+    pub dir: String,
+    /// name of the checkpoint files
+    pub name: String,
+    /// save a checkpoint every `every` iterations (every iteration if `None`)
+    pub every: Option<u64>,
+}
+
+/// Logger attached to the `Executor`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoggerConfig {
+    /// `ArgminSlogLogger::term`
+    Term,
+    /// `ArgminSlogLogger::term_noblock`
+    TermNoblock,
+}
+
+/// Writer of the parameter vectors (`WriteToFile` with the JSON serializer)
+#[derive(Clone, Debug, PartialEq)]
+pub struct WriterConfig {
+    /// output directory
+    pub dir: String,
+    /// prefix of the file names
+    pub prefix: String,
+    /// write every `every` iterations (every new best parameter vector if `None`)
+    pub every: Option<u64>,
+}
+
+/// Observers attached to the `Executor`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObserverConfig {
+    /// logger
+    pub logger: Option<LoggerConfig>,
+    /// writer
+    pub writer: Option<WriterConfig>,
+}
+
+/// Run specification
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunConfig {
+    /// solver and its hyperparameters
+    pub solver: SolverConfig,
+    /// maximum number of iterations
+    pub max_iters: Option<u64>,
+    /// target cost function value
+    pub target_cost: Option<f64>,
+    /// checkpoints
+    pub checkpoint: Option<CheckpointConfig>,
+    /// observers
+    pub observers: ObserverConfig,
+}
+
+impl RunConfig {
+    /// Parses a run specification in TOML format
+    pub fn from_toml(s: &str) -> Result<Self, Error> {
+        let value: Value = toml::from_str(s).map_err(|e| config_error("", &e.to_string()))?;
+        RunConfig::from_value(value)
+    }
+
+    /// Parses a run specification in JSON format
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        let value: Value = serde_json::from_str(s).map_err(|e| config_error("", &e.to_string()))?;
+        RunConfig::from_value(value)
+    }
+
+    /// Reads a run specification from a `.toml` or `.json` file
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => RunConfig::from_toml(&s),
+            Some("json") => RunConfig::from_json(&s),
+            _ => Err(config_error(
+                "",
+                &format!("{}: expected a .toml or .json file", path.display()),
+            )),
+        }
+    }
+
+    fn from_value(value: Value) -> Result<Self, Error> {
+        let mut root = Table::new(String::new(), value)?;
+        let solver = root.table("solver")?;
+        let solver = SolverConfig::parse(root.required("solver", solver)?)?;
+        let max_iters = root.u64("max_iters")?;
+        let target_cost = root.f64("target_cost")?;
+        let checkpoint = match root.table("checkpoint")? {
+            None => None,
+            Some(mut table) => {
+                let dir = table.string("dir")?;
+                let name = table.string("name")?;
+                let checkpoint = CheckpointConfig {
+                    dir: table.required("dir", dir)?,
+                    name: table.required("name", name)?,
+                    every: table.positive_u64("every")?,
+                };
+                table.finish()?;
+                Some(checkpoint)
+            }
+        };
+        let observers = match root.table("observers")? {
+            None => ObserverConfig::default(),
+            Some(table) => parse_observers(table)?,
+        };
+        root.finish()?;
+        Ok(RunConfig {
+            solver,
+            max_iters,
+            target_cost,
+            checkpoint,
+            observers,
+        })
+    }
+}
+
+fn parse_observers(mut table: Table) -> Result<ObserverConfig, Error> {
+    let logger = match table.string("logger")? {
+        None => None,
+        Some(ref name) if name == "term" => Some(LoggerConfig::Term),
+        Some(ref name) if name == "term_noblock" => Some(LoggerConfig::TermNoblock),
+        Some(name) => {
+            return Err(config_error(
+                &table.path("logger"),
+                &format!(
+                    "unknown logger `{}` (expected one of term, term_noblock)",
+                    name
+                ),
+            ))
+        }
+    };
+    let writer = match table.table("writer")? {
+        None => None,
+        Some(mut writer) => {
+            let dir = writer.string("dir")?;
+            let prefix = writer.string("prefix")?;
+            let config = WriterConfig {
+                dir: writer.required("dir", dir)?,
+                prefix: writer.required("prefix", prefix)?,
+                every: writer.positive_u64("every")?,
+            };
+            writer.finish()?;
+            Some(config)
+        }
+    };
+    table.finish()?;
+    Ok(ObserverConfig { logger, writer })
+}
+
+/// One of the solvers which can be configured via `RunConfig` or `from_name`
+#[derive(Serialize, Deserialize)]
+pub enum ConfiguredSolver {
+    /// `steepest_descent`
+    SteepestDescent(SteepestDescent<BacktrackingLineSearch<Vec<f64>, ArmijoCondition>>),
+    /// `nelder_mead`
+    NelderMead(NelderMead<Vec<f64>>),
+    /// `simulated_annealing`
+    SimulatedAnnealing(SimulatedAnnealing<Vec<f64>>),
+    /// `lbfgsb`
+    LBFGSB(LBFGSB),
+}
+
+impl ConfiguredSolver {
+    /// Constructs the solver `name` (one of `SOLVERS`) with the hyperparameters `params` (a JSON
+    /// object or `null`) for the initial parameter vector `init_param`. Unknown names and keys and
+    /// invalid hyperparameters are reported as `ConfigError`.
+    pub fn from_name(name: &str, params: Value, init_param: &[f64]) -> Result<Self, Error> {
+        SolverConfig::from_name(name, params)?.build(init_param)
+    }
+}
+
+impl<O> Solver<O> for ConfiguredSolver
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    O::Hessian: Default,
+    OpWrapper<O>: ArgminBulkOp<O>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        match self {
+            ConfiguredSolver::SteepestDescent(solver) => solver.init(op, state),
+            ConfiguredSolver::NelderMead(solver) => solver.init(op, state),
+            ConfiguredSolver::SimulatedAnnealing(solver) => solver.init(op, state),
+            ConfiguredSolver::LBFGSB(solver) => solver.init(op, state),
+        }
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        match self {
+            ConfiguredSolver::SteepestDescent(solver) => solver.next_iter(op, state),
+            ConfiguredSolver::NelderMead(solver) => solver.next_iter(op, state),
+            ConfiguredSolver::SimulatedAnnealing(solver) => solver.next_iter(op, state),
+            ConfiguredSolver::LBFGSB(solver) => solver.next_iter(op, state),
+        }
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        match self {
+            ConfiguredSolver::SteepestDescent(solver) => solver.terminate(state),
+            ConfiguredSolver::NelderMead(solver) => solver.terminate(state),
+            ConfiguredSolver::SimulatedAnnealing(solver) => solver.terminate(state),
+            ConfiguredSolver::LBFGSB(solver) => solver.terminate(state),
+        }
+    }
+}
+
+/// Constructs the solver described by `config` and an `Executor` with the termination criteria,
+/// checkpoints and observers of `config`.
+pub fn configure_executor<O>(
+    op: O,
+    init_param: Vec<f64>,
+    config: &RunConfig,
+) -> Result<Executor<O, ConfiguredSolver>, Error>
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    O::Hessian: Default,
+    OpWrapper<O>: ArgminBulkOp<O>,
+{
+    let solver = config.solver.build(&init_param)?;
+    let mut executor = Executor::new(op, solver, init_param);
+    if let Some(max_iters) = config.max_iters {
+        executor = executor.max_iters(max_iters);
+    }
+    if let Some(target_cost) = config.target_cost {
+        executor = executor.target_cost(target_cost);
+    }
+    if let Some(ref checkpoint) = config.checkpoint {
+        let mode = match checkpoint.every {
+            Some(0) => {
+                return Err(config_error(
+                    "checkpoint.every",
+                    "expected a positive integer",
+                ))
+            }
+            Some(every) => CheckpointMode::Every(every),
+            None => CheckpointMode::Always,
+        };
+        executor = executor
+            .checkpoint_dir(&checkpoint.dir)
+            .checkpoint_name(&checkpoint.name)
+            .checkpoint_mode(mode);
+    }
+    match config.observers.logger {
+        Some(LoggerConfig::Term) => executor = executor.add_logger(ArgminSlogLogger::term()),
+        Some(LoggerConfig::TermNoblock) => {
+            executor = executor.add_logger(ArgminSlogLogger::term_noblock())
+        }
+        None => {}
+    }
+    if let Some(ref config) = config.observers.writer {
+        let mut writer = WriteToFile::new(&config.dir, &config.prefix);
+        writer.set_mode(match config.every {
+            Some(0) => {
+                return Err(config_error(
+                    "observers.writer.every",
+                    "expected a positive integer",
+                ))
+            }
+            Some(every) => WriterMode::Every(every),
+            None => WriterMode::NewBest,
+        });
+        writer.set_serializer(WriteToFileSerializer::JSON);
+        executor = executor.add_writer(Arc::new(writer));
+    }
+    Ok(executor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::testfunctions::problem::annealable::Annealable;
+    use crate::testfunctions::problem::TestProblem;
+
+    send_sync_test!(configured_solver, ConfiguredSolver);
+
+    fn error<T>(res: Result<T, Error>) -> ConfigError {
+        res.err()
+            .unwrap()
+            .downcast_ref::<ConfigError>()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn test_config_steepest_descent_toml() {
+        let config = RunConfig::from_toml(
+            r#"
+            max_iters = 1000
+            [solver]
+            name = "steepest_descent"
+            c = 1e-4
+            rho = 0.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.solver.name(), "steepest_descent");
+        let problem = TestProblem::sphere(2);
+        let res = configure_executor(problem.clone(), problem.start().to_vec(), &config)
+            .unwrap()
+            .run_fast()
+            .unwrap();
+        assert!(problem.is_solution(&res.param));
+    }
+
+    #[test]
+    fn test_config_nelder_mead_json() {
+        let config = RunConfig::from_json(
+            r#"{
+                "max_iters": 500,
+                "solver": { "name": "nelder_mead", "alpha": 1.0, "tol_f": 1e-14 },
+                "observers": { "writer": { "dir": "params", "prefix": "nm", "every": 10 } }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.max_iters, Some(500));
+        assert_eq!(
+            config.observers.writer,
+            Some(WriterConfig {
+                dir: "params".to_string(),
+                prefix: "nm".to_string(),
+                every: Some(10),
+            })
+        );
+        let mut config = config;
+        config.observers.writer = None;
+        let res = configure_executor(TestProblem::rosenbrock(2), vec![-1.2, 1.0], &config)
+            .unwrap()
+            .run_fast()
+            .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_config_simulated_annealing() {
+        let config = RunConfig::from_toml(
+            r#"
+            max_iters = 2000
+            [solver]
+            name = "simulated_annealing"
+            init_temp = 10.0
+            seed = 42
+            temp_func = { exponential = 0.99 }
+            stall_best = 1000
+            "#,
+        )
+        .unwrap();
+        match config.solver {
+            SolverConfig::SimulatedAnnealing {
+                temp_func: Some(SATempFunc::Exponential(x)),
+                seed: Some(42),
+                ..
+            } => assert!((x - 0.99).abs() < std::f64::EPSILON),
+            _ => panic!("wrong solver configuration"),
+        }
+        let op = Annealable::new(TestProblem::sphere(2), 0.1, 42);
+        let init = vec![1.0, -1.0];
+        let init_cost = op.apply(&init).unwrap();
+        let res = configure_executor(op, init, &config)
+            .unwrap()
+            .run_fast()
+            .unwrap();
+        assert!(res.cost < init_cost);
+    }
+
+    #[test]
+    fn test_config_lbfgsb() {
+        let config = RunConfig::from_toml(
+            r#"
+            max_iters = 100
+            [solver]
+            name = "lbfgsb"
+            m = 5
+            pg_tol = 1e-10
+            lower = [-2.0, 1.5]
+            upper = [2.0, 3.0]
+            "#,
+        )
+        .unwrap();
+        let res = configure_executor(TestProblem::rosenbrock(2), vec![-1.2, 2.0], &config)
+            .unwrap()
+            .run_fast()
+            .unwrap();
+        // the minimizer (1, 1) is excluded by the lower bound of the second component
+        assert!(res.param[1] >= 1.5);
+        assert!(res.cost < TestProblem::rosenbrock(2).apply(&vec![-1.2, 2.0]).unwrap());
+    }
+
+    #[test]
+    fn test_config_checkpoint_and_logger() {
+        let config = RunConfig::from_toml(
+            r#"
+            [solver]
+            name = "nelder_mead"
+            [checkpoint]
+            dir = ".checkpoints"
+            name = "run"
+            [observers]
+            logger = "term_noblock"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.checkpoint,
+            Some(CheckpointConfig {
+                dir: ".checkpoints".to_string(),
+                name: "run".to_string(),
+                every: None,
+            })
+        );
+        assert_eq!(config.observers.logger, Some(LoggerConfig::TermNoblock));
+    }
+
+    #[test]
+    fn test_config_errors() {
+        let e = error(RunConfig::from_toml("[solver]\nname = \"bfgs\""));
+        assert_eq!(e.path(), "solver.name");
+        assert!(e.message().contains("nelder_mead"));
+
+        let e = error(RunConfig::from_toml("max_iters = 10"));
+        assert_eq!(e.path(), "solver");
+
+        let e = error(RunConfig::from_toml("[solver]\nalpha = 1.0"));
+        assert_eq!(e.path(), "solver.name");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"nelder_mead\"\nalpah = 1.0",
+        ));
+        assert_eq!((e.path(), e.message()), ("solver.alpah", "unknown key"));
+
+        let e = error(RunConfig::from_json(
+            r#"{"solver": {"name": "nelder_mead"}, "max_iters": -1}"#,
+        ));
+        assert_eq!(e.path(), "max_iters");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"simulated_annealing\"",
+        ));
+        assert_eq!(e.path(), "solver.init_temp");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"lbfgsb\"\nlower = [1.0, \"a\"]",
+        ));
+        assert_eq!(e.path(), "solver.lower");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"nelder_mead\"\n[observers]\nlogger = \"file\"",
+        ));
+        assert_eq!(e.path(), "observers.logger");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"nelder_mead\"\n[checkpoint]\ndir = \".checkpoints\"",
+        ));
+        assert_eq!(e.path(), "checkpoint.name");
+
+        let e = error(RunConfig::from_toml(
+            "[solver]\nname = \"nelder_mead\"\n[checkpoint]\ndir = \".checkpoints\"\nname = \"nm\"\n\
+             every = 0",
+        ));
+        assert_eq!(e.path(), "checkpoint.every");
+        assert_eq!(e.message(), "expected a positive integer");
+
+        let e = error(RunConfig::from_json(
+            r#"{"solver": {"name": "nelder_mead"},
+                "observers": {"writer": {"dir": "params", "prefix": "nm", "every": 0}}}"#,
+        ));
+        assert_eq!(e.path(), "observers.writer.every");
+
+        // also when the configuration is constructed by hand
+        let mut config = RunConfig::from_toml(
+            "[solver]\nname = \"nelder_mead\"\n[checkpoint]\ndir = \".checkpoints\"\nname = \"nm\"",
+        )
+        .unwrap();
+        config.checkpoint.as_mut().unwrap().every = Some(0);
+        let e = error(configure_executor(
+            TestProblem::sphere(2),
+            vec![1.0, 1.0],
+            &config,
+        ));
+        assert_eq!(e.path(), "checkpoint.every");
+
+        let e = error(RunConfig::from_json("{\"solver\": "));
+        assert_eq!(e.path(), "");
+    }
+
+    #[test]
+    fn test_config_invalid_hyperparameters() {
+        let build = |s: &str| {
+            let config = RunConfig::from_toml(s).unwrap();
+            configure_executor(TestProblem::sphere(2), vec![1.0, 1.0], &config)
+                .err()
+                .unwrap()
+                .downcast_ref::<ConfigError>()
+                .unwrap()
+                .path()
+                .to_string()
+        };
+        assert_eq!(
+            build("[solver]\nname = \"nelder_mead\"\nalpha = -1.0"),
+            "solver.alpha"
+        );
+        assert_eq!(
+            build("[solver]\nname = \"nelder_mead\"\nstep = 0.0"),
+            "solver.step"
+        );
+        assert_eq!(
+            build("[solver]\nname = \"simulated_annealing\"\ninit_temp = 0.0"),
+            "solver.init_temp"
+        );
+        assert_eq!(build("[solver]\nname = \"lbfgsb\"\nm = 0"), "solver.m");
+        assert_eq!(
+            build("[solver]\nname = \"lbfgsb\"\nlower = [0.0, 0.0]\nupper = [1.0, -1.0]"),
+            "solver.upper"
+        );
+        assert_eq!(
+            build("[solver]\nname = \"lbfgsb\"\nlower = [0.0, 0.0]\nupper = [1.0]"),
+            "solver.upper"
+        );
+        assert_eq!(
+            build("[solver]\nname = \"steepest_descent\"\nrho = 2.0"),
+            "solver.rho"
+        );
+    }
+
+    #[test]
+    fn test_configured_solver_from_name() {
+        let problem = TestProblem::sphere(2);
+        let init = problem.start().to_vec();
+        let solver = ConfiguredSolver::from_name("steepest_descent", Value::Null, &init).unwrap();
+        let res = Executor::new(problem.clone(), solver, init)
+            .max_iters(1000)
+            .run_fast()
+            .unwrap();
+        assert!(problem.is_solution(&res.param));
+
+        let params = serde_json::json!({ "alpha": 1.0, "tol_f": 1e-14 });
+        let solver = ConfiguredSolver::from_name("nelder_mead", params, &[-1.2, 1.0]).unwrap();
+        let res = Executor::new(TestProblem::rosenbrock(2), solver, vec![-1.2, 1.0])
+            .max_iters(500)
+            .run_fast()
+            .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-4));
+
+        let params = serde_json::json!({ "init_temp": 10.0, "seed": 42 });
+        let init = vec![1.0, -1.0];
+        let solver = ConfiguredSolver::from_name("simulated_annealing", params, &init).unwrap();
+        let op = Annealable::new(TestProblem::sphere(2), 0.1, 42);
+        let init_cost = op.apply(&init).unwrap();
+        let res = Executor::new(op, solver, init)
+            .max_iters(2000)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost < init_cost);
+    }
+
+    #[test]
+    fn test_configured_solver_from_name_errors() {
+        let init = [1.0, 1.0];
+        let e = error(ConfiguredSolver::from_name("bfgs", Value::Null, &init));
+        assert_eq!(e.path(), "solver.name");
+        assert!(e.message().contains("unknown solver `bfgs`"));
+
+        let params = serde_json::json!({ "alpah": 1.0 });
+        let e = error(ConfiguredSolver::from_name("nelder_mead", params, &init));
+        assert_eq!((e.path(), e.message()), ("solver.alpah", "unknown key"));
+
+        let params = serde_json::json!({ "alpha": -1.0 });
+        let e = error(ConfiguredSolver::from_name("nelder_mead", params, &init));
+        assert_eq!(e.path(), "solver.alpha");
+
+        let params = serde_json::json!({ "name": "nelder_mead" });
+        let e = error(ConfiguredSolver::from_name("nelder_mead", params, &init));
+        assert_eq!(e.path(), "solver.name");
+
+        let e = error(ConfiguredSolver::from_name(
+            "nelder_mead",
+            1.0.into(),
+            &init,
+        ));
+        assert_eq!(e.path(), "solver");
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Constraints
+//!
+//! `Constraint` describes a feasible set by a predicate `is_satisfied` and, optionally, a measure
+//! of how much a parameter vector violates it. Constraints can be named and combined with `All`.
+//! Every closure `Fn(&P) -> bool` is a constraint, therefore closures can be used wherever a
+//! `&dyn Constraint<P>` is expected.
+
+use crate::operator::Bounds;
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A constraint on parameter vectors of type `P`
+pub trait Constraint<P> {
+    /// Returns `true` if `param` satisfies the constraint
+    fn is_satisfied(&self, param: &P) -> bool;
+
+    /// Amount by which `param` violates the constraint. Must be `0` if the constraint is
+    /// satisfied. By default `infinity` is returned for violated constraints.
+    fn violation(&self, param: &P) -> f64 {
+        if self.is_satisfied(param) {
+            0.0
+        } else {
+            std::f64::INFINITY
+        }
+    }
+
+    /// Name of the constraint, used for reporting
+    fn name(&self) -> String {
+        "constraint".to_string()
+    }
+}
+
+impl<P, F> Constraint<P> for F
+where
+    F: Fn(&P) -> bool,
+{
+    fn is_satisfied(&self, param: &P) -> bool {
+        self(param)
+    }
+}
+
+/// The constraint which is always satisfied
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Unconstrained;
+
+impl<P> Constraint<P> for Unconstrained {
+    fn is_satisfied(&self, _param: &P) -> bool {
+        true
+    }
+
+    fn name(&self) -> String {
+        "unconstrained".to_string()
+    }
+}
+
+/// Linear inequality `a^T x <= b`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LinearInequality<P> {
+    /// normal vector
+    a: P,
+    /// offset
+    b: f64,
+}
+
+impl<P> LinearInequality<P> {
+    /// Constructor
+    pub fn new(a: P, b: f64) -> Self {
+        LinearInequality { a, b }
+    }
+}
+
+impl<P> Constraint<P> for LinearInequality<P>
+where
+    P: ArgminDot<P, f64>,
+{
+    fn is_satisfied(&self, param: &P) -> bool {
+        self.a.dot(param) <= self.b
+    }
+
+    fn violation(&self, param: &P) -> f64 {
+        (self.a.dot(param) - self.b).max(0.0)
+    }
+
+    fn name(&self) -> String {
+        "linear inequality".to_string()
+    }
+}
+
+/// Ball `||x - center|| <= radius` with respect to the Euclidean norm
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NormBall<P> {
+    /// center
+    center: P,
+    /// radius
+    radius: f64,
+}
+
+impl<P> NormBall<P> {
+    /// Constructor. Returns an error if `radius` is negative or `NaN`.
+    pub fn new(center: P, radius: f64) -> Result<Self, Error> {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NormBall: radius must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(NormBall { center, radius })
+    }
+}
+
+impl<P> Constraint<P> for NormBall<P>
+where
+    P: ArgminSub<P, P> + ArgminNorm<f64>,
+{
+    fn is_satisfied(&self, param: &P) -> bool {
+        param.sub(&self.center).norm() <= self.radius
+    }
+
+    fn violation(&self, param: &P) -> f64 {
+        (param.sub(&self.center).norm() - self.radius).max(0.0)
+    }
+
+    fn name(&self) -> String {
+        "norm ball".to_string()
+    }
+}
+
+macro_rules! make_bounds_constraint {
+    ($p:ty) => {
+        impl Constraint<$p> for Bounds<$p> {
+            fn is_satisfied(&self, param: &$p) -> bool {
+                self.contains(param)
+            }
+
+            /// `sum_i max(0, lower_i - x_i) + max(0, x_i - upper_i)`
+            fn violation(&self, param: &$p) -> f64 {
+                let below: f64 = self
+                    .lower()
+                    .map(|l| {
+                        l.iter()
+                            .zip(param.iter())
+                            .map(|(l, x)| (l - x).max(0.0))
+                            .sum()
+                    })
+                    .unwrap_or(0.0);
+                let above: f64 = self
+                    .upper()
+                    .map(|u| {
+                        u.iter()
+                            .zip(param.iter())
+                            .map(|(u, x)| (x - u).max(0.0))
+                            .sum()
+                    })
+                    .unwrap_or(0.0);
+                below + above
+            }
+
+            fn name(&self) -> String {
+                "bounds".to_string()
+            }
+        }
+    };
+}
+
+make_bounds_constraint!(Vec<f64>);
+#[cfg(feature = "ndarray")]
+make_bounds_constraint!(ndarray::Array1<f64>);
+
+/// Intersection of constraints: satisfied if all constraints are satisfied. The violation is the
+/// sum of the individual violations.
+pub struct All<P>(pub Vec<Box<dyn Constraint<P> + Send + Sync>>);
+
+impl<P> All<P> {
+    /// Constructor
+    pub fn new() -> Self {
+        All(vec![])
+    }
+
+    /// Add a constraint
+    pub fn and<C>(mut self, constraint: C) -> Self
+    where
+        C: Constraint<P> + Send + Sync + 'static,
+    {
+        self.0.push(Box::new(constraint));
+        self
+    }
+
+    /// Index of the first constraint which is not satisfied by `param`
+    pub fn first_violated(&self, param: &P) -> Option<usize> {
+        self.0.iter().position(|c| !c.is_satisfied(param))
+    }
+
+    /// Names of all constraints which are not satisfied by `param`
+    pub fn violated(&self, param: &P) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|c| !c.is_satisfied(param))
+            .map(|c| c.name())
+            .collect()
+    }
+}
+
+impl<P> Default for All<P> {
+    fn default() -> Self {
+        All::new()
+    }
+}
+
+impl<P> Constraint<P> for All<P> {
+    fn is_satisfied(&self, param: &P) -> bool {
+        self.0.iter().all(|c| c.is_satisfied(param))
+    }
+
+    fn violation(&self, param: &P) -> f64 {
+        self.0.iter().map(|c| c.violation(param)).sum()
+    }
+
+    fn name(&self) -> String {
+        format!(
+            "all({})",
+            self.0
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(unconstrained, Unconstrained);
+    send_sync_test!(linear_inequality, LinearInequality<Vec<f64>>);
+    send_sync_test!(norm_ball, NormBall<Vec<f64>>);
+    send_sync_test!(all, All<Vec<f64>>);
+
+    #[test]
+    fn test_closure() {
+        let c = |x: &Vec<f64>| x[0] > 0.0;
+        assert!(c.is_satisfied(&vec![1.0]));
+        assert!(!c.is_satisfied(&vec![-1.0]));
+        assert!(c.violation(&vec![1.0]).abs() < std::f64::EPSILON);
+        assert!(c.violation(&vec![-1.0]).is_infinite());
+        let d: &dyn Constraint<Vec<f64>> = &c;
+        assert!(d.is_satisfied(&vec![2.0]));
+    }
+
+    #[test]
+    fn test_unconstrained() {
+        assert!(Unconstrained.is_satisfied(&vec![std::f64::NAN]));
+        assert!(Constraint::<f64>::violation(&Unconstrained, &1.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_linear_inequality() {
+        let c = LinearInequality::new(vec![1.0, 1.0], 1.0);
+        assert!(c.is_satisfied(&vec![0.5, 0.5]));
+        assert!(c.violation(&vec![0.5, 0.5]).abs() < std::f64::EPSILON);
+        assert!(!c.is_satisfied(&vec![1.0, 1.0]));
+        assert!((c.violation(&vec![1.0, 1.0]) - 1.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_norm_ball() {
+        let c = NormBall::new(vec![1.0, 1.0], 1.0).unwrap();
+        assert!(c.is_satisfied(&vec![1.0, 2.0]));
+        assert!(!c.is_satisfied(&vec![4.0, 5.0]));
+        assert!((c.violation(&vec![4.0, 5.0]) - 4.0).abs() < std::f64::EPSILON);
+        assert!(NormBall::new(vec![0.0], -1.0).is_err());
+        assert!(NormBall::new(vec![0.0], std::f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let c = Bounds::new(vec![0.0, 0.0], vec![1.0, 1.0]).unwrap();
+        assert!(c.is_satisfied(&vec![0.0, 1.0]));
+        assert!(!c.is_satisfied(&vec![-1.0, 3.0]));
+        assert!((c.violation(&vec![-1.0, 3.0]) - 3.0).abs() < std::f64::EPSILON);
+        let c = Bounds::from_options(None, Some(vec![1.0])).unwrap();
+        assert!(c.is_satisfied(&vec![-100.0]));
+        assert!((c.violation(&vec![1.5]) - 0.5).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_all() {
+        let c = All::new()
+            .and(LinearInequality::new(vec![1.0, 0.0], 1.0))
+            .and(NormBall::new(vec![0.0, 0.0], 2.0).unwrap())
+            .and(|x: &Vec<f64>| x[1] >= 0.0);
+        assert!(c.is_satisfied(&vec![0.5, 0.5]));
+        assert_eq!(c.first_violated(&vec![0.5, 0.5]), None);
+        assert!(!c.is_satisfied(&vec![1.5, 0.0]));
+        assert_eq!(c.first_violated(&vec![1.5, 0.0]), Some(0));
+        assert_eq!(c.violated(&vec![0.0, 3.0]), vec!["norm ball".to_string()]);
+        assert_eq!(
+            c.violated(&vec![3.0, -1.0]),
+            vec![
+                "linear inequality".to_string(),
+                "norm ball".to_string(),
+                "constraint".to_string()
+            ]
+        );
+        assert!(c.violation(&vec![3.0, -1.0]).is_infinite());
+        assert!(
+            (c.violation(&vec![1.5, 0.0]) - 0.5).abs() < std::f64::EPSILON,
+            "only the linear inequality is violated"
+        );
+        assert_eq!(
+            c.name(),
+            "all(linear inequality, norm ball, constraint)".to_string()
+        );
+        assert!(All::<Vec<f64>>::new().is_satisfied(&vec![1.0]));
+    }
+}
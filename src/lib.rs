@@ -56,6 +56,7 @@
 //!   - [Steihaug method](solver/trustregion/steihaug/struct.Steihaug.html)
 //! - [Steepest descent](solver/gradientdescent/steepestdescent/struct.SteepestDescent.html)
 //! - [Conjugate gradient method](solver/conjugategradient/cg/struct.ConjugateGradient.html)
+//! - [Preconditioned conjugate gradient method](solver/conjugategradient/pcg/struct.PreconditionedConjugateGradient.html)
 //! - [Nonlinear conjugate gradient method](solver/conjugategradient/nonlinear_cg/struct.NonlinearConjugateGradient.html)
 //! - [Newton methods](solver/newton/index.html)
 //!   - [Newton's method](solver/newton/newton_method/struct.Newton.html)
@@ -63,8 +64,11 @@
 //! - [Quasi-Newton methods](solver/quasinewton/index.html)
 //!   - [BFGS](solver/quasinewton/bfgs/struct.BFGS.html)
 //!   - [DFP](solver/quasinewton/dfp/struct.DFP.html)
+//!   - [L-BFGS-B](solver/quasinewton/lbfgsb/struct.LBFGSB.html)
 //! - [Landweber iteration](solver/landweber/struct.Landweber.html)
+//! - [Nelder-Mead method](solver/neldermead/struct.NelderMead.html)
 //! - [Simulated Annealing](solver/simulatedannealing/struct.SimulatedAnnealing.html)
+//! - [Newton-bisection root finding method](solver/newtonbisection/struct.NewtonBisection.html)
 //!
 //! # Usage
 //!
@@ -90,6 +94,28 @@
 //! - `ctrlc`: Uses the `ctrlc` crate to properly stop the optimization (and return the current best
 //!    result) after pressing Ctrl+C.
 //! - `ndarrayl`: Support for `ndarray` and `ndarray-linalg`.
+//! - `ndarray`: Support for `ndarray` in the math traits of this crate without linking
+//!    `ndarray-linalg`. Linear solves fall back to a pure Rust implementation.
+//! - `nalgebra`: Support for `nalgebra`'s `DVector` and `DMatrix` in the math traits,
+//!    proximal operators and projections of this crate.
+//! - `sprs`: Sparse linear operators based on `sprs` matrices, for solvers which only need
+//!    matrix-vector products.
+//! - `benchmark`: Comparison of solvers on the test problems via data profiles
+//!    (`utils::run_benchmark`).
+//! - `simd`: Vectorized reductions (`math::simd`) for the L1 norm of `Vec<f32>`/`Vec<f64>` and
+//!    the dot products of L-BFGS-B.
+//! - `wasm`: Support for `wasm32-unknown-unknown`, which has neither an entropy source nor
+//!    `std::time::Instant`: `SimulatedAnnealing` uses a fixed seed unless one is provided via
+//!    `seed`, `TimedOp` only measures durations with a user-provided clock and
+//!    `ArgminParameter::{random_thread, modify_thread}` are not available. Without the `rayon`
+//!    feature, no threads are spawned.
+//! - `config`: Run specifications (solver, hyperparameters, termination criteria, checkpoints
+//!    and observers) read from TOML or JSON (`config::RunConfig`, `config::configure_executor`).
+//! - `replay`: Recording of seeded runs to a binary file and their exact replay
+//!    (`utils::record`, `utils::replay`), which reports the first iteration that differs.
+//! - `rayon`: Evaluate independent parameter vectors in parallel (for instance in
+//!    `ArgminBulkOp::bulk_apply`, which Nelder-Mead uses for its initial simplex and shrink
+//!    steps).
 //!
 //! # Defining a problem
 //!
@@ -274,19 +300,49 @@ extern crate rand;
 /// Definition of all relevant traits and types
 pub mod prelude;
 
+/// Math
+pub mod math;
+
+/// Operators
+pub mod operator;
+
+/// Random parameter vectors
+pub mod parameter;
+
+/// Constraints on parameter vectors
+pub mod constraint;
+
 /// Solvers
 pub mod solver;
 
+/// Proximal operators
+pub mod prox;
+
+/// Run specifications
+#[cfg(feature = "config")]
+pub mod config;
+
+/// Chaining of solvers
+pub mod pipeline;
+
+/// Step-by-step execution of solvers
+pub mod stepper;
+
+/// Classification of termination reasons
+pub mod termination;
+
+/// Utilities
+pub mod utils;
+
 /// Macros
 #[macro_use]
 mod macros;
 
+/// Counting of allocations in tests
+#[cfg(test)]
+mod allocations;
+
 use argmin_core::*;
 
 /// Testfunctions
-pub mod testfunctions {
-    //! # Testfunctions
-    //!
-    //! Reexport of `argmin-testfunctions`.
-    pub use argmin_testfunctions::*;
-}
+pub mod testfunctions;
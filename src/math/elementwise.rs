@@ -0,0 +1,339 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Element-wise operations
+
+/// Element-wise multiplication and division
+pub trait ArgminElementwise {
+    /// Element-wise product `self_i * other_i`
+    fn mul_elementwise(&self, other: &Self) -> Self;
+
+    /// Element-wise quotient `self_i / other_i`
+    fn div_elementwise(&self, other: &Self) -> Self;
+}
+
+/// Element-wise comparison
+pub trait ArgminElementwiseCmp {
+    /// Number of elements
+    fn num_elements(&self) -> usize;
+
+    /// Returns `true` if `self_i <= other_i` for all `i`. Comparisons involving `NaN` are `false`.
+    fn all_le(&self, other: &Self) -> bool;
+
+    /// Returns `true` if `self_i < other_i` for all `i`. Comparisons involving `NaN` are `false`.
+    fn all_lt(&self, other: &Self) -> bool;
+}
+
+/// Congruence transform `D * H * D` of a matrix `H` with a diagonal matrix `D`, where the diagonal
+/// of `D` is given by a vector of type `P`.
+pub trait ArgminDiagCongruence<P> {
+    /// Compute `D * self * D`, where `D = diag(d)`
+    fn diag_congruence(&self, d: &P) -> Self;
+}
+
+/// Nothing to transform if there is no Hessian
+impl<P> ArgminDiagCongruence<P> for () {
+    fn diag_congruence(&self, _d: &P) -> Self {}
+}
+
+macro_rules! make_elementwise {
+    ($t:ty) => {
+        impl ArgminElementwise for $t {
+            fn mul_elementwise(&self, other: &Self) -> Self {
+                self * other
+            }
+
+            fn div_elementwise(&self, other: &Self) -> Self {
+                self / other
+            }
+        }
+
+        impl ArgminElementwiseCmp for $t {
+            fn num_elements(&self) -> usize {
+                1
+            }
+
+            fn all_le(&self, other: &Self) -> bool {
+                self <= other
+            }
+
+            fn all_lt(&self, other: &Self) -> bool {
+                self < other
+            }
+        }
+
+        impl ArgminDiagCongruence<$t> for $t {
+            fn diag_congruence(&self, d: &$t) -> Self {
+                d * self * d
+            }
+        }
+
+        impl ArgminElementwise for Vec<$t> {
+            fn mul_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self.iter().zip(other.iter()).map(|(a, b)| a * b).collect()
+            }
+
+            fn div_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self.iter().zip(other.iter()).map(|(a, b)| a / b).collect()
+            }
+        }
+
+        impl ArgminElementwiseCmp for Vec<$t> {
+            fn num_elements(&self) -> usize {
+                self.len()
+            }
+
+            fn all_le(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+            }
+
+            fn all_lt(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a < b)
+            }
+        }
+
+        impl ArgminDiagCongruence<Vec<$t>> for Vec<Vec<$t>> {
+            fn diag_congruence(&self, d: &Vec<$t>) -> Self {
+                assert_eq!(self.len(), d.len());
+                self.iter()
+                    .zip(d.iter())
+                    .map(|(row, di)| {
+                        assert_eq!(row.len(), d.len());
+                        row.iter()
+                            .zip(d.iter())
+                            .map(|(h, dj)| di * h * dj)
+                            .collect()
+                    })
+                    .collect()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminElementwise for ndarray::Array1<$t> {
+            fn mul_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self * other
+            }
+
+            fn div_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self / other
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminElementwiseCmp for ndarray::Array1<$t> {
+            fn num_elements(&self) -> usize {
+                self.len()
+            }
+
+            fn all_le(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+            }
+
+            fn all_lt(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a < b)
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminDiagCongruence<ndarray::Array1<$t>> for ndarray::Array2<$t> {
+            fn diag_congruence(&self, d: &ndarray::Array1<$t>) -> Self {
+                assert_eq!(self.shape(), &[d.len(), d.len()]);
+                let mut out = self.clone();
+                for ((i, j), h) in out.indexed_iter_mut() {
+                    *h *= d[i] * d[j];
+                }
+                out
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminElementwise for nalgebra::DVector<$t> {
+            fn mul_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self.component_mul(other)
+            }
+
+            fn div_elementwise(&self, other: &Self) -> Self {
+                assert_eq!(self.len(), other.len());
+                self.component_div(other)
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminElementwiseCmp for nalgebra::DVector<$t> {
+            fn num_elements(&self) -> usize {
+                self.len()
+            }
+
+            fn all_le(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+            }
+
+            fn all_lt(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a < b)
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminDiagCongruence<nalgebra::DVector<$t>> for nalgebra::DMatrix<$t> {
+            fn diag_congruence(&self, d: &nalgebra::DVector<$t>) -> Self {
+                assert_eq!(self.shape(), (d.len(), d.len()));
+                let mut out = self.clone();
+                for j in 0..d.len() {
+                    for i in 0..d.len() {
+                        out[(i, j)] *= d[i] * d[j];
+                    }
+                }
+                out
+            }
+        }
+
+        impl<const N: usize> ArgminElementwise for [$t; N] {
+            fn mul_elementwise(&self, other: &Self) -> Self {
+                let mut out = *self;
+                out.iter_mut().zip(other.iter()).for_each(|(a, b)| *a *= b);
+                out
+            }
+
+            fn div_elementwise(&self, other: &Self) -> Self {
+                let mut out = *self;
+                out.iter_mut().zip(other.iter()).for_each(|(a, b)| *a /= b);
+                out
+            }
+        }
+
+        impl<const N: usize> ArgminElementwiseCmp for [$t; N] {
+            fn num_elements(&self) -> usize {
+                N
+            }
+
+            fn all_le(&self, other: &Self) -> bool {
+                self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+            }
+
+            fn all_lt(&self, other: &Self) -> bool {
+                self.iter().zip(other.iter()).all(|(a, b)| a < b)
+            }
+        }
+
+        impl<const N: usize> ArgminDiagCongruence<[$t; N]> for [[$t; N]; N] {
+            fn diag_congruence(&self, d: &[$t; N]) -> Self {
+                let mut out = *self;
+                for (row, di) in out.iter_mut().zip(d.iter()) {
+                    for (h, dj) in row.iter_mut().zip(d.iter()) {
+                        *h *= di * dj;
+                    }
+                }
+                out
+            }
+        }
+    };
+}
+
+make_elementwise!(f32);
+make_elementwise!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elementwise_vec() {
+        let a = vec![1.0f64, -2.0, 3.0];
+        let b = vec![2.0f64, 4.0, -0.5];
+        assert_eq!(a.mul_elementwise(&b), vec![2.0, -8.0, -1.5]);
+        assert_eq!(a.div_elementwise(&b), vec![0.5, -0.5, -6.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_elementwise_vec_length_mismatch() {
+        vec![1.0f64, 2.0].mul_elementwise(&vec![1.0]);
+    }
+
+    #[test]
+    fn test_elementwise_cmp_vec() {
+        let a = vec![1.0f64, 2.0];
+        let b = vec![1.0f64, 3.0];
+        assert!(a.all_le(&b));
+        assert!(!a.all_lt(&b));
+        assert!(!b.all_le(&a));
+        assert!(!a.all_le(&vec![1.0, 2.0, 3.0]));
+        assert!(!vec![std::f64::NAN, 0.0].all_le(&b));
+    }
+
+    #[test]
+    fn test_elementwise_scalar() {
+        assert!((2.0f64.mul_elementwise(&3.0) - 6.0).abs() < std::f64::EPSILON);
+        assert!((3.0f64.div_elementwise(&2.0) - 1.5).abs() < std::f64::EPSILON);
+        assert_eq!(1.0f64.num_elements(), 1);
+        assert!(1.0f64.all_le(&1.0));
+        assert!(!1.0f64.all_lt(&1.0));
+        assert!(!std::f64::NAN.all_le(&1.0));
+        assert!((3.0f64.diag_congruence(&2.0) - 12.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_elementwise_array() {
+        let a = [1.0f64, -2.0, 3.0];
+        let b = [2.0f64, 4.0, -0.5];
+        assert_eq!(a.mul_elementwise(&b), [2.0, -8.0, -1.5]);
+        assert_eq!(a.div_elementwise(&b), [0.5, -0.5, -6.0]);
+        assert_eq!(a.num_elements(), 3);
+        assert!(a.all_le(&[1.0, 0.0, 3.0]));
+        assert!(!a.all_lt(&[1.0, 0.0, 3.0]));
+        let h = [[1.0f64, 2.0], [3.0, 4.0]];
+        assert_eq!(h.diag_congruence(&[2.0, -1.0]), [[4.0, -4.0], [-6.0, 4.0]]);
+    }
+
+    #[test]
+    fn test_diag_congruence_vec() {
+        let h = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+        let d = vec![2.0f64, -1.0];
+        assert_eq!(
+            h.diag_congruence(&d),
+            vec![vec![4.0, -4.0], vec![-6.0, 4.0]]
+        );
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_diag_congruence_ndarray() {
+        use ndarray::array;
+        let h = array![[1.0f64, 2.0], [3.0, 4.0]];
+        let d = array![2.0f64, -1.0];
+        assert_eq!(h.diag_congruence(&d), array![[4.0, -4.0], [-6.0, 4.0]]);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_nalgebra() {
+        use nalgebra::{DMatrix, DVector};
+        let a = DVector::from_iterator(3, vec![1.0f64, -2.0, 3.0]);
+        let b = DVector::from_iterator(3, vec![2.0f64, 4.0, -0.5]);
+        assert_eq!(
+            a.mul_elementwise(&b),
+            DVector::from_iterator(3, vec![2.0, -8.0, -1.5])
+        );
+        assert_eq!(
+            a.div_elementwise(&b),
+            DVector::from_iterator(3, vec![0.5, -0.5, -6.0])
+        );
+        assert!(!a.all_le(&b));
+        assert!(a.all_lt(&DVector::from_iterator(3, vec![2.0, -1.0, 4.0])));
+        let h = DMatrix::from_row_slice(2, 2, &[1.0f64, 2.0, 3.0, 4.0]);
+        let d = DVector::from_iterator(2, vec![2.0f64, -1.0]);
+        assert_eq!(
+            h.diag_congruence(&d),
+            DMatrix::from_row_slice(2, 2, &[4.0, -4.0, -6.0, 4.0])
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Constant-filled values of matching shape
+//!
+//! `ArgminZero::zero_like` (from `argmin-core`) creates a zero of the same shape as a given
+//! parameter vector. `ArgminFull::full_like` generalizes this to arbitrary fill values, which is
+//! for instance needed to initialize step sizes, moment estimates or bounds from the first
+//! parameter vector a solver sees instead of guessing dimensions via `Default`.
+
+/// Create a value of the same shape as `self` with all elements set to `value`
+pub trait ArgminFull<T> {
+    /// Returns a value of the same shape as `self` with all elements set to `value`
+    fn full_like(&self, value: T) -> Self;
+}
+
+macro_rules! make_full {
+    ($t:ty) => {
+        impl ArgminFull<$t> for $t {
+            fn full_like(&self, value: $t) -> Self {
+                value
+            }
+        }
+
+        impl<const N: usize> ArgminFull<$t> for [$t; N] {
+            fn full_like(&self, value: $t) -> Self {
+                [value; N]
+            }
+        }
+
+        impl ArgminFull<$t> for Vec<$t> {
+            fn full_like(&self, value: $t) -> Self {
+                vec![value; self.len()]
+            }
+        }
+
+        impl ArgminFull<$t> for Vec<Vec<$t>> {
+            fn full_like(&self, value: $t) -> Self {
+                self.iter().map(|row| vec![value; row.len()]).collect()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl<D: ndarray::Dimension> ArgminFull<$t> for ndarray::Array<$t, D> {
+            fn full_like(&self, value: $t) -> Self {
+                ndarray::Array::from_elem(self.raw_dim(), value)
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminFull<$t> for nalgebra::DVector<$t> {
+            fn full_like(&self, value: $t) -> Self {
+                nalgebra::DVector::from_element(self.len(), value)
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminFull<$t> for nalgebra::DMatrix<$t> {
+            fn full_like(&self, value: $t) -> Self {
+                nalgebra::DMatrix::from_element(self.nrows(), self.ncols(), value)
+            }
+        }
+    };
+}
+
+make_full!(f32);
+make_full!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_like_scalar() {
+        assert!((1.0f64.full_like(3.0) - 3.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_full_like_array() {
+        assert_eq!([0.0f64; 7].full_like(2.0), [2.0; 7]);
+    }
+
+    #[test]
+    fn test_full_like_vec() {
+        let p = vec![1.0f64; 7];
+        assert_eq!(p.full_like(0.5), vec![0.5; 7]);
+        let m = vec![vec![1.0f32; 3], vec![1.0f32; 3]];
+        assert_eq!(m.full_like(-1.0), vec![vec![-1.0; 3], vec![-1.0; 3]]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_full_like_ndarray() {
+        use ndarray::{Array1, Array2};
+        let p = Array1::<f64>::zeros(7);
+        assert_eq!(p.full_like(0.5), Array1::from_elem(7, 0.5));
+        let m = Array2::<f64>::zeros((2, 3));
+        assert_eq!(m.full_like(1.0), Array2::from_elem((2, 3), 1.0));
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_full_like_nalgebra() {
+        use nalgebra::{DMatrix, DVector};
+        let p = DVector::<f64>::zeros(7);
+        assert_eq!(p.full_like(0.5), DVector::from_element(7, 0.5));
+        let m = DMatrix::<f64>::zeros(2, 3);
+        assert_eq!(m.full_like(1.0), DMatrix::from_element(2, 3, 1.0));
+    }
+}
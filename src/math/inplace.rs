@@ -0,0 +1,201 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # In-place operations
+//!
+//! In-place counterparts of `ArgminScaledAdd`, `ArgminScaledSub` and `ArgminMul` (with a scalar)
+//! which update `self` instead of allocating a new value. Element-wise, they perform exactly the
+//! same floating point operations as their allocating counterparts, therefore the results are
+//! bit-identical. Operands of different shapes cause a panic.
+
+/// In-place scaled addition, scaled subtraction and multiplication with a scalar
+pub trait ArgminInPlace<T> {
+    /// `self_i = self_i + factor * other_i` (axpy)
+    fn scaled_add_assign(&mut self, factor: &T, other: &Self);
+
+    /// `self_i = self_i - factor * other_i`
+    fn scaled_sub_assign(&mut self, factor: &T, other: &Self);
+
+    /// `self_i = self_i * factor`
+    fn mul_assign_scalar(&mut self, factor: &T);
+}
+
+macro_rules! make_inplace {
+    ($t:ty) => {
+        impl ArgminInPlace<$t> for $t {
+            fn scaled_add_assign(&mut self, factor: &$t, other: &Self) {
+                *self += factor * other;
+            }
+
+            fn scaled_sub_assign(&mut self, factor: &$t, other: &Self) {
+                *self -= factor * other;
+            }
+
+            fn mul_assign_scalar(&mut self, factor: &$t) {
+                *self *= factor;
+            }
+        }
+
+        impl ArgminInPlace<$t> for Vec<$t> {
+            fn scaled_add_assign(&mut self, factor: &$t, other: &Self) {
+                assert_eq!(self.len(), other.len());
+                self.iter_mut()
+                    .zip(other.iter())
+                    .for_each(|(a, b)| a.scaled_add_assign(factor, b));
+            }
+
+            fn scaled_sub_assign(&mut self, factor: &$t, other: &Self) {
+                assert_eq!(self.len(), other.len());
+                self.iter_mut()
+                    .zip(other.iter())
+                    .for_each(|(a, b)| a.scaled_sub_assign(factor, b));
+            }
+
+            fn mul_assign_scalar(&mut self, factor: &$t) {
+                self.iter_mut().for_each(|a| a.mul_assign_scalar(factor));
+            }
+        }
+
+        impl<const N: usize> ArgminInPlace<$t> for [$t; N] {
+            fn scaled_add_assign(&mut self, factor: &$t, other: &Self) {
+                self.iter_mut()
+                    .zip(other.iter())
+                    .for_each(|(a, b)| a.scaled_add_assign(factor, b));
+            }
+
+            fn scaled_sub_assign(&mut self, factor: &$t, other: &Self) {
+                self.iter_mut()
+                    .zip(other.iter())
+                    .for_each(|(a, b)| a.scaled_sub_assign(factor, b));
+            }
+
+            fn mul_assign_scalar(&mut self, factor: &$t) {
+                self.iter_mut().for_each(|a| a.mul_assign_scalar(factor));
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminInPlace<$t> for ndarray::Array1<$t> {
+            fn scaled_add_assign(&mut self, factor: &$t, other: &Self) {
+                assert_eq!(self.shape(), other.shape());
+                self.zip_mut_with(other, |a, b| a.scaled_add_assign(factor, b));
+            }
+
+            fn scaled_sub_assign(&mut self, factor: &$t, other: &Self) {
+                assert_eq!(self.shape(), other.shape());
+                self.zip_mut_with(other, |a, b| a.scaled_sub_assign(factor, b));
+            }
+
+            fn mul_assign_scalar(&mut self, factor: &$t) {
+                self.map_inplace(|a| a.mul_assign_scalar(factor));
+            }
+        }
+    };
+}
+
+make_inplace!(f32);
+make_inplace!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocations::allocations;
+    use crate::prelude::*;
+
+    fn data() -> (Vec<f64>, Vec<f64>) {
+        let a = (0..17).map(|i| (i as f64 * 0.37).sin() * 1e3).collect();
+        let b = (0..17).map(|i| (i as f64 * 1.13).cos() / 7.0).collect();
+        (a, b)
+    }
+
+    fn bits(x: &[f64]) -> Vec<u64> {
+        x.iter().map(|x| x.to_bits()).collect()
+    }
+
+    #[test]
+    fn test_inplace_bit_identical_vec() {
+        let (a, b) = data();
+        let factor = 0.1;
+
+        let mut x = a.clone();
+        x.scaled_add_assign(&factor, &b);
+        assert_eq!(bits(&x), bits(&a.scaled_add(&factor, &b)));
+
+        let mut x = a.clone();
+        x.scaled_sub_assign(&factor, &b);
+        assert_eq!(bits(&x), bits(&a.scaled_sub(&factor, &b)));
+
+        let mut x = a.clone();
+        x.mul_assign_scalar(&factor);
+        assert_eq!(bits(&x), bits(&a.mul(&factor)));
+    }
+
+    #[test]
+    fn test_inplace_f32() {
+        let mut x = vec![1.0f32, 2.0];
+        x.scaled_add_assign(&2.0, &vec![0.5, -1.0]);
+        x.mul_assign_scalar(&3.0);
+        x.scaled_sub_assign(&1.0, &vec![6.0, 0.0]);
+        assert!(x[0].abs() < std::f32::EPSILON);
+        assert!(x[1].abs() < std::f32::EPSILON);
+
+        let mut x = [1.0f32, 2.0];
+        x.scaled_sub_assign(&0.5, &[2.0, 4.0]);
+        assert!(x[0].abs() < std::f32::EPSILON);
+        assert!(x[1].abs() < std::f32::EPSILON);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_inplace_shape_mismatch() {
+        let mut x = vec![1.0f64, 2.0];
+        x.scaled_add_assign(&1.0, &vec![1.0]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_inplace_bit_identical_ndarray() {
+        let (a, b) = data();
+        let a = ndarray::Array1::from(a);
+        let b = ndarray::Array1::from(b);
+        let factor = 0.1;
+
+        let mut x = a.clone();
+        x.scaled_add_assign(&factor, &b);
+        assert_eq!(x, a.scaled_add(&factor, &b));
+
+        let mut x = a.clone();
+        x.scaled_sub_assign(&factor, &b);
+        assert_eq!(x, a.scaled_sub(&factor, &b));
+
+        let mut x = a.clone();
+        x.mul_assign_scalar(&factor);
+        assert_eq!(x, a.mul(&factor));
+    }
+
+    #[test]
+    fn test_inplace_allocations() {
+        let (mut x, b) = data();
+        assert_eq!(allocations(|| x.scaled_add_assign(&0.1, &b)).0.count, 0);
+        assert_eq!(allocations(|| x.scaled_sub_assign(&0.1, &b)).0.count, 0);
+        assert_eq!(allocations(|| x.mul_assign_scalar(&0.1)).0.count, 0);
+        // The allocating counterpart needs a new vector.
+        assert!(allocations(|| x = x.scaled_sub(&0.1, &b)).0.count > 0);
+
+        // A gradient descent loop on `f(x) = ||x||^2 / 2` whose gradient is written into a
+        // preallocated buffer does not allocate at all.
+        let mut grad = vec![0.0; x.len()];
+        let (allocated, _) = allocations(|| {
+            for _ in 0..100 {
+                grad.copy_from_slice(&x);
+                x.scaled_sub_assign(&0.5, &grad);
+            }
+        });
+        assert_eq!(allocated.count, 0);
+        assert!(x.iter().all(|x| x.abs() < 1e-20));
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Element-wise minimum, maximum and clamping
+//!
+//! `NaN`s are propagated: if either operand of an element-wise `min` or `max` is `NaN`, the
+//! corresponding element of the result is `NaN`. Consequently, clamping a `NaN` (or clamping with
+//! a `NaN` bound) yields `NaN`. Operands of different shapes cause a panic.
+
+/// Element-wise minimum and maximum
+pub trait ArgminMinMax: Sized {
+    /// Element-wise minimum `min(a_i, b_i)`
+    fn min(a: &Self, b: &Self) -> Self;
+
+    /// Element-wise maximum `max(a_i, b_i)`
+    fn max(a: &Self, b: &Self) -> Self;
+
+    /// Element-wise clamping `min(max(x_i, lower_i), upper_i)`
+    fn clamp(x: &Self, lower: &Self, upper: &Self) -> Self {
+        Self::min(&Self::max(x, lower), upper)
+    }
+}
+
+macro_rules! make_minmax {
+    ($t:ty) => {
+        impl ArgminMinMax for $t {
+            fn min(a: &Self, b: &Self) -> Self {
+                if a.is_nan() || b.is_nan() {
+                    std::f64::NAN as $t
+                } else {
+                    a.min(*b)
+                }
+            }
+
+            fn max(a: &Self, b: &Self) -> Self {
+                if a.is_nan() || b.is_nan() {
+                    std::f64::NAN as $t
+                } else {
+                    a.max(*b)
+                }
+            }
+        }
+
+        impl ArgminMinMax for Vec<$t> {
+            fn min(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.len(), b.len());
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| <$t as ArgminMinMax>::min(a, b))
+                    .collect()
+            }
+
+            fn max(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.len(), b.len());
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| <$t as ArgminMinMax>::max(a, b))
+                    .collect()
+            }
+        }
+
+        impl ArgminMinMax for Vec<Vec<$t>> {
+            fn min(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.len(), b.len());
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| <Vec<$t> as ArgminMinMax>::min(a, b))
+                    .collect()
+            }
+
+            fn max(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.len(), b.len());
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| <Vec<$t> as ArgminMinMax>::max(a, b))
+                    .collect()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl<D: ndarray::Dimension> ArgminMinMax for ndarray::Array<$t, D> {
+            fn min(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.shape(), b.shape());
+                let mut out = a.clone();
+                out.zip_mut_with(b, |a, b| *a = <$t as ArgminMinMax>::min(a, b));
+                out
+            }
+
+            fn max(a: &Self, b: &Self) -> Self {
+                assert_eq!(a.shape(), b.shape());
+                let mut out = a.clone();
+                out.zip_mut_with(b, |a, b| *a = <$t as ArgminMinMax>::max(a, b));
+                out
+            }
+        }
+
+        impl<const N: usize> ArgminMinMax for [$t; N] {
+            fn min(a: &Self, b: &Self) -> Self {
+                let mut out = *a;
+                out.iter_mut()
+                    .zip(b.iter())
+                    .for_each(|(a, b)| *a = <$t as ArgminMinMax>::min(a, b));
+                out
+            }
+
+            fn max(a: &Self, b: &Self) -> Self {
+                let mut out = *a;
+                out.iter_mut()
+                    .zip(b.iter())
+                    .for_each(|(a, b)| *a = <$t as ArgminMinMax>::max(a, b));
+                out
+            }
+        }
+    };
+}
+
+make_minmax!(f32);
+make_minmax!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minmax_vec() {
+        let a = vec![1.0f64, -2.0, 3.0];
+        let b = vec![0.0f64, 4.0, 3.0];
+        assert_eq!(ArgminMinMax::min(&a, &b), vec![0.0, -2.0, 3.0]);
+        assert_eq!(ArgminMinMax::max(&a, &b), vec![1.0, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn test_clamp_vec() {
+        let x = vec![-5.0f32, 0.5, 5.0];
+        let lower = vec![-1.0f32, -1.0, -1.0];
+        let upper = vec![1.0f32, 1.0, 1.0];
+        assert_eq!(
+            ArgminMinMax::clamp(&x, &lower, &upper),
+            vec![-1.0, 0.5, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_minmax_vec_vec() {
+        let a = vec![vec![1.0f64, -2.0], vec![3.0, 0.0]];
+        let b = vec![vec![0.0f64, 4.0], vec![-3.0, 0.0]];
+        assert_eq!(
+            ArgminMinMax::min(&a, &b),
+            vec![vec![0.0, -2.0], vec![-3.0, 0.0]]
+        );
+        assert_eq!(
+            ArgminMinMax::max(&a, &b),
+            vec![vec![1.0, 4.0], vec![3.0, 0.0]]
+        );
+    }
+
+    #[test]
+    fn test_clamp_array() {
+        let x = [-5.0f64, 0.5, 5.0];
+        let c = ArgminMinMax::clamp(&x, &[-1.0; 3], &[1.0; 3]);
+        assert_eq!(c, [-1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_minmax_nan() {
+        let a = vec![std::f64::NAN, 1.0];
+        let b = vec![0.0f64, std::f64::NAN];
+        let min = ArgminMinMax::min(&a, &b);
+        let max = ArgminMinMax::max(&a, &b);
+        assert!(min.iter().all(|x| x.is_nan()));
+        assert!(max.iter().all(|x| x.is_nan()));
+        let c = ArgminMinMax::clamp(&vec![std::f64::NAN], &vec![0.0], &vec![1.0]);
+        assert!(c[0].is_nan());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_minmax_length_mismatch() {
+        ArgminMinMax::min(&vec![1.0f64, 2.0], &vec![1.0f64]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_minmax_ndarray() {
+        use ndarray::array;
+        let a = array![1.0f64, -2.0, 3.0];
+        let b = array![0.0f64, 4.0, 3.0];
+        assert_eq!(ArgminMinMax::min(&a, &b), array![0.0, -2.0, 3.0]);
+        assert_eq!(ArgminMinMax::max(&a, &b), array![1.0, 4.0, 3.0]);
+        let m = array![[5.0f64, -5.0], [0.0, 2.0]];
+        let l = array![[-1.0f64, -1.0], [-1.0, -1.0]];
+        let u = array![[1.0f64, 1.0], [1.0, 1.0]];
+        assert_eq!(
+            ArgminMinMax::clamp(&m, &l, &u),
+            array![[1.0, -1.0], [0.0, 1.0]]
+        );
+        let c = ArgminMinMax::max(&array![std::f64::NAN], &array![1.0]);
+        assert!(c[0].is_nan());
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Math
+//!
+//! Additional math traits which complement the ones defined in `argmin-core`. They are implemented
+//! for `f32`, `f64`, fixed size arrays `[f32; N]`/`[f64; N]`, `Vec<f32>`, `Vec<f64>` and, if the
+//! `ndarray` (or `ndarrayl`) feature is enabled, for the corresponding `ndarray` types.
+
+/// Element-wise operations
+pub mod elementwise;
+/// Constant-filled values of matching shape
+pub mod full;
+/// In-place operations
+pub mod inplace;
+/// Element-wise minimum and maximum
+pub mod minmax;
+/// Negation
+pub mod neg;
+/// Norms
+pub mod norm;
+/// Compensated dot products and norms
+pub mod precise;
+/// Shapes and finiteness
+pub mod shape;
+/// Vectorized reductions on slices
+#[cfg(feature = "simd")]
+pub mod simd;
+/// Linear solves
+pub mod solve;
+/// Diagonal metrics
+pub mod weighted;
+
+pub use self::elementwise::*;
+pub use self::full::*;
+pub use self::inplace::*;
+pub use self::minmax::*;
+pub use self::neg::*;
+pub use self::norm::*;
+pub use self::precise::*;
+pub use self::shape::*;
+pub use self::solve::*;
+pub use self::weighted::*;
+
+/// The BFGS, DFP and SR1 updates are built from the outer product (`ArgminDot` of two vectors),
+/// matrix-matrix products, transposes and `eye_like` provided by `argmin-core`. These tests pin
+/// down the behavior of those operations on small non-symmetric matrices.
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn assert_mat_eq(a: &[Vec<f64>], b: &[Vec<f64>]) {
+        assert_eq!(a.len(), b.len());
+        for (ra, rb) in a.iter().zip(b.iter()) {
+            assert_eq!(ra.len(), rb.len());
+            for (x, y) in ra.iter().zip(rb.iter()) {
+                assert!((x - y).abs() < std::f64::EPSILON, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_outer_vec() {
+        let a = vec![1.0f64, 2.0];
+        let b = vec![3.0f64, -1.0];
+        let res: Vec<Vec<f64>> = a.dot(&b);
+        assert_mat_eq(&res, &[vec![3.0, -1.0], vec![6.0, -2.0]]);
+        let a = vec![1.0f64, 2.0, 3.0];
+        let b = vec![-1.0f64, 0.0, 2.0];
+        let res: Vec<Vec<f64>> = a.dot(&b);
+        assert_mat_eq(
+            &res,
+            &[
+                vec![-1.0, 0.0, 2.0],
+                vec![-2.0, 0.0, 4.0],
+                vec![-3.0, 0.0, 6.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_matmul_vec() {
+        let a = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+        let b = vec![vec![0.0f64, 1.0], vec![-1.0, 2.0]];
+        let res: Vec<Vec<f64>> = a.dot(&b);
+        assert_mat_eq(&res, &[vec![-2.0, 5.0], vec![-4.0, 11.0]]);
+        let a = vec![
+            vec![1.0f64, 0.0, 2.0],
+            vec![0.0, 3.0, -1.0],
+            vec![4.0, 1.0, 0.0],
+        ];
+        let b = vec![
+            vec![2.0f64, 1.0, 0.0],
+            vec![0.0, -1.0, 1.0],
+            vec![1.0, 0.0, 3.0],
+        ];
+        let res: Vec<Vec<f64>> = a.dot(&b);
+        assert_mat_eq(
+            &res,
+            &[
+                vec![4.0, 1.0, 6.0],
+                vec![-1.0, -3.0, 0.0],
+                vec![8.0, 3.0, 1.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_matvec_vec() {
+        let a = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+        let x = vec![1.0f64, -1.0];
+        let res: Vec<f64> = a.dot(&x);
+        assert!((res[0] + 1.0).abs() < std::f64::EPSILON);
+        assert!((res[1] + 1.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_transpose_vec() {
+        let a = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+        assert_mat_eq(&a.t(), &[vec![1.0, 3.0], vec![2.0, 4.0]]);
+        let a = vec![
+            vec![1.0f64, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+        assert_mat_eq(
+            &a.t(),
+            &[
+                vec![1.0, 4.0, 7.0],
+                vec![2.0, 5.0, 8.0],
+                vec![3.0, 6.0, 9.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_eye_like_vec() {
+        let a = vec![
+            vec![5.0f64, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            vec![7.0, 8.0, 9.0],
+        ];
+        assert_mat_eq(
+            &a.eye_like(),
+            &[
+                vec![1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0],
+            ],
+        );
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_matrix_ops_ndarray() {
+        use ndarray::{array, Array2};
+        let a = array![1.0f64, 2.0, 3.0];
+        let b = array![-1.0f64, 0.0, 2.0];
+        let outer: Array2<f64> = ArgminDot::dot(&a, &b);
+        assert_eq!(
+            outer,
+            array![[-1.0, 0.0, 2.0], [-2.0, 0.0, 4.0], [-3.0, 0.0, 6.0]]
+        );
+        let m = array![[1.0f64, 0.0, 2.0], [0.0, 3.0, -1.0], [4.0, 1.0, 0.0]];
+        let n = array![[2.0f64, 1.0, 0.0], [0.0, -1.0, 1.0], [1.0, 0.0, 3.0]];
+        let prod: Array2<f64> = ArgminDot::dot(&m, &n);
+        assert_eq!(
+            prod,
+            array![[4.0, 1.0, 6.0], [-1.0, -3.0, 0.0], [8.0, 3.0, 1.0]]
+        );
+        assert_eq!(
+            ArgminTranspose::t(m.clone()),
+            array![[1.0, 0.0, 4.0], [0.0, 3.0, 1.0], [2.0, -1.0, 0.0]]
+        );
+        assert_eq!(m.eye_like(), Array2::eye(3));
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Negation
+//!
+//! `ArgminNeg` flips the sign of every element. It is implemented for parameter vectors as well as
+//! for Hessians (including `()` for operators without Hessian), which makes it possible to negate
+//! cost function values, gradients and Hessians alike.
+
+/// Element-wise negation
+pub trait ArgminNeg {
+    /// Returns `-self`
+    fn neg(&self) -> Self;
+}
+
+impl ArgminNeg for () {
+    fn neg(&self) -> Self {}
+}
+
+macro_rules! make_neg {
+    ($t:ty) => {
+        impl ArgminNeg for $t {
+            fn neg(&self) -> Self {
+                -*self
+            }
+        }
+
+        impl<const N: usize> ArgminNeg for [$t; N] {
+            fn neg(&self) -> Self {
+                let mut out = *self;
+                out.iter_mut().for_each(|x| *x = -*x);
+                out
+            }
+        }
+
+        impl ArgminNeg for Vec<$t> {
+            fn neg(&self) -> Self {
+                self.iter().map(|x| -x).collect()
+            }
+        }
+
+        impl ArgminNeg for Vec<Vec<$t>> {
+            fn neg(&self) -> Self {
+                self.iter().map(|row| row.neg()).collect()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl<D: ndarray::Dimension> ArgminNeg for ndarray::Array<$t, D> {
+            fn neg(&self) -> Self {
+                self.mapv(|x| -x)
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminNeg for nalgebra::DVector<$t> {
+            fn neg(&self) -> Self {
+                -self
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminNeg for nalgebra::DMatrix<$t> {
+            fn neg(&self) -> Self {
+                -self
+            }
+        }
+    };
+}
+
+make_neg!(f32);
+make_neg!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neg() {
+        assert!((ArgminNeg::neg(&2.0f64) + 2.0).abs() < std::f64::EPSILON);
+        assert_eq!(ArgminNeg::neg(&[1.0f32, -2.0]), [-1.0, 2.0]);
+        assert_eq!(
+            ArgminNeg::neg(&vec![1.0f64, 0.0, -3.0]),
+            vec![-1.0, 0.0, 3.0]
+        );
+        assert_eq!(
+            ArgminNeg::neg(&vec![vec![1.0f64, 2.0], vec![-3.0, 4.0]]),
+            vec![vec![-1.0, -2.0], vec![3.0, -4.0]]
+        );
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_neg_ndarray() {
+        let m = ndarray::arr2(&[[1.0f64, -2.0], [0.5, 0.0]]);
+        assert_eq!(
+            ArgminNeg::neg(&m),
+            ndarray::arr2(&[[-1.0, 2.0], [-0.5, 0.0]])
+        );
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_neg_nalgebra() {
+        let p = nalgebra::DVector::from_vec(vec![1.0f64, -2.0]);
+        assert_eq!(
+            ArgminNeg::neg(&p),
+            nalgebra::DVector::from_vec(vec![-1.0, 2.0])
+        );
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Norms
+//!
+//! `ArgminNorm` (from `argmin-core`) computes the Euclidean norm. `ArgminLpNorm` adds the L1 and
+//! the L-infinity norm.
+//!
+//! The norms of an empty vector are `0`. If any component is `NaN`, all norms are `NaN`.
+//!
+//! `norm_of` computes the Euclidean norm with compensated summation (`ArgminPreciseNorm`) because
+//! it is used in termination checks, where small gradients would otherwise lose digits.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Choice of vector norm
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Norm {
+    /// `sum_i |x_i|`
+    L1,
+    /// `sqrt(sum_i x_i^2)`
+    L2,
+    /// `max_i |x_i|`
+    Inf,
+}
+
+impl Default for Norm {
+    fn default() -> Self {
+        Norm::L2
+    }
+}
+
+/// L1 and L-infinity norm
+pub trait ArgminLpNorm<U> {
+    /// `sum_i |x_i|`
+    fn norm_l1(&self) -> U;
+
+    /// `max_i |x_i|`
+    fn norm_inf(&self) -> U;
+}
+
+/// Compute the norm of `p` chosen by `norm`
+pub fn norm_of<P, U>(p: &P, norm: Norm) -> U
+where
+    P: ArgminPreciseNorm<U> + ArgminLpNorm<U>,
+{
+    match norm {
+        Norm::L1 => p.norm_l1(),
+        Norm::L2 => p.norm_precise(),
+        Norm::Inf => p.norm_inf(),
+    }
+}
+
+macro_rules! make_lpnorm {
+    ($t:ty) => {
+        impl ArgminLpNorm<$t> for $t {
+            fn norm_l1(&self) -> $t {
+                self.abs()
+            }
+
+            fn norm_inf(&self) -> $t {
+                self.abs()
+            }
+        }
+
+        impl ArgminLpNorm<$t> for Vec<$t> {
+            #[cfg(not(feature = "simd"))]
+            fn norm_l1(&self) -> $t {
+                self.iter().map(|x| x.abs()).sum()
+            }
+
+            #[cfg(feature = "simd")]
+            fn norm_l1(&self) -> $t {
+                crate::math::simd::norm_l1(self)
+            }
+
+            fn norm_inf(&self) -> $t {
+                self.iter().fold(0.0, |acc: $t, x| {
+                    if acc.is_nan() || x.is_nan() {
+                        std::$t::NAN
+                    } else {
+                        acc.max(x.abs())
+                    }
+                })
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminLpNorm<$t> for ndarray::Array1<$t> {
+            fn norm_l1(&self) -> $t {
+                self.iter().map(|x| x.abs()).sum()
+            }
+
+            fn norm_inf(&self) -> $t {
+                self.iter().fold(0.0, |acc: $t, x| {
+                    if acc.is_nan() || x.is_nan() {
+                        std::$t::NAN
+                    } else {
+                        acc.max(x.abs())
+                    }
+                })
+            }
+        }
+
+        impl<const N: usize> ArgminLpNorm<$t> for [$t; N] {
+            fn norm_l1(&self) -> $t {
+                self.iter().map(|x| x.abs()).sum()
+            }
+
+            fn norm_inf(&self) -> $t {
+                self.iter().fold(0.0, |acc: $t, x| {
+                    if acc.is_nan() || x.is_nan() {
+                        std::$t::NAN
+                    } else {
+                        acc.max(x.abs())
+                    }
+                })
+            }
+        }
+    };
+}
+
+make_lpnorm!(f32);
+make_lpnorm!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_norms_vec() {
+        let a = vec![3.0f64, -4.0, 0.0, -1.0];
+        assert!((a.norm_l1() - 8.0).abs() < std::f64::EPSILON);
+        assert!((a.norm_inf() - 4.0).abs() < std::f64::EPSILON);
+        assert!((norm_of(&a, Norm::L2) - 26.0f64.sqrt()).abs() < std::f64::EPSILON);
+        assert!((norm_of(&a, Norm::L1) - 8.0).abs() < std::f64::EPSILON);
+        assert!((norm_of(&a, Norm::Inf) - 4.0).abs() < std::f64::EPSILON);
+
+        let b = vec![-0.5f32, 0.25];
+        assert!((b.norm_l1() - 0.75).abs() < std::f32::EPSILON);
+        assert!((b.norm_inf() - 0.5).abs() < std::f32::EPSILON);
+    }
+
+    #[test]
+    fn test_norms_scalar() {
+        assert!(((-3.0f64).norm_l1() - 3.0).abs() < std::f64::EPSILON);
+        assert!(((-3.0f64).norm_inf() - 3.0).abs() < std::f64::EPSILON);
+        assert!(std::f64::NAN.norm_inf().is_nan());
+    }
+
+    #[test]
+    fn test_norms_array() {
+        let a = [3.0f64, -4.0, 0.0, -1.0];
+        assert!((a.norm_l1() - 8.0).abs() < std::f64::EPSILON);
+        assert!((a.norm_inf() - 4.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_norms_empty() {
+        let a: Vec<f64> = vec![];
+        assert!(a.norm_l1().abs() < std::f64::EPSILON);
+        assert!(a.norm_inf().abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_norms_nan() {
+        let a = vec![1.0f64, std::f64::NAN, -5.0];
+        assert!(a.norm_l1().is_nan());
+        assert!(a.norm_inf().is_nan());
+        let a = vec![std::f64::NAN, 1.0f64];
+        assert!(a.norm_inf().is_nan());
+    }
+
+    #[test]
+    fn test_norm_ordering() {
+        let mut rng = XorShiftRng::seed_from_u64(42);
+        for n in 1..50 {
+            let a: Vec<f64> = (0..n).map(|_| rng.gen_range(-10.0, 10.0)).collect();
+            let (l1, l2, inf) = (a.norm_l1(), a.norm(), a.norm_inf());
+            assert!(inf <= l2 * (1.0 + 1e-12));
+            assert!(l2 <= l1 * (1.0 + 1e-12));
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_norms_ndarray() {
+        use ndarray::array;
+        let a = array![3.0f64, -4.0, 0.0, -1.0];
+        assert!((a.norm_l1() - 8.0).abs() < std::f64::EPSILON);
+        assert!((a.norm_inf() - 4.0).abs() < std::f64::EPSILON);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Compensated dot products and norms
+//!
+//! Naive summation in `ArgminDot` and `ArgminNorm` loses digits for long vectors or when terms
+//! cancel. `ArgminPreciseDot` and `ArgminPreciseNorm` compute the same quantities with the `Dot2`
+//! algorithm [0]: products are split into their rounded value and rounding error with a fused
+//! multiply-add and all terms are accumulated with compensated (Kahan-Babuška) summation. The
+//! result is as accurate as if it had been computed in twice the working precision.
+//!
+//! These are used where accuracy matters most: the gradient norm termination of the quasi-Newton
+//! methods and the Wolfe conditions.
+//!
+//! # References:
+//!
+//! [0] Takeshi Ogita, Siegfried M. Rump, and Shin'ichi Oishi (2005). Accurate Sum and Dot
+//! Product. SIAM Journal on Scientific Computing 26(6), 1955-1988.
+
+use crate::prelude::*;
+
+/// Dot product with compensated summation
+pub trait ArgminPreciseDot<T, U> {
+    /// Dot product of `self` and `other`
+    fn dot_precise(&self, other: &T) -> U;
+}
+
+/// Euclidean norm with compensated summation
+pub trait ArgminPreciseNorm<U> {
+    /// `sqrt(sum_i x_i^2)`
+    fn norm_precise(&self) -> U;
+}
+
+macro_rules! make_precise {
+    ($t:ty, $dot2:ident) => {
+        /// `Dot2` of two equally long sequences
+        fn $dot2<'a, I>(pairs: I) -> $t
+        where
+            I: Iterator<Item = (&'a $t, &'a $t)>,
+        {
+            let mut s: $t = 0.0;
+            let mut c: $t = 0.0;
+            for (a, b) in pairs {
+                let p = a * b;
+                // exact rounding error of the product
+                let ep = a.mul_add(*b, -p);
+                let t = s + p;
+                // exact rounding error of the sum
+                let es = if s.abs() >= p.abs() {
+                    (s - t) + p
+                } else {
+                    (p - t) + s
+                };
+                s = t;
+                c += es + ep;
+            }
+            s + c
+        }
+
+        impl ArgminPreciseDot<$t, $t> for $t {
+            fn dot_precise(&self, other: &$t) -> $t {
+                self * other
+            }
+        }
+
+        impl ArgminPreciseNorm<$t> for $t {
+            fn norm_precise(&self) -> $t {
+                self.abs()
+            }
+        }
+
+        impl ArgminPreciseDot<Vec<$t>, $t> for Vec<$t> {
+            fn dot_precise(&self, other: &Vec<$t>) -> $t {
+                assert_eq!(self.len(), other.len());
+                $dot2(self.iter().zip(other.iter()))
+            }
+        }
+
+        impl ArgminPreciseNorm<$t> for Vec<$t> {
+            fn norm_precise(&self) -> $t {
+                self.dot_precise(self).sqrt()
+            }
+        }
+
+        impl<const N: usize> ArgminPreciseDot<[$t; N], $t> for [$t; N] {
+            fn dot_precise(&self, other: &[$t; N]) -> $t {
+                $dot2(self.iter().zip(other.iter()))
+            }
+        }
+
+        impl<const N: usize> ArgminPreciseNorm<$t> for [$t; N] {
+            fn norm_precise(&self) -> $t {
+                self.dot_precise(self).sqrt()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminPreciseDot<ndarray::Array1<$t>, $t> for ndarray::Array1<$t> {
+            fn dot_precise(&self, other: &ndarray::Array1<$t>) -> $t {
+                assert_eq!(self.len(), other.len());
+                $dot2(self.iter().zip(other.iter()))
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminPreciseNorm<$t> for ndarray::Array1<$t> {
+            fn norm_precise(&self) -> $t {
+                self.dot_precise(self).sqrt()
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminPreciseDot<nalgebra::DVector<$t>, $t> for nalgebra::DVector<$t> {
+            fn dot_precise(&self, other: &nalgebra::DVector<$t>) -> $t {
+                assert_eq!(self.len(), other.len());
+                $dot2(self.iter().zip(other.iter()))
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminPreciseNorm<$t> for nalgebra::DVector<$t> {
+            fn norm_precise(&self) -> $t {
+                self.dot_precise(self).sqrt()
+            }
+        }
+    };
+}
+
+make_precise!(f32, dot2_f32);
+make_precise!(f64, dot2_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_precise_cancellation() {
+        // 1e16 + 1 rounds to 1e16, therefore the naive sum loses the ones
+        let n = 1000;
+        let mut a = vec![];
+        for _ in 0..n {
+            a.push(1e16);
+            a.push(1.0);
+            a.push(-1e16);
+        }
+        let b = vec![1.0f64; 3 * n];
+        let exact = n as f64;
+        let naive = a.dot(&b);
+        assert!((naive - exact).abs() > 0.5 * exact);
+        assert!((a.dot_precise(&b) - exact).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_dot_precise_product_error() {
+        // (1 + 2^-27) * (1 - 2^-27) = 1 - 2^-54, which rounds to 1
+        let e = 2.0f64.powi(-27);
+        let a = vec![1.0 + e, -1.0];
+        let b = vec![1.0 - e, 1.0];
+        let exact = -2.0f64.powi(-54);
+        assert!(a.dot(&b).abs() < std::f64::MIN_POSITIVE);
+        assert!((a.dot_precise(&b) - exact).abs() < std::f64::MIN_POSITIVE);
+    }
+
+    #[test]
+    fn test_dot_precise_long_vector() {
+        let n = 1_000_000;
+        let a = vec![0.1f64; n];
+        let b = vec![1.0f64; n];
+        // single rounding of the exact result of n * fl(0.1)
+        let reference = 0.1f64 * n as f64;
+        assert!((a.dot(&b) - reference).abs() > 1e-10);
+        assert!((a.dot_precise(&b) - reference).abs() < 1e-11);
+        assert!((a.norm_precise() - (0.01f64 * n as f64).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_precise_scalar_array() {
+        assert!(((-3.0f64).norm_precise() - 3.0).abs() < std::f64::EPSILON);
+        assert!((2.0f32.dot_precise(&3.0) - 6.0).abs() < std::f32::EPSILON);
+        let a = [3.0f64, 4.0];
+        assert!((a.norm_precise() - 5.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_precise_empty() {
+        let a: Vec<f64> = vec![];
+        assert!(a.norm_precise().abs() < std::f64::EPSILON);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_dot_precise_ndarray() {
+        use ndarray::Array1;
+        let n = 1000;
+        let a = Array1::from_iter((0..3 * n).map(|i| match i % 3 {
+            0 => 1e16,
+            1 => 1.0,
+            _ => -1e16,
+        }));
+        let b = Array1::from_elem(3 * n, 1.0f64);
+        assert!((a.dot_precise(&b) - n as f64).abs() < std::f64::EPSILON);
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Shapes
+//!
+//! `ArgminShape` reports the lengths of a value along each of its axes and whether all of its
+//! elements are finite. This is used to validate the outputs of operators before solvers operate
+//! on them.
+
+/// Shape and finiteness of a value
+pub trait ArgminShape {
+    /// Lengths along each axis (empty for scalars). `None` if the shape is not well defined, for
+    /// instance for nested vectors whose rows have different lengths.
+    fn shape(&self) -> Option<Vec<usize>>;
+
+    /// `true` if no element is `NaN` or infinite
+    fn all_finite(&self) -> bool;
+}
+
+impl ArgminShape for () {
+    fn shape(&self) -> Option<Vec<usize>> {
+        Some(vec![])
+    }
+
+    fn all_finite(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! make_shape {
+    ($t:ty) => {
+        impl ArgminShape for $t {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.is_finite()
+            }
+        }
+
+        impl<const N: usize> ArgminShape for [$t; N] {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![N])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|x| x.is_finite())
+            }
+        }
+
+        impl<const N: usize, const M: usize> ArgminShape for [[$t; N]; M] {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![M, N])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|row| row.all_finite())
+            }
+        }
+
+        impl ArgminShape for Vec<$t> {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![self.len()])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|x| x.is_finite())
+            }
+        }
+
+        impl ArgminShape for Vec<Vec<$t>> {
+            fn shape(&self) -> Option<Vec<usize>> {
+                let cols = self.first().map(|row| row.len()).unwrap_or(0);
+                if self.iter().all(|row| row.len() == cols) {
+                    Some(vec![self.len(), cols])
+                } else {
+                    None
+                }
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|row| row.all_finite())
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl<D: ndarray::Dimension> ArgminShape for ndarray::Array<$t, D> {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(ndarray::ArrayBase::shape(self).to_vec())
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|x| x.is_finite())
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminShape for nalgebra::DVector<$t> {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![self.len()])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|x| x.is_finite())
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminShape for nalgebra::DMatrix<$t> {
+            fn shape(&self) -> Option<Vec<usize>> {
+                Some(vec![self.nrows(), self.ncols()])
+            }
+
+            fn all_finite(&self) -> bool {
+                self.iter().all(|x| x.is_finite())
+            }
+        }
+    };
+}
+
+make_shape!(f32);
+make_shape!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape() {
+        assert_eq!(1.0f64.shape(), Some(vec![]));
+        assert_eq!([1.0f32; 3].shape(), Some(vec![3]));
+        assert_eq!([[1.0f64; 3]; 2].shape(), Some(vec![2, 3]));
+        assert_eq!(vec![1.0f64; 4].shape(), Some(vec![4]));
+        assert_eq!(vec![vec![1.0f64; 3]; 2].shape(), Some(vec![2, 3]));
+        assert_eq!(Vec::<Vec<f64>>::new().shape(), Some(vec![0, 0]));
+        assert_eq!(vec![vec![1.0f64; 3], vec![1.0]].shape(), None);
+    }
+
+    #[test]
+    fn test_all_finite() {
+        assert!(vec![1.0f64, -2.0].all_finite());
+        assert!(!vec![1.0f64, std::f64::NAN].all_finite());
+        assert!(!vec![vec![1.0f32], vec![std::f32::INFINITY]].all_finite());
+        assert!(!std::f64::NEG_INFINITY.all_finite());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_shape_ndarray() {
+        let m = ndarray::Array2::<f64>::zeros((2, 3));
+        assert_eq!(ArgminShape::shape(&m), Some(vec![2, 3]));
+        assert!(m.all_finite());
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_shape_nalgebra() {
+        let m = nalgebra::DMatrix::<f64>::zeros(2, 3);
+        assert_eq!(ArgminShape::shape(&m), Some(vec![2, 3]));
+        let p = nalgebra::DVector::<f64>::zeros(4);
+        assert_eq!(ArgminShape::shape(&p), Some(vec![4]));
+    }
+}
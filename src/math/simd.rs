@@ -0,0 +1,121 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Vectorized reductions on slices (`simd` feature)
+//!
+//! A scalar reduction `sum_i x_i` cannot be vectorized by the compiler because floating point
+//! addition is not associative. The kernels in this module accumulate into four independent
+//! partial sums, which the compiler maps onto SIMD lanes, and add the remaining elements with the
+//! scalar loop. The results therefore differ from the scalar loop by rounding (at most
+//! `~n * EPSILON * sum_i |x_i|`), but not more.
+//!
+//! Element-wise operations such as `scaled_add_assign` are vectorized by the compiler as they are
+//! and need no special treatment.
+//!
+//! With the `simd` feature, `ArgminLpNorm::norm_l1` of `Vec<f32>`/`Vec<f64>` and the dot products
+//! of the L-BFGS-B solver use these kernels. `ArgminDot` and `ArgminNorm` of `Vec<f32>`/`Vec<f64>`
+//! are implemented in `argmin-core` and are not affected.
+
+use num::Float;
+
+/// Sum of `f(a_i, b_i)` with four partial sums
+fn reduce2<T, F>(a: &[T], b: &[T], f: F) -> T
+where
+    T: Float,
+    F: Fn(T, T) -> T,
+{
+    assert_eq!(a.len(), b.len());
+    let mut acc = [T::zero(); 4];
+    let chunks_a = a.chunks_exact(4);
+    let chunks_b = b.chunks_exact(4);
+    let (rem_a, rem_b) = (chunks_a.remainder(), chunks_b.remainder());
+    for (x, y) in chunks_a.zip(chunks_b) {
+        acc[0] = acc[0] + f(x[0], y[0]);
+        acc[1] = acc[1] + f(x[1], y[1]);
+        acc[2] = acc[2] + f(x[2], y[2]);
+        acc[3] = acc[3] + f(x[3], y[3]);
+    }
+    let mut sum = (acc[0] + acc[1]) + (acc[2] + acc[3]);
+    for (x, y) in rem_a.iter().zip(rem_b.iter()) {
+        sum = sum + f(*x, *y);
+    }
+    sum
+}
+
+/// Dot product `sum_i a_i * b_i`
+pub fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    reduce2(a, b, |x, y| x * y)
+}
+
+/// Euclidean norm `sqrt(sum_i a_i^2)`
+pub fn norm<T: Float>(a: &[T]) -> T {
+    dot(a, a).sqrt()
+}
+
+/// L1 norm `sum_i |a_i|`
+pub fn norm_l1<T: Float>(a: &[T]) -> T {
+    reduce2(a, a, |x, _| x.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    fn random_vec(rng: &mut XorShiftRng, n: usize) -> Vec<f64> {
+        (0..n).map(|_| rng.gen_range(-1e3, 1e3)).collect()
+    }
+
+    /// Rounding error bound of a sum of `n` terms with magnitudes summing up to `abs_sum`
+    fn bound(n: usize, abs_sum: f64) -> f64 {
+        2.0 * (n + 1) as f64 * std::f64::EPSILON * abs_sum
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let mut rng = XorShiftRng::seed_from_u64(17);
+        for n in (0..40).chain(vec![999, 1000, 1001, 10_000]) {
+            let a = random_vec(&mut rng, n);
+            let b = random_vec(&mut rng, n);
+
+            let scalar: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+            let abs_sum: f64 = a.iter().zip(b.iter()).map(|(x, y)| (x * y).abs()).sum();
+            assert!((dot(&a, &b) - scalar).abs() <= bound(n, abs_sum));
+
+            let scalar: f64 = a.iter().map(|x| x.abs()).sum();
+            assert!((norm_l1(&a) - scalar).abs() <= bound(n, scalar));
+
+            let scalar: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+            assert!((norm(&a) - scalar).abs() <= bound(n, scalar));
+        }
+    }
+
+    #[test]
+    fn test_simd_exact_on_integers() {
+        // sums of small integers are exact regardless of the order of summation
+        let a: Vec<f32> = (0..103).map(|i| (i % 7) as f32).collect();
+        let b: Vec<f32> = (0..103).map(|i| (i % 5) as f32 - 2.0).collect();
+        let scalar: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+        assert_eq!(dot(&a, &b).to_bits(), scalar.to_bits());
+        assert!(dot::<f64>(&[], &[]).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_simd_nan() {
+        let mut a = vec![1.0f64; 10];
+        a[9] = std::f64::NAN;
+        assert!(dot(&a, &a).is_nan());
+        assert!(norm_l1(&a).is_nan());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_simd_length_mismatch() {
+        dot(&[1.0f64, 2.0], &[1.0]);
+    }
+}
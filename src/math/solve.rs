@@ -0,0 +1,455 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Linear solves
+//!
+//! Solving `A x = b` directly is cheaper and numerically more accurate than computing `A^-1` and
+//! multiplying it with `b`. For symmetric matrices a Cholesky factorization is tried first; if it
+//! fails (the matrix is not positive definite) or the matrix is not symmetric, an LU factorization
+//! with partial pivoting is used.
+//!
+//! `Vec<Vec<f64>>` and `[[f64; N]; N]` use a pure Rust implementation. `ndarray::Array2<f64>` uses
+//! `ndarray-linalg` if the `ndarrayl` feature is enabled and falls back to the pure Rust
+//! implementation if only the `ndarray` feature is enabled.
+
+use crate::prelude::*;
+
+/// Solve the linear system `self * x = b` for `x`
+pub trait ArgminSolve<P> {
+    /// Returns `x` such that `self * x = b`
+    fn solve(&self, b: &P) -> Result<P, Error>;
+}
+
+/// Adds a multiple of the identity to a square matrix, for instance to regularize `H + tau * I`
+/// before solving a Newton system with a singular Hessian `H`.
+pub trait ArgminAddDiagonal {
+    /// Returns `self + tau * I`
+    fn add_diagonal(&self, tau: f64) -> Self;
+}
+
+fn singular(text: String) -> Error {
+    ArgminError::ConditionViolated { text }.into()
+}
+
+/// Returns `true` if the square matrix `a` is symmetric (up to rounding errors)
+fn is_symmetric(a: &[Vec<f64>]) -> bool {
+    let n = a.len();
+    (0..n).all(|i| {
+        (0..i).all(|j| (a[i][j] - a[j][i]).abs() <= 1e-12 * (a[i][j].abs() + a[j][i].abs()))
+    })
+}
+
+/// Cholesky factorization `A = L L^T` followed by forward and back substitution. Returns `None` if
+/// `a` is not (numerically) positive definite.
+pub(crate) fn cholesky_solve(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    let n = a.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for (i, ai) in a.iter().enumerate() {
+        for j in 0..=i {
+            let s: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let d = ai[i] - s;
+                if d.is_nan() || d <= 0.0 {
+                    return None;
+                }
+                l[i][i] = d.sqrt();
+            } else {
+                l[i][j] = (ai[j] - s) / l[j][j];
+            }
+        }
+    }
+    let mut y = vec![0.0; n];
+    for (i, bi) in b.iter().enumerate() {
+        let s: f64 = (0..i).map(|k| l[i][k] * y[k]).sum();
+        y[i] = (bi - s) / l[i][i];
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let s: f64 = (i + 1..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (y[i] - s) / l[i][i];
+    }
+    Some(x)
+}
+
+/// LU factorization with partial pivoting followed by forward and back substitution. Returns
+/// `None` if `a` is (numerically) singular.
+pub(crate) fn lu_solve(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    let n = a.len();
+    let mut lu: Vec<Vec<f64>> = a.to_vec();
+    let mut x: Vec<f64> = b.to_vec();
+    let scale = a
+        .iter()
+        .flat_map(|row| row.iter())
+        .fold(0.0f64, |acc, v| acc.max(v.abs()));
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| {
+            lu[i][k]
+                .abs()
+                .partial_cmp(&lu[j][k].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        let p = lu[pivot][k].abs();
+        if p.is_nan() || p <= std::f64::EPSILON * scale * n as f64 {
+            return None;
+        }
+        lu.swap(k, pivot);
+        x.swap(k, pivot);
+        for i in k + 1..n {
+            let f = lu[i][k] / lu[k][k];
+            for j in k..n {
+                lu[i][j] -= f * lu[k][j];
+            }
+            x[i] -= f * x[k];
+        }
+    }
+    for i in (0..n).rev() {
+        let s: f64 = (i + 1..n).map(|k| lu[i][k] * x[k]).sum();
+        x[i] = (x[i] - s) / lu[i][i];
+    }
+    Some(x)
+}
+
+/// Scalar "matrices", for instance second derivatives of one dimensional problems
+macro_rules! make_solve_scalar {
+    ($t:ty) => {
+        impl ArgminSolve<$t> for $t {
+            fn solve(&self, b: &$t) -> Result<$t, Error> {
+                if self.is_nan() || self.abs() < std::$t::MIN_POSITIVE {
+                    return Err(singular(
+                        "ArgminSolve: cannot divide by a zero or NaN scalar.".to_string(),
+                    ));
+                }
+                Ok(b / self)
+            }
+        }
+    };
+}
+
+make_solve_scalar!(f32);
+make_solve_scalar!(f64);
+
+impl ArgminAddDiagonal for f32 {
+    fn add_diagonal(&self, tau: f64) -> Self {
+        self + tau as f32
+    }
+}
+
+impl ArgminAddDiagonal for f64 {
+    fn add_diagonal(&self, tau: f64) -> Self {
+        self + tau
+    }
+}
+
+impl ArgminAddDiagonal for Vec<Vec<f64>> {
+    fn add_diagonal(&self, tau: f64) -> Self {
+        let mut out = self.clone();
+        for (i, row) in out.iter_mut().enumerate() {
+            row[i] += tau;
+        }
+        out
+    }
+}
+
+impl<const N: usize> ArgminAddDiagonal for [[f64; N]; N] {
+    fn add_diagonal(&self, tau: f64) -> Self {
+        let mut out = *self;
+        for (i, row) in out.iter_mut().enumerate() {
+            row[i] += tau;
+        }
+        out
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl ArgminAddDiagonal for ndarray::Array2<f64> {
+    fn add_diagonal(&self, tau: f64) -> Self {
+        let mut out = self.clone();
+        out.diag_mut().map_inplace(|d| *d += tau);
+        out
+    }
+}
+
+/// Pure Rust solve of a dense system: Cholesky for symmetric matrices, LU with partial pivoting
+/// otherwise or if Cholesky fails.
+fn solve_dense(a: &[Vec<f64>], b: &[f64]) -> Result<Vec<f64>, Error> {
+    let n = a.len();
+    if a.iter().any(|row| row.len() != n) || b.len() != n {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "ArgminSolve: matrix must be square with {} rows to match the right hand side.",
+                b.len()
+            ),
+        }
+        .into());
+    }
+    if is_symmetric(a) {
+        if let Some(x) = cholesky_solve(a, b) {
+            return Ok(x);
+        }
+    }
+    lu_solve(a, b).ok_or_else(|| {
+        singular("ArgminSolve: LU factorization failed, matrix is singular.".to_string())
+    })
+}
+
+impl ArgminSolve<Vec<f64>> for Vec<Vec<f64>> {
+    fn solve(&self, b: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        solve_dense(self, b)
+    }
+}
+
+/// Fixed size matrices are solved with LU decomposition with partial pivoting on the stack,
+/// without any heap allocations.
+impl<const N: usize> ArgminSolve<[f64; N]> for [[f64; N]; N] {
+    fn solve(&self, b: &[f64; N]) -> Result<[f64; N], Error> {
+        let mut lu = *self;
+        let mut x = *b;
+        let scale = self
+            .iter()
+            .flat_map(|row| row.iter())
+            .fold(0.0f64, |acc, v| acc.max(v.abs()));
+        for k in 0..N {
+            let mut pivot = k;
+            for i in k + 1..N {
+                if lu[i][k].abs() > lu[pivot][k].abs() {
+                    pivot = i;
+                }
+            }
+            let p = lu[pivot][k].abs();
+            if p.is_nan() || p <= std::f64::EPSILON * scale * N as f64 {
+                return Err(singular(
+                    "ArgminSolve: LU factorization failed, matrix is singular.".to_string(),
+                ));
+            }
+            lu.swap(k, pivot);
+            x.swap(k, pivot);
+            let row_k = lu[k];
+            for i in k + 1..N {
+                let f = lu[i][k] / row_k[k];
+                for (l, r) in lu[i][k..].iter_mut().zip(row_k[k..].iter()) {
+                    *l -= f * r;
+                }
+                x[i] -= f * x[k];
+            }
+        }
+        for i in (0..N).rev() {
+            let s: f64 = (i + 1..N).map(|k| lu[i][k] * x[k]).sum();
+            x[i] = (x[i] - s) / lu[i][i];
+        }
+        Ok(x)
+    }
+}
+
+#[cfg(feature = "ndarrayl")]
+impl ArgminSolve<ndarray::Array1<f64>> for ndarray::Array2<f64> {
+    fn solve(&self, b: &ndarray::Array1<f64>) -> Result<ndarray::Array1<f64>, Error> {
+        let symmetric = self.shape()[0] == self.shape()[1]
+            && self.indexed_iter().all(|((i, j), v)| {
+                (v - self[(j, i)]).abs() <= 1e-12 * (v.abs() + self[(j, i)].abs())
+            });
+        if symmetric {
+            if let Ok(x) = ndarray_linalg::SolveC::solvec(self, b) {
+                return Ok(x);
+            }
+        }
+        ndarray_linalg::Solve::solve(self, b)
+            .map_err(|e| singular(format!("ArgminSolve: LU factorization failed: {:?}", e)))
+    }
+}
+
+/// Pure Rust fallback which is used if `ndarray` is enabled without `ndarray-linalg` (i.e. the
+/// `ndarray` feature without `ndarrayl`). It copies the matrix into a `Vec<Vec<f64>>`, which is
+/// negligible compared to the `O(n^3)` factorization. The accuracy is comparable to LAPACK for
+/// well-conditioned systems; beyond a few hundred unknowns LAPACK's blocked algorithms are
+/// considerably faster and `ndarrayl` should be preferred.
+#[cfg(all(feature = "ndarray", not(feature = "ndarrayl")))]
+impl ArgminSolve<ndarray::Array1<f64>> for ndarray::Array2<f64> {
+    fn solve(&self, b: &ndarray::Array1<f64>) -> Result<ndarray::Array1<f64>, Error> {
+        let a: Vec<Vec<f64>> = self.outer_iter().map(|row| row.to_vec()).collect();
+        Ok(ndarray::Array1::from_vec(solve_dense(&a, &b.to_vec())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn residual(a: &[Vec<f64>], x: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(row, bi)| (row.iter().zip(x.iter()).map(|(a, x)| a * x).sum::<f64>() - bi).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_solve_spd() {
+        let a = vec![
+            vec![4.0, 1.0, 0.5],
+            vec![1.0, 3.0, -0.5],
+            vec![0.5, -0.5, 2.0],
+        ];
+        let b = vec![1.0, -2.0, 3.0];
+        assert!(cholesky_solve(&a, &b).is_some());
+        let x = a.solve(&b).unwrap();
+        assert!(residual(&a, &x, &b) < 1e-12);
+    }
+
+    #[test]
+    fn test_solve_symmetric_indefinite() {
+        let a = vec![vec![1.0, 2.0], vec![2.0, 1.0]];
+        let b = vec![3.0, 3.0];
+        assert!(cholesky_solve(&a, &b).is_none());
+        let x = a.solve(&b).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_solve_nonsymmetric() {
+        let a = vec![
+            vec![0.0, 2.0, 1.0],
+            vec![1.0, -1.0, 0.0],
+            vec![3.0, 0.0, 4.0],
+        ];
+        let b = vec![5.0, -1.0, 15.0];
+        let x = a.solve(&b).unwrap();
+        assert!(residual(&a, &x, &b) < 1e-12);
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] - 2.0).abs() < 1e-12);
+        assert!((x[2] - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_solve_scalar() {
+        assert!((4.0f64.solve(&2.0).unwrap() - 0.5).abs() < std::f64::EPSILON);
+        assert!(0.0f64.solve(&2.0).is_err());
+        assert!(std::f32::NAN.solve(&2.0).is_err());
+    }
+
+    #[test]
+    fn test_solve_array() {
+        let a = [[0.0, 2.0, 1.0], [1.0, -1.0, 0.0], [3.0, 0.0, 4.0]];
+        let b = [5.0, -1.0, 15.0];
+        let x = a.solve(&b).unwrap();
+        let x_vec = a
+            .iter()
+            .map(|row| row.to_vec())
+            .collect::<Vec<Vec<f64>>>()
+            .solve(&b.to_vec())
+            .unwrap();
+        for (xa, xv) in x.iter().zip(x_vec.iter()) {
+            assert!((xa - xv).abs() < 1e-12);
+        }
+        assert!([[1.0, 2.0], [2.0, 4.0]].solve(&[1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        let err = a.solve(&vec![1.0, 2.0]).unwrap_err();
+        assert!(format!("{}", err).contains("LU factorization"));
+        assert!(vec![vec![1.0, 2.0]].solve(&vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_add_diagonal() {
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        assert!(a.solve(&vec![1.0, 0.0]).is_err());
+        let x = a.add_diagonal(1.0).solve(&vec![1.0, 0.0]).unwrap();
+        assert!((x[0] - 5.0 / 6.0).abs() < 1e-12);
+        assert!((x[1] + 1.0 / 3.0).abs() < 1e-12);
+        let b = [[1.0, 2.0], [2.0, 4.0]].add_diagonal(1.0);
+        assert!((b[0][0] - 2.0).abs() < std::f64::EPSILON);
+        assert!((b[1][1] - 5.0).abs() < std::f64::EPSILON);
+        assert!((b[0][1] - 2.0).abs() < std::f64::EPSILON);
+        assert!((0.5f64.add_diagonal(0.25) - 0.75).abs() < std::f64::EPSILON);
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_solve_matches_inverse_ndarray() {
+        use ndarray::array;
+        let a = array![[4.0, 1.0, 0.5], [1.0, 3.0, -0.5], [0.5, -0.5, 2.0]];
+        let b = array![1.0, -2.0, 3.0];
+        let x_solve = ArgminSolve::solve(&a, &b).unwrap();
+        let x_inv: ndarray::Array1<f64> = ArgminDot::dot(&a.inv().unwrap(), &b);
+        for (s, i) in x_solve.iter().zip(x_inv.iter()) {
+            assert!((s - i).abs() < 1e-12);
+        }
+        let a = array![[0.0, 2.0, 1.0], [1.0, -1.0, 0.0], [3.0, 0.0, 4.0]];
+        let b = array![5.0, -1.0, 15.0];
+        let x_solve = ArgminSolve::solve(&a, &b).unwrap();
+        let x_inv: ndarray::Array1<f64> = ArgminDot::dot(&a.inv().unwrap(), &b);
+        for (s, i) in x_solve.iter().zip(x_inv.iter()) {
+            assert!((s - i).abs() < 1e-12);
+        }
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_pure_rust_matches_ndarray_linalg() {
+        use ndarray::Array2;
+        use rand::prelude::*;
+        use rand_xorshift::XorShiftRng;
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        for n in 1..20 {
+            // diagonally dominant, hence well-conditioned
+            let a = Array2::from_shape_fn((n, n), |(i, j)| {
+                let v: f64 = rng.gen_range(-1.0, 1.0);
+                if i == j {
+                    v + 2.0 * n as f64
+                } else {
+                    v
+                }
+            });
+            let b: ndarray::Array1<f64> = (0..n).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+            let a_vec: Vec<Vec<f64>> = a.outer_iter().map(|row| row.to_vec()).collect();
+            let x_rust = solve_dense(&a_vec, &b.to_vec()).unwrap();
+            let x_lapack = ArgminSolve::solve(&a, &b).unwrap();
+            for (r, l) in x_rust.iter().zip(x_lapack.iter()) {
+                assert!((r - l).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "ndarray", not(feature = "ndarrayl")))]
+    #[test]
+    fn test_solve_ndarray_fallback() {
+        use ndarray::array;
+        let a = array![[0.0, 2.0, 1.0], [1.0, -1.0, 0.0], [3.0, 0.0, 4.0]];
+        let x = ArgminSolve::solve(&a, &array![5.0, -1.0, 15.0]).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] - 2.0).abs() < 1e-12);
+        assert!((x[2] - 3.0).abs() < 1e-12);
+        let err = ArgminSolve::solve(&array![[1.0, 2.0], [2.0, 4.0]], &array![1.0, 2.0]);
+        assert!(format!("{}", err.unwrap_err()).contains("LU factorization"));
+    }
+
+    /// Same as `test_solve_matches_inverse_ndarray` for a larger tridiagonal matrix (the timing
+    /// comparison of both is in `benches/solve.rs`)
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_solve_matches_inverse_ndarray_tridiagonal() {
+        use ndarray::{Array1, Array2};
+        let n = 100;
+        let a = Array2::from_shape_fn((n, n), |(i, j)| {
+            if i == j {
+                4.0
+            } else if (i as i64 - j as i64).abs() == 1 {
+                -1.0
+            } else {
+                0.0
+            }
+        });
+        let b = Array1::from_shape_fn(n, |i| (i as f64).sin());
+        let x_inv: Array1<f64> = ArgminDot::dot(&a.inv().unwrap(), &b);
+        let x_solve = ArgminSolve::solve(&a, &b).unwrap();
+        for (s, i) in x_solve.iter().zip(x_inv.iter()) {
+            assert!((s - i).abs() < 1e-10);
+        }
+    }
+}
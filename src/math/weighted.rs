@@ -0,0 +1,156 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Diagonal metrics
+//!
+//! `ArgminWeightedDot` (from `argmin-core`) computes `<u, v>_M = u^T M v` for a full matrix `M`.
+//! Wrapping the diagonal of `M` in a `Diagonal` allows the same trait to be used with a diagonal
+//! metric without ever forming the matrix. The diagonal entries must be positive and finite, which
+//! is checked on construction.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Diagonal weight matrix given by its diagonal
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Diagonal<P> {
+    /// diagonal entries
+    diag: P,
+}
+
+impl<P> Diagonal<P> {
+    /// Return the diagonal
+    pub fn diag(&self) -> &P {
+        &self.diag
+    }
+}
+
+/// Computes `sqrt(<u, u>_M)`
+pub fn weighted_norm<P, W>(u: &P, w: &W) -> f64
+where
+    P: ArgminWeightedDot<P, f64, W>,
+{
+    u.weighted_dot(w, u).sqrt()
+}
+
+macro_rules! make_diagonal {
+    ($t:ty) => {
+        impl Diagonal<Vec<$t>> {
+            /// Constructor. Returns an error if any diagonal entry is not positive and finite.
+            pub fn new(diag: Vec<$t>) -> Result<Self, Error> {
+                check_weights(diag.iter().map(|d| f64::from(*d)))?;
+                Ok(Diagonal { diag })
+            }
+        }
+
+        impl ArgminWeightedDot<Vec<$t>, $t, Diagonal<Vec<$t>>> for Vec<$t> {
+            fn weighted_dot(&self, w: &Diagonal<Vec<$t>>, v: &Vec<$t>) -> $t {
+                assert_eq!(self.len(), w.diag.len());
+                assert_eq!(self.len(), v.len());
+                self.iter()
+                    .zip(w.diag.iter())
+                    .zip(v.iter())
+                    .map(|((a, d), b)| a * d * b)
+                    .sum()
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl Diagonal<ndarray::Array1<$t>> {
+            /// Constructor. Returns an error if any diagonal entry is not positive and finite.
+            pub fn new(diag: ndarray::Array1<$t>) -> Result<Self, Error> {
+                check_weights(diag.iter().map(|d| f64::from(*d)))?;
+                Ok(Diagonal { diag })
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminWeightedDot<ndarray::Array1<$t>, $t, Diagonal<ndarray::Array1<$t>>>
+            for ndarray::Array1<$t>
+        {
+            fn weighted_dot(
+                &self,
+                w: &Diagonal<ndarray::Array1<$t>>,
+                v: &ndarray::Array1<$t>,
+            ) -> $t {
+                assert_eq!(self.len(), w.diag.len());
+                assert_eq!(self.len(), v.len());
+                self.iter()
+                    .zip(w.diag.iter())
+                    .zip(v.iter())
+                    .map(|((a, d), b)| a * d * b)
+                    .sum()
+            }
+        }
+    };
+}
+
+/// Checks that all weights are positive and finite
+fn check_weights<I: Iterator<Item = f64>>(weights: I) -> Result<(), Error> {
+    for (i, w) in weights.enumerate() {
+        if !w.is_finite() || w <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "Diagonal: weights must be positive and finite, but entry {} is {}.",
+                    i, w
+                ),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+make_diagonal!(f32);
+make_diagonal!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(diagonal, Diagonal<Vec<f64>>);
+
+    #[test]
+    fn test_weighted_dot_diagonal() {
+        let u = vec![1.0f64, 2.0, 3.0];
+        let v = vec![4.0f64, -2.0, 1.0];
+        let w = Diagonal::new(vec![2.0f64, 0.5, 1.0]).unwrap();
+        assert!((u.weighted_dot(&w, &v) - 9.0).abs() < std::f64::EPSILON);
+        assert!((weighted_norm(&u, &w) - 13.0f64.sqrt()).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_weighted_dot_full() {
+        // full matrix weights are provided by argmin-core
+        let u = vec![1.0f64, 2.0];
+        let v = vec![-1.0f64, 1.0];
+        let m = vec![vec![2.0f64, 1.0], vec![0.0, 3.0]];
+        // u^T M v = [1, 2] * [-1, 3]^T = 5
+        assert!((u.weighted_dot(&m, &v) - 5.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_degenerate_weights() {
+        assert!(Diagonal::new(vec![1.0f64, 0.0]).is_err());
+        assert!(Diagonal::new(vec![1.0f64, -2.0]).is_err());
+        assert!(Diagonal::new(vec![std::f64::NAN]).is_err());
+        let err = Diagonal::new(vec![1.0f32, -2.0]).unwrap_err();
+        assert!(format!("{}", err).contains("entry 1"));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_weighted_dot_diagonal_ndarray() {
+        use ndarray::array;
+        let u = array![1.0f64, 2.0, 3.0];
+        let v = array![4.0f64, -2.0, 1.0];
+        let w = Diagonal::new(array![2.0f64, 0.5, 1.0]).unwrap();
+        assert!((u.weighted_dot(&w, &v) - 9.0).abs() < std::f64::EPSILON);
+        assert!(Diagonal::new(array![0.0f64]).is_err());
+    }
+}
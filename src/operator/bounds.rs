@@ -0,0 +1,268 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Bounds
+//!
+//! Optional lower and upper bounds attached to a problem.
+//!
+//! For bounded problems, an initial parameter vector does not have to be chosen by hand:
+//! `random_init_param` draws a reproducible one from the bounds of the problem.
+
+use crate::math::ArgminElementwiseCmp;
+use crate::prelude::*;
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+use serde::{Deserialize, Serialize};
+
+/// Lower and upper bounds on the parameter vector. Each side is optional; a missing side means
+/// that the parameter vector is unbounded in this direction.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Bounds<P> {
+    /// lower bound
+    lower: Option<P>,
+    /// upper bound
+    upper: Option<P>,
+}
+
+impl<P: ArgminElementwiseCmp> Bounds<P> {
+    /// Constructor. Returns an error if `lower_i < upper_i` does not hold for all components.
+    pub fn new(lower: P, upper: P) -> Result<Self, Error> {
+        Bounds::from_options(Some(lower), Some(upper))
+    }
+
+    /// Construct bounds where either side may be missing
+    pub fn from_options(lower: Option<P>, upper: Option<P>) -> Result<Self, Error> {
+        if let (Some(l), Some(u)) = (lower.as_ref(), upper.as_ref()) {
+            if l.num_elements() != u.num_elements() {
+                return Err(ArgminError::InvalidParameter {
+                    text: format!(
+                        "Bounds: lower bound has {} components but upper bound has {}.",
+                        l.num_elements(),
+                        u.num_elements()
+                    ),
+                }
+                .into());
+            }
+            if !l.all_lt(u) {
+                return Err(ArgminError::InvalidParameter {
+                    text: "Bounds: lower bound must be lower than upper bound for all components."
+                        .to_string(),
+                }
+                .into());
+            }
+        }
+        Ok(Bounds { lower, upper })
+    }
+
+    /// No bounds at all
+    pub fn unbounded() -> Self {
+        Bounds {
+            lower: None,
+            upper: None,
+        }
+    }
+
+    /// Return lower bound
+    pub fn lower(&self) -> Option<&P> {
+        self.lower.as_ref()
+    }
+
+    /// Return upper bound
+    pub fn upper(&self) -> Option<&P> {
+        self.upper.as_ref()
+    }
+
+    /// Returns `true` if neither a lower nor an upper bound is set
+    pub fn is_unbounded(&self) -> bool {
+        self.lower.is_none() && self.upper.is_none()
+    }
+
+    /// Checks that the bounds have as many components as `param`
+    pub fn check_dims(&self, param: &P) -> Result<(), Error> {
+        for b in self.lower.iter().chain(self.upper.iter()) {
+            if b.num_elements() != param.num_elements() {
+                return Err(ArgminError::InvalidParameter {
+                    text: format!(
+                        "Bounds: bounds have {} components but the parameter vector has {}.",
+                        b.num_elements(),
+                        param.num_elements()
+                    ),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if `param` lies within the bounds (bounds included)
+    pub fn contains(&self, param: &P) -> bool {
+        self.lower.as_ref().map(|l| l.all_le(param)).unwrap_or(true)
+            && self.upper.as_ref().map(|u| param.all_le(u)).unwrap_or(true)
+    }
+
+    /// Returns an error if `param` has the wrong dimension or lies outside of the bounds.
+    pub fn check_feasible(&self, param: &P) -> Result<(), Error> {
+        self.check_dims(param)?;
+        if !self.contains(param) {
+            return Err(ArgminError::InvalidParameter {
+                text: "Bounds: parameter vector violates the bounds.".to_string(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Problems with (optional) lower and upper bounds on the parameter vector.
+///
+/// Both methods return `None` by default, which means that the problem is unbounded. Solvers which
+/// are able to take bounds into account obtain them via `bounds()`, see `LBFGSB::from_op` and
+/// `SimulatedAnnealing::from_op`.
+pub trait ArgminBoundedOp: ArgminOp
+where
+    Self::Param: ArgminElementwiseCmp,
+{
+    /// Lower bound of the parameter vector
+    fn lower_bound(&self) -> Option<Self::Param> {
+        None
+    }
+
+    /// Upper bound of the parameter vector
+    fn upper_bound(&self) -> Option<Self::Param> {
+        None
+    }
+
+    /// Validated bounds of the problem
+    fn bounds(&self) -> Result<Bounds<Self::Param>, Error> {
+        Bounds::from_options(self.lower_bound(), self.upper_bound())
+    }
+}
+
+/// Checks whether an initial parameter vector is compatible with the bounds of `op`. This should
+/// be called before running a solver on a bounded problem; `Stepper::new_checked` and
+/// `Pipeline::run_checked` do so.
+pub fn check_init_param<O>(op: &O, init_param: &O::Param) -> Result<(), Error>
+where
+    O: ArgminBoundedOp,
+    O::Param: ArgminElementwiseCmp,
+{
+    op.bounds()?.check_feasible(init_param)
+}
+
+/// Draws an initial parameter vector from the bounds of `op` with a random number generator
+/// seeded with `seed` (see `ArgminParameter::random` for the distribution). Returns an error if
+/// the problem has no bounds, in which case an initial parameter vector has to be given.
+pub fn random_init_param<O>(op: &O, seed: u64) -> Result<O::Param, Error>
+where
+    O: ArgminBoundedOp,
+    O::Param: ArgminElementwiseCmp + ArgminParameter,
+{
+    let bounds = op.bounds()?;
+    if bounds.is_unbounded() {
+        return Err(ArgminError::InvalidParameter {
+            text: "random_init_param: the problem has no bounds; an initial parameter vector is \
+                   required."
+                .to_string(),
+        }
+        .into());
+    }
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+    O::Param::random(&mut rng, bounds.lower(), bounds.upper())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(bounds, Bounds<Vec<f64>>);
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Unbounded {}
+
+    impl ArgminOp for Unbounded {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+    }
+
+    impl ArgminBoundedOp for Unbounded {}
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Bounded {}
+
+    impl ArgminOp for Bounded {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+    }
+
+    impl ArgminBoundedOp for Bounded {
+        fn lower_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![-1.0, 0.0])
+        }
+
+        fn upper_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![1.0, 2.0])
+        }
+    }
+
+    #[test]
+    fn test_bounds_validation() {
+        assert!(Bounds::new(vec![0.0, 0.0], vec![1.0, 1.0]).is_ok());
+        assert!(Bounds::new(vec![0.0, 2.0], vec![1.0, 1.0]).is_err());
+        assert!(Bounds::new(vec![0.0, 1.0], vec![1.0, 1.0]).is_err());
+        assert!(Bounds::new(vec![0.0], vec![1.0, 1.0]).is_err());
+        assert!(Bounds::new(vec![std::f64::NAN], vec![1.0]).is_err());
+        assert!(Bounds::from_options(Some(vec![0.0]), None).is_ok());
+    }
+
+    #[test]
+    fn test_bounds_check_feasible() {
+        let b = Bounds::new(vec![0.0, 0.0], vec![1.0, 1.0]).unwrap();
+        assert!(b.check_feasible(&vec![0.0, 1.0]).is_ok());
+        assert!(b.check_feasible(&vec![0.5, 1.5]).is_err());
+        assert!(b.check_feasible(&vec![0.5]).is_err());
+        let b = Bounds::from_options(None, Some(vec![1.0])).unwrap();
+        assert!(b.contains(&vec![-1e300]));
+        assert!(!b.contains(&vec![2.0]));
+    }
+
+    #[test]
+    fn test_bounded_op() {
+        let op = Bounded {};
+        assert!(check_init_param(&op, &vec![0.0, 1.0]).is_ok());
+        assert!(check_init_param(&op, &vec![0.0, 3.0]).is_err());
+        assert!(check_init_param(&op, &vec![0.0]).is_err());
+        // NaN placeholders are rejected
+        assert!(check_init_param(&op, &vec![std::f64::NAN, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_random_init_param() {
+        let op = Bounded {};
+        let bounds = op.bounds().unwrap();
+        for seed in 0..20 {
+            let param = random_init_param(&op, seed).unwrap();
+            assert!(bounds.contains(&param));
+            // reproducible
+            assert_eq!(param, random_init_param(&op, seed).unwrap());
+        }
+        assert_ne!(
+            random_init_param(&op, 0).unwrap(),
+            random_init_param(&op, 1).unwrap()
+        );
+        assert!(random_init_param(&Unbounded {}, 0).is_err());
+    }
+
+    #[test]
+    fn test_unbounded_op() {
+        let op = Unbounded {};
+        assert!(op.bounds().unwrap().is_unbounded());
+        assert!(check_init_param(&op, &vec![1e300, -1e300]).is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Bulk evaluation
+//!
+//! Population based methods (particle swarm, differential evolution, genetic algorithms,
+//! multi-start, ...) evaluate many independent parameter vectors per iteration. `bulk_apply`
+//! evaluates all of them in one call. Without the `rayon` feature this is a plain loop; with the
+//! `rayon` feature enabled, the evaluations are distributed over the rayon thread pool.
+
+use crate::prelude::*;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Evaluation of many parameter vectors at once.
+///
+/// Solvers which need to evaluate several independent parameter vectors per iteration should call
+/// `bulk_apply` instead of looping over `apply`. The cost function counter is increased by the
+/// number of evaluated parameter vectors in a single update.
+pub trait ArgminBulkOp<O: ArgminOp> {
+    /// Apply the cost function to all parameter vectors in `params`. The outputs are returned in
+    /// the same order as the parameter vectors.
+    fn bulk_apply(&mut self, params: &[O::Param]) -> Result<Vec<O::Output>, Error>;
+}
+
+#[cfg(not(feature = "rayon"))]
+impl<O: ArgminOp> ArgminBulkOp<O> for OpWrapper<O> {
+    fn bulk_apply(&mut self, params: &[O::Param]) -> Result<Vec<O::Output>, Error> {
+        let out = params
+            .iter()
+            .map(|p| self.op.apply(p))
+            .collect::<Result<Vec<O::Output>, Error>>()?;
+        self.cost_func_count += params.len() as u64;
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<O> ArgminBulkOp<O> for OpWrapper<O>
+where
+    O: ArgminOp + Sync,
+    O::Param: Sync,
+    O::Output: Send,
+{
+    fn bulk_apply(&mut self, params: &[O::Param]) -> Result<Vec<O::Output>, Error> {
+        let op = &self.op;
+        let out = params
+            .par_iter()
+            .map(|p| op.apply(p))
+            .collect::<Result<Vec<O::Output>, Error>>()?;
+        self.cost_func_count += params.len() as u64;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Sum {}
+
+    impl ArgminOp for Sum {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p.iter().sum())
+        }
+    }
+
+    fn population(n: usize) -> Vec<Vec<f64>> {
+        (0..n).map(|i| vec![i as f64, 1.0]).collect()
+    }
+
+    #[test]
+    fn test_bulk_apply_order_and_counts() {
+        let mut op = OpWrapper::new(&Sum {});
+        let params = population(17);
+        let out = op.bulk_apply(&params).unwrap();
+        let expected: Vec<f64> = params.iter().map(|p| p.iter().sum()).collect();
+        assert_eq!(out, expected);
+        assert_eq!(op.cost_func_count, 17);
+    }
+
+    #[test]
+    fn test_bulk_apply_counts_match_serial() {
+        let params = population(9);
+        let mut serial = OpWrapper::new(&Sum {});
+        for p in params.iter() {
+            serial.apply(p).unwrap();
+        }
+        let mut bulk = OpWrapper::new(&Sum {});
+        bulk.bulk_apply(&params).unwrap();
+        assert_eq!(serial.cost_func_count, bulk.cost_func_count);
+    }
+}
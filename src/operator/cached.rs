@@ -0,0 +1,188 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Caching of the last cost function evaluation
+//!
+//! Solvers frequently evaluate the cost function at a parameter vector which was evaluated
+//! immediately before, for instance a line search at its accepted point followed by the solver at
+//! the beginning of its next iteration. `CachedOp` remembers the last evaluation and answers such
+//! repeated calls without evaluating the wrapped operator again.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Wraps an operator and caches the parameter vector and output of the last call to `apply`. If
+/// `apply` is called again with an equal parameter vector (compared via `PartialEq`), the cached
+/// output is returned without evaluating the wrapped operator. Failed evaluations are not cached,
+/// and `gradient`, `hessian` and `modify` are passed on unchanged.
+///
+/// The cache and the counters are shared between all clones of a `CachedOp`, therefore repeated
+/// evaluations are also detected across the copies of the operator held by the `Executor` and by
+/// the line searches of a solver. `evaluations` returns the number of evaluations of the wrapped
+/// operator and `hits` the number of evaluations which were saved. Note that the counters of the
+/// `OpWrapper` count all calls, including the ones answered from the cache.
+///
+/// Caching can be switched off with `caching(false)`, which is necessary for stochastic cost
+/// functions whose repeated evaluation is intended.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CachedOp<O: ArgminOp> {
+    /// wrapped operator
+    op: O,
+    /// whether caching is enabled
+    enabled: bool,
+    /// last parameter vector and output
+    #[serde(skip)]
+    cache: Arc<Mutex<Option<(O::Param, O::Output)>>>,
+    /// number of evaluations of the wrapped operator
+    #[serde(skip)]
+    evaluations: Arc<AtomicU64>,
+    /// number of evaluations answered from the cache
+    #[serde(skip)]
+    hits: Arc<AtomicU64>,
+}
+
+impl<O: ArgminOp> CachedOp<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        CachedOp {
+            op,
+            enabled: true,
+            cache: Arc::new(Mutex::new(None)),
+            evaluations: Arc::new(AtomicU64::new(0)),
+            hits: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Enable or disable caching (default: enabled)
+    pub fn caching(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Number of evaluations of the wrapped operator's `apply`
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations.load(Ordering::SeqCst)
+    }
+
+    /// Number of calls to `apply` which were answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::SeqCst)
+    }
+}
+
+impl<O> ArgminOp for CachedOp<O>
+where
+    O: ArgminOp,
+    O::Param: Clone + PartialEq,
+    O::Output: Clone,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        if self.enabled {
+            if let Some((ref p, ref out)) = *self.cache.lock().unwrap() {
+                if p == param {
+                    self.hits.fetch_add(1, Ordering::SeqCst);
+                    return Ok(out.clone());
+                }
+            }
+        }
+        // The lock is not held during the evaluation such that clones may evaluate in parallel.
+        let out = self.op.apply(param)?;
+        self.evaluations.fetch_add(1, Ordering::SeqCst);
+        if self.enabled {
+            *self.cache.lock().unwrap() = Some((param.clone(), out.clone()));
+        }
+        Ok(out)
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        self.op.gradient(param)
+    }
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        self.op.hessian(param)
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        self.op.modify(param, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::testfunctions::problem::TestProblem;
+
+    send_sync_test!(cached_op, CachedOp<TestProblem>);
+
+    #[test]
+    fn test_cached_op_repeated_evaluation() {
+        let op = CachedOp::new(TestProblem::sphere(2));
+        let a = vec![1.0, 2.0];
+        let b = vec![2.0, 1.0];
+        assert!((op.apply(&a).unwrap() - 5.0).abs() < std::f64::EPSILON);
+        assert!((op.apply(&a).unwrap() - 5.0).abs() < std::f64::EPSILON);
+        assert_eq!((op.evaluations(), op.hits()), (1, 1));
+        // only the last evaluation is cached
+        op.apply(&b).unwrap();
+        op.apply(&a).unwrap();
+        assert_eq!((op.evaluations(), op.hits()), (3, 1));
+        // the cache is shared between clones
+        op.clone().apply(&a).unwrap();
+        assert_eq!((op.evaluations(), op.hits()), (3, 2));
+    }
+
+    #[test]
+    fn test_cached_op_disabled() {
+        let op = CachedOp::new(TestProblem::sphere(2)).caching(false);
+        let a = vec![1.0, 2.0];
+        op.apply(&a).unwrap();
+        op.apply(&a).unwrap();
+        assert_eq!((op.evaluations(), op.hits()), (2, 0));
+    }
+
+    #[test]
+    fn test_cached_op_steepest_descent() {
+        let run = |op: CachedOp<TestProblem>| {
+            let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+                .rho(0.5)
+                .unwrap();
+            let solver = SteepestDescent::new(linesearch).unwrap();
+            Executor::new(op, solver, vec![-1.2, 1.0])
+                .max_iters(100)
+                .run_fast()
+                .unwrap()
+        };
+        let cached = CachedOp::new(TestProblem::rosenbrock(2));
+        let uncached = CachedOp::new(TestProblem::rosenbrock(2)).caching(false);
+        let res_cached = run(cached.clone());
+        let res_uncached = run(uncached.clone());
+
+        // From the second iteration on, the solver evaluates the cost at the point the line
+        // search has just accepted.
+        assert!(cached.hits() >= 99);
+        assert_eq!(uncached.hits(), 0);
+        assert_eq!(cached.evaluations() + cached.hits(), uncached.evaluations());
+        assert_eq!(res_cached.cost.to_bits(), res_uncached.cost.to_bits());
+        for (a, b) in res_cached.param.iter().zip(res_uncached.param.iter()) {
+            assert_eq!(a.to_bits(), b.to_bits());
+        }
+    }
+}
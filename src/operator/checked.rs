@@ -0,0 +1,356 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Validation of operator outputs
+//!
+//! A gradient of the wrong length or a Hessian of the wrong shape usually surfaces as a panic deep
+//! inside a solver. `CheckedOp` validates the outputs of the wrapped operator instead and returns
+//! a descriptive error at the first evaluation:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! # use argmin::operator::{Bounds, CheckedOp};
+//! # use argmin::solver::quasinewton::LBFGSB;
+//! # use argmin::testfunctions::problem::TestProblem;
+//! # fn main() -> Result<(), Error> {
+//! let op = CheckedOp::new(TestProblem::rosenbrock(2));
+//! let solver = LBFGSB::new(Bounds::unbounded(), 5)?;
+//! // fails at the first evaluation if the gradient or the cost function value is invalid
+//! Executor::new(op, solver, vec![-1.2, 1.0])
+//!     .max_iters(100)
+//!     .run_fast()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Wraps an operator and validates its outputs:
+///
+/// * `apply`: the cost function value is finite
+/// * `gradient`: the gradient has the shape of the parameter vector and is finite
+/// * `hessian`: the Hessian is square with the dimension of the parameter vector and is finite
+/// * `modify`: the modified parameter vector has the shape of the parameter vector and is finite
+///
+/// Violations are reported as `ArgminError::ConditionViolated` naming the output and the
+/// mismatch. By default, each kind of output is only validated at its first successful
+/// evaluation, which costs next to nothing in long runs. `check_every_call(true)` validates all
+/// evaluations. Non-finite values (`NaN`, infinities) can be allowed with `allow_non_finite(true)`,
+/// for instance for cost functions which return infinity outside of their domain.
+///
+/// Whether an output has been validated is shared between all clones of a `CheckedOp`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CheckedOp<O> {
+    /// wrapped operator
+    op: O,
+    /// whether all evaluations are validated
+    every_call: bool,
+    /// whether `NaN` and infinite values are allowed
+    allow_non_finite: bool,
+    /// whether `apply`, `gradient`, `hessian` and `modify` have been validated
+    #[serde(skip)]
+    checked: Arc<[AtomicBool; 4]>,
+}
+
+const APPLY: usize = 0;
+const GRADIENT: usize = 1;
+const HESSIAN: usize = 2;
+const MODIFY: usize = 3;
+
+impl<O> CheckedOp<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        CheckedOp {
+            op,
+            every_call: false,
+            allow_non_finite: false,
+            checked: Arc::new(Default::default()),
+        }
+    }
+
+    /// Validate all evaluations instead of only the first one of each kind (default: `false`)
+    pub fn check_every_call(mut self, every_call: bool) -> Self {
+        self.every_call = every_call;
+        self
+    }
+
+    /// Allow `NaN` and infinite values in the outputs (default: `false`)
+    pub fn allow_non_finite(mut self, allow: bool) -> Self {
+        self.allow_non_finite = allow;
+        self
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Validates `out` with `check` unless this kind of output has already been validated
+    fn validate<T, F>(&self, kind: usize, out: T, check: F) -> Result<T, Error>
+    where
+        T: ArgminShape,
+        F: FnOnce(&T) -> Result<(), String>,
+    {
+        if !self.every_call && self.checked[kind].load(Ordering::SeqCst) {
+            return Ok(out);
+        }
+        let name = [
+            "cost function value",
+            "gradient",
+            "Hessian",
+            "modified parameter vector",
+        ][kind];
+        check(&out).map_err(|text| violated(&format!("{} {}", name, text)))?;
+        if !self.allow_non_finite && !out.all_finite() {
+            return Err(violated(&format!(
+                "{} contains NaN or infinite values",
+                name
+            )));
+        }
+        self.checked[kind].store(true, Ordering::SeqCst);
+        Ok(out)
+    }
+}
+
+fn violated(text: &str) -> Error {
+    ArgminError::ConditionViolated {
+        text: format!("CheckedOp: {}.", text),
+    }
+    .into()
+}
+
+/// Checks that `shape` equals `expected`
+fn expect_shape(shape: Option<Vec<usize>>, expected: &[usize]) -> Result<(), String> {
+    match shape {
+        None => Err("has rows of different lengths".to_string()),
+        Some(ref shape) if shape[..] != *expected => Err(format!(
+            "has shape {:?}, expected {:?} (from the parameter vector)",
+            shape, expected
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+impl<O> ArgminOp for CheckedOp<O>
+where
+    O: ArgminOp,
+    O::Param: ArgminShape,
+    O::Output: ArgminShape,
+    O::Hessian: ArgminShape,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        self.validate(APPLY, self.op.apply(param)?, |_| Ok(()))
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        let grad = self.op.gradient(param)?;
+        self.validate(GRADIENT, grad, |grad| {
+            expect_shape(grad.shape(), &param_shape(param)?)
+        })
+    }
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        let hessian = self.op.hessian(param)?;
+        self.validate(HESSIAN, hessian, |hessian| {
+            let shape = param_shape(param)?;
+            let expected: Vec<usize> = shape.iter().chain(shape.iter()).cloned().collect();
+            expect_shape(hessian.shape(), &expected)
+        })
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        let modified = self.op.modify(param, extent)?;
+        self.validate(MODIFY, modified, |modified| {
+            expect_shape(modified.shape(), &param_shape(param)?)
+        })
+    }
+}
+
+/// Shape of the parameter vector the outputs are compared with
+fn param_shape<P: ArgminShape>(param: &P) -> Result<Vec<usize>, String> {
+    param
+        .shape()
+        .ok_or_else(|| "cannot be validated: parameter vector has no well defined shape".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::Bounds;
+    use crate::send_sync_test;
+    use crate::solver::quasinewton::LBFGSB;
+    use crate::testfunctions::problem::TestProblem;
+    use std::sync::atomic::AtomicU64;
+
+    /// `f(x) = sum_i x_i^2` with deliberately broken derivatives
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Broken {
+        /// number of components missing from the gradient
+        missing: usize,
+        /// number of columns missing from the Hessian
+        missing_cols: usize,
+        /// the second row of the Hessian is one element short
+        ragged: bool,
+        /// the gradient is `NaN` from this call on
+        nan_from: Option<u64>,
+        #[serde(skip)]
+        calls: Arc<AtomicU64>,
+    }
+
+    impl ArgminOp for Broken {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p.iter().map(|x| x * x).sum())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let mut grad: Vec<f64> = p.iter().map(|x| 2.0 * x).collect();
+            grad.truncate(p.len() - self.missing);
+            if self.nan_from.map(|n| call >= n).unwrap_or(false) {
+                grad[0] = std::f64::NAN;
+            }
+            Ok(grad)
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            let n = p.len();
+            let mut h: Vec<Vec<f64>> = (0..n)
+                .map(|i| {
+                    (0..n - self.missing_cols)
+                        .map(|j| if i == j { 2.0 } else { 0.0 })
+                        .collect()
+                })
+                .collect();
+            if self.ragged {
+                h[1].pop();
+            }
+            Ok(h)
+        }
+    }
+
+    fn message(err: Error) -> String {
+        err.to_string()
+    }
+
+    send_sync_test!(checked_op, CheckedOp<TestProblem>);
+
+    #[test]
+    fn test_checked_op_valid() {
+        let op = CheckedOp::new(TestProblem::rosenbrock(3));
+        let x = vec![0.5, -0.5, 1.0];
+        assert!(op.apply(&x).is_ok());
+        assert!(op.gradient(&x).is_ok());
+        assert!(op.hessian(&x).is_ok());
+    }
+
+    #[test]
+    fn test_checked_op_gradient_length() {
+        let op = CheckedOp::new(Broken {
+            missing: 1,
+            ..Broken::default()
+        });
+        let msg = message(op.gradient(&vec![1.0, 2.0, 3.0]).err().unwrap());
+        assert!(
+            msg.contains("gradient has shape [2], expected [3]"),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn test_checked_op_hessian_shape() {
+        let op = CheckedOp::new(Broken {
+            missing_cols: 1,
+            ..Broken::default()
+        });
+        let msg = message(op.hessian(&vec![1.0, 2.0, 3.0]).err().unwrap());
+        assert!(
+            msg.contains("Hessian has shape [3, 2], expected [3, 3]"),
+            "{}",
+            msg
+        );
+
+        let op = CheckedOp::new(Broken {
+            ragged: true,
+            ..Broken::default()
+        });
+        let msg = message(op.hessian(&vec![1.0, 2.0, 3.0]).err().unwrap());
+        assert!(
+            msg.contains("Hessian has rows of different lengths"),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn test_checked_op_nan_gradient() {
+        let broken = Broken {
+            nan_from: Some(0),
+            ..Broken::default()
+        };
+        let op = CheckedOp::new(broken.clone());
+        let msg = message(op.gradient(&vec![1.0, 2.0]).err().unwrap());
+        assert!(msg.contains("gradient contains NaN"), "{}", msg);
+
+        let op = CheckedOp::new(broken).allow_non_finite(true);
+        assert!(op.gradient(&vec![1.0, 2.0]).unwrap()[0].is_nan());
+
+        let op = CheckedOp::new(TestProblem::sphere(2));
+        let msg = message(op.apply(&vec![std::f64::INFINITY, 0.0]).err().unwrap());
+        assert!(msg.contains("cost function value contains NaN"), "{}", msg);
+    }
+
+    #[test]
+    fn test_checked_op_first_call_only() {
+        // the gradient becomes NaN at the second call
+        let broken = Broken {
+            nan_from: Some(1),
+            ..Broken::default()
+        };
+        let op = CheckedOp::new(broken.clone());
+        assert!(op.gradient(&vec![1.0, 2.0]).is_ok());
+        // validated once, also for clones
+        assert!(op.clone().gradient(&vec![1.0, 2.0]).is_ok());
+
+        let op = CheckedOp::new(Broken {
+            calls: Arc::new(AtomicU64::new(0)),
+            ..broken
+        })
+        .check_every_call(true);
+        assert!(op.gradient(&vec![1.0, 2.0]).is_ok());
+        assert!(op.gradient(&vec![1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_checked_op_lbfgsb() {
+        let op = CheckedOp::new(Broken {
+            missing: 1,
+            ..Broken::default()
+        });
+        let solver = LBFGSB::new(Bounds::unbounded(), 5).unwrap();
+        let res = Executor::new(op, solver, vec![1.0, 2.0, 3.0])
+            .max_iters(10)
+            .run_fast();
+        let msg = message(res.err().unwrap());
+        assert!(
+            msg.contains("gradient has shape [2], expected [3]"),
+            "{}",
+            msg
+        );
+    }
+}
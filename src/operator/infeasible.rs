@@ -0,0 +1,191 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Infeasible points
+//!
+//! Some cost functions cannot be evaluated everywhere (a simulation does not converge, a mesh
+//! cannot be generated, ...). Returning an `InfeasiblePoint` error from `apply` signals that the
+//! parameter vector is merely a very bad point rather than a reason to abort the run.
+//!
+//! Solvers which can cope with this call `try_apply` instead of `apply`:
+//!
+//! * Simulated Annealing treats infeasible points as points with infinite cost, i.e. they are
+//!   always rejected.
+//! * The Backtracking line search treats infeasible points as points with infinite cost and
+//!   shrinks the step.
+//!
+//! All other solvers (in particular gradient based methods such as Newton's method) abort with
+//! the error as before. The number of infeasible evaluations is reported as `infeas` in the
+//! key-value store of each iteration.
+
+use crate::prelude::*;
+
+/// Error signalling that the cost function cannot be evaluated at a given parameter vector.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InfeasiblePoint {
+    /// description of why the point is infeasible
+    pub text: String,
+}
+
+impl InfeasiblePoint {
+    /// Constructor
+    pub fn new(text: &str) -> Self {
+        InfeasiblePoint {
+            text: text.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for InfeasiblePoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Infeasible point: {}", self.text)
+    }
+}
+
+impl std::error::Error for InfeasiblePoint {}
+
+/// Returns `true` if `err` is an `InfeasiblePoint` error
+pub fn is_infeasible(err: &Error) -> bool {
+    err.downcast_ref::<InfeasiblePoint>().is_some()
+}
+
+/// Evaluation of the cost function which distinguishes infeasible points from hard errors.
+pub trait ArgminTryApply<O: ArgminOp> {
+    /// Apply the cost function to `param`. Returns `Ok(None)` if the operator signalled an
+    /// `InfeasiblePoint` error; all other errors are passed on.
+    fn try_apply(&mut self, param: &O::Param) -> Result<Option<O::Output>, Error>;
+}
+
+impl<O: ArgminOp> ArgminTryApply<O> for OpWrapper<O> {
+    fn try_apply(&mut self, param: &O::Param) -> Result<Option<O::Output>, Error> {
+        match self.apply(param) {
+            Ok(out) => Ok(Some(out)),
+            Err(ref e) if is_infeasible(e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+    use crate::testfunctions::{sphere, sphere_shifted, sphere_shifted_derivative};
+    use rand::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    send_sync_test!(infeasible_point, InfeasiblePoint);
+
+    /// Sphere around `(2, 2)` which cannot be evaluated inside the unit circle
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Holey {}
+
+    impl ArgminOp for Holey {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            if sphere(p) < 1.0 {
+                return Err(InfeasiblePoint::new("inside of the unit circle").into());
+            }
+            Ok(sphere_shifted(p, &[2.0; 2]))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sphere_shifted_derivative(p, &[2.0; 2]))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _extent: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = rand::thread_rng();
+            Ok(p.iter().map(|x| x + rng.gen_range(-0.25, 0.25)).collect())
+        }
+    }
+
+    #[test]
+    fn test_try_apply() {
+        let mut op = OpWrapper::new(&Holey {});
+        assert!(op.try_apply(&vec![0.0, 0.0]).unwrap().is_none());
+        assert!(op.try_apply(&vec![2.0, 2.0]).unwrap().is_some());
+        assert_eq!(op.cost_func_count, 2);
+
+        let err: Error = ArgminError::NotImplemented {
+            text: "nope".to_string(),
+        }
+        .into();
+        assert!(!is_infeasible(&err));
+        let err: Error = InfeasiblePoint::new("nope").into();
+        assert!(is_infeasible(&err));
+    }
+
+    #[test]
+    fn test_sa_avoids_infeasible_region() {
+        let solver = SimulatedAnnealing::new(1.0).unwrap();
+        let res = Executor::new(Holey {}, solver, vec![-2.0, -2.0])
+            .max_iters(5000)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost.is_finite());
+        assert!((res.param[0] - 2.0).abs() < 0.25, "{:?}", res.param);
+        assert!((res.param[1] - 2.0).abs() < 0.25, "{:?}", res.param);
+    }
+
+    #[test]
+    fn test_backtracking_shrinks_infeasible_step() {
+        let mut solver = BacktrackingLineSearch::new(ArmijoCondition::new(0.5).unwrap())
+            .rho(0.5)
+            .unwrap();
+        solver.set_search_direction(vec![1.0, 0.0]);
+        // the first two trial points (0.5, 0) and (-0.5, 0) are infeasible
+        solver.set_init_alpha(2.0).unwrap();
+        let res = Executor::new(Holey {}, solver, vec![-1.5, 0.0])
+            .max_iters(20)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost.is_finite());
+        assert!(res.param[0].abs() >= 1.0);
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_newton_hard_errors() {
+        use crate::solver::newton::Newton;
+        use ndarray::{Array1, Array2};
+
+        #[derive(Clone, Default, Serialize, Deserialize)]
+        struct Infeasible {}
+
+        impl ArgminOp for Infeasible {
+            type Param = Array1<f64>;
+            type Output = f64;
+            type Hessian = Array2<f64>;
+
+            fn apply(&self, _p: &Array1<f64>) -> Result<f64, Error> {
+                Err(InfeasiblePoint::new("always").into())
+            }
+
+            fn gradient(&self, _p: &Array1<f64>) -> Result<Array1<f64>, Error> {
+                Err(InfeasiblePoint::new("always").into())
+            }
+
+            fn hessian(&self, _p: &Array1<f64>) -> Result<Array2<f64>, Error> {
+                Err(InfeasiblePoint::new("always").into())
+            }
+        }
+
+        let res = Executor::new(
+            Infeasible {},
+            Newton::new(),
+            Array1::from_vec(vec![1.0, 1.0]),
+        )
+        .max_iters(10)
+        .run_fast();
+        assert!(res.is_err());
+    }
+}
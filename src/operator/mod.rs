@@ -0,0 +1,64 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Operators
+//!
+//! Extensions of `OpWrapper` and wrappers around `ArgminOp`s which modify or instrument the
+//! evaluation of a problem.
+//!
+//! * [Bounds](bounds/struct.Bounds.html)
+//! * [Bulk evaluation](bulk/trait.ArgminBulkOp.html)
+//! * [Caching of the last evaluation](cached/struct.CachedOp.html)
+//! * [Validation of outputs](checked/struct.CheckedOp.html)
+//! * [Infeasible points](infeasible/struct.InfeasiblePoint.html)
+//! * [Penalty functions](penalty/struct.PenaltyOp.html)
+//! * [Maximization](negated/struct.Negated.html)
+//! * [Mini-batches](sampled/struct.MiniBatches.html)
+//! * [Resampling of noisy cost functions](resampling/struct.ResamplingOp.html)
+//! * [Sparse linear operators](sparse/struct.SparseLinearOp.html) (`sprs` feature)
+//! * [Variable scaling](scaled/struct.ScaledOp.html)
+//! * [Timing of operator evaluations](timed/struct.TimedOp.html)
+
+/// Lower and upper bounds
+pub mod bounds;
+/// Bulk evaluation of many parameter vectors
+pub mod bulk;
+/// Caching of the last cost function evaluation
+pub mod cached;
+/// Validation of operator outputs
+pub mod checked;
+/// Infeasible points
+pub mod infeasible;
+/// Maximization by negation
+pub mod negated;
+/// Penalty functions for constrained problems
+pub mod penalty;
+/// Resampling of noisy cost functions
+pub mod resampling;
+/// Mini-batches of samples
+pub mod sampled;
+/// Scaling of parameter vectors
+pub mod scaled;
+/// Sparse linear operators
+#[cfg(feature = "sprs")]
+pub mod sparse;
+/// Timing of operator evaluations
+pub mod timed;
+
+pub use self::bounds::*;
+pub use self::bulk::*;
+pub use self::cached::*;
+pub use self::checked::*;
+pub use self::infeasible::*;
+pub use self::negated::*;
+pub use self::penalty::*;
+pub use self::resampling::*;
+pub use self::sampled::*;
+pub use self::scaled::*;
+#[cfg(feature = "sprs")]
+pub use self::sparse::*;
+pub use self::timed::*;
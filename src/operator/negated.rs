@@ -0,0 +1,290 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Maximization
+//!
+//! All solvers minimize. An objective `f` is maximized by minimizing `-f`, which `Negated` does
+//! without the need to flip the signs of the cost function, gradient and Hessian by hand:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! # use argmin::operator::Negated;
+//! # use argmin::solver::neldermead::{NelderMead, SimplexStep};
+//! # use serde::{Deserialize, Serialize};
+//! /// `f(x) = 1 - (x - 2)^2` with its maximum `f(2) = 1`
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Concave;
+//!
+//! impl ArgminOp for Concave {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!     type Hessian = ();
+//!
+//!     fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+//!         Ok(1.0 - (p[0] - 2.0).powi(2))
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), Error> {
+//! let op = Negated::new(Concave);
+//! let solver = NelderMead::from_point(vec![0.0], SimplexStep::default())?;
+//! let res = Executor::new(op.clone(), solver, vec![0.0])
+//!     // stop once the objective reaches 0.999999
+//!     .target_cost(op.target_cost(0.999_999))
+//!     .max_iters(100)
+//!     .run_fast()?;
+//! let value = op.objective(res.cost);
+//! # assert!(value >= 0.999_999);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `Executor` and its observers only see the negated problem: the reported costs are `-f` and
+//! the best cost is the smallest `-f`, i.e. the largest `f`. `objective` turns such a cost back
+//! into the value of the objective, and `target_cost` turns a target value of the objective into
+//! a target cost for the `Executor`.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Wraps an operator `f` and presents `-f` to the solver, such that minimizing the wrapped
+/// operator maximizes `f`.
+///
+/// * `apply(x) = -f(x)`
+/// * `gradient(x) = -\nabla f(x)`
+/// * `hessian(x) = -H(x)`
+///
+/// `modify` is passed on unchanged.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Negated<O> {
+    /// wrapped operator
+    op: O,
+}
+
+impl<O> Negated<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        Negated { op }
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Value of the objective for a cost reported by the `Executor`
+    pub fn objective(&self, cost: f64) -> f64 {
+        -cost
+    }
+
+    /// Target cost for the `Executor` such that the run stops once the objective is at least
+    /// `target`
+    pub fn target_cost(&self, target: f64) -> f64 {
+        -target
+    }
+}
+
+impl<O> ArgminOp for Negated<O>
+where
+    O: ArgminOp,
+    O::Param: ArgminNeg,
+    O::Output: ArgminNeg,
+    O::Hessian: ArgminNeg,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(self.op.apply(param)?.neg())
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(self.op.gradient(param)?.neg())
+    }
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        Ok(self.op.hessian(param)?.neg())
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        self.op.modify(param, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::solver::neldermead::{NelderMead, SimplexStep};
+    use crate::utils::{check_gradient, check_hessian};
+
+    /// `f(x) = 3 - sum_i (x_i - c_i)^2` with its maximum `f(c) = 3`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Concave {
+        c: Vec<f64>,
+    }
+
+    impl ArgminOp for Concave {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(3.0
+                - p.iter()
+                    .zip(self.c.iter())
+                    .map(|(x, c)| (x - c).powi(2))
+                    .sum::<f64>())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.iter()
+                .zip(self.c.iter())
+                .map(|(x, c)| -2.0 * (x - c))
+                .collect())
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            let n = p.len();
+            Ok((0..n)
+                .map(|i| (0..n).map(|j| if i == j { -2.0 } else { 0.0 }).collect())
+                .collect())
+        }
+    }
+
+    /// Log-likelihood of normally distributed samples as a function of the mean `mu` and the
+    /// logarithm `s` of the standard deviation, up to a constant:
+    ///
+    /// `l(mu, s) = -n * s - sum_i (y_i - mu)^2 / (2 * exp(2 * s))`
+    ///
+    /// The maximum is at the sample mean and the (biased) sample standard deviation.
+    #[derive(Clone, Serialize, Deserialize)]
+    struct LogLikelihood {
+        samples: Vec<f64>,
+    }
+
+    impl LogLikelihood {
+        fn mle(&self) -> (f64, f64) {
+            let n = self.samples.len() as f64;
+            let mean = self.samples.iter().sum::<f64>() / n;
+            let var = self.samples.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / n;
+            (mean, var.sqrt())
+        }
+    }
+
+    impl ArgminOp for LogLikelihood {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            let (mu, s) = (p[0], p[1]);
+            let n = self.samples.len() as f64;
+            let sq: f64 = self.samples.iter().map(|y| (y - mu).powi(2)).sum();
+            Ok(-n * s - sq / (2.0 * (2.0 * s).exp()))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            let (mu, s) = (p[0], p[1]);
+            let n = self.samples.len() as f64;
+            let var = (2.0 * s).exp();
+            let sum: f64 = self.samples.iter().map(|y| y - mu).sum();
+            let sq: f64 = self.samples.iter().map(|y| (y - mu).powi(2)).sum();
+            Ok(vec![sum / var, -n + sq / var])
+        }
+    }
+
+    fn log_likelihood() -> LogLikelihood {
+        LogLikelihood {
+            samples: vec![1.2, 0.7, 2.3, 1.9, 0.4, 1.6, 1.1, 2.8],
+        }
+    }
+
+    fn steepest_descent() -> SteepestDescent<BacktrackingLineSearch<Vec<f64>, ArmijoCondition>> {
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap());
+        SteepestDescent::new(linesearch).unwrap()
+    }
+
+    send_sync_test!(negated, Negated<Concave>);
+
+    #[test]
+    fn test_negated_derivatives() {
+        let concave = Concave { c: vec![1.0, -2.0] };
+        let op = Negated::new(concave.clone());
+        let x = vec![0.3, 0.4];
+        assert!((op.apply(&x).unwrap() + concave.apply(&x).unwrap()).abs() < 1e-15);
+
+        let points = vec![vec![0.3, 0.4], vec![-1.0, 2.5]];
+        assert!(check_gradient(&op, &points, 1e-6, 1e-8).unwrap().passed());
+        assert!(check_hessian(&op, &points, 1e-6, 1e-8).unwrap().passed());
+        let points = vec![vec![1.0, 0.0], vec![2.0, -0.5]];
+        let op = Negated::new(log_likelihood());
+        assert!(check_gradient(&op, &points, 1e-6, 1e-8).unwrap().passed());
+    }
+
+    #[test]
+    fn test_negated_concave_quadratic() {
+        let op = Negated::new(Concave { c: vec![1.0, -2.0] });
+        let res = Executor::new(op.clone(), steepest_descent(), vec![0.0, 0.0])
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        assert!((op.objective(res.cost) - 3.0).abs() < 1e-10);
+        assert!((res.param[0] - 1.0).abs() < 1e-5);
+        assert!((res.param[1] + 2.0).abs() < 1e-5);
+
+        let solver = NelderMead::from_point(vec![0.0, 0.0], SimplexStep::default()).unwrap();
+        let res = Executor::new(op.clone(), solver, vec![0.0, 0.0])
+            .max_iters(500)
+            .run_fast()
+            .unwrap();
+        assert!((op.objective(res.cost) - 3.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_negated_log_likelihood() {
+        let op = Negated::new(log_likelihood());
+        let (mean, std) = op.inner().mle();
+        let max = op.inner().apply(&vec![mean, std.ln()]).unwrap();
+        let init = vec![0.0, 0.0];
+
+        let res = Executor::new(op.clone(), steepest_descent(), init.clone())
+            .max_iters(1000)
+            .run_fast()
+            .unwrap();
+        assert!(op.objective(res.cost) <= max + 1e-12);
+        assert!((op.objective(res.cost) - max).abs() < 1e-8);
+        assert!((res.param[0] - mean).abs() < 1e-4);
+        assert!((res.param[1].exp() - std).abs() < 1e-4);
+
+        let solver = NelderMead::from_point(init.clone(), SimplexStep::Absolute(0.5)).unwrap();
+        let res = Executor::new(op.clone(), solver, init)
+            .max_iters(1000)
+            .run_fast()
+            .unwrap();
+        assert!((op.objective(res.cost) - max).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_negated_target_cost() {
+        let op = Negated::new(log_likelihood());
+        let (mean, std) = op.inner().mle();
+        let max = op.inner().apply(&vec![mean, std.ln()]).unwrap();
+        let target = max - 0.5;
+        let res = Executor::new(op.clone(), steepest_descent(), vec![0.0, 0.0])
+            .target_cost(op.target_cost(target))
+            .max_iters(1000)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::TargetCostReached);
+        assert!(op.objective(res.cost) >= target);
+        assert!(op.objective(res.cost) < max - 1e-8);
+    }
+}
@@ -0,0 +1,454 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Penalty functions
+//!
+//! Turns a constrained problem
+//!
+//! `min f(x)` subject to `g_i(x) <= 0` and `h_j(x) = 0`
+//!
+//! into an unconstrained one by adding a penalty for constraint violations to the cost function.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Constraint function
+type ConstraintFn<P> = Arc<dyn Fn(&P) -> Result<f64, Error> + Send + Sync>;
+/// Gradient of a constraint function
+type ConstraintGradFn<P> = Arc<dyn Fn(&P) -> Result<P, Error> + Send + Sync>;
+
+/// Type of penalty
+///
+/// With `v_i = max(0, g_i(x))` for inequality constraints and `v_j = |h_j(x)|` for equality
+/// constraints the penalty is given by:
+///
+/// * `PenaltyKind::Quadratic`: `mu * sum_i v_i^2` (smooth, but only exact for `mu -> inf`)
+/// * `PenaltyKind::ExactL1`: `mu * sum_i v_i` (exact for sufficiently large `mu`, nonsmooth)
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum PenaltyKind {
+    /// Quadratic exterior penalty
+    Quadratic,
+    /// Exact L1 penalty
+    ExactL1,
+}
+
+impl std::default::Default for PenaltyKind {
+    fn default() -> Self {
+        PenaltyKind::Quadratic
+    }
+}
+
+/// A single constraint, optionally with its gradient
+struct PenaltyConstraint<P> {
+    /// constraint function
+    func: ConstraintFn<P>,
+    /// gradient of the constraint function
+    grad: Option<ConstraintGradFn<P>>,
+    /// name used for reporting
+    name: Option<String>,
+}
+
+impl<P> Clone for PenaltyConstraint<P> {
+    fn clone(&self) -> Self {
+        PenaltyConstraint {
+            func: self.func.clone(),
+            grad: self.grad.clone(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// Wraps an operator and adds a penalty for violated constraints to the cost function and, if the
+/// gradients of all constraints are provided, to the gradient.
+///
+/// The penalty weight `mu` can be changed between runs via `set_weight` which allows for a
+/// sequential penalty method (solve, increase `mu`, solve again starting from the previous
+/// solution, ...).
+///
+/// The constraint closures are not serialized. A `PenaltyOp` restored from a checkpoint needs its
+/// constraints to be added again.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PenaltyOp<O: ArgminOp> {
+    /// wrapped operator
+    op: O,
+    /// inequality constraints `g_i(x) <= 0`
+    #[serde(skip)]
+    inequality: Vec<PenaltyConstraint<O::Param>>,
+    /// equality constraints `h_j(x) = 0`
+    #[serde(skip)]
+    equality: Vec<PenaltyConstraint<O::Param>>,
+    /// penalty weight
+    weight: f64,
+    /// type of penalty
+    kind: PenaltyKind,
+}
+
+impl<O: ArgminOp> PenaltyOp<O> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `op`: operator to be wrapped
+    /// * `weight`: penalty weight `mu`
+    pub fn new(op: O, weight: f64) -> Result<Self, Error> {
+        let mut out = PenaltyOp {
+            op,
+            inequality: vec![],
+            equality: vec![],
+            weight: 0.0,
+            kind: PenaltyKind::default(),
+        };
+        out.set_weight(weight)?;
+        Ok(out)
+    }
+
+    /// Set type of penalty
+    pub fn kind(mut self, kind: PenaltyKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Add inequality constraint `g(x) <= 0`
+    pub fn inequality<F>(mut self, g: F) -> Self
+    where
+        F: Fn(&O::Param) -> Result<f64, Error> + Send + Sync + 'static,
+    {
+        self.inequality.push(PenaltyConstraint {
+            func: Arc::new(g),
+            grad: None,
+            name: None,
+        });
+        self
+    }
+
+    /// Add inequality constraint `g(x) <= 0` together with its gradient
+    pub fn inequality_with_gradient<F, G>(mut self, g: F, grad: G) -> Self
+    where
+        F: Fn(&O::Param) -> Result<f64, Error> + Send + Sync + 'static,
+        G: Fn(&O::Param) -> Result<O::Param, Error> + Send + Sync + 'static,
+    {
+        self.inequality.push(PenaltyConstraint {
+            func: Arc::new(g),
+            grad: Some(Arc::new(grad)),
+            name: None,
+        });
+        self
+    }
+
+    /// Add equality constraint `h(x) = 0`
+    pub fn equality<F>(mut self, h: F) -> Self
+    where
+        F: Fn(&O::Param) -> Result<f64, Error> + Send + Sync + 'static,
+    {
+        self.equality.push(PenaltyConstraint {
+            func: Arc::new(h),
+            grad: None,
+            name: None,
+        });
+        self
+    }
+
+    /// Add equality constraint `h(x) = 0` together with its gradient
+    pub fn equality_with_gradient<F, G>(mut self, h: F, grad: G) -> Self
+    where
+        F: Fn(&O::Param) -> Result<f64, Error> + Send + Sync + 'static,
+        G: Fn(&O::Param) -> Result<O::Param, Error> + Send + Sync + 'static,
+    {
+        self.equality.push(PenaltyConstraint {
+            func: Arc::new(h),
+            grad: Some(Arc::new(grad)),
+            name: None,
+        });
+        self
+    }
+
+    /// Add a `Constraint` as inequality constraint `violation(x) <= 0`. Its name is used by
+    /// `violated`.
+    pub fn constraint<C>(mut self, c: C) -> Self
+    where
+        C: Constraint<O::Param> + Send + Sync + 'static,
+    {
+        let name = c.name();
+        self.inequality.push(PenaltyConstraint {
+            func: Arc::new(move |x: &O::Param| Ok(c.violation(x))),
+            grad: None,
+            name: Some(name),
+        });
+        self
+    }
+
+    /// Names of all constraints which are violated by more than `tol` at `param`. Constraints
+    /// which were not added via `constraint` are reported as `inequality i` and `equality j`.
+    pub fn violated(&self, param: &O::Param, tol: f64) -> Result<Vec<String>, Error> {
+        let mut out = vec![];
+        for (i, c) in self.inequality.iter().enumerate() {
+            if (c.func)(param)? > tol {
+                out.push(
+                    c.name
+                        .clone()
+                        .unwrap_or_else(|| format!("inequality {}", i)),
+                );
+            }
+        }
+        for (j, c) in self.equality.iter().enumerate() {
+            if (c.func)(param)?.abs() > tol {
+                out.push(c.name.clone().unwrap_or_else(|| format!("equality {}", j)));
+            }
+        }
+        Ok(out)
+    }
+
+    /// Set penalty weight `mu`. Must be non-negative.
+    pub fn set_weight(&mut self, weight: f64) -> Result<(), Error> {
+        if weight.is_nan() || weight < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "PenaltyOp: weight must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.weight = weight;
+        Ok(())
+    }
+
+    /// Return penalty weight `mu`
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Compute the penalty term at `param` (without the cost function)
+    pub fn penalty(&self, param: &O::Param) -> Result<f64, Error> {
+        let mut sum = 0.0;
+        for c in self.inequality.iter() {
+            sum += self.violation_penalty((c.func)(param)?.max(0.0));
+        }
+        for c in self.equality.iter() {
+            sum += self.violation_penalty((c.func)(param)?.abs());
+        }
+        Ok(self.weight * sum)
+    }
+
+    /// Returns `true` if all constraints are satisfied up to `tol`
+    pub fn is_feasible(&self, param: &O::Param, tol: f64) -> Result<bool, Error> {
+        for c in self.inequality.iter() {
+            if (c.func)(param)? > tol {
+                return Ok(false);
+            }
+        }
+        for c in self.equality.iter() {
+            if (c.func)(param)?.abs() > tol {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn violation_penalty(&self, v: f64) -> f64 {
+        match self.kind {
+            PenaltyKind::Quadratic => v.powi(2),
+            PenaltyKind::ExactL1 => v,
+        }
+    }
+
+    /// Derivative of the penalty of a single constraint with respect to the constraint value
+    fn penalty_derivative(&self, value: f64, equality: bool) -> f64 {
+        let v = if equality { value } else { value.max(0.0) };
+        match self.kind {
+            PenaltyKind::Quadratic => 2.0 * v,
+            PenaltyKind::ExactL1 => {
+                if v > 0.0 {
+                    1.0
+                } else if v < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+impl<O> ArgminOp for PenaltyOp<O>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: ArgminScaledAdd<O::Param, f64, O::Param>,
+{
+    type Param = O::Param;
+    type Output = f64;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(self.op.apply(param)? + self.penalty(param)?)
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        let mut grad = self.op.gradient(param)?;
+        let constraints = self
+            .inequality
+            .iter()
+            .map(|c| (c, false))
+            .chain(self.equality.iter().map(|c| (c, true)));
+        for (c, equality) in constraints {
+            let factor = self.weight * self.penalty_derivative((c.func)(param)?, equality);
+            if factor == 0.0 {
+                continue;
+            }
+            let cgrad = match c.grad {
+                Some(ref g) => g(param)?,
+                None => {
+                    return Err(ArgminError::NotImplemented {
+                        text: "PenaltyOp: gradient of a violated constraint is not provided."
+                            .to_string(),
+                    }
+                    .into())
+                }
+            };
+            grad = grad.scaled_add(&factor, &cgrad);
+        }
+        Ok(grad)
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        self.op.modify(param, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::landweber::Landweber;
+    use crate::testfunctions::fd::assert_gradient;
+    use crate::testfunctions::{sphere, sphere_derivative};
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Square {}
+
+    impl ArgminOp for Square {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere(p))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sphere_derivative(p))
+        }
+    }
+
+    send_sync_test!(penalty_op, PenaltyOp<Square>);
+
+    /// `x^2` subject to `1 - x <= 0`
+    fn constrained(weight: f64, kind: PenaltyKind) -> PenaltyOp<Square> {
+        PenaltyOp::new(Square {}, weight)
+            .unwrap()
+            .kind(kind)
+            .inequality_with_gradient(
+                |x: &Vec<f64>| Ok(1.0 - x[0]),
+                |_x: &Vec<f64>| Ok(vec![-1.0]),
+            )
+    }
+
+    #[test]
+    fn test_invalid_weight() {
+        assert!(PenaltyOp::new(Square {}, -1.0).is_err());
+        assert!(PenaltyOp::new(Square {}, std::f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_penalty_values() {
+        let op = constrained(10.0, PenaltyKind::Quadratic);
+        assert!((op.apply(&vec![0.5]).unwrap() - (0.25 + 10.0 * 0.25)).abs() < 1e-12);
+        assert!((op.apply(&vec![2.0]).unwrap() - 4.0).abs() < 1e-12);
+        let op = constrained(10.0, PenaltyKind::ExactL1);
+        assert!((op.apply(&vec![0.5]).unwrap() - (0.25 + 10.0 * 0.5)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sequential_penalty() {
+        let mut op = constrained(1.0, PenaltyKind::Quadratic);
+        let mut param = vec![0.0];
+        for &weight in [1.0, 10.0, 100.0, 1000.0, 10000.0].iter() {
+            op.set_weight(weight).unwrap();
+            // Landweber converges for omega < 2 / (2 + 2 * weight)
+            let solver = Landweber::new(0.9 / (1.0 + weight)).unwrap();
+            let res = Executor::new(op.clone(), solver, param)
+                .max_iters(500)
+                .run_fast()
+                .unwrap();
+            param = res.param;
+            // minimizer of x^2 + mu * (1 - x)^2
+            assert!((param[0] - weight / (1.0 + weight)).abs() < 1e-6);
+        }
+        assert!((param[0] - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_gradient_finite_differences() {
+        let equality = |x: &Vec<f64>| Ok(x[0] + x[1] - 3.0);
+        let equality_grad = |_x: &Vec<f64>| Ok(vec![1.0, 1.0]);
+        for kind in [PenaltyKind::Quadratic, PenaltyKind::ExactL1].iter() {
+            let op = PenaltyOp::new(Square {}, 5.0)
+                .unwrap()
+                .kind(*kind)
+                .inequality_with_gradient(
+                    |x: &Vec<f64>| Ok(1.0 - x[0] * x[1]),
+                    |x: &Vec<f64>| Ok(vec![-x[1], -x[0]]),
+                )
+                .equality_with_gradient(equality, equality_grad);
+            assert_gradient(
+                |x| op.apply(&x.to_vec()).unwrap(),
+                |x| op.gradient(&x.to_vec()).unwrap(),
+                &[vec![0.3, 0.7], vec![-1.2, 0.4], vec![2.5, 1.5]],
+            );
+        }
+    }
+
+    #[test]
+    fn test_missing_constraint_gradient() {
+        let op = PenaltyOp::new(Square {}, 1.0)
+            .unwrap()
+            .inequality(|x: &Vec<f64>| Ok(1.0 - x[0]));
+        // constraint satisfied: gradient of the constraint not needed
+        assert!(op.gradient(&vec![2.0]).is_ok());
+        // constraint violated: gradient of the constraint is required
+        assert!(op.gradient(&vec![0.0]).is_err());
+    }
+
+    #[test]
+    fn test_constraint_objects() {
+        use crate::constraint::{LinearInequality, NormBall};
+        let op = PenaltyOp::new(Square {}, 1.0)
+            .unwrap()
+            .constraint(LinearInequality::new(vec![-1.0, 0.0], -1.0))
+            .constraint(NormBall::new(vec![0.0, 0.0], 2.0).unwrap())
+            .inequality(|x: &Vec<f64>| Ok(x[1] - 1.0));
+        assert!(op.violated(&vec![1.0, 0.0], 1e-12).unwrap().is_empty());
+        assert_eq!(
+            op.violated(&vec![0.0, 3.0], 1e-12).unwrap(),
+            vec![
+                "linear inequality".to_string(),
+                "norm ball".to_string(),
+                "inequality 2".to_string()
+            ]
+        );
+        // x = (0, 0) violates x_0 >= 1 by 1
+        assert!((op.penalty(&vec![0.0, 0.0]).unwrap() - 1.0).abs() < std::f64::EPSILON);
+    }
+}
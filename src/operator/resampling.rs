@@ -0,0 +1,299 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Resampling of noisy cost functions
+//!
+//! Stochastic cost functions (for instance Monte Carlo estimates) are often too noisy to be
+//! optimized directly. `ResamplingOp` evaluates the wrapped cost function `k` times per call to
+//! `apply` and returns the mean, which reduces the variance by a factor of `k`.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Hook which is called before every evaluation of the wrapped operator
+type SampleHook<O> = Arc<dyn Fn(&O, u64) + Send + Sync>;
+
+/// Number of samples per evaluation depending on the precision level `l`
+///
+/// * `ResamplingSchedule::Constant(k)`: `k`
+/// * `ResamplingSchedule::Linear { initial, increment }`: `initial + increment * l`
+/// * `ResamplingSchedule::Geometric { initial, factor }`: `ceil(initial * factor^l)`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum ResamplingSchedule {
+    /// Same number of samples for all precision levels
+    Constant(u64),
+    /// Linearly increasing number of samples
+    Linear {
+        /// number of samples at precision level 0
+        initial: u64,
+        /// additional samples per precision level
+        increment: u64,
+    },
+    /// Geometrically increasing number of samples
+    Geometric {
+        /// number of samples at precision level 0
+        initial: u64,
+        /// growth factor per precision level
+        factor: f64,
+    },
+}
+
+impl ResamplingSchedule {
+    /// Number of samples at precision level `level`
+    pub fn samples(&self, level: u64) -> u64 {
+        match *self {
+            ResamplingSchedule::Constant(k) => k,
+            ResamplingSchedule::Linear { initial, increment } => {
+                initial.saturating_add(increment.saturating_mul(level))
+            }
+            ResamplingSchedule::Geometric { initial, factor } => {
+                ((initial as f64) * factor.powf(level as f64)).ceil() as u64
+            }
+        }
+    }
+}
+
+/// Wraps a noisy operator and returns the mean of `k` evaluations of the cost function.
+///
+/// The wrapped operator must be re-entrant, i.e. repeated evaluations at the same parameter
+/// vector must be independent samples. If the operator draws its noise from a random number
+/// generator, the generator can be reseeded before every evaluation via `sample_hook`.
+///
+/// The number of samples `k` is determined by a `ResamplingSchedule` and the current precision
+/// level which a solver (or the user) can increase via `set_precision` as the optimization
+/// progresses. The number of evaluations of the wrapped operator is available via
+/// `num_evaluations` and is shared between all clones of a `ResamplingOp`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ResamplingOp<O> {
+    /// wrapped operator
+    op: O,
+    /// number of samples per precision level
+    schedule: ResamplingSchedule,
+    /// current precision level
+    level: u64,
+    /// Hook which is called before each evaluation with the index of the evaluation
+    #[serde(skip)]
+    hook: Option<SampleHook<O>>,
+    /// Number of evaluations of the wrapped operator
+    #[serde(skip)]
+    evaluations: Arc<AtomicU64>,
+}
+
+impl<O> ResamplingOp<O> {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `op`: operator to be wrapped
+    /// * `k`: number of samples per evaluation (must be > 0)
+    pub fn new(op: O, k: u64) -> Result<Self, Error> {
+        ResamplingOp::with_schedule(op, ResamplingSchedule::Constant(k))
+    }
+
+    /// Constructor with a number of samples which depends on the precision level
+    pub fn with_schedule(op: O, schedule: ResamplingSchedule) -> Result<Self, Error> {
+        let valid = match schedule {
+            ResamplingSchedule::Constant(k) => k > 0,
+            ResamplingSchedule::Linear { initial, .. } => initial > 0,
+            ResamplingSchedule::Geometric { initial, factor } => initial > 0 && factor >= 1.0,
+        };
+        if !valid {
+            return Err(ArgminError::InvalidParameter {
+                text: "ResamplingOp: schedule must yield at least one sample per evaluation and \
+                       must not decrease."
+                    .to_string(),
+            }
+            .into());
+        }
+        Ok(ResamplingOp {
+            op,
+            schedule,
+            level: 0,
+            hook: None,
+            evaluations: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Set a hook which is called before each evaluation of the wrapped operator. The second
+    /// argument is the (unique) index of the evaluation and can be used to reseed a random number
+    /// generator.
+    pub fn sample_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&O, u64) + Send + Sync + 'static,
+    {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set the precision level, which determines the number of samples per evaluation
+    pub fn set_precision(&mut self, level: u64) {
+        self.level = level;
+    }
+
+    /// Return the current precision level
+    pub fn precision(&self) -> u64 {
+        self.level
+    }
+
+    /// Number of samples per evaluation at the current precision level
+    pub fn samples(&self) -> u64 {
+        self.schedule.samples(self.level)
+    }
+
+    /// Total number of evaluations of the wrapped operator
+    pub fn num_evaluations(&self) -> u64 {
+        self.evaluations.load(Ordering::SeqCst)
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+}
+
+impl<O> ArgminOp for ResamplingOp<O>
+where
+    O: ArgminOp<Output = f64>,
+{
+    type Param = O::Param;
+    type Output = f64;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        let k = self.samples();
+        let mut sum = 0.0;
+        for _ in 0..k {
+            let idx = self.evaluations.fetch_add(1, Ordering::SeqCst);
+            if let Some(ref hook) = self.hook {
+                hook(&self.op, idx);
+            }
+            sum += self.op.apply(param)?;
+        }
+        Ok(sum / k as f64)
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        self.op.gradient(param)
+    }
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        self.op.hessian(param)
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        self.op.modify(param, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use std::sync::Mutex;
+
+    /// `x^2` plus uniform noise in `[-1, 1)`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Noisy {
+        #[serde(skip, default = "default_rng")]
+        rng: Arc<Mutex<XorShiftRng>>,
+    }
+
+    fn default_rng() -> Arc<Mutex<XorShiftRng>> {
+        Arc::new(Mutex::new(XorShiftRng::seed_from_u64(0)))
+    }
+
+    impl ArgminOp for Noisy {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            let noise: f64 = self.rng.lock().unwrap().gen_range(-1.0, 1.0);
+            Ok(p[0].powi(2) + noise)
+        }
+    }
+
+    send_sync_test!(resampling_op, ResamplingOp<Noisy>);
+
+    fn variance(op: &ResamplingOp<Noisy>, n: usize) -> f64 {
+        let values: Vec<f64> = (0..n).map(|_| op.apply(&vec![0.0]).unwrap()).collect();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        let op = Noisy { rng: default_rng() };
+        assert!(ResamplingOp::new(op.clone(), 0).is_err());
+        let schedule = ResamplingSchedule::Geometric {
+            initial: 1,
+            factor: 0.5,
+        };
+        assert!(ResamplingOp::with_schedule(op, schedule).is_err());
+    }
+
+    #[test]
+    fn test_variance_reduction() {
+        let single = ResamplingOp::new(Noisy { rng: default_rng() }, 1).unwrap();
+        let averaged = ResamplingOp::new(Noisy { rng: default_rng() }, 16).unwrap();
+        let var_single = variance(&single, 500);
+        let var_averaged = variance(&averaged, 500);
+        // expected ratio: 16
+        assert!(var_single / var_averaged > 8.0);
+        assert_eq!(single.num_evaluations(), 500);
+        assert_eq!(averaged.num_evaluations(), 500 * 16);
+    }
+
+    #[test]
+    fn test_schedule_accounting() {
+        let schedule = ResamplingSchedule::Linear {
+            initial: 1,
+            increment: 2,
+        };
+        let mut op = ResamplingOp::with_schedule(Noisy { rng: default_rng() }, schedule).unwrap();
+        let mut expected = 0;
+        for level in 0..4 {
+            op.set_precision(level);
+            assert_eq!(op.samples(), 1 + 2 * level);
+            op.apply(&vec![1.0]).unwrap();
+            expected += 1 + 2 * level;
+        }
+        assert_eq!(op.num_evaluations(), expected);
+        // clones share the counter
+        let cloned = op.clone();
+        cloned.apply(&vec![1.0]).unwrap();
+        assert_eq!(op.num_evaluations(), expected + 7);
+    }
+
+    #[test]
+    fn test_sample_hook_reseeds() {
+        let op = ResamplingOp::new(Noisy { rng: default_rng() }, 4)
+            .unwrap()
+            .sample_hook(|op: &Noisy, idx| {
+                *op.rng.lock().unwrap() = XorShiftRng::seed_from_u64(idx % 4);
+            });
+        // reseeding with the same sequence of seeds yields identical results
+        let a = op.apply(&vec![0.5]).unwrap();
+        let b = op.apply(&vec![0.5]).unwrap();
+        assert!((a - b).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_geometric_schedule() {
+        let schedule = ResamplingSchedule::Geometric {
+            initial: 2,
+            factor: 1.5,
+        };
+        assert_eq!(schedule.samples(0), 2);
+        assert_eq!(schedule.samples(1), 3);
+        assert_eq!(schedule.samples(2), 5);
+    }
+}
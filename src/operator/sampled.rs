@@ -0,0 +1,237 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Mini-batches
+//!
+//! Cost functions of the form `f(x) = 1/N * sum_i f_i(x)` (for instance the loss over a training
+//! set of `N` samples) allow stochastic methods to work with the gradient of a random subset
+//! ("mini-batch") of the samples. `ArgminSampledOp` is the interface for such problems and
+//! `MiniBatches` produces reproducible random mini-batches.
+
+use crate::prelude::*;
+use rand::prelude::*;
+use rand::seq::SliceRandom;
+use rand_xorshift::XorShiftRng;
+use serde::{Deserialize, Serialize};
+
+/// Problems whose cost function is the mean over `num_samples()` individual samples.
+pub trait ArgminSampledOp: ArgminOp {
+    /// Number of samples
+    fn num_samples(&self) -> usize;
+
+    /// Mean gradient of the samples with the given `indices`
+    fn sample_gradient(&self, param: &Self::Param, indices: &[usize])
+        -> Result<Self::Param, Error>;
+
+    /// Gradient over all samples. Deterministic solvers can use this as the gradient of the
+    /// problem.
+    fn full_gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        let indices: Vec<usize> = (0..self.num_samples()).collect();
+        self.sample_gradient(param, &indices)
+    }
+}
+
+/// Produces random mini-batches of sample indices.
+///
+/// In every epoch, the indices `0..num_samples` are shuffled and split into batches of size
+/// `batch_size`. If `num_samples` is not divisible by `batch_size`, the last batch of an epoch is
+/// smaller. Every index is therefore contained in exactly one batch per epoch. The sequence of
+/// batches is fully determined by the seed.
+///
+/// `MiniBatches` is an endless iterator over batches; `epoch` returns all batches of the next
+/// epoch at once.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MiniBatches {
+    /// number of samples
+    num_samples: usize,
+    /// batch size
+    batch_size: usize,
+    /// shuffled indices of the current epoch
+    indices: Vec<usize>,
+    /// position of the next batch in `indices`
+    pos: usize,
+    /// number of started epochs
+    epoch: u64,
+    /// random number generator
+    rng: XorShiftRng,
+}
+
+impl MiniBatches {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `num_samples`: number of samples
+    /// * `batch_size`: number of samples per batch
+    /// * `seed`: seed of the random number generator
+    pub fn new(num_samples: usize, batch_size: usize, seed: u64) -> Result<Self, Error> {
+        if num_samples == 0 || batch_size == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "MiniBatches: num_samples and batch_size must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(MiniBatches {
+            num_samples,
+            batch_size,
+            indices: (0..num_samples).collect(),
+            pos: num_samples,
+            epoch: 0,
+            rng: XorShiftRng::seed_from_u64(seed),
+        })
+    }
+
+    /// Number of batches per epoch
+    pub fn batches_per_epoch(&self) -> usize {
+        (self.num_samples + self.batch_size - 1) / self.batch_size
+    }
+
+    /// Number of started epochs
+    pub fn num_epochs(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Return all batches of the next epoch. A partially consumed epoch is discarded.
+    pub fn epoch(&mut self) -> Vec<Vec<usize>> {
+        self.shuffle();
+        let batches: Vec<Vec<usize>> = self
+            .indices
+            .chunks(self.batch_size)
+            .map(|c| c.to_vec())
+            .collect();
+        self.pos = self.num_samples;
+        batches
+    }
+
+    fn shuffle(&mut self) {
+        self.indices.shuffle(&mut self.rng);
+        self.pos = 0;
+        self.epoch += 1;
+    }
+}
+
+impl Iterator for MiniBatches {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if self.pos >= self.num_samples {
+            self.shuffle();
+        }
+        let end = (self.pos + self.batch_size).min(self.num_samples);
+        let batch = self.indices[self.pos..end].to_vec();
+        self.pos = end;
+        Some(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(minibatches, MiniBatches);
+
+    /// Linear least squares `1/N * sum_i 0.5 * (a_i^T x - b_i)^2`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Linear {
+        a: Vec<Vec<f64>>,
+        b: Vec<f64>,
+    }
+
+    impl ArgminOp for Linear {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+    }
+
+    impl ArgminSampledOp for Linear {
+        fn num_samples(&self) -> usize {
+            self.b.len()
+        }
+
+        fn sample_gradient(&self, x: &Vec<f64>, indices: &[usize]) -> Result<Vec<f64>, Error> {
+            let mut g = vec![0.0; x.len()];
+            for &i in indices {
+                let r: f64 = self.a[i]
+                    .iter()
+                    .zip(x.iter())
+                    .map(|(a, x)| a * x)
+                    .sum::<f64>()
+                    - self.b[i];
+                for (gj, aij) in g.iter_mut().zip(self.a[i].iter()) {
+                    *gj += r * aij / indices.len() as f64;
+                }
+            }
+            Ok(g)
+        }
+    }
+
+    fn linear() -> Linear {
+        let a = (0..11)
+            .map(|i| vec![1.0, i as f64, (i as f64).sin()])
+            .collect();
+        let b = (0..11).map(|i| (i as f64).cos()).collect();
+        Linear { a, b }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(MiniBatches::new(0, 1, 0).is_err());
+        assert!(MiniBatches::new(1, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_each_index_once_per_epoch() {
+        let mut batches = MiniBatches::new(11, 3, 42).unwrap();
+        assert_eq!(batches.batches_per_epoch(), 4);
+        for _ in 0..5 {
+            let epoch = batches.epoch();
+            assert_eq!(epoch.len(), 4);
+            assert_eq!(epoch[3].len(), 2);
+            let mut all: Vec<usize> = epoch.into_iter().flatten().collect();
+            all.sort();
+            assert_eq!(all, (0..11).collect::<Vec<usize>>());
+        }
+        assert_eq!(batches.num_epochs(), 5);
+    }
+
+    #[test]
+    fn test_iterator_matches_epochs() {
+        let mut a = MiniBatches::new(11, 3, 7).unwrap();
+        let b = MiniBatches::new(11, 3, 7).unwrap();
+        let expected: Vec<Vec<usize>> = a.epoch().into_iter().chain(a.epoch()).collect();
+        let got: Vec<Vec<usize>> = b.take(8).collect();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let a: Vec<Vec<usize>> = MiniBatches::new(20, 6, 3).unwrap().take(10).collect();
+        let b: Vec<Vec<usize>> = MiniBatches::new(20, 6, 3).unwrap().take(10).collect();
+        let c: Vec<Vec<usize>> = MiniBatches::new(20, 6, 4).unwrap().take(10).collect();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_batch_gradients_sum_to_full_gradient() {
+        let op = linear();
+        let x = vec![0.3, -0.1, 2.0];
+        let full = op.full_gradient(&x).unwrap();
+        let mut sum = vec![0.0; 3];
+        for batch in MiniBatches::new(11, 4, 1).unwrap().epoch() {
+            let g = op.sample_gradient(&x, &batch).unwrap();
+            let w = batch.len() as f64 / op.num_samples() as f64;
+            for (s, gi) in sum.iter_mut().zip(g.iter()) {
+                *s += w * gi;
+            }
+        }
+        for (a, b) in sum.iter().zip(full.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Variable scaling
+//!
+//! Badly scaled parameters (e.g. components ranging from `1e-9` to `1e3`) lead to ill-conditioned
+//! problems. `ScaledOp` presents the solver with the affinely transformed variables
+//!
+//! `y = (x - offset) / scale`
+//!
+//! and maps them back to `x = scale * y + offset` before evaluating the wrapped operator.
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::math::{ArgminDiagCongruence, ArgminElementwise, ArgminShape};
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Wraps an operator such that the solver works on scaled variables `y = (x - offset) / scale`.
+///
+/// * `apply(y) = f(scale * y + offset)`
+/// * `gradient(y) = scale * \nabla f(x)` (chain rule)
+/// * `hessian(y) = diag(scale) * H(x) * diag(scale)`
+///
+/// Use `scale_param` to transform the initial parameter vector and `unscale_result` to obtain the
+/// result in the original variables.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScaledOp<O: ArgminOp> {
+    /// wrapped operator
+    op: O,
+    /// per-component scale
+    scale: O::Param,
+    /// optional per-component offset
+    offset: Option<O::Param>,
+}
+
+/// Result of an optimization run on a `ScaledOp`, transformed back to the original variables.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScaledResult<P, U> {
+    /// Parameter vector in the original variables
+    pub param: P,
+    /// Parameter vector in the scaled variables (as seen by the solver)
+    pub scaled_param: P,
+    /// Cost function value
+    pub cost: U,
+}
+
+impl<O> ScaledOp<O>
+where
+    O: ArgminOp,
+    O::Param: ArgminElementwise
+        + ArgminAdd<O::Param, O::Param>
+        + ArgminSub<O::Param, O::Param>
+        + ArgminShape,
+{
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `op`: operator to be wrapped
+    /// * `scale`: per-component scale (finite and nonzero)
+    pub fn new(op: O, scale: O::Param) -> Result<Self, Error> {
+        // `scale_i / scale_i` is `NaN` for zero and non-finite entries
+        if !scale.div_elementwise(&scale).all_finite() {
+            return Err(ArgminError::InvalidParameter {
+                text: "ScaledOp: scale must be finite and nonzero.".to_string(),
+            }
+            .into());
+        }
+        Ok(ScaledOp {
+            op,
+            scale,
+            offset: None,
+        })
+    }
+
+    /// Set per-component offset, which must have the same shape as the scale
+    pub fn offset(mut self, offset: O::Param) -> Result<Self, Error> {
+        if offset.shape() != self.scale.shape() {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "ScaledOp: offset has shape {:?} but scale has shape {:?}.",
+                    offset.shape(),
+                    self.scale.shape()
+                ),
+            }
+            .into());
+        }
+        if !offset.all_finite() {
+            return Err(ArgminError::InvalidParameter {
+                text: "ScaledOp: offset must be finite.".to_string(),
+            }
+            .into());
+        }
+        self.offset = Some(offset);
+        Ok(self)
+    }
+}
+
+impl<O> ScaledOp<O>
+where
+    O: ArgminOp,
+    O::Param: ArgminElementwise + ArgminAdd<O::Param, O::Param> + ArgminSub<O::Param, O::Param>,
+{
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    /// Transform a parameter vector from the original to the scaled variables:
+    /// `y = (x - offset) / scale`. This is used to obtain the initial parameter vector.
+    pub fn scale_param(&self, x: &O::Param) -> O::Param {
+        match self.offset {
+            Some(ref offset) => x.sub(offset).div_elementwise(&self.scale),
+            None => x.div_elementwise(&self.scale),
+        }
+    }
+
+    /// Transform a parameter vector from the scaled to the original variables:
+    /// `x = scale * y + offset`.
+    pub fn unscale_param(&self, y: &O::Param) -> O::Param {
+        let x = y.mul_elementwise(&self.scale);
+        match self.offset {
+            Some(ref offset) => x.add(offset),
+            None => x,
+        }
+    }
+
+    /// Transform the final parameter vector of a run back to the original variables.
+    pub fn unscale_result(
+        &self,
+        scaled_param: O::Param,
+        cost: O::Output,
+    ) -> ScaledResult<O::Param, O::Output> {
+        ScaledResult {
+            param: self.unscale_param(&scaled_param),
+            scaled_param,
+            cost,
+        }
+    }
+}
+
+impl<O> ArgminOp for ScaledOp<O>
+where
+    O: ArgminOp,
+    O::Param: ArgminElementwise + ArgminAdd<O::Param, O::Param> + ArgminSub<O::Param, O::Param>,
+    O::Hessian: ArgminDiagCongruence<O::Param>,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, y: &Self::Param) -> Result<Self::Output, Error> {
+        self.op.apply(&self.unscale_param(y))
+    }
+
+    fn gradient(&self, y: &Self::Param) -> Result<Self::Param, Error> {
+        Ok(self
+            .op
+            .gradient(&self.unscale_param(y))?
+            .mul_elementwise(&self.scale))
+    }
+
+    fn hessian(&self, y: &Self::Param) -> Result<Self::Hessian, Error> {
+        Ok(self
+            .op
+            .hessian(&self.unscale_param(y))?
+            .diag_congruence(&self.scale))
+    }
+
+    fn modify(&self, y: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        Ok(self.scale_param(&self.op.modify(&self.unscale_param(y), extent)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::landweber::Landweber;
+
+    /// `f(x) = 0.5 * sum_i d_i * (x_i - c_i)^2`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Quadratic {
+        d: Vec<f64>,
+        c: Vec<f64>,
+    }
+
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p.iter()
+                .zip(self.d.iter().zip(self.c.iter()))
+                .map(|(x, (d, c))| 0.5 * d * (x - c).powi(2))
+                .sum())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.iter()
+                .zip(self.d.iter().zip(self.c.iter()))
+                .map(|(x, (d, c))| d * (x - c))
+                .collect())
+        }
+
+        fn hessian(&self, _p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            let n = self.d.len();
+            Ok((0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| if i == j { self.d[i] } else { 0.0 })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    fn problem() -> Quadratic {
+        Quadratic {
+            d: vec![1e8, 1.0],
+            c: vec![1e-3, 2.0],
+        }
+    }
+
+    send_sync_test!(scaled_op, ScaledOp<Quadratic>);
+
+    #[test]
+    fn test_scale_roundtrip() {
+        let op = ScaledOp::new(problem(), vec![1e-4, 10.0])
+            .unwrap()
+            .offset(vec![1.0, -3.0])
+            .unwrap();
+        let x = vec![0.5, 7.0];
+        let y = op.scale_param(&x);
+        let x2 = op.unscale_param(&y);
+        assert!((x[0] - x2[0]).abs() < 1e-12);
+        assert!((x[1] - x2[1]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scaled_derivatives() {
+        let scale = vec![1e-4, 1.0];
+        let op = ScaledOp::new(problem(), scale)
+            .unwrap()
+            .offset(vec![0.0, 1.0])
+            .unwrap();
+        let y = vec![3.0, 4.0];
+        // curvature in the scaled variables is 1e8 * 1e-8 = 1 and 1 * 1 = 1
+        let h = op.hessian(&y).unwrap();
+        assert!((h[0][0] - 1.0).abs() < 1e-8);
+        assert!((h[1][1] - 1.0).abs() < 1e-12);
+        let g = op.gradient(&y).unwrap();
+        let x = op.unscale_param(&y);
+        assert!((g[0] - 1e-4 * 1e8 * (x[0] - 1e-3)).abs() < 1e-8);
+        assert!((g[1] - (x[1] - 2.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scaled_gradient_descent() {
+        // condition number 1e8 in the original variables, 1 in the scaled variables
+        let op = ScaledOp::new(problem(), vec![1e-4, 1.0]).unwrap();
+        let init_param = op.scale_param(&vec![0.0, 0.0]);
+        let res = Executor::new(op.clone(), Landweber::new(1.0).unwrap(), init_param)
+            .max_iters(3)
+            .run_fast()
+            .unwrap();
+        let res = op.unscale_result(res.param, res.cost);
+        assert!((res.param[0] - 1e-3).abs() < 1e-12);
+        assert!((res.param[1] - 2.0).abs() < 1e-12);
+        assert!(res.cost.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scaled_invalid() {
+        for scale in [
+            vec![0.0, 1.0],
+            vec![1.0, std::f64::NAN],
+            vec![std::f64::INFINITY, 1.0],
+        ]
+        .iter()
+        {
+            let e = ScaledOp::new(problem(), scale.clone()).err().unwrap();
+            assert!(e.to_string().contains("scale"), "{}", e);
+        }
+        let op = ScaledOp::new(problem(), vec![1e-4, 1.0]).unwrap();
+        let e = op.clone().offset(vec![1.0]).err().unwrap();
+        assert!(e.to_string().contains("offset has shape"), "{}", e);
+        assert!(op.offset(vec![std::f64::NAN, 0.0]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Sparse linear operators
+//!
+//! Large sparse matrices (Hessians, Jacobians, system matrices) must never be stored densely.
+//! Solvers which only need matrix-vector products (such as the conjugate gradient method) work
+//! directly on a `SparseLinearOp`, which stores a `sprs` matrix in compressed sparse row format
+//! and implements `apply` as a sparse matrix-vector product on `Vec<f64>`.
+//!
+//! Only available with the `sprs` feature.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Sparse matrix `A` in compressed sparse row format, applied as `x -> A * x`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SparseLinearOp {
+    /// number of rows
+    nrows: usize,
+    /// number of columns
+    ncols: usize,
+    /// index of the first entry of each row in `indices` and `data` (length `nrows + 1`)
+    indptr: Vec<usize>,
+    /// column indices of the entries
+    indices: Vec<usize>,
+    /// values of the entries
+    data: Vec<f64>,
+}
+
+impl SparseLinearOp {
+    /// Constructor
+    pub fn new(a: &sprs::CsMat<f64>) -> Self {
+        let a = a.to_csr();
+        let (nrows, ncols) = a.shape();
+        let mut indptr = Vec::with_capacity(nrows + 1);
+        let mut indices = Vec::with_capacity(a.nnz());
+        let mut data = Vec::with_capacity(a.nnz());
+        indptr.push(0);
+        for row in a.outer_iterator() {
+            for (j, v) in row.iter() {
+                indices.push(j);
+                data.push(*v);
+            }
+            indptr.push(indices.len());
+        }
+        SparseLinearOp {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        }
+    }
+
+    /// Shape `(nrows, ncols)` of the matrix
+    pub fn shape(&self) -> (usize, usize) {
+        (self.nrows, self.ncols)
+    }
+
+    /// Number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl ArgminOp for SparseLinearOp {
+    type Param = Vec<f64>;
+    type Output = Vec<f64>;
+    type Hessian = ();
+
+    fn apply(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        if x.len() != self.ncols {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "SparseLinearOp: matrix has {} columns but vector has {} entries.",
+                    self.ncols,
+                    x.len()
+                ),
+            }
+            .into());
+        }
+        Ok(self
+            .indptr
+            .windows(2)
+            .map(|w| {
+                self.indices[w[0]..w[1]]
+                    .iter()
+                    .zip(self.data[w[0]..w[1]].iter())
+                    .map(|(j, v)| v * x[*j])
+                    .sum()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::conjugategradient::ConjugateGradient;
+
+    send_sync_test!(sparse_linear_op, SparseLinearOp);
+
+    /// `I + lambda * D^T D`, where `D` is the 1D forward difference operator
+    fn laplacian_regularized(n: usize, lambda: f64) -> sprs::CsMat<f64> {
+        let mut tri = sprs::TriMat::new((n, n));
+        for i in 0..n {
+            let neighbors = if i == 0 || i == n - 1 { 1.0 } else { 2.0 };
+            tri.add_triplet(i, i, 1.0 + lambda * neighbors);
+            if i + 1 < n {
+                tri.add_triplet(i, i + 1, -lambda);
+                tri.add_triplet(i + 1, i, -lambda);
+            }
+        }
+        tri.to_csr()
+    }
+
+    #[test]
+    fn test_apply() {
+        let op = SparseLinearOp::new(&laplacian_regularized(3, 1.0));
+        assert_eq!(op.shape(), (3, 3));
+        assert_eq!(op.nnz(), 7);
+        assert_eq!(op.apply(&vec![1.0, 2.0, 3.0]).unwrap(), vec![0.0, 2.0, 4.0]);
+        assert!(op.apply(&vec![1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_regularized_least_squares() {
+        // Denoising `y` with a smoothness penalty leads to the linear system
+        // `(I + lambda * D^T D) x = y`, which is solved with CG on a problem that would need
+        // 800 MB as a dense matrix.
+        let n = 10_000;
+        let a = laplacian_regularized(n, 10.0);
+        let y: Vec<f64> = (0..n)
+            .map(|i| (i as f64 / 100.0).sin() + if i % 2 == 0 { 0.1 } else { -0.1 })
+            .collect();
+        let op = SparseLinearOp::new(&a);
+        let solver = ConjugateGradient::new(y.clone()).unwrap();
+        let res = Executor::new(op.clone(), solver, vec![0.0; n])
+            .max_iters(200)
+            .target_cost(1e-10)
+            .run_fast()
+            .unwrap();
+        let residual: f64 = op
+            .apply(&res.param)
+            .unwrap()
+            .iter()
+            .zip(y.iter())
+            .map(|(ax, y)| (ax - y).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let norm_y: f64 = y.iter().map(|y| y.powi(2)).sum::<f64>().sqrt();
+        assert!(residual < 1e-8 * norm_y, "residual: {}", residual);
+    }
+}
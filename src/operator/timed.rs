@@ -0,0 +1,335 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Timing of operator evaluations
+//!
+//! `TimedOp` measures how much time is spent in the cost function, the gradient, the Hessian and
+//! `modify` of the wrapped operator.
+//!
+//! By default, durations are measured with `std::time::Instant`. On platforms without a clock
+//! (such as `wasm32-unknown-unknown`, see the `wasm` feature) a clock can be provided via
+//! `TimedOp::clock`.
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+
+/// Clock which returns the time elapsed since an arbitrary, but fixed, point in time (for
+/// instance `performance.now()` in a browser)
+pub type Clock = fn() -> Duration;
+
+/// Accumulated number of calls and time of one kind of operator method
+#[derive(Default, Debug)]
+struct MethodTiming {
+    /// number of calls
+    calls: AtomicU64,
+    /// total duration in nanoseconds
+    nanos: AtomicU64,
+}
+
+impl MethodTiming {
+    fn record(&self, duration: Duration) {
+        let nanos = duration.as_secs() * 1_000_000_000 + u64::from(duration.subsec_nanos());
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        self.nanos.fetch_add(nanos, Ordering::SeqCst);
+    }
+
+    fn calls(&self) -> u64 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+/// Timings of all methods of an operator
+#[derive(Default, Debug)]
+struct OpTimings {
+    apply: MethodTiming,
+    gradient: MethodTiming,
+    hessian: MethodTiming,
+    modify: MethodTiming,
+}
+
+/// Method of an operator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpMethod {
+    /// `apply`
+    Apply,
+    /// `gradient`
+    Gradient,
+    /// `hessian`
+    Hessian,
+    /// `modify`
+    Modify,
+}
+
+/// Wraps an operator and records the number of calls and the accumulated time spent in `apply`,
+/// `gradient`, `hessian` and `modify`.
+///
+/// Timing is enabled by default and can be switched off with `timing(false)`, in which case no
+/// calls to the clock are made and all timings remain zero. With the `wasm` feature, durations
+/// are only measured if a clock was set via `clock`; otherwise only the calls are counted. The recorded timings are shared
+/// between all clones of a `TimedOp`, therefore they also include the evaluations performed by the
+/// `Executor` on its own copy of the operator.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimedOp<O> {
+    /// wrapped operator
+    op: O,
+    /// whether timing is enabled
+    enabled: bool,
+    /// clock used instead of `Instant`
+    #[serde(skip)]
+    clock: Option<Clock>,
+    /// timings
+    #[serde(skip)]
+    timings: Arc<OpTimings>,
+}
+
+impl<O> TimedOp<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        TimedOp {
+            op,
+            enabled: true,
+            clock: None,
+            timings: Arc::new(OpTimings::default()),
+        }
+    }
+
+    /// Enable or disable timing
+    pub fn timing(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Measure durations with `clock` instead of `std::time::Instant`
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Return a reference to the wrapped operator
+    pub fn inner(&self) -> &O {
+        &self.op
+    }
+
+    fn timing_of(&self, method: OpMethod) -> &MethodTiming {
+        match method {
+            OpMethod::Apply => &self.timings.apply,
+            OpMethod::Gradient => &self.timings.gradient,
+            OpMethod::Hessian => &self.timings.hessian,
+            OpMethod::Modify => &self.timings.modify,
+        }
+    }
+
+    /// Total time spent in `method`
+    pub fn duration(&self, method: OpMethod) -> Duration {
+        self.timing_of(method).duration()
+    }
+
+    /// Number of timed calls of `method`
+    pub fn calls(&self, method: OpMethod) -> u64 {
+        self.timing_of(method).calls()
+    }
+
+    /// Snapshot of the timings (in seconds) and the number of calls which can be attached to the
+    /// `ArgminIterData` of an iteration.
+    pub fn kv(&self) -> ArgminKV {
+        fn secs(d: Duration) -> f64 {
+            d.as_secs() as f64 + f64::from(d.subsec_nanos()) * 1e-9
+        }
+        make_kv!(
+            "t_apply" => secs(self.duration(OpMethod::Apply));
+            "t_grad" => secs(self.duration(OpMethod::Gradient));
+            "t_hess" => secs(self.duration(OpMethod::Hessian));
+            "t_modify" => secs(self.duration(OpMethod::Modify));
+            "n_apply" => self.calls(OpMethod::Apply);
+            "n_grad" => self.calls(OpMethod::Gradient);
+            "n_hess" => self.calls(OpMethod::Hessian);
+            "n_modify" => self.calls(OpMethod::Modify);
+        )
+    }
+
+    fn time<T, F: FnOnce(&O) -> T>(&self, method: OpMethod, f: F) -> T {
+        if !self.enabled {
+            return f(&self.op);
+        }
+        if let Some(clock) = self.clock {
+            let start = clock();
+            let out = f(&self.op);
+            let elapsed = clock().checked_sub(start).unwrap_or_default();
+            self.timing_of(method).record(elapsed);
+            return out;
+        }
+        self.time_default(method, f)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn time_default<T, F: FnOnce(&O) -> T>(&self, method: OpMethod, f: F) -> T {
+        let start = Instant::now();
+        let out = f(&self.op);
+        self.timing_of(method).record(start.elapsed());
+        out
+    }
+
+    /// Without `Instant`, only the call is counted.
+    #[cfg(feature = "wasm")]
+    fn time_default<T, F: FnOnce(&O) -> T>(&self, method: OpMethod, f: F) -> T {
+        let out = f(&self.op);
+        self.timing_of(method).record(Duration::from_secs(0));
+        out
+    }
+}
+
+impl<O> fmt::Display for TimedOp<O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>10} {:>10} {:>16}", "method", "calls", "time")?;
+        for (name, method) in [
+            ("apply", OpMethod::Apply),
+            ("gradient", OpMethod::Gradient),
+            ("hessian", OpMethod::Hessian),
+            ("modify", OpMethod::Modify),
+        ]
+        .iter()
+        {
+            writeln!(
+                f,
+                "{:>10} {:>10} {:>16?}",
+                name,
+                self.calls(*method),
+                self.duration(*method)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<O: ArgminOp> ArgminOp for TimedOp<O> {
+    type Param = O::Param;
+    type Output = O::Output;
+    type Hessian = O::Hessian;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        self.time(OpMethod::Apply, |op| op.apply(param))
+    }
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Param, Error> {
+        self.time(OpMethod::Gradient, |op| op.gradient(param))
+    }
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        self.time(OpMethod::Hessian, |op| op.hessian(param))
+    }
+
+    fn modify(&self, param: &Self::Param, extent: f64) -> Result<Self::Param, Error> {
+        self.time(OpMethod::Modify, |op| op.modify(param, extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Sleepy {}
+
+    fn sleep(ms: u64) {
+        std::thread::sleep(Duration::from_millis(ms));
+    }
+
+    impl ArgminOp for Sleepy {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, _p: &Vec<f64>) -> Result<f64, Error> {
+            sleep(1);
+            Ok(0.0)
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            sleep(5);
+            Ok(p.clone())
+        }
+
+        fn hessian(&self, _p: &Vec<f64>) -> Result<(), Error> {
+            sleep(20);
+            Ok(())
+        }
+    }
+
+    send_sync_test!(timed_op, TimedOp<Sleepy>);
+
+    fn evaluate(op: &TimedOp<Sleepy>) {
+        let p = vec![1.0];
+        for _ in 0..3 {
+            op.apply(&p).unwrap();
+            op.gradient(&p).unwrap();
+            op.hessian(&p).unwrap();
+        }
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    #[test]
+    fn test_timing_order() {
+        let op = TimedOp::new(Sleepy {});
+        evaluate(&op);
+        assert_eq!(op.calls(OpMethod::Apply), 3);
+        assert_eq!(op.calls(OpMethod::Gradient), 3);
+        assert_eq!(op.calls(OpMethod::Hessian), 3);
+        assert_eq!(op.calls(OpMethod::Modify), 0);
+        assert!(op.duration(OpMethod::Apply) >= Duration::from_millis(3));
+        assert!(op.duration(OpMethod::Apply) < op.duration(OpMethod::Gradient));
+        assert!(op.duration(OpMethod::Gradient) < op.duration(OpMethod::Hessian));
+    }
+
+    #[test]
+    fn test_timing_clock() {
+        // a clock which advances by one second per call
+        fn ticks() -> Duration {
+            static TICKS: AtomicU64 = AtomicU64::new(0);
+            Duration::from_secs(TICKS.fetch_add(1, Ordering::SeqCst))
+        }
+        let op = TimedOp::new(Sleepy {}).clock(ticks);
+        evaluate(&op);
+        assert_eq!(op.calls(OpMethod::Apply), 3);
+        assert_eq!(op.duration(OpMethod::Apply), Duration::from_secs(3));
+        assert_eq!(op.duration(OpMethod::Hessian), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_timing_shared_between_clones() {
+        let op = TimedOp::new(Sleepy {});
+        evaluate(&op.clone());
+        assert_eq!(op.calls(OpMethod::Apply), 3);
+    }
+
+    #[test]
+    fn test_timing_disabled() {
+        let op = TimedOp::new(Sleepy {}).timing(false);
+        evaluate(&op);
+        for method in [
+            OpMethod::Apply,
+            OpMethod::Gradient,
+            OpMethod::Hessian,
+            OpMethod::Modify,
+        ]
+        .iter()
+        {
+            assert_eq!(op.calls(*method), 0);
+            assert_eq!(op.duration(*method), Duration::from_secs(0));
+        }
+    }
+}
@@ -0,0 +1,931 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Random parameter vectors
+//!
+//! `ArgminParameter` generates random parameter vectors within bounds and randomly perturbs
+//! existing ones. This is typically needed for starting points of population based methods and
+//! for implementing `ArgminOp::modify` for Simulated Annealing.
+//!
+//! All methods take the random number generator as an argument. Using a seeded generator (for
+//! instance `XorShiftRng::seed_from_u64`) makes the generated parameter vectors reproducible.
+//! `random_thread` and `modify_thread` are convenience methods which use `rand::thread_rng()`
+//! (not available with the `wasm` feature, since they require the system's entropy source).
+//!
+//! The size and distribution of the steps taken by `modify` are chosen with `Perturbation`, the
+//! number of components perturbed per call with `Components`.
+
+use crate::math::ArgminMinMax;
+use crate::prelude::*;
+use rand::distributions::StandardNormal;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Distribution of the perturbation applied to a component `x_i` by `ArgminParameter::modify`.
+///
+/// The relative variants scale the perturbation by `|x_i|`. Components which are exactly zero are
+/// perturbed as if `|x_i| = 1`, otherwise they could never leave zero.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Perturbation {
+    /// Uniformly distributed in `[-delta, delta]`
+    UniformAbs(f64),
+    /// Uniformly distributed in `[-fraction * |x_i|, fraction * |x_i|]`
+    UniformRel(f64),
+    /// Normally distributed with standard deviation `sigma`
+    Gaussian(f64),
+    /// Normally distributed with standard deviation `fraction * |x_i|`
+    GaussianRel(f64),
+}
+
+impl Default for Perturbation {
+    fn default() -> Self {
+        Perturbation::UniformAbs(1.0)
+    }
+}
+
+impl Perturbation {
+    /// Draw the perturbation of a component with current value `x`
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R, x: f64) -> f64 {
+        let scale = if x.abs() > 0.0 { x.abs() } else { 1.0 };
+        match self {
+            Perturbation::UniformAbs(delta) => delta * rng.gen_range(-1.0, 1.0),
+            Perturbation::UniformRel(fraction) => fraction * scale * rng.gen_range(-1.0, 1.0),
+            Perturbation::Gaussian(sigma) => sigma * rng.sample::<f64, _>(StandardNormal),
+            Perturbation::GaussianRel(fraction) => {
+                fraction * scale * rng.sample::<f64, _>(StandardNormal)
+            }
+        }
+    }
+}
+
+/// Number of components perturbed by `ArgminParameter::modify`.
+///
+/// The perturbed components are sampled without replacement. At least one component is perturbed
+/// in any case, and never more than the parameter vector has.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Components {
+    /// A fixed number of components
+    Count(usize),
+    /// A fraction of all components (rounded to the nearest integer)
+    Fraction(f64),
+    /// All components
+    All,
+}
+
+impl Default for Components {
+    fn default() -> Self {
+        Components::Count(1)
+    }
+}
+
+impl Components {
+    /// Number of components to perturb in a parameter vector with `n` components
+    pub fn count(self, n: usize) -> usize {
+        let k = match self {
+            Components::Count(k) => k,
+            Components::Fraction(f) => (f * n as f64).round() as usize,
+            Components::All => n,
+        };
+        k.max(1).min(n)
+    }
+
+    /// Scale the number of components by `ratio` (typically in `[0, 1]`). This allows to perturb
+    /// many components early and few components late during an optimization run, for instance
+    /// by passing the ratio of the current and the initial temperature of Simulated Annealing
+    /// (which hands the current temperature to `ArgminOp::modify`).
+    pub fn annealed(self, ratio: f64) -> Self {
+        match self {
+            Components::Count(k) => Components::Count((k as f64 * ratio).round() as usize),
+            Components::Fraction(f) => Components::Fraction(f * ratio),
+            Components::All => Components::Fraction(ratio),
+        }
+    }
+}
+
+/// Random generation and perturbation of parameter vectors
+///
+/// Lower and upper bounds are optional. Components which are bounded on both sides are drawn
+/// uniformly from `[lower_i, upper_i)`. Equal bounds `lower_i == upper_i` are allowed and fix the
+/// component to this value. A component which is bounded only on one side is drawn
+/// from a half-normal distribution with unit scale, starting at the bound and extending into the
+/// feasible direction. For problems without any bounds, `random_around` draws from a standard
+/// normal distribution around a given center.
+pub trait ArgminParameter: Sized {
+    /// Draw a random parameter vector respecting the (optional) bounds. Returns an error if both
+    /// bounds are given and have different lengths or if `lower_i <= upper_i` does not hold for all
+    /// components, and if neither bound is given (use `random_around` in this case).
+    fn random<R: Rng + ?Sized>(
+        rng: &mut R,
+        lower: Option<&Self>,
+        upper: Option<&Self>,
+    ) -> Result<Self, Error>;
+
+    /// Draw a random parameter vector from a standard normal distribution centered at `center`
+    fn random_around<R: Rng + ?Sized>(rng: &mut R, center: &Self) -> Self;
+
+    /// Perturb randomly chosen components according to `perturbation` and clamp the result to the
+    /// bounds which are given. This is repeated until `constraint` is satisfied, for at most
+    /// `MAX_MODIFY_ATTEMPTS` attempts, after which an error is returned. Plain closures
+    /// `Fn(&Self) -> bool` can be passed as constraints, `Unconstrained` accepts everything.
+    /// `Perturbation::default()` adds a uniformly distributed value from `[-1, 1]` and
+    /// `Components::default()` perturbs a single component.
+    fn modify<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        lower: Option<&Self>,
+        upper: Option<&Self>,
+        perturbation: Perturbation,
+        components: Components,
+        constraint: &dyn Constraint<Self>,
+    ) -> Result<Self, Error>;
+
+    /// Same as `random`, using `rand::thread_rng()`
+    #[cfg(not(feature = "wasm"))]
+    fn random_thread(lower: Option<&Self>, upper: Option<&Self>) -> Result<Self, Error> {
+        Self::random(&mut rand::thread_rng(), lower, upper)
+    }
+
+    /// Same as `modify`, using `rand::thread_rng()`
+    #[cfg(not(feature = "wasm"))]
+    fn modify_thread(
+        &self,
+        lower: Option<&Self>,
+        upper: Option<&Self>,
+        perturbation: Perturbation,
+        components: Components,
+        constraint: &dyn Constraint<Self>,
+    ) -> Result<Self, Error> {
+        self.modify(
+            &mut rand::thread_rng(),
+            lower,
+            upper,
+            perturbation,
+            components,
+            constraint,
+        )
+    }
+}
+
+/// Checks that both bounds have the same length and that `lower_i <= upper_i` for all `i`. `NaN`
+/// bounds are rejected.
+fn check_bounds<'a, T, I>(lower: I, upper: I, len_l: usize, len_u: usize) -> Result<(), Error>
+where
+    T: 'a + PartialOrd,
+    I: Iterator<Item = &'a T>,
+{
+    if len_l != len_u {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "ArgminParameter: lower bound has {} components but upper bound has {}.",
+                len_l, len_u
+            ),
+        }
+        .into());
+    }
+    if let Some(i) = lower
+        .zip(upper)
+        .position(|(l, u)| l.partial_cmp(u).is_none() || l > u)
+    {
+        return Err(ArgminError::InvalidParameter {
+            text: format!(
+                "ArgminParameter: lower bound must not be greater than upper bound (component {}).",
+                i
+            ),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Error returned by `random` if neither bound is given
+fn unbounded_error() -> Error {
+    ArgminError::InvalidParameter {
+        text: "ArgminParameter: `random` requires at least one bound; use `random_around` for \
+               unbounded problems."
+            .to_string(),
+    }
+    .into()
+}
+
+/// Maximum number of perturbations `ArgminParameter::modify` tries before giving up on finding one
+/// which satisfies the constraint
+pub const MAX_MODIFY_ATTEMPTS: usize = 1000;
+
+/// Error returned by `modify` if no perturbation satisfied the constraint
+fn infeasible_error() -> Error {
+    ArgminError::InvalidParameter {
+        text: format!(
+            "ArgminParameter: no perturbation satisfying the constraint found in {} attempts.",
+            MAX_MODIFY_ATTEMPTS
+        ),
+    }
+    .into()
+}
+
+/// Indices of the components to perturb in a parameter vector with `n` components
+fn perturbed_indices<R: Rng + ?Sized>(rng: &mut R, n: usize, components: Components) -> Vec<usize> {
+    match components.count(n) {
+        1 => vec![rng.gen_range(0, n)],
+        k if k == n => (0..n).collect(),
+        k => rand::seq::index::sample(rng, n, k).into_vec(),
+    }
+}
+
+/// Clamp `param` to the bounds which are given
+fn clamp_opt<P: ArgminMinMax>(param: P, lower: Option<&P>, upper: Option<&P>) -> P {
+    let param = match lower {
+        Some(l) => ArgminMinMax::max(&param, l),
+        None => param,
+    };
+    match upper {
+        Some(u) => ArgminMinMax::min(&param, u),
+        None => param,
+    }
+}
+
+macro_rules! make_parameter {
+    ($t:ty, $sample:ident) => {
+        /// Sample a single component respecting the (optional) bounds
+        fn $sample<R: Rng + ?Sized>(rng: &mut R, lower: Option<$t>, upper: Option<$t>) -> $t {
+            match (lower, upper) {
+                (Some(l), Some(u)) if l < u => rng.gen_range(l, u),
+                (Some(l), Some(_)) => l,
+                (Some(l), None) => l + (rng.sample::<f64, _>(StandardNormal) as $t).abs(),
+                (None, Some(u)) => u - (rng.sample::<f64, _>(StandardNormal) as $t).abs(),
+                (None, None) => rng.sample::<f64, _>(StandardNormal) as $t,
+            }
+        }
+
+        impl ArgminParameter for $t {
+            fn random<R: Rng + ?Sized>(
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+            ) -> Result<Self, Error> {
+                if let (Some(l), Some(u)) = (lower, upper) {
+                    check_bounds(std::iter::once(l), std::iter::once(u), 1, 1)?;
+                }
+                if lower.is_none() && upper.is_none() {
+                    return Err(unbounded_error());
+                }
+                Ok($sample(rng, lower.cloned(), upper.cloned()))
+            }
+
+            fn random_around<R: Rng + ?Sized>(rng: &mut R, center: &Self) -> Self {
+                center + $sample(rng, None, None)
+            }
+
+            fn modify<R: Rng + ?Sized>(
+                &self,
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+                perturbation: Perturbation,
+                _components: Components,
+                constraint: &dyn Constraint<Self>,
+            ) -> Result<Self, Error> {
+                for _ in 0..MAX_MODIFY_ATTEMPTS {
+                    let param = self + perturbation.sample(rng, f64::from(*self)) as $t;
+                    let param = clamp_opt(param, lower, upper);
+                    if constraint.is_satisfied(&param) {
+                        return Ok(param);
+                    }
+                }
+                Err(infeasible_error())
+            }
+        }
+
+        impl ArgminParameter for Vec<$t> {
+            fn random<R: Rng + ?Sized>(
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+            ) -> Result<Self, Error> {
+                if let (Some(l), Some(u)) = (lower, upper) {
+                    check_bounds(l.iter(), u.iter(), l.len(), u.len())?;
+                }
+                let n = lower
+                    .or(upper)
+                    .map(|b| b.len())
+                    .ok_or_else(unbounded_error)?;
+                Ok((0..n)
+                    .map(|i| $sample(rng, lower.map(|l| l[i]), upper.map(|u| u[i])))
+                    .collect())
+            }
+
+            fn random_around<R: Rng + ?Sized>(rng: &mut R, center: &Self) -> Self {
+                center
+                    .iter()
+                    .map(|c| c + $sample(rng, None, None))
+                    .collect()
+            }
+
+            fn modify<R: Rng + ?Sized>(
+                &self,
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+                perturbation: Perturbation,
+                components: Components,
+                constraint: &dyn Constraint<Self>,
+            ) -> Result<Self, Error> {
+                for b in lower.iter().chain(upper.iter()) {
+                    assert_eq!(self.len(), b.len());
+                }
+                for _ in 0..MAX_MODIFY_ATTEMPTS {
+                    let mut param = self.clone();
+                    for idx in perturbed_indices(rng, self.len(), components) {
+                        param[idx] += perturbation.sample(rng, f64::from(param[idx])) as $t;
+                    }
+                    let param = clamp_opt(param, lower, upper);
+                    if constraint.is_satisfied(&param) {
+                        return Ok(param);
+                    }
+                }
+                Err(infeasible_error())
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminParameter for ndarray::Array1<$t> {
+            fn random<R: Rng + ?Sized>(
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+            ) -> Result<Self, Error> {
+                if let (Some(l), Some(u)) = (lower, upper) {
+                    check_bounds(l.iter(), u.iter(), l.len(), u.len())?;
+                }
+                let n = lower
+                    .or(upper)
+                    .map(|b| b.len())
+                    .ok_or_else(unbounded_error)?;
+                Ok((0..n)
+                    .map(|i| $sample(rng, lower.map(|l| l[i]), upper.map(|u| u[i])))
+                    .collect())
+            }
+
+            fn random_around<R: Rng + ?Sized>(rng: &mut R, center: &Self) -> Self {
+                center.map(|c| c + $sample(rng, None, None))
+            }
+
+            fn modify<R: Rng + ?Sized>(
+                &self,
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+                perturbation: Perturbation,
+                components: Components,
+                constraint: &dyn Constraint<Self>,
+            ) -> Result<Self, Error> {
+                for b in lower.iter().chain(upper.iter()) {
+                    assert_eq!(self.len(), b.len());
+                }
+                for _ in 0..MAX_MODIFY_ATTEMPTS {
+                    let mut param = self.clone();
+                    for idx in perturbed_indices(rng, self.len(), components) {
+                        param[idx] += perturbation.sample(rng, f64::from(param[idx])) as $t;
+                    }
+                    let param = clamp_opt(param, lower, upper);
+                    if constraint.is_satisfied(&param) {
+                        return Ok(param);
+                    }
+                }
+                Err(infeasible_error())
+            }
+        }
+
+        impl<const N: usize> ArgminParameter for [$t; N] {
+            fn random<R: Rng + ?Sized>(
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+            ) -> Result<Self, Error> {
+                if let (Some(l), Some(u)) = (lower, upper) {
+                    check_bounds(l.iter(), u.iter(), N, N)?;
+                }
+                if lower.is_none() && upper.is_none() {
+                    return Err(unbounded_error());
+                }
+                let mut out = [0.0; N];
+                for (i, x) in out.iter_mut().enumerate() {
+                    *x = $sample(rng, lower.map(|l| l[i]), upper.map(|u| u[i]));
+                }
+                Ok(out)
+            }
+
+            fn random_around<R: Rng + ?Sized>(rng: &mut R, center: &Self) -> Self {
+                let mut out = *center;
+                out.iter_mut().for_each(|x| *x += $sample(rng, None, None));
+                out
+            }
+
+            fn modify<R: Rng + ?Sized>(
+                &self,
+                rng: &mut R,
+                lower: Option<&Self>,
+                upper: Option<&Self>,
+                perturbation: Perturbation,
+                components: Components,
+                constraint: &dyn Constraint<Self>,
+            ) -> Result<Self, Error> {
+                for _ in 0..MAX_MODIFY_ATTEMPTS {
+                    let mut param = *self;
+                    for idx in perturbed_indices(rng, N, components) {
+                        param[idx] += perturbation.sample(rng, f64::from(param[idx])) as $t;
+                    }
+                    let param = clamp_opt(param, lower, upper);
+                    if constraint.is_satisfied(&param) {
+                        return Ok(param);
+                    }
+                }
+                Err(infeasible_error())
+            }
+        }
+    };
+}
+
+make_parameter!(f32, sample_f32);
+make_parameter!(f64, sample_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_random_within_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let lower = vec![-1.0f64, 0.0, 10.0];
+        let upper = vec![1.0f64, 0.5, 20.0];
+        for _ in 0..100 {
+            let p = Vec::random(&mut rng, Some(&lower), Some(&upper)).unwrap();
+            assert!(p
+                .iter()
+                .zip(lower.iter().zip(upper.iter()))
+                .all(|(x, (l, u))| l <= x && x < u));
+        }
+    }
+
+    #[test]
+    fn test_scalar() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let p = f64::random(&mut rng, Some(&-1.0), Some(&1.0)).unwrap();
+        assert!(p >= -1.0 && p < 1.0);
+        assert!(f64::random(&mut rng, Some(&1.0), Some(&-1.0)).is_err());
+        let q = p
+            .modify(
+                &mut rng,
+                Some(&-1.0),
+                Some(&1.0),
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+            .unwrap();
+        assert!(q >= -1.0 && q <= 1.0);
+    }
+
+    #[test]
+    fn test_array() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let lower = [-1.0f64, 0.0, 10.0];
+        let upper = [1.0f64, 0.5, 20.0];
+        let p = <[f64; 3]>::random(&mut rng, Some(&lower), Some(&upper)).unwrap();
+        assert!(lower.all_le(&p) && p.all_lt(&upper));
+        assert!(<[f64; 3]>::random(&mut rng, Some(&upper), Some(&lower)).is_err());
+        let q = p
+            .modify(
+                &mut rng,
+                Some(&lower),
+                Some(&upper),
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+            .unwrap();
+        assert!(lower.all_le(&q) && q.all_le(&upper));
+
+        // same sequence as the Vec implementation
+        let mut rng_a = XorShiftRng::seed_from_u64(3);
+        let mut rng_v = XorShiftRng::seed_from_u64(3);
+        let a = <[f64; 3]>::random(&mut rng_a, Some(&lower), Some(&upper)).unwrap();
+        let v = Vec::random(&mut rng_v, Some(&lower.to_vec()), Some(&upper.to_vec())).unwrap();
+        assert_eq!(a.to_vec(), v);
+    }
+
+    #[test]
+    fn test_random_invalid_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        assert!(Vec::random(&mut rng, Some(&vec![1.0f64]), Some(&vec![0.0])).is_err());
+        assert!(Vec::random(&mut rng, Some(&vec![0.0f64]), Some(&vec![1.0, 2.0])).is_err());
+    }
+
+    #[test]
+    fn test_random_reproducible() {
+        let lower = vec![-5.0f64; 4];
+        let upper = vec![5.0f64; 4];
+        let sequence = |seed: u64| {
+            let mut rng = XorShiftRng::seed_from_u64(seed);
+            (0..10)
+                .map(|_| Vec::random(&mut rng, Some(&lower), Some(&upper)).unwrap())
+                .collect::<Vec<Vec<f64>>>()
+        };
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+
+        // fixed values for seed 42, which must not change between runs or releases
+        let expected = [
+            [
+                -3.2441513291292035,
+                4.583111507648304,
+                -0.4400412394720732,
+                -1.6105003137185214,
+            ],
+            [
+                1.019295072320423,
+                -0.3165365382098191,
+                4.862446255631951,
+                -0.5138159190281026,
+            ],
+        ];
+        for (p, e) in sequence(42).iter().zip(expected.iter()) {
+            assert!(p.iter().zip(e.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_modify() {
+        let mut rng = XorShiftRng::seed_from_u64(7);
+        let lower = vec![-1.0f64; 3];
+        let upper = vec![1.0f64; 3];
+        let p = vec![0.0f64; 3];
+        for _ in 0..100 {
+            let q = p
+                .modify(
+                    &mut rng,
+                    Some(&lower),
+                    Some(&upper),
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            // exactly one component changes and all stay within bounds
+            assert!(q.iter().filter(|x| x.abs() > 0.0).count() <= 1);
+            assert!(q.iter().all(|x| x.abs() <= 1.0));
+        }
+        // the constraint is enforced
+        let q = p
+            .modify(
+                &mut rng,
+                Some(&lower),
+                Some(&upper),
+                Perturbation::default(),
+                Components::default(),
+                &|x: &Vec<f64>| x.iter().all(|x| *x >= 0.0),
+            )
+            .unwrap();
+        assert!(q.iter().all(|x| *x >= 0.0));
+
+        // an unsatisfiable constraint gives up after `MAX_MODIFY_ATTEMPTS` attempts
+        let calls = std::cell::Cell::new(0);
+        let never = |_: &Vec<f64>| {
+            calls.set(calls.get() + 1);
+            false
+        };
+        let err = p
+            .modify(
+                &mut rng,
+                Some(&lower),
+                Some(&upper),
+                Perturbation::default(),
+                Components::default(),
+                &never,
+            )
+            .unwrap_err();
+        assert!(format!("{}", err).contains("constraint"));
+        assert_eq!(calls.get(), MAX_MODIFY_ATTEMPTS);
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    #[test]
+    fn test_thread_rng_convenience() {
+        let p = Vec::random_thread(Some(&vec![0.0f64, 0.0]), Some(&vec![1.0, 1.0])).unwrap();
+        let q = p
+            .modify_thread(
+                Some(&vec![0.0, 0.0]),
+                Some(&vec![1.0, 1.0]),
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+            .unwrap();
+        assert_eq!(q.len(), 2);
+    }
+
+    #[test]
+    fn test_random_optional_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        let lower = vec![-1.0f64, 2.0];
+        let upper = vec![1.0f64, 3.0];
+        for _ in 0..100 {
+            let p = Vec::random(&mut rng, Some(&lower), None).unwrap();
+            assert!(lower.all_le(&p));
+            let p = Vec::random(&mut rng, None, Some(&upper)).unwrap();
+            assert!(p.all_le(&upper));
+            let p = Vec::random(&mut rng, Some(&lower), Some(&upper)).unwrap();
+            assert!(lower.all_le(&p) && p.all_lt(&upper));
+        }
+        assert!(Vec::<f64>::random(&mut rng, None, None).is_err());
+        assert!(f64::random(&mut rng, None, None).is_err());
+        assert!(<[f64; 2]>::random(&mut rng, None, None).is_err());
+        assert!(f64::random(&mut rng, Some(&3.0), None).unwrap() >= 3.0);
+        let a = <[f64; 2]>::random(&mut rng, None, Some(&[0.0, 1.0])).unwrap();
+        assert!(a[0] <= 0.0 && a[1] <= 1.0);
+    }
+
+    #[test]
+    fn test_random_around() {
+        let mut rng = XorShiftRng::seed_from_u64(11);
+        let center = vec![10.0f64, -10.0];
+        let n = 10000;
+        let samples: Vec<Vec<f64>> = (0..n)
+            .map(|_| Vec::random_around(&mut rng, &center))
+            .collect();
+        for (i, c) in center.iter().enumerate() {
+            let mean = samples.iter().map(|p| p[i]).sum::<f64>() / n as f64;
+            let var = samples.iter().map(|p| (p[i] - c).powi(2)).sum::<f64>() / n as f64;
+            assert!((mean - c).abs() < 0.05);
+            assert!((var - 1.0).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_modify_optional_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(13);
+        let lower = vec![0.0f64; 3];
+        let upper = vec![0.5f64; 3];
+        let p = vec![0.25f64; 3];
+        for _ in 0..100 {
+            let q = p
+                .modify(
+                    &mut rng,
+                    None,
+                    None,
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            assert!(q.iter().all(|x| (x - 0.25).abs() <= 1.0));
+            let q = p
+                .modify(
+                    &mut rng,
+                    Some(&lower),
+                    None,
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            assert!(lower.all_le(&q));
+            let q = p
+                .modify(
+                    &mut rng,
+                    None,
+                    Some(&upper),
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            assert!(q.all_le(&upper));
+            let q = p
+                .modify(
+                    &mut rng,
+                    Some(&lower),
+                    Some(&upper),
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            assert!(lower.all_le(&q) && q.all_le(&upper));
+        }
+        // one-sided bounds only clamp on their side
+        let mut below = false;
+        let mut above = false;
+        for _ in 0..100 {
+            let q = p
+                .modify(
+                    &mut rng,
+                    Some(&lower),
+                    None,
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            above |= q.iter().any(|x| *x > 0.5);
+            let q = p
+                .modify(
+                    &mut rng,
+                    None,
+                    Some(&upper),
+                    Perturbation::default(),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            below |= q.iter().any(|x| *x < 0.0);
+        }
+        assert!(above && below);
+    }
+
+    /// Sample mean and variance of the change of a scalar parameter `x` under `perturbation`
+    fn perturbation_moments(x: f64, perturbation: Perturbation) -> (f64, f64) {
+        let mut rng = XorShiftRng::seed_from_u64(17);
+        let n = 20000;
+        let d: Vec<f64> = (0..n)
+            .map(|_| {
+                x.modify(
+                    &mut rng,
+                    None,
+                    None,
+                    perturbation,
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap()
+                    - x
+            })
+            .collect();
+        let mean = d.iter().sum::<f64>() / n as f64;
+        let var = d.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n as f64;
+        (mean, var)
+    }
+
+    #[test]
+    fn test_perturbation_distributions() {
+        // uniform on [-a, a] has variance a^2 / 3
+        let (mean, var) = perturbation_moments(5.0, Perturbation::UniformAbs(2.0));
+        assert!(mean.abs() < 0.05);
+        assert!((var / (4.0 / 3.0) - 1.0).abs() < 0.05);
+
+        let (mean, var) = perturbation_moments(-4e-6, Perturbation::UniformRel(0.5));
+        assert!(mean.abs() < 0.05 * 2e-6);
+        assert!((var / ((2e-6f64).powi(2) / 3.0) - 1.0).abs() < 0.05);
+
+        let (mean, var) = perturbation_moments(5.0, Perturbation::Gaussian(3.0));
+        assert!(mean.abs() < 0.1);
+        assert!((var / 9.0 - 1.0).abs() < 0.05);
+
+        let (mean, var) = perturbation_moments(1e4, Perturbation::GaussianRel(0.01));
+        assert!(mean.abs() < 5.0);
+        assert!((var / 1e4 - 1.0).abs() < 0.05);
+
+        // zero components are perturbed as if they were of magnitude one
+        let (_, var) = perturbation_moments(0.0, Perturbation::GaussianRel(0.1));
+        assert!((var / 0.01 - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_perturbation_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(19);
+        let p = vec![1e-6f64; 4];
+        let lower = vec![0.0f64; 4];
+        let upper = vec![2e-6f64; 4];
+        for _ in 0..100 {
+            let q = p
+                .modify(
+                    &mut rng,
+                    Some(&lower),
+                    Some(&upper),
+                    Perturbation::GaussianRel(10.0),
+                    Components::default(),
+                    &Unconstrained,
+                )
+                .unwrap();
+            assert!(lower.all_le(&q) && q.all_le(&upper));
+            assert!(q.iter().filter(|x| (*x - 1e-6).abs() > 0.0).count() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_random_degenerate_bounds() {
+        let mut rng = XorShiftRng::seed_from_u64(23);
+        // equal bounds fix the component
+        let p = Vec::random(&mut rng, Some(&vec![0.0f64, 2.0]), Some(&vec![1.0, 2.0])).unwrap();
+        assert!((p[1] - 2.0).abs() < std::f64::EPSILON);
+        let p = f64::random(&mut rng, Some(&3.0), Some(&3.0)).unwrap();
+        assert!((p - 3.0).abs() < std::f64::EPSILON);
+        let p = <[f64; 2]>::random(&mut rng, Some(&[1.0, 1.0]), Some(&[1.0, 1.0])).unwrap();
+        assert_eq!(p, [1.0, 1.0]);
+
+        // inverted and NaN bounds are errors, reporting the offending component
+        let err =
+            Vec::random(&mut rng, Some(&vec![0.0f64, 2.0]), Some(&vec![1.0, 1.0])).unwrap_err();
+        assert!(format!("{}", err).contains("component 1"));
+        assert!(Vec::random(&mut rng, Some(&vec![std::f64::NAN]), Some(&vec![1.0])).is_err());
+
+        // mismatched lengths
+        assert!(Vec::random(&mut rng, Some(&vec![0.0f64; 2]), Some(&vec![1.0; 3])).is_err());
+    }
+
+    #[test]
+    fn test_components_count() {
+        assert_eq!(Components::Count(3).count(10), 3);
+        assert_eq!(Components::Count(0).count(10), 1);
+        assert_eq!(Components::Count(20).count(10), 10);
+        assert_eq!(Components::Fraction(0.25).count(10), 3);
+        assert_eq!(Components::Fraction(0.0).count(10), 1);
+        assert_eq!(Components::All.count(10), 10);
+        assert_eq!(Components::Count(10).annealed(0.5), Components::Count(5));
+        assert_eq!(Components::All.annealed(0.1).count(100), 10);
+    }
+
+    #[test]
+    fn test_modify_multiple_components() {
+        let mut rng = XorShiftRng::seed_from_u64(31);
+        let p = vec![0.0f64; 10];
+        for (components, k) in &[
+            (Components::Count(3), 3),
+            (Components::Fraction(0.5), 5),
+            (Components::All, 10),
+        ] {
+            for _ in 0..20 {
+                let q = p
+                    .modify(
+                        &mut rng,
+                        None,
+                        None,
+                        Perturbation::Gaussian(1.0),
+                        *components,
+                        &Unconstrained,
+                    )
+                    .unwrap();
+                assert_eq!(q.iter().filter(|x| x.abs() > 0.0).count(), *k);
+            }
+        }
+        let a = [0.0f64; 6];
+        let q = a
+            .modify(
+                &mut rng,
+                None,
+                None,
+                Perturbation::Gaussian(1.0),
+                Components::Count(4),
+                &Unconstrained,
+            )
+            .unwrap();
+        assert_eq!(q.iter().filter(|x| x.abs() > 0.0).count(), 4);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_ndarray() {
+        use ndarray::{array, Array1};
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let lower = array![-1.0f64, 0.0];
+        let upper = array![1.0f64, 0.5];
+        let p = Array1::random(&mut rng, Some(&lower), Some(&upper)).unwrap();
+        assert!(p.all_le(&upper) && lower.all_le(&p));
+        assert!(Array1::random(&mut rng, Some(&upper), Some(&lower)).is_err());
+        let q = p
+            .modify(
+                &mut rng,
+                Some(&lower),
+                Some(&upper),
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+            .unwrap();
+        assert!(q.all_le(&upper) && lower.all_le(&q));
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_ndarray_degenerate_bounds() {
+        use ndarray::{array, Array1};
+        let mut rng = XorShiftRng::seed_from_u64(29);
+        let p = Array1::random(
+            &mut rng,
+            Some(&array![0.0f64, 2.0]),
+            Some(&array![1.0, 2.0]),
+        )
+        .unwrap();
+        assert!((p[1] - 2.0).abs() < std::f64::EPSILON);
+        assert!(Array1::random(&mut rng, Some(&array![2.0f64]), Some(&array![1.0])).is_err());
+        assert!(Array1::random(&mut rng, Some(&array![0.0f64]), Some(&array![1.0, 2.0])).is_err());
+        assert!(
+            Array1::random(&mut rng, Some(&array![std::f64::NAN]), Some(&array![1.0])).is_err()
+        );
+    }
+}
@@ -0,0 +1,844 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Chaining of solvers
+//!
+//! A `Pipeline` runs several solvers one after another on the same operator, for instance a
+//! global method such as Simulated Annealing followed by a local method which polishes its
+//! result:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! # use argmin::pipeline::{Pipeline, StopAfter};
+//! # use argmin::solver::neldermead::{NelderMead, SimplexStep};
+//! # use argmin::testfunctions::problem::TestProblem;
+//! # fn main() -> Result<(), Error> {
+//! let coarse = NelderMead::from_point(vec![-1.2, 1.0], SimplexStep::Absolute(1.0))?;
+//! let res = Pipeline::new(TestProblem::rosenbrock(2))
+//!     .then(coarse, StopAfter::Iters(100))
+//!     .then_from(
+//!         |param: &Vec<f64>| NelderMead::from_point(param.clone(), SimplexStep::default()),
+//!         StopAfter::Converged,
+//!     )
+//!     .run(vec![-1.2, 1.0])?;
+//! println!("{} evaluations in {} stages", res.cost_evals(), res.stages.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each stage is executed by its own `Stepper`, starting from the best parameter vector of the
+//! previous stage. Loggers added to the pipeline are attached to every stage and receive the name
+//! of the stage as `stage` in the KV. At the end of every stage, they receive a final `log_info`
+//! whose message is the summary of the termination reason, such as `"Converged
+//! (TargetPrecisionReached)"` or `"Stopped (MaxItersReached)"`, and whose KV contains
+//! `termination_reason`, `status` (`"converged"` or `"stopped"`) and `best_cost`.
+//!
+//! A stage fails if its run returns an error, for instance an error of the operator, a failed
+//! line search or a singular Hessian. What happens then is decided by the `InnerFailurePolicy`
+//! set with `on_failure`: the pipeline aborts (default), skips the stage or runs it again. Stages
+//! added with `then_retrying` construct their solver from the number of the attempt, such that
+//! a retry can use a different seed or smaller step sizes. Every failure is reported to the
+//! loggers as a `log_info` with the message `"Failed (<error>)"` and `attempt`, `failures` (the
+//! number of failures so far) and `error` in the KV, and is listed in `PipelineResult::failures`.
+//! The evaluations spent in failed attempts are included in the totals of the `PipelineResult`.
+
+use crate::operator::check_init_param;
+use crate::prelude::*;
+use crate::stepper::Stepper;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Termination criterion of a stage, in addition to the termination criteria of its solver
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopAfter {
+    /// Stop after the given number of iterations
+    Iters(u64),
+    /// Stop once the cost function value reaches the given target
+    TargetCost(f64),
+    /// Stop only when the solver terminates on its own
+    Converged,
+}
+
+/// What a pipeline does when a stage fails with an error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InnerFailurePolicy {
+    /// Return the error of the stage
+    Abort,
+    /// Skip the stage. The next stage starts from the parameter vector the failed stage started
+    /// from. If all stages fail, the error of the last one is returned.
+    Skip,
+    /// Run the stage again, up to the given number of times, with a newly constructed solver.
+    /// The error of the last attempt is returned if all attempts fail.
+    Retry(u32),
+}
+
+impl Default for InnerFailurePolicy {
+    fn default() -> Self {
+        InnerFailurePolicy::Abort
+    }
+}
+
+/// A failed attempt to run a stage of a pipeline
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageFailure {
+    /// name of the stage
+    pub name: String,
+    /// number of the attempt (counted from 0)
+    pub attempt: u32,
+    /// error message
+    pub reason: String,
+    /// number of cost function evaluations before the failure
+    pub cost_evals: u64,
+    /// number of gradient evaluations before the failure
+    pub grad_evals: u64,
+    /// number of Hessian evaluations before the failure
+    pub hessian_evals: u64,
+    /// number of calls to `modify` before the failure
+    pub modify_evals: u64,
+}
+
+/// Statistics of one stage of a pipeline
+#[derive(Clone, Debug)]
+pub struct StageResult {
+    /// name of the stage
+    pub name: String,
+    /// best cost function value at the end of the stage
+    pub cost: f64,
+    /// reason why the stage terminated
+    pub termination_reason: TerminationReason,
+    /// number of cost function evaluations
+    pub cost_evals: u64,
+    /// number of gradient evaluations
+    pub grad_evals: u64,
+    /// number of Hessian evaluations
+    pub hessian_evals: u64,
+    /// number of calls to `modify`
+    pub modify_evals: u64,
+    /// duration of the stage (zero with the `wasm` feature)
+    pub elapsed: Duration,
+}
+
+/// Result of a pipeline: the best parameter vector of the last stage and the statistics of all
+/// stages
+#[derive(Clone, Debug)]
+pub struct PipelineResult<P> {
+    /// best parameter vector of the last stage
+    pub param: P,
+    /// cost function value of `param`
+    pub cost: f64,
+    /// statistics of the stages which succeeded, in the order in which they were run
+    pub stages: Vec<StageResult>,
+    /// failed attempts to run a stage, in the order in which they occurred
+    pub failures: Vec<StageFailure>,
+}
+
+impl<P> PipelineResult<P> {
+    /// Total number of cost function evaluations, including those of failed attempts
+    pub fn cost_evals(&self) -> u64 {
+        self.stages.iter().map(|s| s.cost_evals).sum::<u64>()
+            + self.failures.iter().map(|f| f.cost_evals).sum::<u64>()
+    }
+
+    /// Total number of gradient evaluations, including those of failed attempts
+    pub fn grad_evals(&self) -> u64 {
+        self.stages.iter().map(|s| s.grad_evals).sum::<u64>()
+            + self.failures.iter().map(|f| f.grad_evals).sum::<u64>()
+    }
+
+    /// Total number of Hessian evaluations, including those of failed attempts
+    pub fn hessian_evals(&self) -> u64 {
+        self.stages.iter().map(|s| s.hessian_evals).sum::<u64>()
+            + self.failures.iter().map(|f| f.hessian_evals).sum::<u64>()
+    }
+
+    /// Total number of calls to `modify`, including those of failed attempts
+    pub fn modify_evals(&self) -> u64 {
+        self.stages.iter().map(|s| s.modify_evals).sum::<u64>()
+            + self.failures.iter().map(|f| f.modify_evals).sum::<u64>()
+    }
+
+    /// Total duration of all stages
+    pub fn elapsed(&self) -> Duration {
+        self.stages.iter().map(|s| s.elapsed).sum()
+    }
+
+    /// `true` if the last stage converged
+    pub fn is_success(&self) -> bool {
+        self.stages
+            .last()
+            .map(|s| s.termination_reason.is_success())
+            .unwrap_or(false)
+    }
+}
+
+/// Passes the KV on to the wrapped logger with the name of the stage added
+struct StageLogger {
+    /// name of the stage
+    stage: String,
+    /// wrapped logger
+    logger: Arc<dyn ArgminLog>,
+}
+
+impl StageLogger {
+    fn with_stage(&self, kv: &ArgminKV) -> ArgminKV {
+        let mut kv = kv.clone();
+        kv.push("stage", self.stage.clone());
+        kv
+    }
+}
+
+impl ArgminLog for StageLogger {
+    fn log_info(&self, msg: &str, kv: &ArgminKV) -> Result<(), Error> {
+        self.logger.log_info(msg, &self.with_stage(kv))
+    }
+
+    fn log_iter(&self, kv: &ArgminKV) -> Result<(), Error> {
+        self.logger.log_iter(&self.with_stage(kv))
+    }
+}
+
+/// A stage whose solver is constructed from the parameter vector the stage starts from. A failed
+/// run returns the error together with the `StageFailure` which records it.
+trait Stage<O: ArgminOp>: Send {
+    fn name(&self) -> &str;
+
+    fn run(
+        &mut self,
+        op: O,
+        init_param: O::Param,
+        attempt: u32,
+        loggers: &[Arc<dyn ArgminLog>],
+    ) -> Result<(O::Param, StageResult), (Error, StageFailure)>;
+}
+
+struct SolverStage<O: ArgminOp, S> {
+    name: String,
+    solver: Box<dyn FnMut(&O::Param, u32) -> Result<S, Error> + Send>,
+    stop: StopAfter,
+}
+
+impl<O, S> Stage<O> for SolverStage<O, S>
+where
+    O: ArgminOp<Output = f64>,
+    S: Solver<O> + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(
+        &mut self,
+        op: O,
+        init_param: O::Param,
+        attempt: u32,
+        loggers: &[Arc<dyn ArgminLog>],
+    ) -> Result<(O::Param, StageResult), (Error, StageFailure)> {
+        let name = self.name.clone();
+        let failure = |err: Error, op: Option<&OpWrapper<O>>| {
+            let failure = StageFailure {
+                name: name.clone(),
+                attempt,
+                reason: err.to_string(),
+                cost_evals: op.map_or(0, |op| op.cost_func_count),
+                grad_evals: op.map_or(0, |op| op.grad_func_count),
+                hessian_evals: op.map_or(0, |op| op.hessian_func_count),
+                modify_evals: op.map_or(0, |op| op.modify_func_count),
+            };
+            (err, failure)
+        };
+        let solver = (self.solver)(&init_param, attempt).map_err(|err| failure(err, None))?;
+        // A `Stepper` instead of an `Executor` keeps the evaluation counters if the run fails.
+        let mut stepper = Stepper::new(op, solver, init_param);
+        match self.stop {
+            StopAfter::Iters(iters) => stepper = stepper.max_iters(iters),
+            StopAfter::TargetCost(cost) => stepper = stepper.target_cost(cost),
+            StopAfter::Converged => {}
+        }
+        let loggers: Vec<Arc<StageLogger>> = loggers
+            .iter()
+            .map(|logger| {
+                Arc::new(StageLogger {
+                    stage: self.name.clone(),
+                    logger: logger.clone(),
+                })
+            })
+            .collect();
+        for logger in &loggers {
+            stepper = stepper.add_logger(logger.clone());
+        }
+        let (res, elapsed) = stopwatch(|| stepper.run());
+        let termination_reason = res.map_err(|err| failure(err, Some(stepper.operator())))?;
+        let mut kv = ArgminKV::new();
+        kv.push("termination_reason", format!("{:?}", termination_reason));
+        kv.push("status", termination_reason.status().to_string());
+        kv.push("best_cost", stepper.best_cost().to_string());
+        for logger in &loggers {
+            logger
+                .log_info(&termination_reason.summary(), &kv)
+                .map_err(|err| failure(err, Some(stepper.operator())))?;
+        }
+        let op = stepper.operator();
+        let stage = StageResult {
+            name: self.name.clone(),
+            cost: stepper.best_cost(),
+            termination_reason,
+            cost_evals: op.cost_func_count,
+            grad_evals: op.grad_func_count,
+            hessian_evals: op.hessian_func_count,
+            modify_evals: op.modify_func_count,
+            elapsed,
+        };
+        Ok((stepper.best_param().clone(), stage))
+    }
+}
+
+#[cfg(not(feature = "wasm"))]
+fn stopwatch<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = std::time::Instant::now();
+    let out = f();
+    (out, start.elapsed())
+}
+
+/// Without `Instant`, durations are not measured.
+#[cfg(feature = "wasm")]
+fn stopwatch<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    (f(), Duration::from_secs(0))
+}
+
+/// Name of a solver type without its path and generic parameters
+fn solver_name<S>() -> String {
+    let name = std::any::type_name::<S>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+/// Sequence of solvers which are run one after another on the same operator. Each stage starts
+/// from the best parameter vector of the previous stage. The evaluation counters and durations of
+/// all stages are reported in the `PipelineResult`.
+pub struct Pipeline<O: ArgminOp> {
+    /// operator
+    op: O,
+    /// stages
+    stages: Vec<Box<dyn Stage<O>>>,
+    /// loggers attached to every stage
+    loggers: Vec<Arc<dyn ArgminLog>>,
+    /// what happens when a stage fails
+    on_failure: InnerFailurePolicy,
+}
+
+impl<O: ArgminOp<Output = f64> + 'static> Pipeline<O> {
+    /// Constructor
+    pub fn new(op: O) -> Self {
+        Pipeline {
+            op,
+            stages: vec![],
+            loggers: vec![],
+            on_failure: InnerFailurePolicy::default(),
+        }
+    }
+
+    /// Append a stage which runs `solver` until `stop` (or until the solver terminates). The
+    /// stage is named after the type of the solver.
+    pub fn then<S>(self, solver: S, stop: StopAfter) -> Self
+    where
+        S: Solver<O> + Send + 'static,
+    {
+        self.then_from(move |_: &O::Param| Ok(solver), stop)
+    }
+
+    /// Same as `then`, but the solver is constructed from the parameter vector the stage starts
+    /// from. This is needed for solvers which are initialized with more than the initial
+    /// parameter vector, such as the simplex of `NelderMead`.
+    pub fn then_from<S, F>(self, solver: F, stop: StopAfter) -> Self
+    where
+        S: Solver<O> + 'static,
+        F: FnOnce(&O::Param) -> Result<S, Error> + Send + 'static,
+    {
+        let name = solver_name::<S>();
+        self.then_named(&name, solver, stop)
+    }
+
+    /// Same as `then_from`, with a custom name of the stage
+    pub fn then_named<S, F>(self, name: &str, solver: F, stop: StopAfter) -> Self
+    where
+        S: Solver<O> + 'static,
+        F: FnOnce(&O::Param) -> Result<S, Error> + Send + 'static,
+    {
+        let stage = name.to_string();
+        let mut solver = Some(solver);
+        self.then_retrying(
+            name,
+            move |param: &O::Param, _attempt| match solver.take() {
+                Some(solver) => solver(param),
+                None => Err(ArgminError::InvalidParameter {
+                    text: format!(
+                        "Pipeline: the solver of stage `{}` can only be constructed once; add the \
+                         stage with `then_retrying` to retry it.",
+                        stage
+                    ),
+                }
+                .into()),
+            },
+            stop,
+        )
+    }
+
+    /// Same as `then_named`, but the solver is constructed from the parameter vector the stage
+    /// starts from and the number of the attempt (counted from 0). With
+    /// `InnerFailurePolicy::Retry`, a failed stage is run again with a newly constructed solver,
+    /// which can for instance use another seed or smaller step sizes.
+    pub fn then_retrying<S, F>(mut self, name: &str, solver: F, stop: StopAfter) -> Self
+    where
+        S: Solver<O> + 'static,
+        F: FnMut(&O::Param, u32) -> Result<S, Error> + Send + 'static,
+    {
+        self.stages.push(Box::new(SolverStage {
+            name: name.to_string(),
+            solver: Box::new(solver),
+            stop,
+        }));
+        self
+    }
+
+    /// Set what happens when a stage fails (default: `InnerFailurePolicy::Abort`)
+    pub fn on_failure(mut self, policy: InnerFailurePolicy) -> Self {
+        self.on_failure = policy;
+        self
+    }
+
+    /// Attach a logger to every stage
+    pub fn add_logger(mut self, logger: Arc<dyn ArgminLog>) -> Self {
+        self.loggers.push(logger);
+        self
+    }
+
+    /// Names of the stages
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|s| s.name()).collect()
+    }
+
+    /// Run all stages, starting from `init_param`
+    pub fn run(self, init_param: O::Param) -> Result<PipelineResult<O::Param>, Error> {
+        if self.stages.is_empty() {
+            return Err(ArgminError::InvalidParameter {
+                text: "Pipeline: at least one stage is required.".to_string(),
+            }
+            .into());
+        }
+        let mut param = init_param;
+        let mut stages = Vec::with_capacity(self.stages.len());
+        let mut failures = vec![];
+        let mut last_err = None;
+        for mut stage in self.stages {
+            let mut attempt = 0;
+            loop {
+                match stage.run(self.op.clone(), param.clone(), attempt, &self.loggers) {
+                    Ok((best, result)) => {
+                        param = best;
+                        stages.push(result);
+                        break;
+                    }
+                    Err((err, failure)) => {
+                        let mut kv = ArgminKV::new();
+                        kv.push("stage", failure.name.clone());
+                        kv.push("attempt", attempt.to_string());
+                        kv.push("failures", (failures.len() + 1).to_string());
+                        kv.push("error", failure.reason.clone());
+                        for logger in &self.loggers {
+                            logger.log_info(&format!("Failed ({})", failure.reason), &kv)?;
+                        }
+                        failures.push(failure);
+                        match self.on_failure {
+                            InnerFailurePolicy::Retry(retries) if attempt < retries => {
+                                attempt += 1;
+                            }
+                            InnerFailurePolicy::Skip => {
+                                last_err = Some(err);
+                                break;
+                            }
+                            _ => return Err(err),
+                        }
+                    }
+                }
+            }
+        }
+        let cost = match (stages.last(), last_err) {
+            (Some(stage), _) => stage.cost,
+            (None, Some(err)) => return Err(err),
+            (None, None) => unreachable!(),
+        };
+        Ok(PipelineResult {
+            param,
+            cost,
+            stages,
+            failures,
+        })
+    }
+}
+
+impl<O> Pipeline<O>
+where
+    O: ArgminBoundedOp<Output = f64> + 'static,
+    O::Param: ArgminElementwiseCmp,
+{
+    /// Same as `run`, but returns an error if `init_param` violates the bounds of the operator
+    /// (see `check_init_param`)
+    pub fn run_checked(self, init_param: O::Param) -> Result<PipelineResult<O::Param>, Error> {
+        check_init_param(&self.op, &init_param)?;
+        self.run(init_param)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::neldermead::{NelderMead, SimplexStep};
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+    use crate::testfunctions::problem::annealable::Annealable;
+    use crate::testfunctions::problem::TestProblem;
+    use serde::{Deserialize, Serialize};
+    use std::sync::Mutex;
+
+    /// Sphere which cannot be evaluated for `x_0 > 2`, like a model which fails outside of its
+    /// domain
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Fragile;
+
+    impl ArgminOp for Fragile {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            if p[0] > 2.0 {
+                return Err(ArgminError::InvalidParameter {
+                    text: format!("Fragile: x_0 = {} is outside of the domain.", p[0]),
+                }
+                .into());
+            }
+            Ok(p.iter().map(|x| x * x).sum())
+        }
+    }
+
+    impl ArgminBoundedOp for Fragile {
+        fn upper_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![2.0, std::f64::INFINITY])
+        }
+    }
+
+    /// Always aborts, like a solver whose line search failed or a run interrupted by the user
+    #[derive(Serialize, Deserialize)]
+    struct Abort;
+
+    impl<O: ArgminOp> Solver<O> for Abort {
+        fn next_iter(
+            &mut self,
+            _op: &mut OpWrapper<O>,
+            state: &IterState<O>,
+        ) -> Result<ArgminIterData<O>, Error> {
+            Ok(ArgminIterData::new()
+                .param(state.get_param())
+                .termination_reason(TerminationReason::Aborted))
+        }
+    }
+
+    fn get(kv: &ArgminKV, key: &str) -> String {
+        kv.kv
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap()
+    }
+
+    /// Records the stage and the best cost of every iteration, the final record of every stage
+    /// and the failures
+    #[derive(Default)]
+    struct Recorder {
+        iters: Mutex<Vec<(String, f64)>>,
+        finals: Mutex<Vec<(String, String, String, String)>>,
+        failures: Mutex<Vec<(String, String, String)>>,
+    }
+
+    impl ArgminLog for Recorder {
+        fn log_info(&self, msg: &str, kv: &ArgminKV) -> Result<(), Error> {
+            if kv.kv.iter().any(|(k, _)| *k == "error") {
+                self.failures.lock().unwrap().push((
+                    get(kv, "stage"),
+                    get(kv, "attempt"),
+                    get(kv, "failures"),
+                ));
+                assert!(
+                    msg.starts_with("Failed (") && msg.contains("Fragile"),
+                    "{}",
+                    msg
+                );
+            }
+            if kv.kv.iter().any(|(k, _)| *k == "termination_reason") {
+                self.finals.lock().unwrap().push((
+                    get(kv, "stage"),
+                    msg.to_string(),
+                    get(kv, "termination_reason"),
+                    get(kv, "status"),
+                ));
+            }
+            Ok(())
+        }
+
+        fn log_iter(&self, kv: &ArgminKV) -> Result<(), Error> {
+            self.iters
+                .lock()
+                .unwrap()
+                .push((get(kv, "stage"), get(kv, "best_cost").parse().unwrap()));
+            Ok(())
+        }
+    }
+
+    fn himmelblau_pipeline() -> Pipeline<Annealable> {
+        let op = Annealable::new(TestProblem::himmelblau(), 0.5, 7);
+        let sa = SimulatedAnnealing::new(10.0).unwrap().seed(7);
+        Pipeline::new(op).then(sa, StopAfter::Iters(200)).then_from(
+            |param: &Vec<f64>| NelderMead::from_point(param.clone(), SimplexStep::default()),
+            StopAfter::Converged,
+        )
+    }
+
+    #[test]
+    fn test_pipeline_sa_nelder_mead() {
+        let pipeline = himmelblau_pipeline();
+        assert_eq!(
+            pipeline.stage_names(),
+            vec!["SimulatedAnnealing", "NelderMead"]
+        );
+        let res = pipeline.run(vec![0.0, 0.0]).unwrap();
+        assert!(res.cost < 1e-8, "{}", res.cost);
+        assert!(TestProblem::himmelblau().is_solution(&res.param));
+        assert_eq!(res.stages.len(), 2);
+        assert!(res.stages[1].cost <= res.stages[0].cost);
+    }
+
+    #[test]
+    fn test_pipeline_counters() {
+        let res = himmelblau_pipeline().run(vec![0.0, 0.0]).unwrap();
+        let (sa, nm) = (&res.stages[0], &res.stages[1]);
+        assert_eq!(res.cost_evals(), sa.cost_evals + nm.cost_evals);
+        assert_eq!(res.modify_evals(), sa.modify_evals + nm.modify_evals);
+        assert_eq!(res.grad_evals(), 0);
+        assert_eq!(res.elapsed(), sa.elapsed + nm.elapsed);
+        // one modification per iteration of SA, none in Nelder-Mead
+        assert_eq!(sa.modify_evals, 200);
+        assert_eq!(nm.modify_evals, 0);
+        assert_eq!(sa.termination_reason, TerminationReason::MaxItersReached);
+        assert!(nm.cost_evals > 0);
+    }
+
+    #[test]
+    fn test_pipeline_loggers() {
+        let recorder = Arc::new(Recorder::default());
+        himmelblau_pipeline()
+            .add_logger(recorder.clone())
+            .run(vec![0.0, 0.0])
+            .unwrap();
+        let iters = recorder.iters.lock().unwrap();
+        let boundary = iters
+            .iter()
+            .position(|(stage, _)| stage == "NelderMead")
+            .unwrap();
+        assert!(boundary > 0);
+        assert!(iters[..boundary]
+            .iter()
+            .all(|(stage, _)| stage == "SimulatedAnnealing"));
+        // the best cost never increases, also not across the stage boundary
+        for w in iters.windows(2) {
+            assert!(w[1].1 <= w[0].1, "{:?}", w);
+        }
+    }
+
+    #[test]
+    fn test_pipeline_final_records() {
+        let recorder = Arc::new(Recorder::default());
+        let res = himmelblau_pipeline()
+            .add_logger(recorder.clone())
+            .run(vec![0.0, 0.0])
+            .unwrap();
+        assert!(res.is_success());
+        let finals = recorder.finals.lock().unwrap();
+        assert_eq!(finals.len(), 2);
+        assert_eq!(
+            finals[0],
+            (
+                "SimulatedAnnealing".to_string(),
+                "Stopped (MaxItersReached)".to_string(),
+                "MaxItersReached".to_string(),
+                "stopped".to_string()
+            )
+        );
+        let reason = &res.stages[1].termination_reason;
+        assert!(reason.is_success());
+        assert_eq!(finals[1].0, "NelderMead");
+        assert_eq!(finals[1].1, reason.summary());
+        assert_eq!(finals[1].2, format!("{:?}", reason));
+        assert_eq!(finals[1].3, "converged");
+    }
+
+    #[test]
+    fn test_pipeline_final_record_aborted() {
+        let recorder = Arc::new(Recorder::default());
+        let res = Pipeline::new(TestProblem::sphere(2))
+            .then(Abort, StopAfter::Iters(10))
+            .add_logger(recorder.clone())
+            .run(vec![1.0, 1.0])
+            .unwrap();
+        assert!(!res.is_success());
+        assert_eq!(res.stages[0].termination_reason, TerminationReason::Aborted);
+        let finals = recorder.finals.lock().unwrap();
+        assert_eq!(finals.len(), 1);
+        assert_eq!(finals[0].1, "Stopped (Aborted)");
+        assert_eq!(finals[0].3, "stopped");
+    }
+
+    #[test]
+    fn test_pipeline_without_stages() {
+        assert!(Pipeline::new(TestProblem::sphere(2))
+            .run(vec![1.0, 1.0])
+            .is_err());
+    }
+
+    /// A stage which starts outside of the domain of `Fragile`, followed by Nelder-Mead
+    fn fragile_pipeline() -> Pipeline<Fragile> {
+        Pipeline::new(Fragile)
+            .then_named(
+                "far",
+                |_: &Vec<f64>| NelderMead::from_point(vec![3.0, 3.0], SimplexStep::Absolute(0.1)),
+                StopAfter::Converged,
+            )
+            .then_from(
+                |param: &Vec<f64>| NelderMead::from_point(param.clone(), SimplexStep::default()),
+                StopAfter::Converged,
+            )
+    }
+
+    #[test]
+    fn test_pipeline_failure_abort() {
+        let recorder = Arc::new(Recorder::default());
+        let err = fragile_pipeline()
+            .add_logger(recorder.clone())
+            .run(vec![1.0, 1.0])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("outside of the domain"), "{}", err);
+        assert_eq!(
+            *recorder.failures.lock().unwrap(),
+            vec![("far".to_string(), "0".to_string(), "1".to_string())]
+        );
+        // the second stage was not run
+        assert!(recorder.iters.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pipeline_failure_skip() {
+        let recorder = Arc::new(Recorder::default());
+        let res = fragile_pipeline()
+            .on_failure(InnerFailurePolicy::Skip)
+            .add_logger(recorder.clone())
+            .run(vec![1.0, 1.0])
+            .unwrap();
+        assert!(res.cost < 1e-8, "{}", res.cost);
+        assert_eq!(res.stages.len(), 1);
+        assert_eq!(res.stages[0].name, "NelderMead");
+        assert_eq!(res.failures.len(), 1);
+        assert_eq!(res.failures[0].name, "far");
+        assert_eq!(res.failures[0].attempt, 0);
+        assert!(res.failures[0].reason.contains("x_0 = 3"));
+        assert_eq!(recorder.failures.lock().unwrap().len(), 1);
+
+        // if all stages fail, the last error is returned
+        let err = Pipeline::new(Fragile)
+            .then(
+                NelderMead::from_point(vec![3.0, 0.0], SimplexStep::default()).unwrap(),
+                StopAfter::Converged,
+            )
+            .on_failure(InnerFailurePolicy::Skip)
+            .run(vec![1.0, 1.0])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("outside of the domain"), "{}", err);
+    }
+
+    #[test]
+    fn test_pipeline_run_checked() {
+        let err = fragile_pipeline()
+            .on_failure(InnerFailurePolicy::Skip)
+            .run_checked(vec![3.0, 1.0])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("violates the bounds"), "{}", err);
+        assert!(fragile_pipeline()
+            .on_failure(InnerFailurePolicy::Skip)
+            .run_checked(vec![1.0, 1.0])
+            .is_ok());
+    }
+
+    /// Starts at `(3 - attempt, 3 - attempt)`, which is inside the domain of `Fragile` from the
+    /// third attempt on
+    fn retrying_pipeline(policy: InnerFailurePolicy) -> Pipeline<Fragile> {
+        Pipeline::new(Fragile)
+            .then_retrying(
+                "restart",
+                |_: &Vec<f64>, attempt| {
+                    let x = 3.0 - f64::from(attempt);
+                    NelderMead::from_point(vec![x, x], SimplexStep::Absolute(0.1))
+                },
+                StopAfter::Converged,
+            )
+            .on_failure(policy)
+    }
+
+    #[test]
+    fn test_pipeline_failure_retry() {
+        let recorder = Arc::new(Recorder::default());
+        let res = retrying_pipeline(InnerFailurePolicy::Retry(2))
+            .add_logger(recorder.clone())
+            .run(vec![1.0, 1.0])
+            .unwrap();
+        assert!(res.cost < 1e-8, "{}", res.cost);
+        assert_eq!(res.stages.len(), 1);
+        assert_eq!(
+            res.failures.iter().map(|f| f.attempt).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        // the evaluations of the failed attempts are included in the totals
+        assert!(res.failures.iter().all(|f| f.cost_evals > 0));
+        assert_eq!(
+            res.cost_evals(),
+            res.stages[0].cost_evals + res.failures.iter().map(|f| f.cost_evals).sum::<u64>()
+        );
+        assert!(res.cost_evals() > res.stages[0].cost_evals);
+        assert_eq!(
+            *recorder.failures.lock().unwrap(),
+            vec![
+                ("restart".to_string(), "0".to_string(), "1".to_string()),
+                ("restart".to_string(), "1".to_string(), "2".to_string())
+            ]
+        );
+
+        // too few retries
+        let recorder = Arc::new(Recorder::default());
+        assert!(retrying_pipeline(InnerFailurePolicy::Retry(1))
+            .add_logger(recorder.clone())
+            .run(vec![1.0, 1.0])
+            .is_err());
+        assert_eq!(recorder.failures.lock().unwrap().len(), 2);
+
+        // stages added with `then_named` cannot be retried
+        let err = fragile_pipeline()
+            .on_failure(InnerFailurePolicy::Retry(1))
+            .run(vec![1.0, 1.0])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("constructed once"), "{}", err);
+    }
+}
@@ -9,4 +9,10 @@
 //!
 //! Put `argmin::prelude::*` on top of your code to get all relevant traits into scope.
 
+pub use crate::constraint::{Constraint, Unconstrained};
+pub use crate::math::*;
+pub use crate::operator::{ArgminBoundedOp, ArgminBulkOp, ArgminSampledOp, ArgminTryApply};
+pub use crate::parameter::{ArgminParameter, Components, Perturbation};
+pub use crate::prox::{ArgminProject, ArgminProx};
+pub use crate::termination::TerminationStatus;
 pub use argmin_core::*;
@@ -0,0 +1,347 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Proximal operators
+//!
+//! The proximal operator of a function `g` with step size `t` is defined as
+//!
+//! `prox_{t g}(x) = argmin_z g(z) + 1/(2t) ||z - x||^2`.
+//!
+//! Proximal methods (ISTA, FISTA, ...) solve problems of the form `min f(x) + g(x)` with smooth `f`
+//! and a possibly nonsmooth `g` for which the proximal operator is cheap to evaluate. For
+//! indicator functions of convex sets the proximal operator is the projection onto the set,
+//! independent of the step size.
+//!
+//! The following proximal operators are implemented for `Vec<f64>`, for `ndarray::Array1<f64>` if
+//! the `ndarray` (or `ndarrayl`) feature is enabled and for `nalgebra::DVector<f64>` if the `nalgebra` feature is
+//! enabled:
+//!
+//! * [L1 norm (soft-thresholding)](struct.L1Norm.html)
+//! * [Elastic net](struct.ElasticNet.html)
+//! * [Box projection](struct.BoxProjection.html)
+//! * [L2 ball projection](struct.L2BallProjection.html)
+//! * [Simplex projection](struct.SimplexProjection.html)
+//!
+//! Projections onto feasible sets are available via the [`ArgminProject`](trait.ArgminProject.html)
+//! trait.
+//!
+//! # References:
+//!
+//! [0] Neal Parikh and Stephen Boyd (2014). Proximal Algorithms. Foundations and Trends in
+//! Optimization 1(3), 127-239.
+//!
+//! [1] John Duchi, Shai Shalev-Shwartz, Yoram Singer and Tushar Chandra (2008). Efficient
+//! projections onto the l1-ball for learning in high dimensions. ICML 2008.
+
+pub mod project;
+
+pub use self::project::*;
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Proximal operator
+pub trait ArgminProx<P> {
+    /// Evaluate the proximal operator at `x` with step size `step`
+    fn prox(&self, x: &P, step: f64) -> P;
+}
+
+macro_rules! impl_prox {
+    ($t:ty) => {
+        impl ArgminProx<Vec<f64>> for $t {
+            fn prox(&self, x: &Vec<f64>, step: f64) -> Vec<f64> {
+                self.prox_slice(x, step)
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminProx<ndarray::Array1<f64>> for $t {
+            fn prox(&self, x: &ndarray::Array1<f64>, step: f64) -> ndarray::Array1<f64> {
+                ndarray::Array1::from_vec(self.prox_slice(&x.to_vec(), step))
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminProx<nalgebra::DVector<f64>> for $t {
+            fn prox(&self, x: &nalgebra::DVector<f64>, step: f64) -> nalgebra::DVector<f64> {
+                nalgebra::DVector::from_iterator(x.len(), self.prox_slice(x.as_slice(), step))
+            }
+        }
+    };
+}
+
+pub(crate) fn invalid(text: &str) -> Error {
+    ArgminError::InvalidParameter {
+        text: text.to_string(),
+    }
+    .into()
+}
+
+/// Soft-thresholding `sign(x_i) * max(|x_i| - t, 0)`
+fn soft_threshold(x: &[f64], t: f64) -> Vec<f64> {
+    x.iter()
+        .map(|xi| xi.signum() * (xi.abs() - t).max(0.0))
+        .collect()
+}
+
+/// `g(x) = lambda * ||x||_1`
+///
+/// The proximal operator is the soft-thresholding operator
+/// `prox(x)_i = sign(x_i) * max(|x_i| - t * lambda, 0)`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct L1Norm {
+    /// weight
+    lambda: f64,
+}
+
+impl L1Norm {
+    /// Constructor
+    pub fn new(lambda: f64) -> Result<Self, Error> {
+        if lambda.is_nan() || lambda < 0.0 {
+            return Err(invalid("L1Norm: lambda must be >= 0."));
+        }
+        Ok(L1Norm { lambda })
+    }
+
+    fn prox_slice(&self, x: &[f64], step: f64) -> Vec<f64> {
+        soft_threshold(x, step * self.lambda)
+    }
+}
+
+impl_prox!(L1Norm);
+
+/// `g(x) = l1 * ||x||_1 + l2 / 2 * ||x||_2^2`
+///
+/// The proximal operator is `prox(x) = soft_threshold(x, t * l1) / (1 + t * l2)`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ElasticNet {
+    /// weight of the L1 term
+    l1: f64,
+    /// weight of the squared L2 term
+    l2: f64,
+}
+
+impl ElasticNet {
+    /// Constructor
+    pub fn new(l1: f64, l2: f64) -> Result<Self, Error> {
+        if l1.is_nan() || l1 < 0.0 || l2.is_nan() || l2 < 0.0 {
+            return Err(invalid("ElasticNet: l1 and l2 must be >= 0."));
+        }
+        Ok(ElasticNet { l1, l2 })
+    }
+
+    fn prox_slice(&self, x: &[f64], step: f64) -> Vec<f64> {
+        let scale = 1.0 / (1.0 + step * self.l2);
+        soft_threshold(x, step * self.l1)
+            .into_iter()
+            .map(|xi| xi * scale)
+            .collect()
+    }
+}
+
+impl_prox!(ElasticNet);
+
+/// Indicator function of the box `lower <= x <= upper`
+///
+/// The proximal operator is the projection `prox(x)_i = min(max(x_i, lower_i), upper_i)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoxProjection {
+    /// lower bound
+    lower: Vec<f64>,
+    /// upper bound
+    upper: Vec<f64>,
+}
+
+impl BoxProjection {
+    /// Constructor
+    pub fn new(lower: Vec<f64>, upper: Vec<f64>) -> Result<Self, Error> {
+        if lower.len() != upper.len() {
+            return Err(invalid(
+                "BoxProjection: lower and upper bound must have the same length.",
+            ));
+        }
+        if !lower.iter().zip(upper.iter()).all(|(l, u)| l <= u) {
+            return Err(invalid(
+                "BoxProjection: lower bound must not exceed upper bound.",
+            ));
+        }
+        Ok(BoxProjection { lower, upper })
+    }
+
+    fn prox_slice(&self, x: &[f64], _step: f64) -> Vec<f64> {
+        assert_eq!(x.len(), self.lower.len());
+        x.iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .map(|(xi, (l, u))| xi.max(*l).min(*u))
+            .collect()
+    }
+}
+
+impl_prox!(BoxProjection);
+
+/// Indicator function of the L2 ball `||x||_2 <= radius`
+///
+/// The proximal operator is the projection `prox(x) = x * min(1, radius / ||x||_2)`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct L2BallProjection {
+    /// radius
+    radius: f64,
+}
+
+impl L2BallProjection {
+    /// Constructor
+    pub fn new(radius: f64) -> Result<Self, Error> {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(invalid("L2BallProjection: radius must be >= 0."));
+        }
+        Ok(L2BallProjection { radius })
+    }
+
+    fn prox_slice(&self, x: &[f64], _step: f64) -> Vec<f64> {
+        let norm = x.iter().map(|xi| xi.powi(2)).sum::<f64>().sqrt();
+        if norm <= self.radius {
+            x.to_vec()
+        } else {
+            let scale = self.radius / norm;
+            x.iter().map(|xi| xi * scale).collect()
+        }
+    }
+}
+
+impl_prox!(L2BallProjection);
+
+/// Indicator function of the simplex `{x : x_i >= 0, sum_i x_i = radius}`
+///
+/// The projection is computed with the sort-based algorithm of Duchi et al. (2008) in
+/// `O(n log n)`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SimplexProjection {
+    /// sum of the components
+    radius: f64,
+}
+
+impl SimplexProjection {
+    /// Constructor
+    pub fn new(radius: f64) -> Result<Self, Error> {
+        if radius.is_nan() || radius <= 0.0 {
+            return Err(invalid("SimplexProjection: radius must be > 0."));
+        }
+        Ok(SimplexProjection { radius })
+    }
+
+    fn prox_slice(&self, x: &[f64], _step: f64) -> Vec<f64> {
+        let mut u = x.to_vec();
+        u.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let mut cumsum = 0.0;
+        let mut theta = 0.0;
+        for (j, uj) in u.iter().enumerate() {
+            cumsum += uj;
+            let t = (cumsum - self.radius) / (j + 1) as f64;
+            if uj - t > 0.0 {
+                theta = t;
+            }
+        }
+        x.iter().map(|xi| (xi - theta).max(0.0)).collect()
+    }
+}
+
+impl_prox!(SimplexProjection);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(l1norm, L1Norm);
+    send_sync_test!(elastic_net, ElasticNet);
+    send_sync_test!(box_projection, BoxProjection);
+    send_sync_test!(l2ball_projection, L2BallProjection);
+    send_sync_test!(simplex_projection, SimplexProjection);
+
+    fn assert_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_l1norm() {
+        let p = L1Norm::new(2.0).unwrap();
+        let x = vec![3.0, -0.5, -4.0, 1.0];
+        assert_close(&p.prox(&x, 0.5), &[2.0, 0.0, -3.0, 0.0]);
+        assert!(L1Norm::new(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_elastic_net() {
+        let p = ElasticNet::new(1.0, 2.0).unwrap();
+        let x = vec![3.0, -0.5, -4.0];
+        // soft threshold with 0.5, then scale by 1 / (1 + 0.5 * 2)
+        assert_close(&p.prox(&x, 0.5), &[1.25, 0.0, -1.75]);
+        // l2 = 0 reduces to L1Norm
+        let p = ElasticNet::new(2.0, 0.0).unwrap();
+        let q = L1Norm::new(2.0).unwrap();
+        assert_close(&p.prox(&x, 0.3), &q.prox(&x, 0.3));
+    }
+
+    #[test]
+    fn test_box_projection() {
+        let p = BoxProjection::new(vec![0.0, -1.0, 0.0], vec![1.0, 1.0, 0.0]).unwrap();
+        let x = vec![2.0, -3.0, 5.0];
+        assert_close(&p.prox(&x, 1.0), &[1.0, -1.0, 0.0]);
+        assert_close(&p.prox(&vec![0.5, 0.5, 0.0], 1.0), &[0.5, 0.5, 0.0]);
+        assert!(BoxProjection::new(vec![1.0], vec![0.0]).is_err());
+        assert!(BoxProjection::new(vec![1.0], vec![2.0, 3.0]).is_err());
+    }
+
+    #[test]
+    fn test_l2ball_projection() {
+        let p = L2BallProjection::new(1.0).unwrap();
+        assert_close(&p.prox(&vec![3.0, 4.0], 1.0), &[0.6, 0.8]);
+        assert_close(&p.prox(&vec![0.3, 0.4], 1.0), &[0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_simplex_projection() {
+        let p = SimplexProjection::new(1.0).unwrap();
+        assert_close(&p.prox(&vec![0.5, 0.5], 1.0), &[0.5, 0.5]);
+        assert_close(&p.prox(&vec![2.0, 0.0], 1.0), &[1.0, 0.0]);
+        assert_close(&p.prox(&vec![1.5, 0.4, -0.2], 1.0), &[1.0, 0.0, 0.0]);
+        assert_close(
+            &p.prox(&vec![0.2, 0.3, 0.9], 1.0),
+            &[0.2 - 0.4 / 3.0, 0.3 - 0.4 / 3.0, 0.9 - 0.4 / 3.0],
+        );
+        let p = SimplexProjection::new(2.0).unwrap();
+        let w = p.prox(&vec![-1.0, 3.0, 0.5, 2.0], 1.0);
+        assert!((w.iter().sum::<f64>() - 2.0).abs() < 1e-12);
+        assert_close(&w, &[0.0, 1.5, 0.0, 0.5]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_prox_ndarray() {
+        use ndarray::array;
+        let p = L1Norm::new(2.0).unwrap();
+        let x = array![3.0, -0.5, -4.0, 1.0];
+        assert_eq!(p.prox(&x, 0.5), array![2.0, 0.0, -3.0, 0.0]);
+        let p = SimplexProjection::new(1.0).unwrap();
+        assert_eq!(p.prox(&array![2.0, 0.0], 1.0), array![1.0, 0.0]);
+    }
+
+    #[cfg(feature = "nalgebra")]
+    #[test]
+    fn test_prox_nalgebra() {
+        use nalgebra::DVector;
+        let p = L1Norm::new(2.0).unwrap();
+        let x = DVector::from_iterator(4, vec![3.0, -0.5, -4.0, 1.0]);
+        assert_eq!(
+            p.prox(&x, 0.5),
+            DVector::from_iterator(4, vec![2.0, 0.0, -3.0, 0.0])
+        );
+    }
+}
@@ -0,0 +1,440 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Projections onto feasible sets
+//!
+//! Projected gradient methods and population based methods which need to clip particles to the
+//! feasible set only need to know how to project a point onto that set. `ArgminProject` is the
+//! common interface for this. It is implemented for:
+//!
+//! * [`Bounds`](../../operator/struct.Bounds.html) (with possibly missing sides)
+//! * [Boxes](../struct.BoxProjection.html)
+//! * [L2 balls](../struct.L2BallProjection.html)
+//! * [Affine sets](struct.AffineProjection.html) `{x : Ax = b}`
+//! * [Intersection of a box and an L2 ball](struct.BoxBallIntersection.html)
+//!
+//! # References:
+//!
+//! [0] James P. Boyle and Richard L. Dykstra (1986). A method for finding projections onto the
+//! intersection of convex sets in Hilbert spaces. Lecture Notes in Statistics 37, 28-47.
+
+use crate::operator::Bounds;
+use crate::prelude::*;
+use crate::prox::{invalid, BoxProjection, L2BallProjection};
+use serde::{Deserialize, Serialize};
+
+/// Euclidean projection onto a closed convex set
+pub trait ArgminProject<P> {
+    /// Return the point of the set closest to `x`
+    fn project(&self, x: &P) -> P;
+}
+
+macro_rules! impl_project {
+    ($t:ty) => {
+        impl ArgminProject<Vec<f64>> for $t {
+            fn project(&self, x: &Vec<f64>) -> Vec<f64> {
+                self.project_slice(x)
+            }
+        }
+
+        #[cfg(feature = "ndarray")]
+        impl ArgminProject<ndarray::Array1<f64>> for $t {
+            fn project(&self, x: &ndarray::Array1<f64>) -> ndarray::Array1<f64> {
+                ndarray::Array1::from_vec(self.project_slice(&x.to_vec()))
+            }
+        }
+
+        #[cfg(feature = "nalgebra")]
+        impl ArgminProject<nalgebra::DVector<f64>> for $t {
+            fn project(&self, x: &nalgebra::DVector<f64>) -> nalgebra::DVector<f64> {
+                nalgebra::DVector::from_iterator(x.len(), self.project_slice(x.as_slice()))
+            }
+        }
+    };
+}
+
+impl BoxProjection {
+    fn project_slice(&self, x: &[f64]) -> Vec<f64> {
+        self.prox_slice(x, 1.0)
+    }
+}
+
+impl_project!(BoxProjection);
+
+impl L2BallProjection {
+    fn project_slice(&self, x: &[f64]) -> Vec<f64> {
+        self.prox_slice(x, 1.0)
+    }
+}
+
+impl_project!(L2BallProjection);
+
+impl ArgminProject<Vec<f64>> for Bounds<Vec<f64>> {
+    fn project(&self, x: &Vec<f64>) -> Vec<f64> {
+        let mut out = x.clone();
+        if let Some(l) = self.lower() {
+            assert_eq!(x.len(), l.len());
+            out.iter_mut()
+                .zip(l.iter())
+                .for_each(|(o, l)| *o = o.max(*l));
+        }
+        if let Some(u) = self.upper() {
+            assert_eq!(x.len(), u.len());
+            out.iter_mut()
+                .zip(u.iter())
+                .for_each(|(o, u)| *o = o.min(*u));
+        }
+        out
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl ArgminProject<ndarray::Array1<f64>> for Bounds<ndarray::Array1<f64>> {
+    fn project(&self, x: &ndarray::Array1<f64>) -> ndarray::Array1<f64> {
+        let mut out = x.clone();
+        if let Some(l) = self.lower() {
+            out.zip_mut_with(l, |o, l| *o = o.max(*l));
+        }
+        if let Some(u) = self.upper() {
+            out.zip_mut_with(u, |o, u| *o = o.min(*u));
+        }
+        out
+    }
+}
+
+/// Inverts a square matrix with Gauss-Jordan elimination and partial pivoting. Returns `None` if
+/// the matrix is (numerically) singular.
+fn invert(mut m: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = m.len();
+    let scale = m
+        .iter()
+        .flat_map(|row| row.iter())
+        .fold(0.0f64, |acc, x| acc.max(x.abs()));
+    let mut inv: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| {
+            m[a][col]
+                .abs()
+                .partial_cmp(&m[b][col].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        let piv = m[pivot][col].abs();
+        if piv.is_nan() || piv <= std::f64::EPSILON * scale * n as f64 {
+            return None;
+        }
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+        let p = m[col][col];
+        m[col].iter_mut().for_each(|x| *x /= p);
+        inv[col].iter_mut().for_each(|x| *x /= p);
+        for row in 0..n {
+            if row != col {
+                let f = m[row][col];
+                for k in 0..n {
+                    m[row][k] -= f * m[col][k];
+                    inv[row][k] -= f * inv[col][k];
+                }
+            }
+        }
+    }
+    Some(inv)
+}
+
+/// Projection onto the affine set `{x : Ax = b}`
+///
+/// `A` must have full row rank. The pseudoinverse `A^T (A A^T)^-1` is computed once in the
+/// constructor; a projection then costs two matrix-vector products:
+/// `P(x) = x - A^+ (Ax - b)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AffineProjection {
+    /// constraint matrix (m x n)
+    a: Vec<Vec<f64>>,
+    /// right hand side (m)
+    b: Vec<f64>,
+    /// pseudoinverse of `a` (n x m)
+    pinv: Vec<Vec<f64>>,
+}
+
+impl AffineProjection {
+    /// Constructor. Returns an error if the dimensions do not match or if `a` does not have full
+    /// row rank.
+    pub fn new(a: Vec<Vec<f64>>, b: Vec<f64>) -> Result<Self, Error> {
+        let m = a.len();
+        if m == 0 || m != b.len() {
+            return Err(invalid(
+                "AffineProjection: `a` must have as many (and at least one) rows as `b` has entries.",
+            ));
+        }
+        let n = a[0].len();
+        if a.iter().any(|row| row.len() != n) {
+            return Err(invalid(
+                "AffineProjection: all rows of `a` must have the same length.",
+            ));
+        }
+        let aat: Vec<Vec<f64>> = a
+            .iter()
+            .map(|ri| {
+                a.iter()
+                    .map(|rj| ri.iter().zip(rj.iter()).map(|(x, y)| x * y).sum())
+                    .collect()
+            })
+            .collect();
+        let aat_inv =
+            invert(aat).ok_or_else(|| invalid("AffineProjection: `a` must have full row rank."))?;
+        let pinv = (0..n)
+            .map(|i| {
+                (0..m)
+                    .map(|j| (0..m).map(|k| a[k][i] * aat_inv[k][j]).sum())
+                    .collect()
+            })
+            .collect();
+        Ok(AffineProjection { a, b, pinv })
+    }
+
+    fn project_slice(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(x.len(), self.pinv.len());
+        let r: Vec<f64> = self
+            .a
+            .iter()
+            .zip(self.b.iter())
+            .map(|(row, bi)| row.iter().zip(x.iter()).map(|(a, x)| a * x).sum::<f64>() - bi)
+            .collect();
+        x.iter()
+            .zip(self.pinv.iter())
+            .map(|(xi, row)| xi - row.iter().zip(r.iter()).map(|(p, r)| p * r).sum::<f64>())
+            .collect()
+    }
+}
+
+impl_project!(AffineProjection);
+
+/// Projection onto the intersection of a box and an L2 ball centered at the origin
+///
+/// The projection is computed with Dykstra's alternating projection algorithm, which (unlike
+/// plain alternating projections) converges to the projection onto the intersection and not just
+/// to some point inside of it. Iteration stops once consecutive iterates differ by less than
+/// `tol` or after `max_iters` iterations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BoxBallIntersection {
+    /// box
+    boxp: BoxProjection,
+    /// ball
+    ball: L2BallProjection,
+    /// maximum number of alternating projections
+    max_iters: u64,
+    /// tolerance on the change between consecutive iterates
+    tol: f64,
+}
+
+impl BoxBallIntersection {
+    /// Constructor. Returns an error if the intersection is empty.
+    pub fn new(boxp: BoxProjection, ball: L2BallProjection) -> Result<Self, Error> {
+        // closest point of the box to the center of the ball
+        let dist = boxp
+            .lower
+            .iter()
+            .zip(boxp.upper.iter())
+            .map(|(l, u)| 0.0f64.max(*l).min(*u).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        if dist > ball.radius {
+            return Err(invalid(
+                "BoxBallIntersection: box and ball do not intersect.",
+            ));
+        }
+        Ok(BoxBallIntersection {
+            boxp,
+            ball,
+            max_iters: 1000,
+            tol: 1e-12,
+        })
+    }
+
+    /// Set maximum number of iterations (default: 1000)
+    pub fn max_iters(mut self, max_iters: u64) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    /// Set tolerance (default: 1e-12)
+    pub fn tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol <= 0.0 {
+            return Err(invalid("BoxBallIntersection: tol must be > 0."));
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    fn project_slice(&self, x0: &[f64]) -> Vec<f64> {
+        let n = x0.len();
+        let mut x = x0.to_vec();
+        let mut p = vec![0.0; n];
+        let mut q = vec![0.0; n];
+        for _ in 0..self.max_iters {
+            let xp: Vec<f64> = x.iter().zip(p.iter()).map(|(a, b)| a + b).collect();
+            let y = self.boxp.project_slice(&xp);
+            p = xp.iter().zip(y.iter()).map(|(a, b)| a - b).collect();
+            let yq: Vec<f64> = y.iter().zip(q.iter()).map(|(a, b)| a + b).collect();
+            let x_new = self.ball.project_slice(&yq);
+            q = yq.iter().zip(x_new.iter()).map(|(a, b)| a - b).collect();
+            let change = x
+                .iter()
+                .zip(x_new.iter())
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            x = x_new;
+            if change < self.tol {
+                break;
+            }
+        }
+        x
+    }
+}
+
+impl_project!(BoxBallIntersection);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    send_sync_test!(affine_projection, AffineProjection);
+    send_sync_test!(box_ball_intersection, BoxBallIntersection);
+
+    fn dist(a: &[f64], b: &[f64]) -> f64 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn random_vec(rng: &mut XorShiftRng, n: usize, scale: f64) -> Vec<f64> {
+        (0..n).map(|_| rng.gen_range(-scale, scale)).collect()
+    }
+
+    /// Checks that `proj(x)` is feasible and that no feasible point (obtained by projecting random
+    /// points) is closer to `x`.
+    fn check_optimality<T: ArgminProject<Vec<f64>>>(
+        proj: &T,
+        feasible: &dyn Fn(&[f64]) -> bool,
+        n: usize,
+        seed: u64,
+        tol: f64,
+    ) {
+        let mut rng = XorShiftRng::seed_from_u64(seed);
+        for _ in 0..50 {
+            let x = random_vec(&mut rng, n, 5.0);
+            let p = proj.project(&x);
+            assert!(feasible(&p), "{:?} is infeasible", p);
+            // projecting a feasible point must not move it
+            assert!(dist(&proj.project(&p), &p) < tol);
+            let d = dist(&x, &p);
+            for _ in 0..50 {
+                let z = proj.project(&random_vec(&mut rng, n, 5.0));
+                assert!(d <= dist(&x, &z) + tol);
+            }
+        }
+    }
+
+    #[test]
+    fn test_box_projection() {
+        let lower = vec![-1.0, 0.0, 2.0];
+        let upper = vec![1.0, 0.5, 3.0];
+        let proj = BoxProjection::new(lower.clone(), upper.clone()).unwrap();
+        let feasible = |x: &[f64]| {
+            x.iter()
+                .zip(lower.iter().zip(upper.iter()))
+                .all(|(x, (l, u))| l <= x && x <= u)
+        };
+        check_optimality(&proj, &feasible, 3, 1, 1e-10);
+    }
+
+    #[test]
+    fn test_bounds_projection() {
+        let bounds = Bounds::from_options(Some(vec![-1.0, 0.0]), None).unwrap();
+        assert_eq!(bounds.project(&vec![-3.0, 5.0]), vec![-1.0, 5.0]);
+        let bounds = Bounds::new(vec![-1.0, 0.0], vec![1.0, 0.5]).unwrap();
+        let feasible = |x: &[f64]| bounds.contains(&x.to_vec());
+        check_optimality(&bounds, &feasible, 2, 2, 1e-10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bounds_projection_wrong_length() {
+        let bounds = Bounds::from_options(None, Some(vec![1.0, 0.5])).unwrap();
+        bounds.project(&vec![3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_l2ball_projection() {
+        let proj = L2BallProjection::new(2.0).unwrap();
+        let feasible = |x: &[f64]| x.iter().map(|x| x.powi(2)).sum::<f64>().sqrt() <= 2.0 + 1e-12;
+        check_optimality(&proj, &feasible, 4, 3, 1e-10);
+    }
+
+    #[test]
+    fn test_affine_projection() {
+        let a = vec![vec![1.0, 1.0, 1.0], vec![1.0, -1.0, 0.0]];
+        let b = vec![1.0, 0.5];
+        let proj = AffineProjection::new(a.clone(), b.clone()).unwrap();
+        let feasible = |x: &[f64]| {
+            a.iter().zip(b.iter()).all(|(row, bi)| {
+                (row.iter().zip(x.iter()).map(|(a, x)| a * x).sum::<f64>() - bi).abs() < 1e-10
+            })
+        };
+        check_optimality(&proj, &feasible, 3, 4, 1e-8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_affine_projection_wrong_length() {
+        let proj = AffineProjection::new(vec![vec![1.0, 1.0, 1.0]], vec![1.0]).unwrap();
+        proj.project(&vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_affine_projection_rank_deficient() {
+        let a = vec![vec![1.0, 2.0], vec![2.0, 4.0]];
+        assert!(AffineProjection::new(a, vec![1.0, 2.0]).is_err());
+        assert!(AffineProjection::new(vec![vec![1.0, 2.0]], vec![1.0, 2.0]).is_err());
+    }
+
+    #[test]
+    fn test_box_ball_intersection() {
+        let boxp = BoxProjection::new(vec![0.5, -1.0], vec![2.0, 1.0]).unwrap();
+        let ball = L2BallProjection::new(1.0).unwrap();
+        let proj = BoxBallIntersection::new(boxp, ball).unwrap();
+        // The closest feasible point to (2, 2) lies on the arc where x_1 >= 0.5,
+        // i.e. (1, 1) / sqrt(2).
+        let p = proj.project(&vec![2.0, 2.0]);
+        let s = 1.0 / 2.0f64.sqrt();
+        assert!(dist(&p, &[s, s]) < 1e-6, "{:?}", p);
+        // The closest feasible point to (0, 3) is (0.5, sqrt(0.75)).
+        let p = proj.project(&vec![0.0, 3.0]);
+        assert!(dist(&p, &[0.5, 0.75f64.sqrt()]) < 1e-6, "{:?}", p);
+        let feasible = |x: &[f64]| {
+            x[0] >= 0.5 - 1e-6
+                && x[0] <= 2.0 + 1e-6
+                && x[1].abs() <= 1.0 + 1e-6
+                && x.iter().map(|x| x.powi(2)).sum::<f64>().sqrt() <= 1.0 + 1e-6
+        };
+        check_optimality(&proj, &feasible, 2, 5, 1e-6);
+    }
+
+    #[test]
+    fn test_box_ball_empty_intersection() {
+        let boxp = BoxProjection::new(vec![2.0, 2.0], vec![3.0, 3.0]).unwrap();
+        let ball = L2BallProjection::new(1.0).unwrap();
+        assert!(BoxBallIntersection::new(boxp, ball).is_err());
+    }
+}
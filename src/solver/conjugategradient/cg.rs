@@ -70,18 +70,18 @@ where
     }
 
     /// Return the current search direction (This is needed by NewtonCG for instance)
-    pub fn p(&self) -> P {
-        self.p.clone()
+    pub fn p(&self) -> &P {
+        &self.p
     }
 
     /// Return the previous search direction (This is needed by NewtonCG for instance)
-    pub fn p_prev(&self) -> P {
-        self.p_prev.clone()
+    pub fn p_prev(&self) -> &P {
+        &self.p_prev
     }
 
     /// Return the current residual (This is needed by NewtonCG for instance)
-    pub fn residual(&self) -> P {
-        self.r.clone()
+    pub fn residual(&self) -> &P {
+        &self.r
     }
 }
 
@@ -96,13 +96,20 @@ where
         + ArgminScaledAdd<P, f64, P>
         + ArgminAdd<P, P>
         + ArgminMul<f64, P>
-        + ArgminDot<P, f64>,
+        + ArgminDot<P, f64>
+        + ArgminInPlace<f64>,
 {
     fn init(
         &mut self,
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<Option<ArgminIterData<O>>, Error> {
+        if !self.b.dot(&self.b).is_finite() {
+            return Err(ArgminError::InvalidParameter {
+                text: "ConjugateGradient: b must be finite.".to_string(),
+            }
+            .into());
+        }
         let init_param = state.get_param();
         let ap = op.apply(&init_param)?;
         let r0 = self.b.sub(&ap).mul(&(-1.0));
@@ -118,15 +125,18 @@ where
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
-        self.p_prev = self.p.clone();
+        self.p_prev.clone_from(&self.p);
         let apk = op.apply(&self.p)?;
         self.alpha = self.rtr / self.p.dot(&apk);
-        let new_param = state.get_param().scaled_add(&self.alpha, &self.p);
-        self.r = self.r.scaled_add(&self.alpha, &apk);
+        let mut new_param = state.get_param();
+        new_param.scaled_add_assign(&self.alpha, &self.p);
+        self.r.scaled_add_assign(&self.alpha, &apk);
         let rtr_n = self.r.dot(&self.r);
         self.beta = rtr_n / self.rtr;
         self.rtr = rtr_n;
-        self.p = self.r.mul(&(-1.0)).scaled_add(&self.beta, &self.p);
+        // `beta * p - r` equals `-r + beta * p` bit by bit
+        self.p.mul_assign_scalar(&self.beta);
+        self.p.scaled_sub_assign(&1.0, &self.r);
         let norm = self.r.dot(&self.r);
 
         Ok(ArgminIterData::new()
@@ -140,9 +150,95 @@ where
 mod tests {
     use super::*;
     use crate::send_sync_test;
+    use crate::testfunctions::{sphere_hessian, sphere_shifted_derivative};
 
     send_sync_test!(
         conjugate_gradient,
         ConjugateGradient<NoOperator<Vec<f64>, Vec<f64>, ()>>
     );
+
+    /// Applies the Hessian of the sphere function, `A = 2 I`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct SphereHessian {}
+
+    impl ArgminOp for SphereHessian {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sphere_hessian(x)
+                .iter()
+                .map(|row| row.iter().zip(x.iter()).map(|(a, b)| a * b).sum())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_cg_sphere_one_sweep() {
+        // minimizing the sphere shifted to `c` amounts to solving `2 I x = 2 c`
+        let c = vec![1.0, -2.0, 3.0];
+        let b: Vec<f64> = sphere_shifted_derivative(&[0.0; 3], &c)
+            .iter()
+            .map(|g| -g)
+            .collect();
+        let solver = ConjugateGradient::new(b).unwrap();
+        let res = Executor::new(SphereHessian {}, solver, vec![0.0; 3])
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        for (x, ci) in res.param.iter().zip(c.iter()) {
+            assert!((x - ci).abs() < 1e-12);
+        }
+    }
+
+    /// Diagonal matrix with the entries `1, 2, ..., 10, 1, 2, ...`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Diagonal {}
+
+    impl ArgminOp for Diagonal {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(x.iter()
+                .enumerate()
+                .map(|(i, x)| (1 + i % 10) as f64 * x)
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_cg_allocations() {
+        use crate::allocations::allocations;
+
+        let n = 100_000;
+        let mut solver = ConjugateGradient::new(vec![1.0; n]).unwrap();
+        let mut op = OpWrapper::new(&Diagonal {});
+        let mut state = IterState::new(vec![0.0; n]);
+        solver.init(&mut op, &state).unwrap();
+        // the first iteration allocates the buffer of the previous search direction
+        let data = solver.next_iter(&mut op, &state).unwrap();
+        state.param(data.get_param().unwrap());
+        for _ in 0..5 {
+            let (allocated, data) = allocations(|| solver.next_iter(&mut op, &state).unwrap());
+            // Only the new parameter vector and the output of the operator are allocated,
+            // the residual and the search direction are updated in place.
+            assert!(allocated.bytes < 3 * n * std::mem::size_of::<f64>());
+            assert!(allocated.count < 20);
+            state.param(data.get_param().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_cg_non_finite_b() {
+        let mut solver = ConjugateGradient::new(vec![1.0, std::f64::NAN]).unwrap();
+        let mut op = OpWrapper::new(&Diagonal {});
+        let e = solver
+            .init(&mut op, &IterState::new(vec![0.0; 2]))
+            .err()
+            .unwrap();
+        assert!(e.to_string().contains("b must be finite"), "{}", e);
+    }
 }
@@ -8,6 +8,7 @@
 //! Conjugate Gradient methods
 //!
 //! * [Conjugate Gradients](cg/struct.ConjugateGradient.html)
+//! * [Preconditioned Conjugate Gradients](pcg/struct.PreconditionedConjugateGradient.html)
 //! * [Nonlinear Conjugate Gradients](nonlinear_cg/struct.NonlinearConjugateGradient.html)
 //!
 //! # References:
@@ -21,9 +22,13 @@ pub mod cg;
 /// Nonlinear conjugate gradient method
 pub mod nonlinear_cg;
 
+/// Preconditioned conjugate gradient method
+pub mod pcg;
+
 /// Beta update methods for nonlinear CG
 pub mod beta;
 
 pub use self::beta::*;
 pub use self::cg::*;
 pub use self::nonlinear_cg::*;
+pub use self::pcg::*;
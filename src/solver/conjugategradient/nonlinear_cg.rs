@@ -14,6 +14,7 @@
 //! Springer. ISBN 0-387-30303-0.
 
 use crate::prelude::*;
+use crate::solver::linesearch::LineSearchFailure;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::default::Default;
@@ -21,6 +22,9 @@ use std::default::Default;
 /// The nonlinear conjugate gradient is a generalization of the conjugate gradient method for
 /// nonlinear optimization problems.
 ///
+/// If the line search gives up, the solver either stops or accepts the step depending on the
+/// `LineSearchFailure` policy (default: stop).
+///
 /// # Example
 ///
 /// ```rust
@@ -45,6 +49,8 @@ pub struct NonlinearConjugateGradient<P, L, B> {
     restart_iter: u64,
     /// Restart based on orthogonality
     restart_orthogonality: Option<f64>,
+    /// behavior on line search failures
+    linesearch_failure: LineSearchFailure,
 }
 
 impl<P, L, B> NonlinearConjugateGradient<P, L, B>
@@ -60,15 +66,22 @@ where
             beta_method: beta_method,
             restart_iter: std::u64::MAX,
             restart_orthogonality: None,
+            linesearch_failure: LineSearchFailure::default(),
         })
     }
 
-    /// Specifiy the number of iterations after which a restart should be performed
+    /// Specifiy the number of iterations (`>= 1`) after which a restart should be performed
     /// This allows the algorithm to "forget" previous information which may not be helpful
     /// anymore.
-    pub fn restart_iters(mut self, iters: u64) -> Self {
+    pub fn restart_iters(mut self, iters: u64) -> Result<Self, Error> {
+        if iters == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NonlinearConjugateGradient: restart_iters must be >= 1.".to_string(),
+            }
+            .into());
+        }
         self.restart_iter = iters;
-        self
+        Ok(self)
     }
 
     /// Set the value for the orthogonality measure.
@@ -78,9 +91,22 @@ where
     ///
     /// `|\nabla f_k^T * \nabla f_{k-1}| / | \nabla f_k ||^2 >= v`
     ///
-    /// A typical value for `v` is 0.1.
-    pub fn restart_orthogonality(mut self, v: f64) -> Self {
+    /// A typical value for `v` is 0.1. `v` must be `> 0`.
+    pub fn restart_orthogonality(mut self, v: f64) -> Result<Self, Error> {
+        if !v.is_finite() || v <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NonlinearConjugateGradient: restart_orthogonality must be in (0, inf)."
+                    .to_string(),
+            }
+            .into());
+        }
         self.restart_orthogonality = Some(v);
+        Ok(self)
+    }
+
+    /// Set the behavior on line search failures (default: `LineSearchFailure::Stop`)
+    pub fn linesearch_failure(mut self, policy: LineSearchFailure) -> Self {
+        self.linesearch_failure = policy;
         self
     }
 }
@@ -134,15 +160,29 @@ where
         self.linesearch.set_search_direction(self.p.clone());
 
         // Run solver
-        let linesearch_result =
-            Executor::new(OpWrapper::new_from_op(&op), self.linesearch.clone(), xk)
-                .grad(grad.clone())
-                .cost(cur_cost)
-                .run_fast()?;
+        let linesearch_result = Executor::new(
+            OpWrapper::new_from_op(&op),
+            self.linesearch.clone(),
+            xk.clone(),
+        )
+        .grad(grad.clone())
+        .cost(cur_cost)
+        .run_fast()?;
 
         // takes care of the counts of function evaluations
         op.consume_op(linesearch_result.operator);
 
+        if self
+            .linesearch_failure
+            .stop(&linesearch_result.termination_reason)
+        {
+            return Ok(ArgminIterData::new()
+                .param(xk)
+                .cost(cur_cost)
+                .grad(grad)
+                .termination_reason(TerminationReason::Aborted));
+        }
+
         let xk1 = linesearch_result.param;
 
         // Update of beta
@@ -184,7 +224,10 @@ mod tests {
     use super::*;
     use crate::send_sync_test;
     use crate::solver::conjugategradient::beta::PolakRibiere;
-    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::linesearch::{
+        ArmijoCondition, BacktrackingLineSearch, MoreThuenteLineSearch,
+    };
+    use crate::testfunctions::{zakharov, zakharov_derivative};
     use crate::MinimalNoOperator;
 
     send_sync_test!(
@@ -195,4 +238,93 @@ mod tests {
             PolakRibiere,
         >
     );
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Zakharov {}
+
+    impl ArgminOp for Zakharov {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(zakharov(p))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(zakharov_derivative(p))
+        }
+    }
+
+    #[test]
+    fn test_nonlinear_cg_zakharov() {
+        let solver =
+            NonlinearConjugateGradient::new(MoreThuenteLineSearch::new(), PolakRibiere::new())
+                .unwrap()
+                .restart_iters(5)
+                .unwrap();
+        let res = Executor::new(Zakharov {}, solver, vec![0.5, -0.3, 0.2, 0.1, -0.4])
+            .max_iters(100)
+            .target_cost(1e-14)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost < 1e-12);
+        assert!(res.param.iter().all(|x| x.abs() < 1e-5));
+    }
+
+    /// `f(x) = sum_i (x_i - 3)^2`, NaN if any `x_i > 0.25`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct NanBeyond {}
+
+    impl ArgminOp for NanBeyond {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            if x.iter().any(|x| *x > 0.25) {
+                return Ok(std::f64::NAN);
+            }
+            Ok(x.iter().map(|x| (x - 3.0).powi(2)).sum())
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(x.iter().map(|x| 2.0 * (x - 3.0)).collect())
+        }
+    }
+
+    fn failing_linesearch() -> BacktrackingLineSearch<Vec<f64>, ArmijoCondition> {
+        // all trial points lie in the NaN region
+        BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap()
+            .max_iters(5)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_nonlinear_cg_linesearch_failure() {
+        let solver =
+            NonlinearConjugateGradient::new(failing_linesearch(), PolakRibiere::new()).unwrap();
+        let res = Executor::new(NanBeyond {}, solver, vec![0.0, 0.0])
+            .max_iters(10)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::Aborted);
+        assert!(res.param.iter().all(|x| x.is_zero()));
+    }
+
+    #[test]
+    fn test_nonlinear_cg_invalid_parameters() {
+        let solver = || {
+            NonlinearConjugateGradient::new(MoreThuenteLineSearch::new(), PolakRibiere::new())
+                .unwrap()
+        };
+        let e = solver().restart_iters(0).err().unwrap();
+        assert!(e.to_string().contains("restart_iters"), "{}", e);
+        for v in [0.0, -0.1, std::f64::NAN].iter() {
+            let e = solver().restart_orthogonality(*v).err().unwrap();
+            assert!(e.to_string().contains("restart_orthogonality"), "{}", e);
+        }
+    }
 }
@@ -0,0 +1,184 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::math::Diagonal;
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::default::Default;
+
+/// The preconditioned conjugate gradient method solves systems of linear equations `A x = b`
+/// with a symmetric and positive-definite matrix `A` using a diagonal preconditioner.
+///
+/// The preconditioner is given as the diagonal of `M^-1`, where `M` approximates `A` (for instance
+/// the inverse of the diagonal of `A`, i.e. Jacobi preconditioning). The inner products
+/// `r^T M^-1 r` are computed with `ArgminWeightedDot`.
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PreconditionedConjugateGradient<P> {
+    /// b (right hand side)
+    b: P,
+    /// diagonal of the inverse preconditioner `M^-1`
+    precond: Diagonal<P>,
+    /// residual
+    r: P,
+    /// p
+    p: P,
+    /// r^T M^-1 r
+    rzr: f64,
+    /// alpha
+    alpha: f64,
+    /// beta
+    beta: f64,
+}
+
+impl<P> PreconditionedConjugateGradient<P>
+where
+    P: Clone + Default,
+{
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// `b`: right hand side of `A * x = b`
+    ///
+    /// `precond`: diagonal of the inverse preconditioner `M^-1`
+    pub fn new(b: P, precond: Diagonal<P>) -> Result<Self, Error> {
+        Ok(PreconditionedConjugateGradient {
+            b,
+            precond,
+            r: P::default(),
+            p: P::default(),
+            rzr: std::f64::NAN,
+            alpha: std::f64::NAN,
+            beta: std::f64::NAN,
+        })
+    }
+
+    /// Return the current residual
+    pub fn residual(&self) -> P {
+        self.r.clone()
+    }
+}
+
+impl<P, O> Solver<O> for PreconditionedConjugateGradient<P>
+where
+    O: ArgminOp<Param = P, Output = P>,
+    P: Clone
+        + Default
+        + Serialize
+        + DeserializeOwned
+        + ArgminSub<P, P>
+        + ArgminDot<P, f64>
+        + ArgminScaledAdd<P, f64, P>
+        + ArgminMul<f64, P>
+        + ArgminElementwise
+        + ArgminWeightedDot<P, f64, Diagonal<P>>
+        + ArgminInPlace<f64>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let init_param = state.get_param();
+        let ap = op.apply(&init_param)?;
+        self.r = ap.sub(&self.b);
+        self.p = self.r.mul_elementwise(self.precond.diag()).mul(&(-1.0));
+        self.rzr = self.r.weighted_dot(&self.precond, &self.r);
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let apk = op.apply(&self.p)?;
+        self.alpha = self.rzr / self.p.dot(&apk);
+        let mut new_param = state.get_param();
+        new_param.scaled_add_assign(&self.alpha, &self.p);
+        self.r.scaled_add_assign(&self.alpha, &apk);
+        let rzr_n = self.r.weighted_dot(&self.precond, &self.r);
+        self.beta = rzr_n / self.rzr;
+        self.rzr = rzr_n;
+        // `beta * p - z` equals `-z + beta * p` bit by bit
+        self.p.mul_assign_scalar(&self.beta);
+        self.p
+            .scaled_sub_assign(&1.0, &self.r.mul_elementwise(self.precond.diag()));
+        let norm = self.r.dot(&self.r);
+
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(norm.sqrt())
+            .kv(make_kv!("alpha" => self.alpha; "beta" => self.beta;)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(
+        preconditioned_conjugate_gradient,
+        PreconditionedConjugateGradient<Vec<f64>>
+    );
+
+    /// `A = diag(1, 10, 100)`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct DiagonalSystem {}
+
+    impl ArgminOp for DiagonalSystem {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![x[0], 10.0 * x[1], 100.0 * x[2]])
+        }
+    }
+
+    #[test]
+    fn test_exact_preconditioner_one_iteration() {
+        let b = vec![1.0, 1.0, 1.0];
+        let precond = Diagonal::new(vec![1.0, 0.1, 0.01]).unwrap();
+        let solver = PreconditionedConjugateGradient::new(b, precond).unwrap();
+        let res = Executor::new(DiagonalSystem {}, solver, vec![0.0; 3])
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        assert!((res.param[0] - 1.0).abs() < 1e-12);
+        assert!((res.param[1] - 0.1).abs() < 1e-12);
+        assert!((res.param[2] - 0.01).abs() < 1e-12);
+        assert!(res.cost < 1e-12);
+    }
+
+    #[test]
+    fn test_identity_preconditioner() {
+        // with M = I, PCG reduces to plain CG and converges in at most n iterations
+        let b = vec![1.0, 2.0, 3.0];
+        let precond = Diagonal::new(vec![1.0; 3]).unwrap();
+        let solver = PreconditionedConjugateGradient::new(b, precond).unwrap();
+        let res = Executor::new(DiagonalSystem {}, solver, vec![0.0; 3])
+            .max_iters(3)
+            .run_fast()
+            .unwrap();
+        assert!((res.param[0] - 1.0).abs() < 1e-10);
+        assert!((res.param[1] - 0.2).abs() < 1e-10);
+        assert!((res.param[2] - 0.03).abs() < 1e-10);
+    }
+}
@@ -15,10 +15,15 @@
 //! Springer. ISBN 0-387-30303-0.
 
 use crate::prelude::*;
+use crate::solver::linesearch::LineSearchFailure;
 use serde::{Deserialize, Serialize};
 
 /// Steepest descent iteratively takes steps in the direction of the strongest negative gradient.
-/// In each iteration, a line search is employed to obtain an appropriate step length.
+/// In each iteration, a line search is employed to obtain an appropriate step length. Any line
+/// search implementing `ArgminLineSearch` can be used; `FixedStepLineSearch` recovers plain
+/// gradient descent with a constant step length. The accepted step length is reported as
+/// `step_length` via the KV of each iteration. If the line search gives up, the solver either
+/// stops or accepts the step depending on the `LineSearchFailure` policy (default: stop).
 ///
 /// # Example
 ///
@@ -34,6 +39,8 @@ use serde::{Deserialize, Serialize};
 pub struct SteepestDescent<L> {
     /// line search
     linesearch: L,
+    /// behavior on line search failures
+    linesearch_failure: LineSearchFailure,
 }
 
 impl<L> SteepestDescent<L> {
@@ -41,8 +48,15 @@ impl<L> SteepestDescent<L> {
     pub fn new(linesearch: L) -> Result<Self, Error> {
         Ok(SteepestDescent {
             linesearch: linesearch,
+            linesearch_failure: LineSearchFailure::default(),
         })
     }
+
+    /// Set the behavior on line search failures (default: `LineSearchFailure::Stop`)
+    pub fn linesearch_failure(mut self, policy: LineSearchFailure) -> Self {
+        self.linesearch_failure = policy;
+        self
+    }
 }
 
 impl<O, L> Solver<O> for SteepestDescent<L>
@@ -69,13 +83,14 @@ where
         let new_cost = op.apply(&param_new)?;
         let new_grad = op.gradient(&param_new)?;
 
-        self.linesearch.set_search_direction(new_grad.mul(&(-1.0)));
+        let direction = new_grad.mul(&(-1.0));
+        self.linesearch.set_search_direction(direction.clone());
 
         // Run solver
         let linesearch_result = Executor::new(
             OpWrapper::new_from_op(&op),
             self.linesearch.clone(),
-            param_new,
+            param_new.clone(),
         )
         .grad(new_grad)
         .cost(new_cost)
@@ -84,9 +99,27 @@ where
         // hack
         op.consume_op(linesearch_result.operator);
 
+        if self
+            .linesearch_failure
+            .stop(&linesearch_result.termination_reason)
+        {
+            return Ok(ArgminIterData::new()
+                .param(param_new)
+                .cost(new_cost)
+                .termination_reason(TerminationReason::Aborted));
+        }
+
+        let direction_norm = direction.norm();
+        let step_length = if direction_norm > 0.0 {
+            linesearch_result.param.sub(&param_new).norm() / direction_norm
+        } else {
+            0.0
+        };
+
         Ok(ArgminIterData::new()
             .param(linesearch_result.param)
-            .cost(linesearch_result.cost))
+            .cost(linesearch_result.cost)
+            .kv(make_kv!("step_length" => step_length;)))
     }
 }
 
@@ -94,10 +127,136 @@ where
 mod tests {
     use super::*;
     use crate::send_sync_test;
-    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::landweber::Landweber;
+    use crate::solver::linesearch::{
+        ArmijoCondition, BacktrackingLineSearch, FixedStepLineSearch, MoreThuenteLineSearch,
+    };
+    use crate::testfunctions::problem::TestProblem;
 
     send_sync_test!(
         steepest_descent,
         SteepestDescent<MinimalNoOperator, MoreThuenteLineSearch<MinimalNoOperator>>
     );
+
+    #[test]
+    fn test_steepest_descent_test_problems() {
+        for problem in vec![
+            TestProblem::booth(),
+            TestProblem::matyas(),
+            TestProblem::sphere(5),
+        ] {
+            let solver = SteepestDescent::new(MoreThuenteLineSearch::new()).unwrap();
+            // the target cost stops the solver before the line search sees a vanishing gradient
+            let res = Executor::new(problem.clone(), solver, problem.start().to_vec())
+                .max_iters(1000)
+                .target_cost(problem.minimum() + 1e-14)
+                .run_fast()
+                .unwrap();
+            assert!(
+                problem.is_solution(&res.param),
+                "{}: {:?}",
+                problem.name(),
+                res.param
+            );
+        }
+    }
+
+    #[test]
+    fn test_steepest_descent_backtracking_rosenbrock() {
+        let problem = TestProblem::rosenbrock(2);
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap();
+        let solver = SteepestDescent::new(linesearch).unwrap();
+        let res = Executor::new(problem, solver, vec![-1.2, 1.0])
+            .max_iters(10000)
+            .target_cost(1e-6)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost <= 1e-6);
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-2));
+    }
+
+    #[test]
+    fn test_steepest_descent_fixed_step_diverges() {
+        let problem = TestProblem::rosenbrock(2);
+        let init = vec![-1.2, 1.0];
+        let init_cost = problem.apply(&init).unwrap();
+        let solver = SteepestDescent::new(FixedStepLineSearch::new(1.0).unwrap()).unwrap();
+        let res = Executor::new(problem, solver, init)
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost > init_cost);
+    }
+
+    #[test]
+    fn test_steepest_descent_fixed_step_matches_landweber() {
+        let problem = TestProblem::rosenbrock(2);
+        let init = vec![-1.2, 1.0];
+        let solver = SteepestDescent::new(FixedStepLineSearch::new(1e-3).unwrap()).unwrap();
+        let res = Executor::new(problem.clone(), solver, init.clone())
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        let expected = Executor::new(problem, Landweber::new(1e-3).unwrap(), init)
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        let bits = |x: &Vec<f64>| x.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&res.param), bits(&expected.param));
+    }
+
+    /// `f(x) = sum_i (x_i - 3)^2`, NaN if any `x_i > 0.25`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct NanBeyond {}
+
+    impl ArgminOp for NanBeyond {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            if x.iter().any(|x| *x > 0.25) {
+                return Ok(std::f64::NAN);
+            }
+            Ok(x.iter().map(|x| (x - 3.0).powi(2)).sum())
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(x.iter().map(|x| 2.0 * (x - 3.0)).collect())
+        }
+    }
+
+    fn failing_linesearch() -> BacktrackingLineSearch<Vec<f64>, ArmijoCondition> {
+        // all trial points lie in the NaN region
+        BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap()
+            .max_iters(5)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_steepest_descent_linesearch_failure() {
+        let solver = SteepestDescent::new(failing_linesearch()).unwrap();
+        let res = Executor::new(NanBeyond {}, solver, vec![0.0, 0.0])
+            .max_iters(10)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::Aborted);
+        assert!(res.param.iter().all(|x| x.is_zero()));
+        assert!((res.cost - 18.0).abs() < std::f64::EPSILON);
+
+        // the degraded step (here: no step at all) is accepted until `max_iters` is reached
+        let solver = SteepestDescent::new(failing_linesearch())
+            .unwrap()
+            .linesearch_failure(LineSearchFailure::Accept);
+        let res = Executor::new(NanBeyond {}, solver, vec![0.0, 0.0])
+            .max_iters(10)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::MaxItersReached);
+        assert!(res.param.iter().all(|x| x.is_zero()));
+    }
 }
@@ -44,7 +44,17 @@ pub struct Landweber {
 
 impl Landweber {
     /// Constructor
+    ///
+    /// Parameter:
+    ///
+    /// * `omega`: step length (`> 0`)
     pub fn new(omega: f64) -> Result<Self, Error> {
+        if !omega.is_finite() || omega <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Landweber: omega must be in (0, inf).".to_string(),
+            }
+            .into());
+        }
         Ok(Landweber { omega })
     }
 }
@@ -52,17 +62,17 @@ impl Landweber {
 impl<O> Solver<O> for Landweber
 where
     O: ArgminOp,
-    O::Param: ArgminScaledSub<O::Param, f64, O::Param>,
+    O::Param: ArgminInPlace<f64>,
 {
     fn next_iter(
         &mut self,
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
-        let param = state.get_param();
+        let mut param = state.get_param();
         let grad = op.gradient(&param)?;
-        let new_param = param.scaled_sub(&self.omega, &grad);
-        Ok(ArgminIterData::new().param(new_param))
+        param.scaled_sub_assign(&self.omega, &grad);
+        Ok(ArgminIterData::new().param(param))
     }
 }
 
@@ -72,4 +82,13 @@ mod tests {
     use crate::send_sync_test;
 
     send_sync_test!(landweber, Landweber);
+
+    #[test]
+    fn test_landweber_omega() {
+        for omega in [0.0, -1.0, std::f64::NAN, std::f64::INFINITY].iter() {
+            let e = Landweber::new(*omega).err().unwrap();
+            assert!(e.to_string().contains("omega"), "{}", e);
+        }
+        assert!(Landweber::new(0.5).is_ok());
+    }
 }
@@ -9,12 +9,23 @@
 
 use crate::prelude::*;
 use crate::solver::linesearch::condition::*;
+use crate::solver::linesearch::search::{LineSearch, LineSearchResult};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 /// The Backtracking line search is a simple method to find a step length which obeys the Armijo
 /// (sufficient decrease) condition.
 ///
+/// If no step length satisfying the condition is found within `max_iters` evaluations (default:
+/// 100), for instance because the cost function returns NaN along the search direction, the line
+/// search gives up and terminates with `TerminationReason::MaxItersReached`. In this case, the
+/// best evaluated step (or the initial parameter vector if all evaluated costs were NaN or
+/// infinite) is returned. How solvers react to this is determined by `LineSearchFailure`.
+///
+/// The step length of each evaluated trial point is reported as `alpha` via the KV. Besides
+/// running it via an `Executor`, the line search can be used on its own via the `LineSearch`
+/// trait.
+///
 /// # Example
 ///
 /// ```rust
@@ -41,8 +52,28 @@ pub struct BacktrackingLineSearch<P, L> {
     rho: f64,
     /// Stopping condition
     condition: Box<L>,
+    /// initial alpha
+    init_alpha: f64,
     /// alpha
     alpha: f64,
+    /// step length of the last evaluated (or, after giving up, the returned) parameter vector
+    step: f64,
+    /// whether the initial gradient was provided via `LineSearch::set_init_grad`
+    has_init_grad: bool,
+    /// Number of infeasible parameter vectors encountered
+    infeasible_count: u64,
+    /// Maximum number of evaluated step lengths
+    max_iters: u64,
+    /// Number of evaluated step lengths
+    iter: u64,
+    /// best evaluated parameter vector
+    best_param: Option<P>,
+    /// cost of the best evaluated parameter vector
+    best_cost: f64,
+    /// step length of the best evaluated parameter vector
+    best_alpha: f64,
+    /// whether the line search gave up
+    failed: bool,
 }
 
 impl<P: Default, L> BacktrackingLineSearch<P, L> {
@@ -55,7 +86,17 @@ impl<P: Default, L> BacktrackingLineSearch<P, L> {
             search_direction: None,
             rho: 0.9,
             condition: Box::new(condition),
+            init_alpha: 1.0,
             alpha: 1.0,
+            step: 0.0,
+            has_init_grad: false,
+            infeasible_count: 0,
+            max_iters: 100,
+            iter: 0,
+            best_param: None,
+            best_cost: std::f64::INFINITY,
+            best_alpha: 0.0,
+            failed: false,
         }
     }
 
@@ -71,6 +112,18 @@ impl<P: Default, L> BacktrackingLineSearch<P, L> {
         self.rho = rho;
         Ok(self)
     }
+
+    /// Set the maximum number of evaluated step lengths (default: 100)
+    pub fn max_iters(mut self, max_iters: u64) -> Result<Self, Error> {
+        if max_iters == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "BacktrackingLineSearch: max_iters must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.max_iters = max_iters;
+        Ok(self)
+    }
 }
 
 impl<P, L> ArgminLineSearch<P> for BacktrackingLineSearch<P, L>
@@ -91,11 +144,70 @@ where
             }
             .into());
         }
+        self.init_alpha = alpha;
         self.alpha = alpha;
         Ok(())
     }
 }
 
+impl<P, L> LineSearch<P> for BacktrackingLineSearch<P, L>
+where
+    P: Clone
+        + Default
+        + Serialize
+        + DeserializeOwned
+        + ArgminSub<P, P>
+        + ArgminDot<P, f64>
+        + ArgminScaledAdd<P, f64, P>,
+    L: LineSearchCondition<P>,
+{
+    fn set_init_param(&mut self, param: P) {
+        self.init_param = param;
+    }
+
+    fn set_init_cost(&mut self, cost: f64) {
+        self.init_cost = cost;
+    }
+
+    fn set_init_grad(&mut self, grad: P) {
+        self.init_grad = grad;
+        self.has_init_grad = true;
+    }
+
+    fn search<O>(&mut self, op: &O) -> Result<LineSearchResult<P>, Error>
+    where
+        O: ArgminOp<Param = P, Output = f64>,
+    {
+        let mut op = OpWrapper::new(op);
+        let mut state = IterState::new(self.init_param.clone());
+        state.cost(self.init_cost);
+        if self.has_init_grad {
+            state.grad(self.init_grad.clone());
+        }
+        Solver::init(self, &mut op, &state)?;
+        let reason = loop {
+            let data = self.next_iter(&mut op, &state)?;
+            state.param(data.get_param().unwrap());
+            state.cost(data.get_cost().unwrap());
+            if let Some(grad) = data.get_grad() {
+                state.grad(grad);
+            }
+            let reason = self.terminate(&state);
+            if reason != TerminationReason::NotTerminated {
+                break reason;
+            }
+        };
+        Ok(LineSearchResult {
+            alpha: self.step,
+            param: state.get_param(),
+            cost: state.get_cost(),
+            condition_met: reason == TerminationReason::LineSearchConditionMet,
+            cost_evals: op.cost_func_count,
+            grad_evals: op.grad_func_count,
+        })
+    }
+}
+
 impl<O, P, L> Solver<O> for BacktrackingLineSearch<P, L>
 where
     P: Clone
@@ -121,7 +233,18 @@ where
             cost
         };
 
-        self.init_grad = state.get_grad().unwrap_or(op.gradient(&self.init_param)?);
+        self.init_grad = match state.get_grad() {
+            Some(grad) => grad,
+            None => op.gradient(&self.init_param)?,
+        };
+
+        self.alpha = self.init_alpha;
+        self.iter = 0;
+        self.best_param = None;
+        self.best_cost = std::f64::INFINITY;
+        self.best_alpha = 0.0;
+        self.step = 0.0;
+        self.failed = false;
 
         if self.search_direction.is_none() {
             return Err(ArgminError::NotInitialized {
@@ -138,33 +261,77 @@ where
         op: &mut OpWrapper<O>,
         _state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
+        self.step = self.alpha;
         let new_param = self
             .init_param
-            .scaled_add(&self.alpha, self.search_direction.as_ref().unwrap());
+            .scaled_add(&self.step, self.search_direction.as_ref().unwrap());
 
-        let cur_cost = op.apply(&new_param)?;
+        // Infeasible parameter vectors are treated as having infinite cost, which forces the step
+        // to shrink.
+        let cur_cost = op.try_apply(&new_param)?;
 
         self.alpha *= self.rho;
 
-        let mut out = ArgminIterData::new()
-            .param(new_param.clone())
-            .cost(cur_cost);
+        let cost = cur_cost.unwrap_or(std::f64::INFINITY);
+        let mut out = ArgminIterData::new().param(new_param.clone()).cost(cost);
+        let mut grad = None;
+
+        if cur_cost.is_none() {
+            self.infeasible_count += 1;
+        } else if self.condition.requires_cur_grad() {
+            let g = op.gradient(&new_param)?;
+            out = out.grad(g.clone());
+            grad = Some(g);
+        }
+
+        // `cost < best_cost` is false for NaN
+        if cost < self.best_cost {
+            self.best_cost = cost;
+            self.best_param = Some(new_param);
+            self.best_alpha = self.step;
+        }
+        self.iter += 1;
 
-        if self.condition.requires_cur_grad() {
-            out = out.grad(op.gradient(&new_param)?);
+        if self.iter >= self.max_iters
+            && !self.condition.eval(
+                cost,
+                grad.unwrap_or_default(),
+                self.init_cost,
+                self.init_grad.clone(),
+                self.search_direction.clone().unwrap(),
+                self.step,
+            )
+        {
+            self.failed = true;
+            let (param, cost) = match self.best_param.take() {
+                Some(param) => (param, self.best_cost),
+                None => (self.init_param.clone(), self.init_cost),
+            };
+            self.step = self.best_alpha;
+            return Ok(ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .termination_reason(TerminationReason::MaxItersReached)
+                .kv(
+                    make_kv!("alpha" => self.step; "infeas" => self.infeasible_count;
+                             "failed" => true;),
+                ));
         }
 
-        Ok(out)
+        Ok(out.kv(make_kv!("alpha" => self.step; "infeas" => self.infeasible_count;)))
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if self.failed {
+            return TerminationReason::MaxItersReached;
+        }
         if self.condition.eval(
             state.get_cost(),
             state.get_grad().unwrap_or(O::Param::default()),
             self.init_cost,
             self.init_grad.clone(),
             self.search_direction.clone().unwrap(),
-            self.alpha,
+            self.step,
         ) {
             TerminationReason::LineSearchConditionMet
         } else {
@@ -177,8 +344,166 @@ where
 mod tests {
     use super::*;
     use crate::send_sync_test;
+    use crate::testfunctions::problem::TestProblem;
     use crate::MinimalNoOperator;
 
     send_sync_test!(backtrackinglinesearch,
                     BacktrackingLineSearch<MinimalNoOperator, ArmijoCondition>);
+
+    /// `f(x) = (x - 3)^2` with a band of NaN in the open interval `(lower, upper)`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Cliff {
+        lower: f64,
+        upper: f64,
+    }
+
+    impl ArgminOp for Cliff {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            if x[0] <= self.lower || x[0] >= self.upper {
+                Ok((x[0] - 3.0).powi(2))
+            } else {
+                Ok(std::f64::NAN)
+            }
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![2.0 * (x[0] - 3.0)])
+        }
+    }
+
+    #[test]
+    fn test_backtracking_max_iters() {
+        assert!(
+            BacktrackingLineSearch::<Vec<f64>, _>::new(ArmijoCondition::new(1e-4).unwrap())
+                .max_iters(0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_backtracking_fails_on_nan() {
+        // the trial points 6, 3, 1.5, 0.75 and 0.375 all lie in the NaN band
+        let mut solver = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap()
+            .max_iters(5)
+            .unwrap();
+        solver.set_search_direction(vec![6.0]);
+        let res = Executor::new(
+            Cliff {
+                lower: 0.25,
+                upper: 10.0,
+            },
+            solver.clone(),
+            vec![0.0],
+        )
+        .max_iters(100)
+        .run_fast()
+        .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::MaxItersReached);
+        assert!(res.param[0].is_zero());
+        assert!((res.cost - 9.0).abs() < std::f64::EPSILON);
+        // without the NaN band the second trial point is accepted
+        let res = Executor::new(
+            Cliff {
+                lower: 20.0,
+                upper: 30.0,
+            },
+            solver,
+            vec![0.0],
+        )
+        .max_iters(100)
+        .run_fast()
+        .unwrap();
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::LineSearchConditionMet
+        );
+    }
+
+    #[test]
+    fn test_backtracking_returns_best_step() {
+        // Armijo is never satisfied in the first 10 steps. The trial points `6 * 0.99^k` lie in the
+        // NaN band for `k >= 7`, therefore the step with `k = 6` is the best one.
+        let mut solver = BacktrackingLineSearch::new(ArmijoCondition::new(0.99).unwrap())
+            .rho(0.99)
+            .unwrap()
+            .max_iters(10)
+            .unwrap();
+        solver.set_search_direction(vec![6.0]);
+        let res = Executor::new(
+            Cliff {
+                lower: 1.0,
+                upper: 5.6,
+            },
+            solver,
+            vec![0.0],
+        )
+        .max_iters(100)
+        .run_fast()
+        .unwrap();
+        let alpha = (0..6).fold(1.0, |a: f64, _| a * 0.99);
+        assert_eq!(res.termination_reason, TerminationReason::MaxItersReached);
+        assert!((res.param[0] - 6.0 * alpha).abs() < 1e-12);
+        assert!((res.cost - (6.0 * alpha - 3.0).powi(2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_backtracking_standalone() {
+        let op = TestProblem::rosenbrock(2);
+        let c = 1e-4;
+        let mut solver = BacktrackingLineSearch::new(ArmijoCondition::new(c).unwrap())
+            .rho(0.5)
+            .unwrap();
+        let mut param = vec![-1.2, 1.0];
+        for _ in 0..10 {
+            let cost = op.apply(&param).unwrap();
+            let grad = op.gradient(&param).unwrap();
+            let direction: Vec<f64> = grad.iter().map(|g| -g).collect();
+            solver.set_search_direction(direction.clone());
+            solver.set_init_param(param.clone());
+            solver.set_init_cost(cost);
+            solver.set_init_grad(grad.clone());
+            let res = solver.search(&op).unwrap();
+            assert!(res.condition_met);
+            assert!(res.alpha > 0.0 && res.alpha <= 1.0);
+            // the search starts at `alpha = 1` in every call and halves it `k` times
+            assert!((res.alpha.log2() + (res.cost_evals - 1) as f64).abs() < 1e-12);
+            assert_eq!(res.grad_evals, 0);
+            let expected = param.scaled_add(&res.alpha, &direction);
+            assert!((res.param[0] - expected[0]).abs() < std::f64::EPSILON);
+            assert!((res.param[1] - expected[1]).abs() < std::f64::EPSILON);
+            // Armijo condition at the returned step length
+            assert!((res.cost - op.apply(&res.param).unwrap()).abs() < std::f64::EPSILON);
+            let slope: f64 = grad.dot(&direction);
+            assert!(res.cost <= cost + c * res.alpha * slope);
+            param = res.param;
+        }
+    }
+
+    #[test]
+    fn test_backtracking_standalone_computes_init() {
+        // without the initial cost and gradient both are computed
+        let mut solver = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap();
+        solver.set_search_direction(vec![6.0]);
+        solver.set_init_param(vec![0.0]);
+        let res = solver
+            .search(&Cliff {
+                lower: 20.0,
+                upper: 30.0,
+            })
+            .unwrap();
+        assert!(res.condition_met);
+        assert!((res.alpha - 0.5).abs() < std::f64::EPSILON);
+        assert!((res.param[0] - 3.0).abs() < std::f64::EPSILON);
+        assert!(res.cost.is_zero());
+        assert_eq!(res.cost_evals, 3);
+        assert_eq!(res.grad_evals, 1);
+    }
 }
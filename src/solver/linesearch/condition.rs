@@ -10,6 +10,7 @@
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
 //! Springer. ISBN 0-387-30303-0.
 
+use crate::math::ArgminPreciseDot;
 use crate::{ArgminDot, ArgminError, Error};
 use serde::{Deserialize, Serialize};
 
@@ -39,7 +40,7 @@ pub struct ArmijoCondition {
 impl ArmijoCondition {
     /// Constructor
     pub fn new(c: f64) -> Result<Self, Error> {
-        if c <= 0.0 || c >= 1.0 {
+        if c.is_nan() || c <= 0.0 || c >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "ArmijoCondition: Parameter c must be in (0, 1)".to_string(),
             }
@@ -71,6 +72,8 @@ where
 }
 
 /// Wolfe Condition
+///
+/// The directional derivatives are computed with compensated summation (`ArgminPreciseDot`).
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct WolfeCondition {
     c1: f64,
@@ -80,13 +83,13 @@ pub struct WolfeCondition {
 impl WolfeCondition {
     /// Constructor
     pub fn new(c1: f64, c2: f64) -> Result<Self, Error> {
-        if c1 <= 0.0 || c1 >= 1.0 {
+        if c1.is_nan() || c1 <= 0.0 || c1 >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "WolfeCondition: Parameter c1 must be in (0, 1)".to_string(),
             }
             .into());
         }
-        if c2 <= c1 || c2 >= 1.0 {
+        if c2.is_nan() || c2 <= c1 || c2 >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "WolfeCondition: Parameter c2 must be in (c1, 1)".to_string(),
             }
@@ -98,7 +101,7 @@ impl WolfeCondition {
 
 impl<T> LineSearchCondition<T> for WolfeCondition
 where
-    T: Clone + ArgminDot<T, f64>,
+    T: Clone + ArgminPreciseDot<T, f64>,
 {
     fn eval(
         &self,
@@ -109,9 +112,9 @@ where
         search_direction: T,
         alpha: f64,
     ) -> bool {
-        let tmp = init_grad.dot(&search_direction);
+        let tmp = init_grad.dot_precise(&search_direction);
         (cur_cost <= init_cost + self.c1 * alpha * tmp)
-            && cur_grad.dot(&search_direction) >= self.c2 * tmp
+            && cur_grad.dot_precise(&search_direction) >= self.c2 * tmp
     }
 
     fn requires_cur_grad(&self) -> bool {
@@ -120,6 +123,8 @@ where
 }
 
 /// Strong Wolfe conditions
+///
+/// The directional derivatives are computed with compensated summation (`ArgminPreciseDot`).
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct StrongWolfeCondition {
     c1: f64,
@@ -129,13 +134,13 @@ pub struct StrongWolfeCondition {
 impl StrongWolfeCondition {
     /// Constructor
     pub fn new(c1: f64, c2: f64) -> Result<Self, Error> {
-        if c1 <= 0.0 || c1 >= 1.0 {
+        if c1.is_nan() || c1 <= 0.0 || c1 >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "StrongWolfeCondition: Parameter c1 must be in (0, 1)".to_string(),
             }
             .into());
         }
-        if c2 <= c1 || c2 >= 1.0 {
+        if c2.is_nan() || c2 <= c1 || c2 >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "StrongWolfeCondition: Parameter c2 must be in (c1, 1)".to_string(),
             }
@@ -147,7 +152,7 @@ impl StrongWolfeCondition {
 
 impl<T> LineSearchCondition<T> for StrongWolfeCondition
 where
-    T: Clone + ArgminDot<T, f64>,
+    T: Clone + ArgminPreciseDot<T, f64>,
 {
     fn eval(
         &self,
@@ -158,9 +163,9 @@ where
         search_direction: T,
         alpha: f64,
     ) -> bool {
-        let tmp = init_grad.dot(&search_direction);
+        let tmp = init_grad.dot_precise(&search_direction);
         (cur_cost <= init_cost + self.c1 * alpha * tmp)
-            && cur_grad.dot(&search_direction).abs() <= self.c2 * tmp.abs()
+            && cur_grad.dot_precise(&search_direction).abs() <= self.c2 * tmp.abs()
     }
 
     fn requires_cur_grad(&self) -> bool {
@@ -177,7 +182,7 @@ pub struct GoldsteinCondition {
 impl GoldsteinCondition {
     /// Constructor
     pub fn new(c: f64) -> Result<Self, Error> {
-        if c <= 0.0 || c >= 0.5 {
+        if c.is_nan() || c <= 0.0 || c >= 0.5 {
             return Err(ArgminError::InvalidParameter {
                 text: "GoldsteinCondition: Parameter c must be in (0, 0.5)".to_string(),
             }
@@ -218,4 +223,32 @@ mod tests {
     send_sync_test!(armijo, ArmijoCondition);
     send_sync_test!(wolfe, WolfeCondition);
     send_sync_test!(strongwolfe, StrongWolfeCondition);
+
+    #[test]
+    fn test_condition_invalid_parameters() {
+        let nan = std::f64::NAN;
+        for c in [0.0, 1.0, nan].iter() {
+            let e = ArmijoCondition::new(*c).err().unwrap();
+            assert!(e.to_string().contains("Parameter c "), "{}", e);
+        }
+        for c in [0.0, 0.5, nan].iter() {
+            let e = GoldsteinCondition::new(*c).err().unwrap();
+            assert!(e.to_string().contains("Parameter c "), "{}", e);
+        }
+        for (c1, c2, name) in [
+            (0.0, 0.9, "c1"),
+            (nan, 0.9, "c1"),
+            (0.5, 0.4, "c2"),
+            (1e-4, nan, "c2"),
+        ]
+        .iter()
+        {
+            let e = WolfeCondition::new(*c1, *c2).err().unwrap();
+            assert!(e.to_string().contains(name), "{}", e);
+            let e = StrongWolfeCondition::new(*c1, *c2).err().unwrap();
+            assert!(e.to_string().contains(name), "{}", e);
+        }
+        assert!(WolfeCondition::new(1e-4, 0.9).is_ok());
+        assert!(StrongWolfeCondition::new(1e-4, 0.9).is_ok());
+    }
 }
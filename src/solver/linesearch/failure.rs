@@ -0,0 +1,41 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [Behavior on line search failures](enum.LineSearchFailure.html)
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Behavior of a solver if its line search gives up without finding a step length which satisfies
+/// its condition. A line search signals this by terminating with
+/// `TerminationReason::MaxItersReached` instead of `TerminationReason::LineSearchConditionMet`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LineSearchFailure {
+    /// Keep the current iterate and terminate the solver with `TerminationReason::Aborted`
+    /// (default)
+    Stop,
+    /// Accept the (degraded) step returned by the line search and continue
+    Accept,
+}
+
+impl std::default::Default for LineSearchFailure {
+    fn default() -> Self {
+        LineSearchFailure::Stop
+    }
+}
+
+impl LineSearchFailure {
+    /// Returns true if the line search terminated with `reason` gave up
+    pub fn failed(reason: &TerminationReason) -> bool {
+        *reason == TerminationReason::MaxItersReached
+    }
+
+    /// Returns true if the solver should stop after its line search terminated with `reason`
+    pub fn stop(self, reason: &TerminationReason) -> bool {
+        self == LineSearchFailure::Stop && LineSearchFailure::failed(reason)
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [Fixed step "line search"](struct.FixedStepLineSearch.html)
+
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Takes a step of fixed length along the search direction without checking any acceptance
+/// condition. With `SteepestDescent` this is the plain gradient descent iteration
+/// `x_{k+1} = x_k - alpha * grad f(x_k)` (which is what `Landweber` computes).
+///
+/// # Example
+///
+/// ```rust
+/// TODO
+/// ```
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FixedStepLineSearch<P> {
+    /// initial parameter vector
+    init_param: P,
+    /// Search direction
+    search_direction: Option<P>,
+    /// step length
+    alpha: f64,
+    /// whether the step was already taken
+    done: bool,
+}
+
+impl<P: Default> FixedStepLineSearch<P> {
+    /// Constructor
+    pub fn new(alpha: f64) -> Result<Self, Error> {
+        if alpha.is_nan() || alpha <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "FixedStepLineSearch: step length must be > 0.".to_string(),
+            }
+            .into());
+        }
+        Ok(FixedStepLineSearch {
+            init_param: P::default(),
+            search_direction: None,
+            alpha,
+            done: false,
+        })
+    }
+}
+
+impl<P> ArgminLineSearch<P> for FixedStepLineSearch<P>
+where
+    P: Clone + Serialize,
+{
+    /// Set search direction
+    fn set_search_direction(&mut self, search_direction: P) {
+        self.search_direction = Some(search_direction);
+    }
+
+    /// Set initial alpha value (the fixed step length)
+    fn set_init_alpha(&mut self, alpha: f64) -> Result<(), Error> {
+        if alpha <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "LineSearch: Inital alpha must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.alpha = alpha;
+        Ok(())
+    }
+}
+
+impl<O, P> Solver<O> for FixedStepLineSearch<P>
+where
+    P: Clone + Default + Serialize + DeserializeOwned + ArgminScaledAdd<P, f64, P>,
+    O: ArgminOp<Param = P, Output = f64>,
+{
+    fn init(
+        &mut self,
+        _op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        self.init_param = state.get_param();
+        self.done = false;
+
+        if self.search_direction.is_none() {
+            return Err(ArgminError::NotInitialized {
+                text: "FixedStepLineSearch: search_direction must be set.".to_string(),
+            }
+            .into());
+        }
+
+        Ok(None)
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let new_param = self
+            .init_param
+            .scaled_add(&self.alpha, self.search_direction.as_ref().unwrap());
+        let cost = op.apply(&new_param)?;
+        self.done = true;
+        Ok(ArgminIterData::new().param(new_param).cost(cost))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.done {
+            TerminationReason::LineSearchConditionMet
+        } else {
+            TerminationReason::NotTerminated
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::MinimalNoOperator;
+
+    send_sync_test!(fixedsteplinesearch, FixedStepLineSearch<MinimalNoOperator>);
+
+    #[test]
+    fn test_fixed_step_invalid() {
+        assert!(FixedStepLineSearch::<Vec<f64>>::new(0.0).is_err());
+        assert!(FixedStepLineSearch::<Vec<f64>>::new(std::f64::NAN).is_err());
+        let mut ls = FixedStepLineSearch::<Vec<f64>>::new(1.0).unwrap();
+        assert!(ls.set_init_alpha(-1.0).is_err());
+    }
+}
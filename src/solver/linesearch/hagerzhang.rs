@@ -154,6 +154,12 @@ where
             }
             .into());
         }
+        if delta > self.sigma {
+            return Err(ArgminError::InvalidParameter {
+                text: "HagerZhangLineSearch: delta must be <= sigma.".to_string(),
+            }
+            .into());
+        }
         self.delta = delta;
         Ok(self)
     }
@@ -551,4 +557,15 @@ mod tests {
     use crate::MinimalNoOperator;
 
     send_sync_test!(hagerzhang, HagerZhangLineSearch<MinimalNoOperator>);
+
+    #[test]
+    fn test_hagerzhang_invalid_parameters() {
+        let ls = || HagerZhangLineSearch::<Vec<f64>>::new();
+        let message =
+            |res: Result<HagerZhangLineSearch<Vec<f64>>, Error>| res.err().unwrap().to_string();
+        assert!(message(ls().delta(0.0)).contains("delta"));
+        assert!(message(ls().sigma(0.5).unwrap().delta(0.6)).contains("delta must be <= sigma"));
+        assert!(message(ls().delta(0.5).unwrap().sigma(0.4)).contains("sigma must be >= delta"));
+        assert!(message(ls().alpha(1.0, 0.5)).contains("alpha_max"));
+    }
 }
@@ -8,9 +8,13 @@
 //! Line search methods
 //!
 //! * [Backtracking line search](backtracking/struct.BacktrackingLineSearch.html)
+//! * [Fixed step length](fixedstep/struct.FixedStepLineSearch.html)
 //! * [More-Thuente line search](morethuente/struct.MoreThuenteLineSearch.html)
 //! * [Hager-Zhang line search](hagerzhang/struct.HagerZhangLineSearch.html)
 //!
+//! Line searches implementing [`LineSearch`](search/trait.LineSearch.html) can also be run
+//! without an `Executor`.
+//!
 //! # References:
 //!
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
@@ -28,12 +32,21 @@
 pub mod backtracking;
 /// Acceptance conditions
 pub mod condition;
+/// Behavior on line search failures
+pub mod failure;
+/// Fixed step length
+pub mod fixedstep;
 /// Hager-Zhang line search algorithm
 pub mod hagerzhang;
 /// More-Thuente line search algorithm
 pub mod morethuente;
+/// Standalone line search interface
+pub mod search;
 
 pub use self::backtracking::*;
 pub use self::condition::*;
+pub use self::failure::*;
+pub use self::fixedstep::*;
 pub use self::hagerzhang::*;
 pub use self::morethuente::*;
+pub use self::search::*;
@@ -134,13 +134,13 @@ impl<P: Default> MoreThuenteLineSearch<P> {
 
     /// Set c1 and c2 where 0 < c1 < c2 < 1.
     pub fn c(mut self, c1: f64, c2: f64) -> Result<Self, Error> {
-        if c1 <= 0.0 || c1 >= c2 {
+        if c1.is_nan() || c1 <= 0.0 || c1 >= c2 {
             return Err(ArgminError::InvalidParameter {
                 text: "MoreThuenteLineSearch: Parameter c1 must be in (0, c2).".to_string(),
             }
             .into());
         }
-        if c2 <= c1 || c2 >= 1.0 {
+        if c2.is_nan() || c2 <= c1 || c2 >= 1.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "MoreThuenteLineSearch: Parameter c2 must be in (c1, 1).".to_string(),
             }
@@ -153,13 +153,13 @@ impl<P: Default> MoreThuenteLineSearch<P> {
 
     /// set alpha limits
     pub fn alpha(mut self, alpha_min: f64, alpha_max: f64) -> Result<Self, Error> {
-        if alpha_min < 0.0 {
+        if alpha_min.is_nan() || alpha_min < 0.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "MoreThuenteLineSearch: alpha_min must be >= 0.0.".to_string(),
             }
             .into());
         }
-        if alpha_max <= alpha_min {
+        if alpha_max.is_nan() || alpha_max <= alpha_min {
             return Err(ArgminError::InvalidParameter {
                 text: "MoreThuenteLineSearch: alpha_min must be smaller than alpha_max."
                     .to_string(),
@@ -577,4 +577,17 @@ mod tests {
     use crate::MinimalNoOperator;
 
     send_sync_test!(morethuente, MoreThuenteLineSearch<MinimalNoOperator>);
+
+    #[test]
+    fn test_morethuente_invalid_parameters() {
+        let ls = || MoreThuenteLineSearch::<Vec<f64>>::new();
+        let nan = std::f64::NAN;
+        for (c1, c2, name) in [(0.0, 0.9, "c1"), (nan, 0.9, "c1"), (1e-4, nan, "c2")].iter() {
+            let e = ls().c(*c1, *c2).err().unwrap();
+            assert!(e.to_string().contains(name), "{}", e);
+        }
+        assert!(ls().alpha(nan, 1.0).is_err());
+        assert!(ls().alpha(0.0, nan).is_err());
+        assert!(ls().c(1e-4, 0.9).unwrap().alpha(0.0, 1.0).is_ok());
+    }
 }
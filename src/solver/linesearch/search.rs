@@ -0,0 +1,100 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [Standalone line search interface](trait.LineSearch.html)
+
+use crate::prelude::*;
+
+/// Result of `LineSearch::search`
+#[derive(Clone, Debug)]
+pub struct LineSearchResult<P> {
+    /// Accepted step length
+    pub alpha: f64,
+    /// Accepted parameter vector `init_param + alpha * search_direction`
+    pub param: P,
+    /// Cost function value at `param`
+    pub cost: f64,
+    /// `false` if the line search gave up without satisfying its condition
+    pub condition_met: bool,
+    /// Number of cost function evaluations
+    pub cost_evals: u64,
+    /// Number of gradient evaluations
+    pub grad_evals: u64,
+}
+
+/// Line search which can be run on its own, for instance inside of a custom solver, without an
+/// `Executor`.
+///
+/// Before calling `search`, the search direction (`set_search_direction`) and the initial
+/// parameter vector (`set_init_param`) must be set. The initial cost and gradient are computed if
+/// they are not provided via `set_init_cost` and `set_init_grad`. The initial step length is set
+/// via `set_init_alpha`.
+///
+/// # Example
+///
+/// A steepest descent loop which uses only the line search:
+///
+/// ```rust
+/// use argmin::prelude::*;
+/// use argmin::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch, LineSearch};
+/// use argmin::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, Default, Serialize, Deserialize)]
+/// struct Rosenbrock {}
+///
+/// impl ArgminOp for Rosenbrock {
+///     type Param = Vec<f64>;
+///     type Output = f64;
+///     type Hessian = ();
+///
+///     fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+///         Ok(rosenbrock_2d(p, 1.0, 100.0))
+///     }
+///
+///     fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+///         Ok(rosenbrock_2d_derivative(p, 1.0, 100.0))
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Error> {
+/// let op = Rosenbrock {};
+/// let mut linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4)?).rho(0.5)?;
+/// let mut param = vec![-1.2, 1.0];
+/// let mut cost = op.apply(&param)?;
+/// for _ in 0..100 {
+///     let grad = op.gradient(&param)?;
+///     linesearch.set_search_direction(grad.mul(&(-1.0)));
+///     linesearch.set_init_param(param);
+///     linesearch.set_init_cost(cost);
+///     linesearch.set_init_grad(grad);
+///     let res = linesearch.search(&op)?;
+///     if !res.condition_met {
+///         break;
+///     }
+///     param = res.param;
+///     cost = res.cost;
+/// }
+/// assert!(cost < rosenbrock_2d(&[-1.2, 1.0], 1.0, 100.0));
+/// # Ok(())
+/// # }
+/// ```
+pub trait LineSearch<P>: ArgminLineSearch<P> {
+    /// Set the initial parameter vector
+    fn set_init_param(&mut self, param: P);
+
+    /// Set the cost function value at the initial parameter vector
+    fn set_init_cost(&mut self, cost: f64);
+
+    /// Set the gradient at the initial parameter vector
+    fn set_init_grad(&mut self, grad: P);
+
+    /// Run the line search on `op`
+    fn search<O>(&mut self, op: &O) -> Result<LineSearchResult<P>, Error>
+    where
+        O: ArgminOp<Param = P, Output = f64>;
+}
@@ -9,7 +9,70 @@ pub mod conjugategradient;
 pub mod gradientdescent;
 pub mod landweber;
 pub mod linesearch;
+pub mod neldermead;
 pub mod newton;
+pub mod newtonbisection;
 pub mod quasinewton;
 pub mod simulatedannealing;
 pub mod trustregion;
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::send_sync_test;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::testfunctions::problem::TestProblem;
+
+    type Descent = SteepestDescent<BacktrackingLineSearch<Vec<f64>, ArmijoCondition>>;
+
+    send_sync_test!(executor, Executor<TestProblem, Descent>);
+    send_sync_test!(slog_logger, ArgminSlogLogger);
+
+    fn descent() -> Descent {
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap();
+        SteepestDescent::new(linesearch).unwrap()
+    }
+
+    #[test]
+    fn test_concurrent_executors() {
+        let problems = vec![
+            TestProblem::sphere(2),
+            TestProblem::rosenbrock(2),
+            TestProblem::booth(),
+            TestProblem::matyas(),
+            TestProblem::himmelblau(),
+            TestProblem::beale(),
+            TestProblem::zakharov(3),
+            TestProblem::styblinski_tang(4),
+        ];
+        let executors: Vec<_> = problems
+            .iter()
+            .map(|p| Executor::new(p.clone(), descent(), p.start().to_vec()))
+            .collect();
+
+        // The executors are created here and moved to the threads.
+        let results: Vec<_> = std::thread::scope(|s| {
+            let handles: Vec<_> = executors
+                .into_iter()
+                .map(|executor| s.spawn(move || executor.max_iters(200).run_fast()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap().unwrap())
+                .collect()
+        });
+
+        assert_eq!(results.len(), 8);
+        for (problem, res) in problems.iter().zip(results.iter()) {
+            let sequential = Executor::new(problem.clone(), descent(), problem.start().to_vec())
+                .max_iters(200)
+                .run_fast()
+                .unwrap();
+            assert_eq!(res.cost.to_bits(), sequential.cost.to_bits());
+            assert!(res.cost <= problem.apply(&problem.start().to_vec()).unwrap());
+        }
+    }
+}
@@ -0,0 +1,918 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [Nelder-Mead method](struct.NelderMead.html)
+//!
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! [1] [Wikipedia](https://en.wikipedia.org/wiki/Nelder%E2%80%93Mead_method)
+
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+/// Operation performed by the Nelder-Mead method in an iteration
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NelderMeadOperation {
+    /// The worst vertex was replaced by its reflection
+    Reflection,
+    /// The worst vertex was replaced by the expanded reflection
+    Expansion,
+    /// The worst vertex was replaced by a contraction towards the reflection
+    OutsideContraction,
+    /// The worst vertex was replaced by a contraction towards the worst vertex
+    InsideContraction,
+    /// All vertices were shrunk towards the best vertex
+    Shrink,
+}
+
+impl NelderMeadOperation {
+    /// Name as reported via the KV
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NelderMeadOperation::Reflection => "reflection",
+            NelderMeadOperation::Expansion => "expansion",
+            NelderMeadOperation::OutsideContraction => "outside_contraction",
+            NelderMeadOperation::InsideContraction => "inside_contraction",
+            NelderMeadOperation::Shrink => "shrink",
+        }
+    }
+}
+
+/// Step used to construct an initial simplex from a single point via `NelderMead::from_point`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SimplexStep {
+    /// Perturb the coordinate by the given (nonzero) absolute step
+    Absolute(f64),
+    /// Perturb the coordinate `x_i` by `factor * |x_i|`, but at least by `min_step` (which is
+    /// used for zero coordinates)
+    Relative {
+        /// relative step
+        factor: f64,
+        /// minimum absolute step
+        min_step: f64,
+    },
+}
+
+impl SimplexStep {
+    /// Relative step of 5 % with a minimum absolute step of 0.00025
+    pub fn relative() -> Self {
+        SimplexStep::Relative {
+            factor: 0.05,
+            min_step: 0.00025,
+        }
+    }
+
+    /// Step for the coordinate `x`
+    fn step(self, x: f64) -> Result<f64, Error> {
+        let step = match self {
+            SimplexStep::Absolute(step) => step,
+            SimplexStep::Relative { factor, min_step } => {
+                if factor.is_nan() || factor < 0.0 || min_step.is_nan() || min_step <= 0.0 {
+                    return Err(ArgminError::InvalidParameter {
+                        text: "NelderMead: relative simplex steps require factor >= 0 and \
+                               min_step > 0."
+                            .to_string(),
+                    }
+                    .into());
+                }
+                (factor * x.abs()).max(min_step)
+            }
+        };
+        if !step.is_finite() || step.is_zero() {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: simplex steps must be finite and nonzero.".to_string(),
+            }
+            .into());
+        }
+        Ok(step)
+    }
+}
+
+impl std::default::Default for SimplexStep {
+    fn default() -> Self {
+        SimplexStep::relative()
+    }
+}
+
+/// The Nelder-Mead method is a heuristic search method for nonlinear optimization problems which
+/// does not require derivatives.
+///
+/// The method is based on a simplex which consists of `n + 1` vertices for an optimization problem
+/// with `n` dimensions. In each iteration, the worst vertex is replaced by a point obtained via
+/// reflection (coefficient `alpha`, default 1), expansion (`gamma`, default 2) or contraction
+/// (`rho`, default 0.5) along the line through the worst vertex and the centroid of the remaining
+/// vertices. If none of these improves on the worst vertex, the simplex is shrunk towards the best
+/// vertex (`sigma`, default 0.5).
+///
+/// The solver terminates with `TargetPrecisionReached` if the standard deviation of the costs of
+/// the vertices falls below `tol_f` or if the diameter of the simplex (the maximum distance of a
+/// vertex to the best vertex) falls below `tol_x`. Both default to `EPSILON`, i.e. the solver
+/// stops once the simplex has collapsed; setting them to 0 disables the respective criterion.
+/// Both quantities are reported as `spread` and `diameter` via the KV of each iteration.
+///
+/// The initial simplex is either provided to the constructor `new` or constructed from a single
+/// point via `from_point` or `from_point_per_dim`; the initial parameter vector passed to the
+/// `Executor` is ignored. The initial simplex needs to be chosen carefully.
+///
+/// The performed operation is reported as `action` via the KV of each iteration. With
+/// `report_simplex(true)`, the costs of all vertices are reported as `simplex_costs` as well, and
+/// with `report_vertices(true)` also the vertices themselves as `simplex` (this requires
+/// `P: Debug` and is not restored from checkpoints). The final simplex of a run is available via
+/// `simplex` if the solver is run with a `Stepper` (`Stepper::solver`).
+///
+/// The vertices of the initial simplex and of a shrunk simplex are evaluated via
+/// `ArgminBulkOp::bulk_apply`, i.e. in parallel if the `rayon` feature is enabled (which requires
+/// the operator to be `Sync`). Reflection, expansion and contraction depend on each other and are
+/// evaluated one after the other.
+///
+/// # Example
+///
+/// ```rust
+/// TODO
+/// ```
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// [1] [Wikipedia](https://en.wikipedia.org/wiki/Nelder%E2%80%93Mead_method)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NelderMead<P> {
+    /// reflection coefficient
+    alpha: f64,
+    /// expansion coefficient
+    gamma: f64,
+    /// contraction coefficient
+    rho: f64,
+    /// shrink coefficient
+    sigma: f64,
+    /// vertices of the simplex and their costs, sorted by cost
+    simplex: Vec<(P, f64)>,
+    /// report the costs of the vertices via the KV
+    report_simplex: bool,
+    /// formats the vertices for the KV if they are reported
+    #[serde(skip)]
+    format_vertices: Option<fn(&[(P, f64)]) -> String>,
+    /// tolerance for the standard deviation of the costs of the vertices
+    tol_f: f64,
+    /// tolerance for the diameter of the simplex
+    tol_x: f64,
+    /// standard deviation of the costs of the vertices
+    spread: f64,
+    /// maximum distance of a vertex to the best vertex
+    diameter: f64,
+}
+
+impl NelderMead<Vec<f64>> {
+    /// Constructs the axis-aligned initial simplex `x0, x0 + h_1 e_1, ..., x0 + h_n e_n` where
+    /// the steps `h_i` are given by `step` (see `SimplexStep`).
+    pub fn from_point(x0: Vec<f64>, step: SimplexStep) -> Result<Self, Error> {
+        let steps = vec![step; x0.len()];
+        NelderMead::from_point_per_dim(x0, &steps)
+    }
+
+    /// Same as `from_point`, but with an individual step for each dimension
+    pub fn from_point_per_dim(x0: Vec<f64>, steps: &[SimplexStep]) -> Result<Self, Error> {
+        if x0.is_empty() || x0.len() != steps.len() {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: one simplex step per dimension is required.".to_string(),
+            }
+            .into());
+        }
+        let mut vertices = vec![x0.clone()];
+        for (i, step) in steps.iter().enumerate() {
+            let mut vertex = x0.clone();
+            vertex[i] += step.step(x0[i])?;
+            vertices.push(vertex);
+        }
+        Ok(NelderMead::new(vertices))
+    }
+}
+
+impl<P> NelderMead<P> {
+    /// Constructor
+    ///
+    /// Parameter:
+    ///
+    /// * `vertices`: vertices of the initial simplex
+    pub fn new(vertices: Vec<P>) -> Self {
+        NelderMead {
+            alpha: 1.0,
+            gamma: 2.0,
+            rho: 0.5,
+            sigma: 0.5,
+            simplex: vertices
+                .into_iter()
+                .map(|v| (v, std::f64::INFINITY))
+                .collect(),
+            report_simplex: false,
+            format_vertices: None,
+            tol_f: std::f64::EPSILON,
+            tol_x: std::f64::EPSILON,
+            spread: std::f64::INFINITY,
+            diameter: std::f64::INFINITY,
+        }
+    }
+
+    /// Set the reflection coefficient `alpha > 0` (default: 1)
+    pub fn alpha(mut self, alpha: f64) -> Result<Self, Error> {
+        if alpha.is_nan() || alpha <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: alpha must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.alpha = alpha;
+        Ok(self)
+    }
+
+    /// Set the expansion coefficient `gamma > 1` (default: 2)
+    pub fn gamma(mut self, gamma: f64) -> Result<Self, Error> {
+        if gamma.is_nan() || gamma <= 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: gamma must be > 1.".to_string(),
+            }
+            .into());
+        }
+        self.gamma = gamma;
+        Ok(self)
+    }
+
+    /// Set the contraction coefficient `rho` in `(0, 0.5]` (default: 0.5)
+    pub fn rho(mut self, rho: f64) -> Result<Self, Error> {
+        if rho.is_nan() || rho <= 0.0 || rho > 0.5 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: rho must be in (0, 0.5].".to_string(),
+            }
+            .into());
+        }
+        self.rho = rho;
+        Ok(self)
+    }
+
+    /// Set the shrink coefficient `sigma` in `(0, 1)` (default: 0.5)
+    pub fn sigma(mut self, sigma: f64) -> Result<Self, Error> {
+        if sigma.is_nan() || sigma <= 0.0 || sigma >= 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: sigma must be in (0, 1).".to_string(),
+            }
+            .into());
+        }
+        self.sigma = sigma;
+        Ok(self)
+    }
+
+    /// Set the tolerance for the standard deviation of the costs of the vertices (default:
+    /// `EPSILON`)
+    pub fn tol_f(mut self, tol_f: f64) -> Result<Self, Error> {
+        if tol_f.is_nan() || tol_f < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: tol_f must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol_f = tol_f;
+        Ok(self)
+    }
+
+    /// Set the tolerance for the diameter of the simplex (default: `EPSILON`)
+    pub fn tol_x(mut self, tol_x: f64) -> Result<Self, Error> {
+        if tol_x.is_nan() || tol_x < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: tol_x must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.tol_x = tol_x;
+        Ok(self)
+    }
+
+    /// Report the costs of the vertices as `simplex_costs` via the KV (default: false)
+    pub fn report_simplex(mut self, report: bool) -> Self {
+        self.report_simplex = report;
+        self
+    }
+
+    /// Report the vertices as `simplex` via the KV (default: false)
+    pub fn report_vertices(mut self, report: bool) -> Self
+    where
+        P: Debug,
+    {
+        self.format_vertices = if report {
+            Some(format_vertices::<P>)
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Vertices of the current simplex and their costs, sorted by cost
+    pub fn simplex(&self) -> &[(P, f64)] {
+        &self.simplex
+    }
+
+    fn sort_simplex(&mut self) {
+        self.simplex
+            .sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    }
+}
+
+impl<P> NelderMead<P>
+where
+    P: Clone + ArgminAdd<P, P> + ArgminSub<P, P> + ArgminMul<f64, P> + ArgminNorm<f64>,
+{
+    /// Update the standard deviation of the costs and the diameter of the simplex
+    fn update_size(&mut self) {
+        let n = self.simplex.len() as f64;
+        let mean = self.simplex.iter().map(|v| v.1).sum::<f64>() / n;
+        self.spread = (self
+            .simplex
+            .iter()
+            .map(|v| (v.1 - mean).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt();
+        let best = &self.simplex[0].0;
+        self.diameter = self.simplex[1..]
+            .iter()
+            .map(|v| v.0.sub(best).norm())
+            .fold(0.0, f64::max);
+    }
+
+    /// Centroid of all but the worst vertex
+    fn centroid(&self) -> P {
+        let n = self.simplex.len() - 1;
+        let sum = self.simplex[1..n]
+            .iter()
+            .fold(self.simplex[0].0.clone(), |acc, (v, _)| acc.add(v));
+        sum.mul(&(1.0 / n as f64))
+    }
+
+    /// `x0 + factor * (x - x0)`
+    fn towards(x0: &P, x: &P, factor: f64) -> P {
+        x0.add(&x.sub(x0).mul(&factor))
+    }
+}
+
+/// Vertices of a simplex in the format reported via the KV
+fn format_vertices<P: Debug>(simplex: &[(P, f64)]) -> String {
+    let vertices: Vec<&P> = simplex.iter().map(|v| &v.0).collect();
+    format!("{:?}", vertices)
+}
+
+impl<O, P> Solver<O> for NelderMead<P>
+where
+    O: ArgminOp<Param = P, Output = f64>,
+    OpWrapper<O>: ArgminBulkOp<O>,
+    P: Clone
+        + Default
+        + Serialize
+        + DeserializeOwned
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<f64, P>
+        + ArgminNorm<f64>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        if self.simplex.len() < 2 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NelderMead: the simplex needs at least two vertices.".to_string(),
+            }
+            .into());
+        }
+        let vertices: Vec<P> = self.simplex.iter().map(|v| v.0.clone()).collect();
+        let costs = op.bulk_apply(&vertices)?;
+        for (vertex, cost) in self.simplex.iter_mut().zip(costs) {
+            vertex.1 = cost;
+        }
+        self.sort_simplex();
+        self.update_size();
+        Ok(Some(
+            ArgminIterData::new()
+                .param(self.simplex[0].0.clone())
+                .cost(self.simplex[0].1),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let n = self.simplex.len() - 1;
+        let best_cost = self.simplex[0].1;
+        let second_worst_cost = self.simplex[n - 1].1;
+        let (worst, worst_cost) = self.simplex[n].clone();
+        let x0 = self.centroid();
+
+        let xr = Self::towards(&x0, &worst, -self.alpha);
+        let xr_cost = op.apply(&xr)?;
+
+        let mut replacement = None;
+        let operation = if xr_cost < best_cost {
+            let xe = Self::towards(&x0, &xr, self.gamma);
+            let xe_cost = op.apply(&xe)?;
+            if xe_cost < xr_cost {
+                replacement = Some((xe, xe_cost));
+                NelderMeadOperation::Expansion
+            } else {
+                replacement = Some((xr, xr_cost));
+                NelderMeadOperation::Reflection
+            }
+        } else if xr_cost < second_worst_cost {
+            replacement = Some((xr, xr_cost));
+            NelderMeadOperation::Reflection
+        } else if xr_cost < worst_cost {
+            let xc = Self::towards(&x0, &xr, self.rho);
+            let xc_cost = op.apply(&xc)?;
+            if xc_cost <= xr_cost {
+                replacement = Some((xc, xc_cost));
+                NelderMeadOperation::OutsideContraction
+            } else {
+                NelderMeadOperation::Shrink
+            }
+        } else {
+            let xc = Self::towards(&x0, &worst, self.rho);
+            let xc_cost = op.apply(&xc)?;
+            if xc_cost < worst_cost {
+                replacement = Some((xc, xc_cost));
+                NelderMeadOperation::InsideContraction
+            } else {
+                NelderMeadOperation::Shrink
+            }
+        };
+
+        if let Some(vertex) = replacement {
+            self.simplex[n] = vertex;
+        } else {
+            let best = &self.simplex[0].0;
+            let shrunk: Vec<P> = self.simplex[1..]
+                .iter()
+                .map(|v| Self::towards(best, &v.0, self.sigma))
+                .collect();
+            let costs = op.bulk_apply(&shrunk)?;
+            for (vertex, new_vertex) in self.simplex[1..]
+                .iter_mut()
+                .zip(shrunk.into_iter().zip(costs))
+            {
+                *vertex = new_vertex;
+            }
+        }
+        self.sort_simplex();
+        self.update_size();
+
+        let mut kv = make_kv!("action" => operation.as_str();
+                              "spread" => self.spread;
+                              "diameter" => self.diameter;);
+        if self.report_simplex {
+            let costs: Vec<f64> = self.simplex.iter().map(|v| v.1).collect();
+            kv.push("simplex_costs", format!("{:?}", costs));
+        }
+        if let Some(format_vertices) = self.format_vertices {
+            kv.push("simplex", format_vertices(&self.simplex));
+        }
+
+        Ok(ArgminIterData::new()
+            .param(self.simplex[0].0.clone())
+            .cost(self.simplex[0].1)
+            .kv(kv))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.spread < self.tol_f || self.diameter < self.tol_x {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::stepper::Stepper;
+    use crate::testfunctions::problem::TestProblem;
+
+    send_sync_test!(nelder_mead, NelderMead<Vec<f64>>);
+
+    /// `f(x, y) = x^2`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Embedded {}
+
+    impl ArgminOp for Embedded {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(x[0].powi(2))
+        }
+    }
+
+    /// Runs `iters` iterations manually and returns the solver and the performed actions
+    fn run<O>(
+        op: O,
+        mut solver: NelderMead<Vec<f64>>,
+        iters: usize,
+    ) -> (NelderMead<Vec<f64>>, Vec<String>)
+    where
+        O: ArgminOp<Param = Vec<f64>, Output = f64>,
+        OpWrapper<O>: ArgminBulkOp<O>,
+    {
+        let mut op = OpWrapper::new(&op);
+        let state = IterState::new(vec![]);
+        solver.init(&mut op, &state).unwrap();
+        let actions = (0..iters)
+            .map(|_| {
+                let kv = solver.next_iter(&mut op, &state).unwrap().get_kv();
+                kv.kv
+                    .iter()
+                    .find(|(k, _)| *k == "action")
+                    .map(|(_, v)| v.clone())
+                    .unwrap()
+            })
+            .collect();
+        (solver, actions)
+    }
+
+    #[test]
+    fn test_nelder_mead_operations() {
+        let solver = NelderMead::new(vec![vec![4.0, 0.0], vec![5.0, 0.0], vec![4.0, 1.0]]);
+        let (solver, actions) = run(Embedded {}, solver, 6);
+        // 1) The reflection (3, 1) of (5, 0) is better than the best vertex and so is the
+        //    expansion (2, 1.5).
+        // 2) The reflection (2, 0.5) of (4, 1) is as good as the best vertex (2, 1.5).
+        // 3) The reflection (0, 2) of (4, 0) is better than the best vertex, but the expansion
+        //    (-2, 3) is not better than the reflection.
+        // 4) The reflection (0, 3) of (2, 0.5) is as good as the best vertex (0, 2).
+        // 5) The reflection (-2, 3.5) of (2, 1.5) is worse than all vertices, the contraction
+        //    (1, 2) towards (2, 1.5) is better.
+        // 6) Same as 5) with the worst vertex (1, 2).
+        assert_eq!(
+            actions,
+            vec![
+                "expansion",
+                "reflection",
+                "reflection",
+                "reflection",
+                "inside_contraction",
+                "inside_contraction"
+            ]
+        );
+        let simplex = solver.simplex();
+        assert_eq!(simplex.len(), 3);
+        assert!((simplex[2].0[0] - 0.5).abs() < std::f64::EPSILON);
+        assert!((simplex[2].0[1] - 2.25).abs() < std::f64::EPSILON);
+        assert!((simplex[2].1 - 0.25).abs() < std::f64::EPSILON);
+    }
+
+    /// `f(x, y) = -|x|`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct NegAbs {}
+
+    impl ArgminOp for NegAbs {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(-x[0].abs())
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_shrink() {
+        // The reflection (1, 0) of the worst vertex (-1, -2) is as bad as the worst vertex and the
+        // contraction (-0.5, -1.5) is worse, therefore the simplex is shrunk towards (-2, -2).
+        let solver = NelderMead::new(vec![vec![-2.0, -2.0], vec![-1.0, -2.0], vec![2.0, 0.0]]);
+        let (solver, actions) = run(NegAbs {}, solver, 1);
+        assert_eq!(actions, vec!["shrink"]);
+        let simplex = solver.simplex();
+        let expected = [(-2.0, -2.0, -2.0), (-1.5, -2.0, -1.5), (0.0, -1.0, 0.0)];
+        for ((vertex, cost), (x, y, c)) in simplex.iter().zip(expected.iter()) {
+            assert!((vertex[0] - x).abs() < std::f64::EPSILON);
+            assert!((vertex[1] - y).abs() < std::f64::EPSILON);
+            assert!((cost - c).abs() < std::f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_simplex_sorted() {
+        let problem = TestProblem::rosenbrock(2);
+        let solver = NelderMead::new(vec![vec![-1.2, 1.0], vec![-1.0, 1.0], vec![-1.2, 1.2]])
+            .report_simplex(true)
+            .report_vertices(true);
+        let (solver, _) = run(problem.clone(), solver, 50);
+        let simplex = solver.simplex();
+        assert_eq!(simplex.len(), 3);
+        for window in simplex.windows(2) {
+            assert!(window[0].1 <= window[1].1);
+        }
+        for (vertex, cost) in simplex {
+            assert!((problem.apply(vertex).unwrap() - cost).abs() < std::f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_final_simplex() {
+        let problem = TestProblem::rosenbrock(2);
+        let solver = NelderMead::from_point(vec![-1.2, 1.0], SimplexStep::default())
+            .unwrap()
+            .report_vertices(true);
+        let mut stepper = Stepper::new(problem.clone(), solver, vec![-1.2, 1.0]).max_iters(100);
+        stepper.run().unwrap();
+        // the simplex after the run, sorted by cost, with the best vertex of the run first
+        let simplex = stepper.solver().simplex();
+        assert_eq!(simplex.len(), 3);
+        assert_eq!(simplex[0].0, *stepper.best_param());
+        assert!((simplex[0].1 - stepper.best_cost()).abs() < std::f64::EPSILON);
+        assert!(simplex[0].1 < problem.apply(&vec![-1.2, 1.0]).unwrap());
+        for window in simplex.windows(2) {
+            assert!(window[0].1 <= window[1].1);
+        }
+        for (vertex, cost) in simplex {
+            assert!((problem.apply(vertex).unwrap() - cost).abs() < std::f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_too_few_vertices() {
+        let res = Executor::new(
+            TestProblem::sphere(2),
+            NelderMead::new(vec![vec![1.0, 1.0]]),
+            vec![],
+        )
+        .max_iters(10)
+        .run_fast();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_nelder_mead_coefficients() {
+        let nm = || NelderMead::new(vec![vec![0.0], vec![1.0]]);
+        assert!(nm().alpha(0.0).is_err());
+        assert!(nm().alpha(1e-10).is_ok());
+        assert!(nm().gamma(1.0).is_err());
+        assert!(nm().gamma(1.0001).is_ok());
+        assert!(nm().rho(0.0).is_err());
+        assert!(nm().rho(1e-10).is_ok());
+        assert!(nm().rho(0.5).is_ok());
+        assert!(nm().rho(0.5001).is_err());
+        assert!(nm().sigma(0.0).is_err());
+        assert!(nm().sigma(1e-10).is_ok());
+        assert!(nm().sigma(0.9999).is_ok());
+        assert!(nm().sigma(1.0).is_err());
+        assert!(nm().alpha(std::f64::NAN).is_err());
+        assert!(nm().gamma(std::f64::NAN).is_err());
+        assert!(nm().rho(std::f64::NAN).is_err());
+        assert!(nm().sigma(std::f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_nelder_mead_gamma_is_used() {
+        let simplex = vec![vec![-1.2, 1.0], vec![-1.0, 1.0], vec![-1.2, 1.2]];
+        let run_gamma = |gamma: f64| {
+            let solver = NelderMead::new(simplex.clone()).gamma(gamma).unwrap();
+            Executor::new(TestProblem::rosenbrock(2), solver, vec![])
+                .max_iters(20)
+                .run_fast()
+                .unwrap()
+                .param
+        };
+        // several expansions are performed in the first 20 iterations
+        let default = run_gamma(2.0);
+        let small = run_gamma(1.0001);
+        assert!((default[0] - small[0]).abs() > 0.1);
+        assert!((default[1] - small[1]).abs() > 0.1);
+    }
+
+    #[test]
+    fn test_nelder_mead_tolerances() {
+        let nm = || NelderMead::new(vec![vec![0.0], vec![1.0]]);
+        assert!(nm().tol_f(-1e-10).is_err());
+        assert!(nm().tol_f(0.0).is_ok());
+        assert!(nm().tol_x(-1e-10).is_err());
+        assert!(nm().tol_x(0.0).is_ok());
+    }
+
+    #[test]
+    fn test_nelder_mead_sphere_terminates() {
+        let simplex = vec![vec![1.0, 1.0], vec![1.5, 1.0], vec![1.0, 1.5]];
+        let solver = NelderMead::new(simplex.clone())
+            .tol_f(1e-10)
+            .unwrap()
+            .tol_x(0.0)
+            .unwrap();
+        let res = Executor::new(TestProblem::sphere(2), solver, vec![])
+            .max_iters(10000)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost < 1e-10);
+        // every iteration evaluates the cost function at least once
+        assert!(res.operator.cost_func_count < 1000);
+
+        // without tolerances all iterations are performed
+        let solver = NelderMead::new(simplex)
+            .tol_f(0.0)
+            .unwrap()
+            .tol_x(0.0)
+            .unwrap();
+        let res = Executor::new(TestProblem::sphere(2), solver, vec![])
+            .max_iters(2000)
+            .run_fast()
+            .unwrap();
+        assert!(res.operator.cost_func_count >= 2003);
+    }
+
+    /// Rank of the matrix with rows `rows` via Gaussian elimination with partial pivoting
+    fn rank(mut rows: Vec<Vec<f64>>) -> usize {
+        let cols = rows[0].len();
+        let mut rank = 0;
+        for col in 0..cols {
+            let pivot = (rank..rows.len())
+                .max_by(|&a, &b| rows[a][col].abs().partial_cmp(&rows[b][col].abs()).unwrap());
+            let pivot = match pivot {
+                Some(p) if rows[p][col].abs() > 1e-12 => p,
+                _ => continue,
+            };
+            rows.swap(rank, pivot);
+            for r in (rank + 1)..rows.len() {
+                let factor = rows[r][col] / rows[rank][col];
+                for c in col..cols {
+                    rows[r][c] -= factor * rows[rank][c];
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+
+    #[test]
+    fn test_nelder_mead_from_point() {
+        let x0 = vec![2.0, 0.0, -4.0];
+        let solver = NelderMead::from_point(x0.clone(), SimplexStep::relative()).unwrap();
+        let vertices: Vec<Vec<f64>> = solver.simplex().iter().map(|v| v.0.clone()).collect();
+        assert_eq!(vertices.len(), 4);
+        let edges: Vec<Vec<f64>> = vertices[1..]
+            .iter()
+            .map(|v| v.iter().zip(x0.iter()).map(|(a, b)| a - b).collect())
+            .collect();
+        assert_eq!(rank(edges.clone()), 3);
+        // 5 % of |x_i| or the minimum step for the zero coordinate
+        for (i, step) in [0.1, 0.00025, 0.2].iter().enumerate() {
+            for (j, e) in edges[i].iter().enumerate() {
+                let expected = if i == j { *step } else { 0.0 };
+                assert!((e - expected).abs() < 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_from_point_per_dim() {
+        let x0 = vec![1.0, 0.0];
+        let steps = [
+            SimplexStep::Absolute(-0.5),
+            SimplexStep::Relative {
+                factor: 0.1,
+                min_step: 0.01,
+            },
+        ];
+        let solver = NelderMead::from_point_per_dim(x0.clone(), &steps).unwrap();
+        let simplex = solver.simplex();
+        assert!((simplex[1].0[0] - 0.5).abs() < std::f64::EPSILON);
+        assert!(simplex[1].0[1].is_zero());
+        assert!((simplex[2].0[0] - 1.0).abs() < std::f64::EPSILON);
+        assert!((simplex[2].0[1] - 0.01).abs() < std::f64::EPSILON);
+        let edges = simplex[1..]
+            .iter()
+            .map(|v| v.0.iter().zip(x0.iter()).map(|(a, b)| a - b).collect())
+            .collect();
+        assert_eq!(rank(edges), 2);
+
+        assert!(NelderMead::from_point_per_dim(x0.clone(), &steps[..1]).is_err());
+        assert!(NelderMead::from_point(x0.clone(), SimplexStep::Absolute(0.0)).is_err());
+        let no_min_step = SimplexStep::Relative {
+            factor: 0.1,
+            min_step: 0.0,
+        };
+        assert!(NelderMead::from_point(x0, no_min_step).is_err());
+    }
+
+    #[test]
+    fn test_nelder_mead_from_point_rosenbrock() {
+        let solver = NelderMead::from_point(vec![-1.2, 1.0], SimplexStep::default()).unwrap();
+        let res = Executor::new(TestProblem::rosenbrock(2), solver, vec![])
+            .max_iters(500)
+            .run_fast()
+            .unwrap();
+        assert!(
+            res.param.iter().all(|x| (x - 1.0).abs() < 1e-5),
+            "{:?}",
+            res.param
+        );
+    }
+
+    /// Smoke test for `wasm32-unknown-unknown` (`wasm-pack test --node -- --features wasm`)
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    fn test_nelder_mead_rosenbrock_wasm() {
+        let solver = NelderMead::from_point(vec![-1.2, 1.0], SimplexStep::default()).unwrap();
+        let res = Executor::new(TestProblem::rosenbrock(2), solver, vec![])
+            .max_iters(500)
+            .run_fast()
+            .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-5));
+    }
+
+    /// Rosenbrock function which counts its evaluations
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct CountingRosenbrock {
+        #[serde(skip)]
+        evaluations: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    }
+
+    impl ArgminOp for CountingRosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            self.evaluations
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            TestProblem::rosenbrock(x.len()).apply(x)
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_bulk_matches_serial() {
+        let problem = CountingRosenbrock::default();
+        let reference = TestProblem::rosenbrock(3);
+        let x0 = vec![-1.2, 1.0, 0.5];
+        let mut solver = NelderMead::from_point(x0, SimplexStep::Absolute(0.5)).unwrap();
+        let mut op = OpWrapper::new(&problem);
+        let state = IterState::new(vec![]);
+        solver.init(&mut op, &state).unwrap();
+        // Once the simplex has (almost) collapsed at the minimum, shrinks occur regularly.
+        let mut shrinks = 0;
+        for _ in 0..600 {
+            // Every vertex carries exactly the cost a serial evaluation yields, and the simplex
+            // is sorted.
+            let simplex = solver.simplex();
+            for (vertex, cost) in simplex {
+                assert_eq!(reference.apply(vertex).unwrap().to_bits(), cost.to_bits());
+            }
+            for window in simplex.windows(2) {
+                assert!(window[0].1 <= window[1].1);
+            }
+            let kv = solver.next_iter(&mut op, &state).unwrap().get_kv();
+            if kv.kv.iter().any(|(k, v)| *k == "action" && v == "shrink") {
+                shrinks += 1;
+            }
+            assert_eq!(
+                op.cost_func_count,
+                problem
+                    .evaluations
+                    .load(std::sync::atomic::Ordering::SeqCst)
+            );
+        }
+        assert!(shrinks > 0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_nelder_mead_parallel_matches_single_thread() {
+        let run_in_pool = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let solver =
+                NelderMead::from_point(vec![-1.2, 1.0, 0.5, 2.0], SimplexStep::default()).unwrap();
+            pool.install(|| run(TestProblem::rosenbrock(4), solver, 600))
+        };
+        let (serial, serial_actions) = run_in_pool(1);
+        let (parallel, parallel_actions) = run_in_pool(8);
+        assert_eq!(serial_actions, parallel_actions);
+        assert!(serial_actions.iter().any(|a| a == "shrink"));
+        for (a, b) in serial.simplex().iter().zip(parallel.simplex().iter()) {
+            assert_eq!(a.1.to_bits(), b.1.to_bits());
+            for (x, y) in a.0.iter().zip(b.0.iter()) {
+                assert_eq!(x.to_bits(), y.to_bits());
+            }
+        }
+    }
+}
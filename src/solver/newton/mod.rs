@@ -14,8 +14,11 @@
 
 /// Newton-CG method
 pub mod newton_cg;
+/// Newton's method with line search
+pub mod newton_ls;
 /// Newton's method
 pub mod newton_method;
 
 pub use self::newton_cg::*;
+pub use self::newton_ls::*;
 pub use self::newton_method::*;
@@ -46,10 +46,16 @@ impl<L> NewtonCG<L> {
         }
     }
 
-    /// Set curvature threshold
-    pub fn curvature_threshold(mut self, threshold: f64) -> Self {
+    /// Set the curvature threshold in `[0, inf)` below which CG stops (default: 0)
+    pub fn curvature_threshold(mut self, threshold: f64) -> Result<Self, Error> {
+        if !threshold.is_finite() || threshold < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NewtonCG: curvature_threshold must be in [0, inf).".to_string(),
+            }
+            .into());
+        }
         self.curvature_threshold = threshold;
-        self
+        Ok(self)
     }
 }
 
@@ -101,7 +107,7 @@ where
             let data = cg.next_iter(&mut cg_op, &cg_state)?;
             x = data.get_param().unwrap();
             let p = cg.p_prev();
-            let curvature = p.dot(&hessian.dot(&p));
+            let curvature = p.dot(&hessian.dot(p));
             if curvature <= self.curvature_threshold {
                 if iter == 0 {
                     x = grad.mul(&(-1.0));
@@ -116,7 +122,8 @@ where
             }
             cg_state.param(x.clone());
             cg_state.cost(data.get_cost().unwrap());
-            x_p = x.clone();
+            // `x` is overwritten in the next iteration, its old value is not needed anymore
+            std::mem::swap(&mut x_p, &mut x);
         }
 
         // take care of counting
@@ -197,4 +204,14 @@ mod tests {
     send_sync_test!(newton_cg, NewtonCG<Operator, MoreThuenteLineSearch<Operator>>);
 
     send_sync_test!(cg_subproblem, CGSubProblem<Vec<f64>, Vec<Vec<f64>>>);
+
+    #[test]
+    fn test_newton_cg_curvature_threshold() {
+        let solver = || NewtonCG::new(MoreThuenteLineSearch::<Vec<f64>>::new());
+        for threshold in [-1.0, std::f64::NAN, std::f64::INFINITY].iter() {
+            let e = solver().curvature_threshold(*threshold).err().unwrap();
+            assert!(e.to_string().contains("curvature_threshold"), "{}", e);
+        }
+        assert!(solver().curvature_threshold(0.1).is_ok());
+    }
 }
@@ -0,0 +1,222 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # References:
+//!
+//! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Damped Newton method: the search direction is the Newton direction `p = -H^-1 g` and the step
+/// length is chosen by a line search (which should start with a step length of 1 to retain the
+/// quadratic convergence close to the minimum).
+///
+/// If the Newton system cannot be solved or the Newton direction is not a descent direction
+/// (`g^T p >= 0`, which may happen if the Hessian is indefinite), the negative gradient is used
+/// instead. The direction (`newton` or `steepest_descent`) and the accepted step length are
+/// reported via the KV of each iteration.
+///
+/// # Example
+///
+/// ```rust
+/// TODO
+/// ```
+///
+/// # References:
+///
+/// [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Serialize, Deserialize)]
+pub struct NewtonLS<L> {
+    /// line search
+    linesearch: L,
+}
+
+impl<L> NewtonLS<L> {
+    /// Constructor
+    pub fn new(linesearch: L) -> Self {
+        NewtonLS { linesearch }
+    }
+}
+
+impl<O, L> Solver<O> for NewtonLS<L>
+where
+    O: ArgminOp<Output = f64>,
+    O::Param: Clone
+        + Default
+        + Serialize
+        + DeserializeOwned
+        + ArgminSub<O::Param, O::Param>
+        + ArgminDot<O::Param, f64>
+        + ArgminMul<f64, O::Param>
+        + ArgminNorm<f64>,
+    O::Hessian: ArgminSolve<O::Param>,
+    L: Clone + ArgminLineSearch<O::Param> + Solver<OpWrapper<O>>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        let cost = op.apply(&param)?;
+        let grad = op.gradient(&param)?;
+        Ok(Some(
+            ArgminIterData::new().param(param).cost(cost).grad(grad),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let grad = if let Some(grad) = state.get_grad() {
+            grad
+        } else {
+            op.gradient(&param)?
+        };
+        let hessian = op.hessian(&param)?;
+
+        let newton = hessian
+            .solve(&grad)
+            .ok()
+            .map(|p| p.mul(&(-1.0)))
+            .filter(|p| p.dot(&grad) < 0.0);
+        let (direction, kind) = match newton {
+            Some(p) => (p, "newton"),
+            None => (grad.mul(&(-1.0)), "steepest_descent"),
+        };
+
+        self.linesearch.set_search_direction(direction.clone());
+
+        // Run solver
+        let linesearch_result = Executor::new(
+            OpWrapper::new_from_op(&op),
+            self.linesearch.clone(),
+            param.clone(),
+        )
+        .grad(grad)
+        .cost(state.get_cost())
+        .run_fast()?;
+
+        // takes care of the counts of function evaluations
+        op.consume_op(linesearch_result.operator);
+
+        let direction_norm = direction.norm();
+        let step_length = if direction_norm > 0.0 {
+            linesearch_result.param.sub(&param).norm() / direction_norm
+        } else {
+            0.0
+        };
+
+        let new_grad = op.gradient(&linesearch_result.param)?;
+
+        Ok(ArgminIterData::new()
+            .param(linesearch_result.param)
+            .cost(linesearch_result.cost)
+            .grad(new_grad)
+            .kv(make_kv!("direction" => kind; "step_length" => step_length;)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::solver::newton::Newton;
+    use crate::testfunctions::problem::TestProblem;
+    use crate::MinimalNoOperator;
+
+    send_sync_test!(
+        newton_ls,
+        NewtonLS<BacktrackingLineSearch<MinimalNoOperator, ArmijoCondition>>
+    );
+
+    fn backtracking() -> BacktrackingLineSearch<Vec<f64>, ArmijoCondition> {
+        BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_newton_ls_rosenbrock() {
+        let problem = TestProblem::rosenbrock(2);
+        let init_cost = problem.apply(&problem.start().to_vec()).unwrap();
+
+        // Newton with a fixed step length of 1 overshoots in the second iteration
+        let res = Executor::new(problem.clone(), Newton::new(), problem.start().to_vec())
+            .max_iters(2)
+            .run_fast()
+            .unwrap();
+        assert!(problem.apply(&res.param).unwrap() > 10.0 * init_cost);
+
+        // the line search guarantees a monotonic decrease of the cost
+        let mut prev_cost = init_cost;
+        for iters in 1..=25 {
+            let solver = NewtonLS::new(backtracking());
+            let res = Executor::new(problem.clone(), solver, problem.start().to_vec())
+                .max_iters(iters)
+                .run_fast()
+                .unwrap();
+            assert!(res.cost <= prev_cost);
+            prev_cost = res.cost;
+        }
+        let solver = NewtonLS::new(backtracking());
+        let res = Executor::new(problem.clone(), solver, problem.start().to_vec())
+            .max_iters(30)
+            .run_fast()
+            .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-8));
+    }
+
+    /// `f(x, y) = x^2 / 2 + y^4 / 4 - y^2 / 2` with minima at `(0, +-1)`. The Hessian
+    /// `diag(1, 3 y^2 - 1)` is indefinite for `|y| < 1 / sqrt(3)`.
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct DoubleWell {}
+
+    impl ArgminOp for DoubleWell {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(x[0].powi(2) / 2.0 + x[1].powi(4) / 4.0 - x[1].powi(2) / 2.0)
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![x[0], x[1].powi(3) - x[1]])
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![1.0, 0.0], vec![0.0, 3.0 * x[1].powi(2) - 1.0]])
+        }
+    }
+
+    #[test]
+    fn test_newton_ls_non_descent_direction() {
+        // At `(0.1, 0.5)` the Newton direction `(-0.1, -1.5)` points uphill, the full steepest
+        // descent step `(-0.1, 0.375)` is accepted instead.
+        let res = Executor::new(DoubleWell {}, NewtonLS::new(backtracking()), vec![0.1, 0.5])
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        assert!(res.param[0].abs() < 1e-15);
+        assert!((res.param[1] - 0.875).abs() < 1e-15);
+
+        let res = Executor::new(DoubleWell {}, NewtonLS::new(backtracking()), vec![0.1, 0.5])
+            .max_iters(20)
+            .run_fast()
+            .unwrap();
+        assert!(res.param[0].abs() < 1e-12 && (res.param[1] - 1.0).abs() < 1e-12);
+    }
+}
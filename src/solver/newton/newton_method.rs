@@ -13,9 +13,42 @@
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Behavior of `Newton` if the Newton system `H p = g` cannot be solved because the Hessian is
+/// (numerically) singular
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NewtonFallback {
+    /// Return the error of the solve (default)
+    Error,
+    /// Take a steepest descent step `x - gamma * g` instead
+    SteepestDescent,
+    /// Solve `(H + tau * I) p = g` instead, starting with the given `tau` and increasing it by a
+    /// factor of 10 until the solve succeeds. Since the first successful `tau` is used, it should
+    /// not be chosen much smaller than the scale of the Hessian, otherwise the step along the
+    /// null space of `H` is of the order `1 / tau`.
+    Tikhonov(f64),
+}
+
+impl std::default::Default for NewtonFallback {
+    fn default() -> Self {
+        NewtonFallback::Error
+    }
+}
+
+/// Maximum number of increases of `tau` for `NewtonFallback::Tikhonov`
+const TIKHONOV_MAX_ATTEMPTS: usize = 20;
+
 /// Newton's method iteratively finds the stationary points of a function f by using a second order
 /// approximation of f at the current point.
 ///
+/// If the Hessian is singular, the behavior is determined by the `NewtonFallback` policy set via
+/// `fallback` (by default, the error is returned). The applied fallback (`none`,
+/// `steepest_descent` or `tikhonov`) and `tau` are reported via the KV of each iteration.
+///
+/// The solver terminates with `TargetPrecisionReached` if the norm of the gradient falls below
+/// `grad_tolerance` or if the Newton decrement `sqrt(g^T H^-1 g)` falls below
+/// `decrement_tolerance` (both default to `sqrt(EPSILON)`). The decrement is obtained from the
+/// solution of the Newton system and is reported via the KV of each iteration.
+///
 /// # Example
 ///
 /// ```rust
@@ -30,32 +63,92 @@ use serde::{Deserialize, Serialize};
 pub struct Newton {
     /// gamma
     gamma: f64,
+    /// behavior on singular Hessians
+    fallback: NewtonFallback,
+    /// tolerance for the norm of the gradient
+    grad_tol: f64,
+    /// tolerance for the Newton decrement
+    decrement_tol: f64,
+    /// Newton decrement of the last iteration
+    decrement: f64,
 }
 
 impl Newton {
     /// Constructor
     pub fn new() -> Self {
-        Newton { gamma: 1.0 }
+        Newton {
+            gamma: 1.0,
+            fallback: NewtonFallback::default(),
+            grad_tol: std::f64::EPSILON.sqrt(),
+            decrement_tol: std::f64::EPSILON.sqrt(),
+            decrement: std::f64::NAN,
+        }
     }
 
-    /// set gamma
-    pub fn set_gamma(mut self, gamma: f64) -> Result<Self, Error> {
-        if gamma <= 0.0 || gamma > 1.0 {
+    /// Set the step length `gamma` in `(0, 1]` (default: 1)
+    pub fn gamma(mut self, gamma: f64) -> Result<Self, Error> {
+        if gamma.is_nan() || gamma <= 0.0 || gamma > 1.0 {
             return Err(ArgminError::InvalidParameter {
-                text: "Newton: gamma must be in  (0, 1].".to_string(),
+                text: "Newton: gamma must be in (0, 1].".to_string(),
             }
             .into());
         }
         self.gamma = gamma;
         Ok(self)
     }
+
+    /// set gamma
+    #[deprecated(since = "0.1.9", note = "use `gamma` instead")]
+    pub fn set_gamma(self, gamma: f64) -> Result<Self, Error> {
+        self.gamma(gamma)
+    }
+
+    /// Set the behavior on singular Hessians (default: `NewtonFallback::Error`)
+    pub fn fallback(mut self, fallback: NewtonFallback) -> Result<Self, Error> {
+        if let NewtonFallback::Tikhonov(tau) = fallback {
+            if tau.is_nan() || tau <= 0.0 {
+                return Err(ArgminError::InvalidParameter {
+                    text: "Newton: Tikhonov regularization tau must be > 0.".to_string(),
+                }
+                .into());
+            }
+        }
+        self.fallback = fallback;
+        Ok(self)
+    }
+
+    /// Set the tolerance for the norm of the gradient (default: `sqrt(EPSILON)`)
+    pub fn grad_tolerance(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Newton: gradient tolerance must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.grad_tol = tol;
+        Ok(self)
+    }
+
+    /// Set the tolerance for the Newton decrement `sqrt(g^T H^-1 g)` (default: `sqrt(EPSILON)`)
+    pub fn decrement_tolerance(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "Newton: decrement tolerance must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.decrement_tol = tol;
+        Ok(self)
+    }
 }
 
 impl<O> Solver<O> for Newton
 where
     O: ArgminOp,
-    O::Param: ArgminScaledSub<O::Param, f64, O::Param>,
-    O::Hessian: ArgminInv<O::Hessian> + ArgminDot<O::Param, O::Param>,
+    O::Param: ArgminScaledSub<O::Param, f64, O::Param>
+        + ArgminPreciseDot<O::Param, f64>
+        + ArgminPreciseNorm<f64>,
+    O::Hessian: ArgminSolve<O::Param> + ArgminAddDiagonal,
 {
     fn next_iter(
         &mut self,
@@ -63,10 +156,58 @@ where
         state: &IterState<O>,
     ) -> Result<ArgminIterData<O>, Error> {
         let param = state.get_param();
-        let grad = op.gradient(&param)?;
+        let grad = if let Some(grad) = state.get_grad() {
+            grad
+        } else {
+            op.gradient(&param)?
+        };
         let hessian = op.hessian(&param)?;
-        let new_param = param.scaled_sub(&self.gamma, &hessian.inv()?.dot(&grad));
-        Ok(ArgminIterData::new().param(new_param))
+        // Solve `H p = g` instead of computing `H^-1 g`
+        let (direction, fallback, tau) = match hessian.solve(&grad) {
+            Ok(p) => (p, "none", 0.0),
+            Err(e) => match self.fallback {
+                NewtonFallback::Error => return Err(e),
+                NewtonFallback::SteepestDescent => (grad.clone(), "steepest_descent", 0.0),
+                NewtonFallback::Tikhonov(tau_init) => {
+                    let mut tau = tau_init;
+                    let mut attempt = 1;
+                    loop {
+                        match hessian.add_diagonal(tau).solve(&grad) {
+                            Ok(p) => break (p, "tikhonov", tau),
+                            Err(e) if attempt >= TIKHONOV_MAX_ATTEMPTS => return Err(e),
+                            Err(_) => {
+                                tau *= 10.0;
+                                attempt += 1;
+                            }
+                        }
+                    }
+                }
+            },
+        };
+        // The decrement is only meaningful if the direction solves a (regularized) Newton system
+        self.decrement = if fallback == "steepest_descent" {
+            std::f64::NAN
+        } else {
+            grad.dot_precise(&direction).abs().sqrt()
+        };
+        let new_param = param.scaled_sub(&self.gamma, &direction);
+        let new_grad = op.gradient(&new_param)?;
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .grad(new_grad)
+            .kv(make_kv!("fallback" => fallback; "tau" => tau; "decrement" => self.decrement;)))
+    }
+
+    fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
+        if let Some(grad) = state.get_grad() {
+            if grad.norm_precise() < self.grad_tol {
+                return TerminationReason::TargetPrecisionReached;
+            }
+        }
+        if self.decrement < self.decrement_tol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        TerminationReason::NotTerminated
     }
 }
 
@@ -74,6 +215,11 @@ where
 mod tests {
     use super::*;
     use crate::send_sync_test;
+    use crate::testfunctions::problem::{all_unconstrained_2d, TestProblem};
+    use crate::testfunctions::{
+        booth, booth_derivative, booth_hessian, rosenbrock, rosenbrock_derivative,
+        rosenbrock_hessian, sphere_hessian, sphere_shifted, sphere_shifted_derivative,
+    };
 
     // Only works with ndarray feature because of the required inverse of a matrix
     #[cfg(feature = "ndarrayl")]
@@ -82,4 +228,348 @@ mod tests {
     // Only works with ndarray feature because of the required inverse of a matrix
     #[cfg(feature = "ndarrayl")]
     send_sync_test!(newton_method, Newton<Operator>);
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Quadratic {}
+
+    /// `f(x) = 0.5 x^T A x - b^T x` with `A = [[3, 1], [1, 2]]` and `b = [1, 1]`
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(0.5 * (3.0 * x[0] * x[0] + 2.0 * x[0] * x[1] + 2.0 * x[1] * x[1]) - x[0] - x[1])
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![3.0 * x[0] + x[1] - 1.0, x[0] + 2.0 * x[1] - 1.0])
+        }
+
+        fn hessian(&self, _x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![3.0, 1.0], vec![1.0, 2.0]])
+        }
+    }
+
+    /// `f(x) = exp(x) - 2x` with the minimum at `x = ln(2)`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Scalar {}
+
+    impl ArgminOp for Scalar {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = f64;
+
+        fn apply(&self, x: &f64) -> Result<f64, Error> {
+            Ok(x.exp() - 2.0 * x)
+        }
+
+        fn gradient(&self, x: &f64) -> Result<f64, Error> {
+            Ok(x.exp() - 2.0)
+        }
+
+        fn hessian(&self, x: &f64) -> Result<f64, Error> {
+            Ok(x.exp())
+        }
+    }
+
+    #[test]
+    fn test_newton_scalar() {
+        let res = Executor::new(Scalar {}, Newton::new(), 0.0)
+            .max_iters(20)
+            .run_fast()
+            .unwrap();
+        assert!((res.param - 2.0f64.ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_newton_quadratic_one_step() {
+        let res = Executor::new(Quadratic {}, Newton::new(), vec![5.0, -3.0])
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        assert!((res.param[0] - 0.2).abs() < 1e-12);
+        assert!((res.param[1] - 0.4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_newton_quadratic_terminates() {
+        let res = Executor::new(Quadratic {}, Newton::new(), vec![5.0, -3.0])
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.operator.hessian_func_count, 1);
+        assert!((res.param[0] - 0.2).abs() < 1e-12);
+        assert!((res.param[1] - 0.4).abs() < 1e-12);
+        let mut solver = Newton::new();
+        let mut state: IterState<Quadratic> = IterState::new(vec![5.0, -3.0]);
+        state.grad(Quadratic {}.gradient(&vec![5.0, -3.0]).unwrap());
+        assert_eq!(solver.terminate(&state), TerminationReason::NotTerminated);
+        state.grad(Quadratic {}.gradient(&res.param).unwrap());
+        assert_eq!(
+            solver.terminate(&state),
+            TerminationReason::TargetPrecisionReached
+        );
+    }
+
+    #[test]
+    fn test_newton_gamma() {
+        for gamma in [0.0, -0.5, 1.5, std::f64::NAN].iter() {
+            let e = Newton::new().gamma(*gamma).err().unwrap();
+            assert!(e.to_string().contains("gamma"), "{}", e);
+        }
+        assert!(Newton::new().gamma(1.0).is_ok());
+        #[allow(deprecated)]
+        let solver = Newton::new().set_gamma(0.5).unwrap();
+        assert!((solver.gamma - 0.5).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_newton_tolerances() {
+        assert!(Newton::new().grad_tolerance(-1.0).is_err());
+        assert!(Newton::new().decrement_tolerance(std::f64::NAN).is_err());
+        assert!(Newton::new().grad_tolerance(0.0).is_ok());
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Booth {}
+
+    impl ArgminOp for Booth {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(booth(&[x[0], x[1]]))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(booth_derivative(&[x[0], x[1]]).to_vec())
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(booth_hessian(&[x[0], x[1]])
+                .iter()
+                .map(|r| r.to_vec())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_newton_booth_one_step() {
+        for init in &[vec![0.0, 0.0], vec![-10.0, 10.0], vec![7.5, -2.0]] {
+            let res = Executor::new(Booth {}, Newton::new(), init.clone())
+                .max_iters(1)
+                .run_fast()
+                .unwrap();
+            assert!((res.param[0] - 1.0).abs() < 1e-12);
+            assert!((res.param[1] - 3.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_newton_test_problems() {
+        for problem in all_unconstrained_2d()
+            .into_iter()
+            .filter(TestProblem::has_hessian)
+        {
+            for m in problem.minimizers() {
+                let init = vec![m[0] + 0.05, m[1] - 0.05];
+                let res = Executor::new(problem.clone(), Newton::new(), init)
+                    .max_iters(20)
+                    .run_fast()
+                    .unwrap();
+                assert!(
+                    problem.is_solution(&res.param),
+                    "{}: {:?}",
+                    problem.name(),
+                    res.param
+                );
+            }
+        }
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Rosenbrock {}
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(rosenbrock(x, 1.0, 100.0))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(rosenbrock_derivative(x, 1.0, 100.0))
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(rosenbrock_hessian(x, 1.0, 100.0))
+        }
+    }
+
+    #[test]
+    fn test_newton_rosenbrock_20d() {
+        let init: Vec<f64> = (0..20)
+            .map(|i| if i % 2 == 0 { 0.9 } else { 1.1 })
+            .collect();
+        let res = Executor::new(Rosenbrock {}, Newton::new(), init)
+            .max_iters(20)
+            .run_fast()
+            .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-8));
+    }
+
+    #[test]
+    fn test_newton_rosenbrock_grad_tolerance() {
+        let solver = Newton::new()
+            .grad_tolerance(1e-3)
+            .unwrap()
+            .decrement_tolerance(0.0)
+            .unwrap();
+        let res = Executor::new(Rosenbrock {}, solver, vec![-1.2, 1.0])
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        let grad = rosenbrock_derivative(&res.param, 1.0, 100.0);
+        assert!(grad.norm_precise() < 1e-3);
+        // Stopped at the tolerance instead of iterating until the minimum is hit exactly
+        assert!(res.operator.hessian_func_count < 100);
+        assert!((res.param[1] - 1.0).abs() > 1e-8);
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-4));
+    }
+
+    /// Sphere function with its minimum at `(1, -2, 3, -4)`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct ShiftedSphere {}
+
+    const CENTER: [f64; 4] = [1.0, -2.0, 3.0, -4.0];
+
+    impl ArgminOp for ShiftedSphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere_shifted(x, &CENTER))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sphere_shifted_derivative(x, &CENTER))
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(sphere_hessian(x))
+        }
+    }
+
+    #[test]
+    fn test_newton_sphere_one_step() {
+        let res = Executor::new(ShiftedSphere {}, Newton::new(), vec![10.0, 7.0, -3.0, 0.5])
+            .max_iters(1)
+            .run_fast()
+            .unwrap();
+        for (x, c) in res.param.iter().zip(CENTER.iter()) {
+            assert!((x - c).abs() < 1e-12);
+        }
+    }
+
+    /// `f(x, y) = x^2 + y^4 / 4 - y` with the minimum at `(0, 1)`. The Hessian is singular for
+    /// `y = 0`.
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Singular {}
+
+    impl ArgminOp for Singular {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(x[0].powi(2) + x[1].powi(4) / 4.0 - x[1])
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![2.0 * x[0], x[1].powi(3) - 1.0])
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![2.0, 0.0], vec![0.0, 3.0 * x[1].powi(2)]])
+        }
+    }
+
+    fn run_singular(fallback: NewtonFallback, iters: u64) -> Result<Vec<f64>, Error> {
+        let solver = Newton::new().fallback(fallback)?;
+        Ok(Executor::new(Singular {}, solver, vec![1.0, 0.0])
+            .max_iters(iters)
+            .run_fast()?
+            .param)
+    }
+
+    #[test]
+    fn test_newton_fallback_error() {
+        assert!(run_singular(NewtonFallback::Error, 10).is_err());
+        assert!(Newton::new()
+            .fallback(NewtonFallback::Tikhonov(0.0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_newton_fallback_steepest_descent() {
+        // `x - g` with `g = (2, -1)`
+        let param = run_singular(NewtonFallback::SteepestDescent, 1).unwrap();
+        assert!((param[0] + 1.0).abs() < std::f64::EPSILON);
+        assert!((param[1] - 1.0).abs() < std::f64::EPSILON);
+        let param = run_singular(NewtonFallback::SteepestDescent, 10).unwrap();
+        assert!(param[0].abs() < 1e-12 && (param[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_newton_fallback_tikhonov() {
+        // `(H + I) p = g` with `H = diag(2, 0)`
+        let param = run_singular(NewtonFallback::Tikhonov(1.0), 1).unwrap();
+        assert!((param[0] - 1.0 / 3.0).abs() < 1e-15);
+        assert!((param[1] - 1.0).abs() < 1e-15);
+        let param = run_singular(NewtonFallback::Tikhonov(1.0), 10).unwrap();
+        assert!(param[0].abs() < 1e-12 && (param[1] - 1.0).abs() < 1e-12);
+    }
+
+    /// `f(x, y) = x^4 / 4 - y^2 / 2`, the Hessian `diag(3 x^2, -1)` is singular for `x = 0` and
+    /// `H + I` is singular as well.
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Saddle {}
+
+    impl ArgminOp for Saddle {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(x[0].powi(4) / 4.0 - x[1].powi(2) / 2.0)
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![x[0].powi(3), -x[1]])
+        }
+
+        fn hessian(&self, x: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![3.0 * x[0].powi(2), 0.0], vec![0.0, -1.0]])
+        }
+    }
+
+    #[test]
+    fn test_newton_fallback_tikhonov_increases_tau() {
+        // `tau = 1` fails, `tau = 10` leads to `(H + 10 I) p = g` with `p = (0, -1 / 9)`
+        let solver = Newton::new()
+            .fallback(NewtonFallback::Tikhonov(1.0))
+            .unwrap();
+        let param = Executor::new(Saddle {}, solver, vec![0.0, 1.0])
+            .max_iters(1)
+            .run_fast()
+            .unwrap()
+            .param;
+        assert!(param[0].abs() < std::f64::EPSILON);
+        assert!((param[1] - 10.0 / 9.0).abs() < 1e-15);
+    }
 }
@@ -0,0 +1,330 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [Newton-bisection root finding method](struct.NewtonBisection.html)
+//!
+//! # References:
+//!
+//! [0] William H. Press, Saul A. Teukolsky, William T. Vetterling, Brian P. Flannery (2007).
+//! Numerical Recipes: The Art of Scientific Computing. Third edition. Cambridge University Press.
+//! Section 9.4 (`rtsafe`)
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Safeguarded Newton method for finding a root of a scalar function `f` within a bracket `[a, b]`
+/// over which `f` changes its sign.
+///
+/// The operator's `apply` computes `f(x)` and its `gradient` computes the derivative `f'(x)`.
+/// Starting from the midpoint of the bracket, a Newton step is taken whenever it stays within the
+/// bracket and reduces `|f|` sufficiently fast; otherwise the bracket is bisected. The bracket is
+/// shrunk after each iteration such that it always encloses a sign change, therefore all iterates
+/// stay within the initial bracket.
+///
+/// The optimization stops once `|f(x)|` is below `f_tol` or the width of the bracket is below
+/// `x_tol`. The iterate's "cost" is `|f(x)|`. The performed step (`newton` or `bisection`) and the
+/// width of the bracket are reported as `step` and `width` via the KV of each iteration. The
+/// initial parameter vector passed to the `Executor` is ignored.
+///
+/// # Example
+///
+/// ```rust
+/// TODO
+/// ```
+///
+/// # References:
+///
+/// [0] William H. Press, Saul A. Teukolsky, William T. Vetterling, Brian P. Flannery (2007).
+/// Numerical Recipes: The Art of Scientific Computing. Third edition. Cambridge University Press.
+/// Section 9.4 (`rtsafe`)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NewtonBisection {
+    /// end of the bracket where `f` is negative
+    x_neg: f64,
+    /// end of the bracket where `f` is positive
+    x_pos: f64,
+    /// current iterate
+    x: f64,
+    /// `f(x)`
+    fx: f64,
+    /// `f'(x)`
+    dfx: f64,
+    /// last step
+    dx: f64,
+    /// step before the last step
+    dx_old: f64,
+    /// tolerance for `|f(x)|`
+    f_tol: f64,
+    /// tolerance for the width of the bracket
+    x_tol: f64,
+}
+
+impl NewtonBisection {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `a`, `b`: ends of the bracket. `f(a)` and `f(b)` must have different signs.
+    pub fn new(a: f64, b: f64) -> Result<Self, Error> {
+        if !a.is_finite() || !b.is_finite() || (a - b).abs().is_zero() {
+            return Err(ArgminError::InvalidParameter {
+                text: "NewtonBisection: bracket ends must be finite and distinct.".to_string(),
+            }
+            .into());
+        }
+        Ok(NewtonBisection {
+            x_neg: a,
+            x_pos: b,
+            x: std::f64::NAN,
+            fx: std::f64::NAN,
+            dfx: std::f64::NAN,
+            dx: std::f64::NAN,
+            dx_old: std::f64::NAN,
+            f_tol: std::f64::EPSILON,
+            x_tol: std::f64::EPSILON.sqrt(),
+        })
+    }
+
+    /// Set the tolerance for `|f(x)|` (default: `EPSILON`)
+    pub fn f_tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NewtonBisection: f_tol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.f_tol = tol;
+        Ok(self)
+    }
+
+    /// Set the tolerance for the width of the bracket (default: `sqrt(EPSILON)`)
+    pub fn x_tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "NewtonBisection: x_tol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.x_tol = tol;
+        Ok(self)
+    }
+
+    /// Current bracket `(lower, upper)`
+    pub fn bracket(&self) -> (f64, f64) {
+        (self.x_neg.min(self.x_pos), self.x_neg.max(self.x_pos))
+    }
+
+    /// Evaluate `f` and `f'` at `x` and shrink the bracket accordingly
+    fn update<O>(&mut self, op: &mut OpWrapper<O>, x: f64) -> Result<(), Error>
+    where
+        O: ArgminOp<Param = f64, Output = f64>,
+    {
+        self.x = x;
+        self.fx = op.apply(&x)?;
+        self.dfx = op.gradient(&x)?;
+        if self.fx < 0.0 {
+            self.x_neg = x;
+        } else {
+            self.x_pos = x;
+        }
+        Ok(())
+    }
+}
+
+impl<O> Solver<O> for NewtonBisection
+where
+    O: ArgminOp<Param = f64, Output = f64>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let (a, b) = (self.x_neg, self.x_pos);
+        let (fa, fb) = (op.apply(&a)?, op.apply(&b)?);
+        if fa.is_nan() || fb.is_nan() || fa * fb > 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "NewtonBisection: f does not change its sign over the bracket [{}, {}] \
+                     (f({}) = {}, f({}) = {}).",
+                    a, b, a, fa, b, fb
+                ),
+            }
+            .into());
+        }
+        if fa > 0.0 || fb < 0.0 {
+            std::mem::swap(&mut self.x_neg, &mut self.x_pos);
+        }
+        self.dx_old = (b - a).abs();
+        self.dx = self.dx_old;
+        if fa.is_zero() {
+            self.update(op, a)?;
+        } else if fb.is_zero() {
+            self.update(op, b)?;
+        } else {
+            self.update(op, 0.5 * (a + b))?;
+        }
+        Ok(Some(
+            ArgminIterData::new()
+                .param(self.x)
+                .cost(self.fx.abs())
+                .kv(make_kv!("width" => (self.x_pos - self.x_neg).abs();)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        _state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        // Bisect if the Newton step leaves the bracket or does not reduce the step size fast
+        // enough. This is also the case if `f'(x) = 0`.
+        let newton_leaves_bracket = ((self.x - self.x_pos) * self.dfx - self.fx)
+            * ((self.x - self.x_neg) * self.dfx - self.fx)
+            > 0.0;
+        let newton_too_slow = (2.0 * self.fx).abs() > (self.dx_old * self.dfx).abs();
+        self.dx_old = self.dx;
+        let step = if newton_leaves_bracket || newton_too_slow {
+            self.dx = 0.5 * (self.x_pos - self.x_neg);
+            let x = self.x_neg + self.dx;
+            self.update(op, x)?;
+            "bisection"
+        } else {
+            self.dx = self.fx / self.dfx;
+            let x = self.x - self.dx;
+            self.update(op, x)?;
+            "newton"
+        };
+        Ok(ArgminIterData::new()
+            .param(self.x)
+            .cost(self.fx.abs())
+            .kv(make_kv!(
+                "step" => step;
+                "width" => (self.x_pos - self.x_neg).abs();
+            )))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.fx.abs() <= self.f_tol || (self.x_pos - self.x_neg).abs() <= self.x_tol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(newton_bisection, NewtonBisection);
+
+    /// `f(x) = atan(x)`, which has an inflection point at its root `0`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Atan {}
+
+    impl ArgminOp for Atan {
+        type Param = f64;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &f64) -> Result<f64, Error> {
+            Ok(x.atan())
+        }
+
+        fn gradient(&self, x: &f64) -> Result<f64, Error> {
+            Ok(1.0 / (1.0 + x.powi(2)))
+        }
+    }
+
+    #[test]
+    fn test_newton_bisection_invalid() {
+        assert!(NewtonBisection::new(1.0, 1.0).is_err());
+        assert!(NewtonBisection::new(std::f64::NAN, 1.0).is_err());
+        assert!(NewtonBisection::new(0.0, std::f64::INFINITY).is_err());
+        assert!(NewtonBisection::new(0.0, 1.0).unwrap().f_tol(-1.0).is_err());
+        assert!(NewtonBisection::new(0.0, 1.0).unwrap().x_tol(-1.0).is_err());
+        let res = Executor::new(Atan {}, NewtonBisection::new(1.0, 2.0).unwrap(), 0.0)
+            .max_iters(10)
+            .run_fast();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_newton_bisection_inflection() {
+        // Pure Newton from the midpoint of the bracket diverges.
+        let op = Atan {};
+        let mut x = 1.5;
+        for _ in 0..5 {
+            x -= op.apply(&x).unwrap() / op.gradient(&x).unwrap();
+        }
+        assert!(x.abs() > 1000.0);
+
+        let res = Executor::new(op, NewtonBisection::new(-2.0, 5.0).unwrap(), 0.0)
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::TargetPrecisionReached
+        );
+        assert!(res.param.abs() < 1e-12);
+        assert!(res.operator.cost_func_count < 20);
+    }
+
+    #[test]
+    fn test_newton_bisection_stays_in_bracket() {
+        let op = Atan {};
+        let mut op = OpWrapper::new(&op);
+        let state = IterState::new(0.0);
+        let mut solver = NewtonBisection::new(5.0, -2.0).unwrap();
+        let mut iterates = vec![solver.init(&mut op, &state).unwrap().unwrap()];
+        let mut steps = vec![];
+        while solver.terminate(&state) == TerminationReason::NotTerminated {
+            let data = solver.next_iter(&mut op, &state).unwrap();
+            steps.push(
+                data.get_kv()
+                    .kv
+                    .iter()
+                    .find(|(k, _)| *k == "step")
+                    .map(|(_, v)| v.clone())
+                    .unwrap(),
+            );
+            iterates.push(data);
+            assert!(iterates.len() < 100);
+        }
+        // The first Newton step from the midpoint 1.5 lands at -1.69. The next one would be
+        // larger than the first one and is replaced by a bisection.
+        assert_eq!(&steps[..2], &["newton", "bisection"]);
+        for data in iterates {
+            let x = data.get_param().unwrap();
+            assert!((-2.0..=5.0).contains(&x));
+        }
+        let (lower, upper) = solver.bracket();
+        assert!(lower <= 0.0 && upper >= 0.0);
+    }
+
+    #[test]
+    fn test_newton_bisection_width() {
+        // Without the tolerance for |f|, the bracket is shrunk until it is narrow enough.
+        let solver = NewtonBisection::new(-2.0, 5.0)
+            .unwrap()
+            .f_tol(0.0)
+            .unwrap()
+            .x_tol(1e-3)
+            .unwrap();
+        let res = Executor::new(Atan {}, solver, 0.0)
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::TargetPrecisionReached
+        );
+        assert!(res.param.abs() < 1e-3);
+    }
+}
@@ -11,12 +11,16 @@
 //! Springer. ISBN 0-387-30303-0.
 
 use crate::prelude::*;
+use crate::solver::linesearch::LineSearchFailure;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 /// BFGS method
 ///
+/// If the line search gives up, the solver either stops or accepts the step depending on the
+/// `LineSearchFailure` policy (default: stop).
+///
 /// # Example
 ///
 /// ```rust
@@ -33,6 +37,10 @@ pub struct BFGS<L, H> {
     inv_hessian: H,
     /// line search
     linesearch: L,
+    /// norm used in the gradient termination criterion
+    grad_norm: Norm,
+    /// behavior on line search failures
+    linesearch_failure: LineSearchFailure,
 }
 
 impl<L, H> BFGS<L, H> {
@@ -41,8 +49,22 @@ impl<L, H> BFGS<L, H> {
         BFGS {
             inv_hessian: init_inverse_hessian,
             linesearch: linesearch,
+            grad_norm: Norm::L2,
+            linesearch_failure: LineSearchFailure::default(),
         }
     }
+
+    /// Set the norm used in the termination criterion `||grad|| < sqrt(EPSILON)` (default: L2)
+    pub fn grad_norm(mut self, norm: Norm) -> Self {
+        self.grad_norm = norm;
+        self
+    }
+
+    /// Set the behavior on line search failures (default: `LineSearchFailure::Stop`)
+    pub fn linesearch_failure(mut self, policy: LineSearchFailure) -> Self {
+        self.linesearch_failure = policy;
+        self
+    }
 }
 
 impl<O, L, H> Solver<O> for BFGS<L, H>
@@ -54,7 +76,8 @@ where
         + ArgminDot<O::Param, f64>
         + ArgminDot<O::Param, O::Hessian>
         + ArgminScaledAdd<O::Param, f64, O::Param>
-        + ArgminNorm<f64>
+        + ArgminPreciseNorm<f64>
+        + ArgminLpNorm<f64>
         + ArgminMul<f64, O::Param>,
     O::Hessian: Clone
         + Default
@@ -113,6 +136,17 @@ where
         // take care of function eval counts
         op.consume_op(linesearch_result.operator);
 
+        if self
+            .linesearch_failure
+            .stop(&linesearch_result.termination_reason)
+        {
+            return Ok(ArgminIterData::new()
+                .param(param)
+                .cost(cur_cost)
+                .grad(prev_grad)
+                .termination_reason(TerminationReason::Aborted));
+        }
+
         let xk1 = linesearch_result.param;
 
         let grad = op.gradient(&xk1)?;
@@ -150,7 +184,7 @@ where
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
-        if state.get_grad().unwrap().norm() < std::f64::EPSILON.sqrt() {
+        if norm_of(&state.get_grad().unwrap(), self.grad_norm) < std::f64::EPSILON.sqrt() {
             return TerminationReason::TargetPrecisionReached;
         }
         if (state.get_prev_cost() - state.get_cost()).abs() < std::f64::EPSILON {
@@ -165,8 +199,89 @@ mod tests {
     use super::*;
     use crate::send_sync_test;
     use crate::solver::linesearch::MoreThuenteLineSearch;
+    #[cfg(feature = "ndarrayl")]
+    use crate::testfunctions::{booth, booth_derivative};
 
     type Operator = MinimalNoOperator;
 
     send_sync_test!(bfgs, BFGS<Operator, MoreThuenteLineSearch<Operator>>);
+
+    #[cfg(feature = "ndarrayl")]
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Booth {}
+
+    #[cfg(feature = "ndarrayl")]
+    impl ArgminOp for Booth {
+        type Param = ndarray::Array1<f64>;
+        type Output = f64;
+        type Hessian = ndarray::Array2<f64>;
+
+        fn apply(&self, x: &Self::Param) -> Result<f64, Error> {
+            Ok(booth(&[x[0], x[1]]))
+        }
+
+        fn gradient(&self, x: &Self::Param) -> Result<Self::Param, Error> {
+            Ok(ndarray::Array1::from_vec(
+                booth_derivative(&[x[0], x[1]]).to_vec(),
+            ))
+        }
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_bfgs_booth() {
+        let init_hessian: ndarray::Array2<f64> = ndarray::Array2::eye(2);
+        let solver = BFGS::new(init_hessian, MoreThuenteLineSearch::new());
+        let res = Executor::new(Booth {}, solver, ndarray::Array1::from_vec(vec![-4.0, 8.0]))
+            .max_iters(50)
+            .run_fast()
+            .unwrap();
+        assert!((res.param[0] - 1.0).abs() < 1e-6);
+        assert!((res.param[1] - 3.0).abs() < 1e-6);
+    }
+
+    /// `f(x) = sum_i (x_i - 3)^2`, NaN if any `x_i > 0.25`
+    #[cfg(feature = "ndarrayl")]
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct NanBeyond {}
+
+    #[cfg(feature = "ndarrayl")]
+    impl ArgminOp for NanBeyond {
+        type Param = ndarray::Array1<f64>;
+        type Output = f64;
+        type Hessian = ndarray::Array2<f64>;
+
+        fn apply(&self, x: &Self::Param) -> Result<f64, Error> {
+            if x.iter().any(|x| *x > 0.25) {
+                return Ok(std::f64::NAN);
+            }
+            Ok(x.iter().map(|x| (x - 3.0).powi(2)).sum())
+        }
+
+        fn gradient(&self, x: &Self::Param) -> Result<Self::Param, Error> {
+            Ok(x.mapv(|x| 2.0 * (x - 3.0)))
+        }
+    }
+
+    #[cfg(feature = "ndarrayl")]
+    #[test]
+    fn test_bfgs_linesearch_failure() {
+        use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+
+        // all trial points lie in the NaN region
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap())
+            .rho(0.5)
+            .unwrap()
+            .max_iters(5)
+            .unwrap();
+        let init_hessian: ndarray::Array2<f64> = ndarray::Array2::eye(2);
+        let solver = BFGS::new(init_hessian, linesearch);
+        let res = Executor::new(NanBeyond {}, solver, ndarray::Array1::zeros(2))
+            .max_iters(50)
+            .run_fast()
+            .unwrap();
+        assert_eq!(res.termination_reason, TerminationReason::Aborted);
+        assert!(res.param.iter().all(|x| x.is_zero()));
+        assert!((res.cost - 18.0).abs() < std::f64::EPSILON);
+    }
 }
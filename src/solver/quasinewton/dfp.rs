@@ -32,6 +32,8 @@ pub struct DFP<L, H> {
     inv_hessian: H,
     /// line search
     linesearch: L,
+    /// norm used in the gradient termination criterion
+    grad_norm: Norm,
 }
 
 impl<L, H> DFP<L, H> {
@@ -40,8 +42,15 @@ impl<L, H> DFP<L, H> {
         DFP {
             inv_hessian: init_inverse_hessian,
             linesearch: linesearch,
+            grad_norm: Norm::L2,
         }
     }
+
+    /// Set the norm used in the termination criterion `||grad|| < sqrt(EPSILON)` (default: L2)
+    pub fn grad_norm(mut self, norm: Norm) -> Self {
+        self.grad_norm = norm;
+        self
+    }
 }
 
 impl<O, L, H> Solver<O> for DFP<L, H>
@@ -54,7 +63,8 @@ where
         + ArgminDot<O::Param, f64>
         + ArgminDot<O::Param, O::Hessian>
         + ArgminScaledAdd<O::Param, f64, O::Param>
-        + ArgminNorm<f64>
+        + ArgminPreciseNorm<f64>
+        + ArgminLpNorm<f64>
         + ArgminMul<f64, O::Param>
         + ArgminTranspose,
     O::Hessian: Clone
@@ -78,6 +88,14 @@ where
         let param = state.get_param();
         let cost = op.apply(&param)?;
         let grad = op.gradient(&param)?;
+        // The first search direction `-H * grad` must be a descent direction
+        let curvature: f64 = grad.dot(&self.inv_hessian.dot(&grad));
+        if curvature < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "DFP: init_inverse_hessian must be positive definite.".to_string(),
+            }
+            .into());
+        }
         Ok(Some(
             ArgminIterData::new().param(param).cost(cost).grad(grad),
         ))
@@ -136,7 +154,7 @@ where
     }
 
     fn terminate(&mut self, state: &IterState<O>) -> TerminationReason {
-        if state.get_grad().unwrap().norm() < std::f64::EPSILON.sqrt() {
+        if norm_of(&state.get_grad().unwrap(), self.grad_norm) < std::f64::EPSILON.sqrt() {
             return TerminationReason::TargetPrecisionReached;
         }
         TerminationReason::NotTerminated
@@ -148,8 +166,21 @@ mod tests {
     use super::*;
     use crate::send_sync_test;
     use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::testfunctions::problem::TestProblem;
 
     type Operator = MinimalNoOperator;
 
     send_sync_test!(dfp, DFP<Operator, MoreThuenteLineSearch<Operator>>);
+
+    #[test]
+    fn test_dfp_indefinite_init_inverse_hessian() {
+        let init_hessian = vec![vec![-1.0, 0.0], vec![0.0, -1.0]];
+        let solver = DFP::new(init_hessian, MoreThuenteLineSearch::new());
+        let e = Executor::new(TestProblem::rosenbrock(2), solver, vec![-1.2, 1.0])
+            .max_iters(10)
+            .run_fast()
+            .err()
+            .unwrap();
+        assert!(e.to_string().contains("init_inverse_hessian"), "{}", e);
+    }
 }
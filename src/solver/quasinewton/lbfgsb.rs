@@ -0,0 +1,826 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! * [L-BFGS-B](struct.LBFGSB.html)
+//!
+//! # References:
+//!
+//! [0] Richard H. Byrd, Peihuang Lu, Jorge Nocedal and Ciyou Zhu (1995). A Limited Memory
+//! Algorithm for Bound Constrained Optimization. SIAM J. Sci. Comput. 16(5), 1190-1208.
+//!
+//! [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::operator::bounds::Bounds;
+use crate::prelude::*;
+use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch, LineSearchFailure};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f64::INFINITY;
+
+/// L-BFGS-B: limited-memory BFGS method for problems with lower and upper bounds `l <= x <= u`.
+///
+/// The bounds are given as `Bounds`; missing bounds mean `-inf` and `inf`, respectively. Each
+/// iteration consists of three steps:
+///
+/// 1. The generalized Cauchy point, i.e. the first local minimizer of the quadratic model along the
+///    projected steepest descent path, determines the set of variables which are fixed at their
+///    bounds.
+/// 2. The quadratic model is minimized over the remaining free variables using the compact
+///    representation of the limited-memory BFGS matrix (direct primal method). The result is
+///    truncated such that it stays within the bounds.
+/// 3. A backtracking line search (Armijo condition) is performed along the segment between the
+///    current point and the result of step 2. Since the box is convex, all trial points are
+///    feasible.
+///
+/// Only the `m` most recent correction pairs are stored (`m` is given to the constructor). Pairs
+/// violating the curvature condition `s^T y > EPSILON * y^T y` are skipped. If the line search
+/// fails, the stored pairs are dropped and the iteration is repeated with the steepest descent
+/// model; if that fails as well, the solver stops with `TerminationReason::Aborted`. An initial
+/// parameter vector outside of the bounds is projected onto them.
+///
+/// The optimization stops once the infinity norm of the projected gradient `P(x - g) - x` is below
+/// `pg_tol` (default: `sqrt(EPSILON)`) or the relative reduction of the cost function is below
+/// `EPSILON`. The number of variables at their bounds, the number of free variables in the
+/// subspace minimization, the number of stored correction pairs and the step length are reported
+/// as `active`, `free`, `pairs` and `step_length` via the KV of each iteration.
+///
+/// This implementation is restricted to `Vec<f64>` parameter vectors.
+///
+/// # Example
+///
+/// ```rust
+/// TODO
+/// ```
+///
+/// # References:
+///
+/// [0] Richard H. Byrd, Peihuang Lu, Jorge Nocedal and Ciyou Zhu (1995). A Limited Memory
+/// Algorithm for Bound Constrained Optimization. SIAM J. Sci. Comput. 16(5), 1190-1208.
+///
+/// [1] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LBFGSB {
+    /// bounds
+    bounds: Bounds<Vec<f64>>,
+    /// lower bound (`-inf` where missing)
+    lower: Vec<f64>,
+    /// upper bound (`inf` where missing)
+    upper: Vec<f64>,
+    /// number of stored correction pairs
+    m: usize,
+    /// stored steps `s_k = x_{k+1} - x_k`, oldest first
+    s: VecDeque<Vec<f64>>,
+    /// stored gradient differences `y_k = g_{k+1} - g_k`, oldest first
+    y: VecDeque<Vec<f64>>,
+    /// scaling `theta` of the initial matrix `theta * I`
+    theta: f64,
+    /// tolerance for the infinity norm of the projected gradient
+    pg_tol: f64,
+    /// infinity norm of the projected gradient at the current iterate
+    pg_norm: f64,
+    /// whether the last step did not reduce the cost function significantly
+    no_change: bool,
+    /// line search
+    linesearch: BacktrackingLineSearch<Vec<f64>, ArmijoCondition>,
+}
+
+impl LBFGSB {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `bounds`: lower and upper bounds
+    /// * `m`: number of stored correction pairs (`m >= 1`)
+    pub fn new(bounds: Bounds<Vec<f64>>, m: usize) -> Result<Self, Error> {
+        if m == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "LBFGSB: number of stored correction pairs must be >= 1.".to_string(),
+            }
+            .into());
+        }
+        Ok(LBFGSB {
+            bounds,
+            lower: vec![],
+            upper: vec![],
+            m,
+            s: VecDeque::with_capacity(m + 1),
+            y: VecDeque::with_capacity(m + 1),
+            theta: 1.0,
+            pg_tol: std::f64::EPSILON.sqrt(),
+            pg_norm: INFINITY,
+            no_change: false,
+            linesearch: BacktrackingLineSearch::new(ArmijoCondition::new(1e-4)?).rho(0.5)?,
+        })
+    }
+
+    /// Constructor which takes the bounds from a bounded problem
+    ///
+    /// Parameters:
+    ///
+    /// * `op`: problem whose `bounds()` are used
+    /// * `m`: number of stored correction pairs (`m >= 1`)
+    pub fn from_op<O>(op: &O, m: usize) -> Result<Self, Error>
+    where
+        O: ArgminBoundedOp<Param = Vec<f64>>,
+    {
+        LBFGSB::new(op.bounds()?, m)
+    }
+
+    /// Set the tolerance for the infinity norm of the projected gradient (default:
+    /// `sqrt(EPSILON)`)
+    pub fn pg_tol(mut self, tol: f64) -> Result<Self, Error> {
+        if tol.is_nan() || tol < 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "LBFGSB: pg_tol must be >= 0.".to_string(),
+            }
+            .into());
+        }
+        self.pg_tol = tol;
+        Ok(self)
+    }
+
+    /// Project `x` onto the bounds
+    fn project(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .map(|(x, (l, u))| x.max(*l).min(*u))
+            .collect()
+    }
+
+    /// Infinity norm of the projected gradient `P(x - g) - x`
+    fn projected_gradient_norm(&self, x: &[f64], g: &[f64]) -> f64 {
+        let step: Vec<f64> = x.iter().zip(g.iter()).map(|(x, g)| x - g).collect();
+        self.project(&step)
+            .iter()
+            .zip(x.iter())
+            .map(|(p, x)| (p - x).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Number of variables at their bounds
+    fn active(&self, x: &[f64]) -> usize {
+        x.iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .filter(|(x, (l, u))| (*x - *l).is_zero() || (*x - *u).is_zero())
+            .count()
+    }
+
+    /// Generalized Cauchy point: first local minimizer of the quadratic model along the projected
+    /// steepest descent path `P(x - t g)`. Returns the Cauchy point and `c = W^T (x_cp - x)`.
+    ///
+    /// Algorithm CP in [0].
+    fn cauchy_point(&self, x: &[f64], g: &[f64], mem: &CompactMemory) -> (Vec<f64>, Vec<f64>) {
+        let n = x.len();
+        let theta = mem.theta;
+        // breakpoints
+        let mut t = vec![INFINITY; n];
+        let mut d: Vec<f64> = g.iter().map(|g| -g).collect();
+        for (i, (ti, di)) in t.iter_mut().zip(d.iter_mut()).enumerate() {
+            if g[i] < 0.0 {
+                *ti = (x[i] - self.upper[i]) / g[i];
+            } else if g[i] > 0.0 {
+                *ti = (x[i] - self.lower[i]) / g[i];
+            }
+            if ti.is_zero() {
+                *di = 0.0;
+            }
+        }
+        let mut order: Vec<usize> = (0..n).filter(|&i| t[i] > 0.0 && t[i] < INFINITY).collect();
+        order.sort_by(|&a, &b| t[a].partial_cmp(&t[b]).unwrap());
+
+        let mut xcp = x.to_vec();
+        let mut p = mem.wt_dot(&d);
+        let mut c = vec![0.0; p.len()];
+        let mut f1: f64 = -d.iter().map(|d| d.powi(2)).sum::<f64>();
+        if f1 >= 0.0 {
+            return (xcp, c);
+        }
+        let mut f2 = -theta * f1 - dot(&p, &mem.m_dot(&p));
+        let mut dt_min = if f2 > 0.0 { -f1 / f2 } else { INFINITY };
+        let mut t_old = 0.0;
+
+        // examine the breakpoints in increasing order
+        for &b in order.iter() {
+            let dt = t[b] - t_old;
+            if dt_min < dt {
+                break;
+            }
+            xcp[b] = if d[b] > 0.0 {
+                self.upper[b]
+            } else {
+                self.lower[b]
+            };
+            let zb = xcp[b] - x[b];
+            for (c, p) in c.iter_mut().zip(p.iter()) {
+                *c += dt * p;
+            }
+            let wb = mem.w_row(b);
+            let gb = g[b];
+            f1 += dt * f2 + gb.powi(2) + theta * gb * zb - gb * dot(&wb, &mem.m_dot(&c));
+            f2 += -theta * gb.powi(2)
+                - 2.0 * gb * dot(&wb, &mem.m_dot(&p))
+                - gb.powi(2) * dot(&wb, &mem.m_dot(&wb));
+            for (p, w) in p.iter_mut().zip(wb.iter()) {
+                *p += gb * w;
+            }
+            d[b] = 0.0;
+            dt_min = if f2 > 0.0 { -f1 / f2 } else { INFINITY };
+            t_old = t[b];
+        }
+
+        // all variables are at their bounds or the model is flat along the remaining path
+        if d.iter().all(|d| d.is_zero()) || !dt_min.is_finite() {
+            dt_min = 0.0;
+        }
+        let dt_min = dt_min.max(0.0);
+        t_old += dt_min;
+        for ((xcp, x), d) in xcp.iter_mut().zip(x.iter()).zip(d.iter()) {
+            if !d.is_zero() {
+                *xcp = x + t_old * d;
+            }
+        }
+        for (c, p) in c.iter_mut().zip(p.iter()) {
+            *c += dt_min * p;
+        }
+        (xcp, c)
+    }
+
+    /// Minimizes the quadratic model over the variables which are not at their bounds at the
+    /// Cauchy point `xcp`, starting from `xcp`. The result is truncated to the bounds. Returns the
+    /// result and the number of free variables.
+    ///
+    /// Direct primal method in [0].
+    fn subspace_minimization(
+        &self,
+        x: &[f64],
+        g: &[f64],
+        xcp: &[f64],
+        c: &[f64],
+        mem: &CompactMemory,
+    ) -> Result<(Vec<f64>, usize), Error> {
+        let theta = mem.theta;
+        let free: Vec<usize> = (0..x.len())
+            .filter(|&i| xcp[i] > self.lower[i] && xcp[i] < self.upper[i])
+            .collect();
+        if free.is_empty() {
+            return Ok((xcp.to_vec(), 0));
+        }
+
+        // reduced gradient of the model at the Cauchy point
+        let wmc = mem.w_dot(&mem.m_dot(c), x.len());
+        let rc: Vec<f64> = free
+            .iter()
+            .map(|&i| g[i] + theta * (xcp[i] - x[i]) - wmc[i])
+            .collect();
+
+        // Newton step of the reduced model via the Sherman-Morrison-Woodbury formula
+        let wz: Vec<Vec<f64>> = free.iter().map(|&i| mem.w_row(i)).collect();
+        let k2 = mem.size();
+        let du: Vec<f64> = if k2 > 0 {
+            let wtr: Vec<f64> = (0..k2)
+                .map(|j| wz.iter().zip(rc.iter()).map(|(w, r)| w[j] * r).sum())
+                .collect();
+            let v = mem.m_dot(&wtr);
+            let wtw: Vec<Vec<f64>> = (0..k2)
+                .map(|i| {
+                    (0..k2)
+                        .map(|j| wz.iter().map(|w| w[i] * w[j]).sum())
+                        .collect()
+                })
+                .collect();
+            let n_mat: Vec<Vec<f64>> = (0..k2)
+                .map(|i| {
+                    (0..k2)
+                        .map(|j| {
+                            let mw: f64 = (0..k2).map(|q| mem.m[i][q] * wtw[q][j]).sum();
+                            let delta = if i == j { 1.0 } else { 0.0 };
+                            delta - mw / theta
+                        })
+                        .collect()
+                })
+                .collect();
+            let v = n_mat.solve(&v)?;
+            wz.iter()
+                .zip(rc.iter())
+                .map(|(w, r)| -r / theta - dot(w, &v) / theta.powi(2))
+                .collect()
+        } else {
+            rc.iter().map(|r| -r / theta).collect()
+        };
+
+        // truncate the step to the bounds
+        let mut alpha: f64 = 1.0;
+        for (&i, du) in free.iter().zip(du.iter()) {
+            if *du > 0.0 {
+                alpha = alpha.min((self.upper[i] - xcp[i]) / du);
+            } else if *du < 0.0 {
+                alpha = alpha.min((self.lower[i] - xcp[i]) / du);
+            }
+        }
+        let mut xbar = xcp.to_vec();
+        for (&i, du) in free.iter().zip(du.iter()) {
+            xbar[i] += alpha * du;
+        }
+        Ok((xbar, free.len()))
+    }
+
+    /// Drop all stored correction pairs
+    fn reset(&mut self) {
+        self.s.clear();
+        self.y.clear();
+        self.theta = 1.0;
+    }
+
+    /// Stay at the current iterate after a failed line search. The iteration is repeated with the
+    /// steepest descent model unless this was already used.
+    fn linesearch_failed<O>(
+        &mut self,
+        param: Vec<f64>,
+        cost: f64,
+        grad: Vec<f64>,
+        free: usize,
+    ) -> Result<ArgminIterData<O>, Error>
+    where
+        O: ArgminOp<Param = Vec<f64>, Output = f64>,
+    {
+        if self.s.is_empty() {
+            return Ok(ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .termination_reason(TerminationReason::Aborted));
+        }
+        self.reset();
+        let active = self.active(&param);
+        Ok(ArgminIterData::new()
+            .param(param)
+            .cost(cost)
+            .grad(grad)
+            .kv(make_kv!(
+                "active" => active;
+                "free" => free;
+                "pairs" => 0;
+                "step_length" => 0.0;
+            )))
+    }
+}
+
+/// Compact representation `B = theta * I - W M W^T` of the limited-memory BFGS matrix with
+/// `W = [Y, theta * S]` and `M = [[-D, L^T], [L, theta * S^T S]]^-1`, where `D` is the diagonal and
+/// `L` the strictly lower triangle of `S^T Y`.
+struct CompactMemory<'a> {
+    /// stored steps
+    s: &'a VecDeque<Vec<f64>>,
+    /// stored gradient differences
+    y: &'a VecDeque<Vec<f64>>,
+    /// scaling
+    theta: f64,
+    /// `M`
+    m: Vec<Vec<f64>>,
+}
+
+impl<'a> CompactMemory<'a> {
+    /// Constructor
+    fn new(
+        s: &'a VecDeque<Vec<f64>>,
+        y: &'a VecDeque<Vec<f64>>,
+        theta: f64,
+    ) -> Result<Self, Error> {
+        let k = s.len();
+        let mut mat = vec![vec![0.0; 2 * k]; 2 * k];
+        for (i, si) in s.iter().enumerate() {
+            for (j, (sj, yj)) in s.iter().zip(y.iter()).enumerate() {
+                let sy = dot(si, yj);
+                if i == j {
+                    mat[i][j] = -sy;
+                } else if i > j {
+                    mat[k + i][j] = sy;
+                    mat[j][k + i] = sy;
+                }
+                mat[k + i][k + j] = theta * dot(si, sj);
+            }
+        }
+        // invert column by column
+        let mut m = vec![vec![0.0; 2 * k]; 2 * k];
+        for j in 0..(2 * k) {
+            let e: Vec<f64> = (0..(2 * k))
+                .map(|i| if i == j { 1.0 } else { 0.0 })
+                .collect();
+            let col = mat.solve(&e)?;
+            for (i, v) in col.into_iter().enumerate() {
+                m[i][j] = v;
+            }
+        }
+        Ok(CompactMemory { s, y, theta, m })
+    }
+
+    /// Number of columns of `W`
+    fn size(&self) -> usize {
+        2 * self.s.len()
+    }
+
+    /// Row `i` of `W`
+    fn w_row(&self, i: usize) -> Vec<f64> {
+        self.y
+            .iter()
+            .map(|y| y[i])
+            .chain(self.s.iter().map(|s| self.theta * s[i]))
+            .collect()
+    }
+
+    /// `W^T v`
+    fn wt_dot(&self, v: &[f64]) -> Vec<f64> {
+        self.y
+            .iter()
+            .map(|y| dot(y, v))
+            .chain(self.s.iter().map(|s| self.theta * dot(s, v)))
+            .collect()
+    }
+
+    /// `W v`
+    fn w_dot(&self, v: &[f64], n: usize) -> Vec<f64> {
+        let k = self.s.len();
+        (0..n)
+            .map(|i| {
+                (0..k)
+                    .map(|j| v[j] * self.y[j][i] + v[k + j] * self.theta * self.s[j][i])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// `M v`
+    fn m_dot(&self, v: &[f64]) -> Vec<f64> {
+        self.m.iter().map(|row| dot(row, v)).collect()
+    }
+}
+
+/// Dot product of two slices
+#[cfg(not(feature = "simd"))]
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+}
+
+/// Dot product of two slices
+#[cfg(feature = "simd")]
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    crate::math::simd::dot(a, b)
+}
+
+impl<O> Solver<O> for LBFGSB
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+{
+    fn init(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<Option<ArgminIterData<O>>, Error> {
+        let param = state.get_param();
+        self.bounds.check_dims(&param)?;
+        let n = param.len();
+        self.lower = self
+            .bounds
+            .lower()
+            .cloned()
+            .unwrap_or_else(|| vec![-INFINITY; n]);
+        self.upper = self
+            .bounds
+            .upper()
+            .cloned()
+            .unwrap_or_else(|| vec![INFINITY; n]);
+        self.reset();
+        self.no_change = false;
+
+        let param = self.project(&param);
+        let cost = op.apply(&param)?;
+        let grad = op.gradient(&param)?;
+        self.pg_norm = self.projected_gradient_norm(&param, &grad);
+        let active = self.active(&param);
+        Ok(Some(
+            ArgminIterData::new()
+                .param(param)
+                .cost(cost)
+                .grad(grad)
+                .kv(make_kv!("active" => active;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        op: &mut OpWrapper<O>,
+        state: &IterState<O>,
+    ) -> Result<ArgminIterData<O>, Error> {
+        let param = state.get_param();
+        let cost = state.get_cost();
+        let grad = if let Some(grad) = state.get_grad() {
+            grad
+        } else {
+            op.gradient(&param)?
+        };
+
+        // If the compact representation cannot be formed, continue with the steepest descent
+        // model.
+        if CompactMemory::new(&self.s, &self.y, self.theta).is_err() {
+            self.reset();
+        }
+        let (xcp, xbar, free) = {
+            let mem = CompactMemory::new(&self.s, &self.y, self.theta)?;
+            let (xcp, c) = self.cauchy_point(&param, &grad, &mem);
+            let (xbar, free) = self.subspace_minimization(&param, &grad, &xcp, &c, &mem)?;
+            (xcp, xbar, free)
+        };
+        let mut direction: Vec<f64> = xbar.iter().zip(param.iter()).map(|(a, b)| a - b).collect();
+        if dot(&grad, &direction) >= 0.0 {
+            // should not happen in exact arithmetic; the Cauchy point always gives descent
+            direction = xcp.iter().zip(param.iter()).map(|(a, b)| a - b).collect();
+        }
+        let direction_norm = dot(&direction, &direction).sqrt();
+
+        // Without curvature information the first step is scaled to unit length. All steps are
+        // at most 1, which keeps the trial points within the bounds.
+        let init_alpha = if self.s.is_empty() && direction_norm > 1.0 {
+            1.0 / direction_norm
+        } else {
+            1.0
+        };
+        let linesearch_result = if direction_norm > 0.0 {
+            self.linesearch.set_search_direction(direction);
+            self.linesearch.set_init_alpha(init_alpha)?;
+            let res = Executor::new(
+                OpWrapper::new_from_op(&op),
+                self.linesearch.clone(),
+                param.clone(),
+            )
+            .grad(grad.clone())
+            .cost(cost)
+            .run_fast()?;
+            op.consume_op(res.operator);
+            Some((res.param, res.cost, res.termination_reason))
+        } else {
+            None
+        };
+
+        let (new_param, new_cost) = match linesearch_result {
+            Some((p, c, reason)) if !LineSearchFailure::failed(&reason) => (p, c),
+            _ => return self.linesearch_failed(param, cost, grad, free),
+        };
+
+        // guard against rounding errors
+        let projected = self.project(&new_param);
+        let (new_param, new_cost) = if projected == new_param {
+            (new_param, new_cost)
+        } else {
+            let cost = op.apply(&projected)?;
+            (projected, cost)
+        };
+        let new_grad = op.gradient(&new_param)?;
+
+        let s: Vec<f64> = new_param
+            .iter()
+            .zip(param.iter())
+            .map(|(a, b)| a - b)
+            .collect();
+        let y: Vec<f64> = new_grad
+            .iter()
+            .zip(grad.iter())
+            .map(|(a, b)| a - b)
+            .collect();
+        let sy = dot(&s, &y);
+        let yy = dot(&y, &y);
+        let step_length = dot(&s, &s).sqrt() / direction_norm;
+        if sy > std::f64::EPSILON * yy {
+            self.s.push_back(s);
+            self.y.push_back(y);
+            if self.s.len() > self.m {
+                self.s.pop_front();
+                self.y.pop_front();
+            }
+            self.theta = yy / sy;
+        }
+
+        self.pg_norm = self.projected_gradient_norm(&new_param, &new_grad);
+        self.no_change =
+            (cost - new_cost) <= std::f64::EPSILON * cost.abs().max(new_cost.abs()).max(1.0);
+
+        let active = self.active(&new_param);
+        Ok(ArgminIterData::new()
+            .param(new_param)
+            .cost(new_cost)
+            .grad(new_grad)
+            .kv(make_kv!(
+                "active" => active;
+                "free" => free;
+                "pairs" => self.s.len();
+                "step_length" => step_length;
+            )))
+    }
+
+    fn terminate(&mut self, _state: &IterState<O>) -> TerminationReason {
+        if self.pg_norm <= self.pg_tol {
+            return TerminationReason::TargetPrecisionReached;
+        }
+        if self.no_change {
+            return TerminationReason::NoChangeInCost;
+        }
+        TerminationReason::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::testfunctions::problem::TestProblem;
+
+    send_sync_test!(lbfgsb, LBFGSB);
+
+    /// `f(x) = 1/2 x^T A x - b^T x` with `A = [[4, 1, 0], [1, 3, 1], [0, 1, 2]]` and
+    /// `b = [10, -8, 1]`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Quadratic {}
+
+    impl Quadratic {
+        fn a_dot(x: &[f64]) -> Vec<f64> {
+            vec![
+                4.0 * x[0] + x[1],
+                x[0] + 3.0 * x[1] + x[2],
+                x[1] + 2.0 * x[2],
+            ]
+        }
+    }
+
+    impl ArgminOp for Quadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(0.5 * dot(x, &Quadratic::a_dot(x)) - dot(&[10.0, -8.0, 1.0], x))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(Quadratic::a_dot(x)
+                .iter()
+                .zip([10.0, -8.0, 1.0].iter())
+                .map(|(ax, b)| ax - b)
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_lbfgsb_invalid() {
+        assert!(LBFGSB::new(Bounds::unbounded(), 0).is_err());
+        assert!(LBFGSB::new(Bounds::unbounded(), 5)
+            .unwrap()
+            .pg_tol(-1.0)
+            .is_err());
+        let bounds = Bounds::new(vec![0.0], vec![1.0]).unwrap();
+        let res = Executor::new(Quadratic {}, LBFGSB::new(bounds, 5).unwrap(), vec![1.0; 3])
+            .max_iters(10)
+            .run_fast();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_lbfgsb_quadratic_kkt() {
+        // KKT point: x_0 at its upper bound with g_0 = -2 < 0, x_1 at its lower bound with
+        // g_1 = 10.5 > 0 and x_2 free with g_2 = 0.
+        let bounds = Bounds::new(vec![0.0; 3], vec![2.0; 3]).unwrap();
+        let res = Executor::new(Quadratic {}, LBFGSB::new(bounds, 5).unwrap(), vec![1.0; 3])
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::TargetPrecisionReached
+        );
+        for (x, expected) in res.param.iter().zip([2.0, 0.0, 0.5].iter()) {
+            assert!((x - expected).abs() < 1e-8, "{:?}", res.param);
+        }
+        assert!((res.cost + 12.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_lbfgsb_rosenbrock_bounded() {
+        // The unconstrained minimizer (1, 1) is excluded; for x_0 <= 0.5 the cost is at least
+        // (1 - x_0)^2 >= 0.25 with equality at (0.5, 0.25).
+        let bounds = Bounds::new(vec![-2.0, -1.0], vec![0.5, 2.0]).unwrap();
+        let res = Executor::new(
+            TestProblem::rosenbrock(2),
+            LBFGSB::new(bounds, 10).unwrap(),
+            vec![-1.2, 1.0],
+        )
+        .max_iters(200)
+        .run_fast()
+        .unwrap();
+        assert!((res.param[0] - 0.5).abs() < 1e-10, "{:?}", res.param);
+        assert!((res.param[1] - 0.25).abs() < 1e-6, "{:?}", res.param);
+        assert!((res.cost - 0.25).abs() < 1e-10);
+    }
+
+    /// `Quadratic` restricted to `[0, 2]^3`
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct BoundedQuadratic {}
+
+    impl ArgminOp for BoundedQuadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Quadratic {}.apply(x)
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Quadratic {}.gradient(x)
+        }
+    }
+
+    impl ArgminBoundedOp for BoundedQuadratic {
+        fn lower_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![0.0; 3])
+        }
+
+        fn upper_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![2.0; 3])
+        }
+    }
+
+    #[test]
+    fn test_lbfgsb_from_op() {
+        // same KKT point as in `test_lbfgsb_quadratic_kkt`, with the bounds taken from the problem
+        let op = BoundedQuadratic {};
+        let solver = LBFGSB::from_op(&op, 5).unwrap();
+        let res = Executor::new(op, solver, vec![1.0; 3])
+            .max_iters(100)
+            .run_fast()
+            .unwrap();
+        for (x, expected) in res.param.iter().zip([2.0, 0.0, 0.5].iter()) {
+            assert!((x - expected).abs() < 1e-8, "{:?}", res.param);
+        }
+    }
+
+    #[test]
+    fn test_lbfgsb_unbounded_rosenbrock() {
+        let bounds = Bounds::from_options(None, None).unwrap();
+        let res = Executor::new(
+            TestProblem::rosenbrock(2),
+            LBFGSB::new(bounds, 10).unwrap(),
+            vec![-1.2, 1.0],
+        )
+        .max_iters(200)
+        .run_fast()
+        .unwrap();
+        assert!(res.param.iter().all(|x| (x - 1.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_lbfgsb_feasible_iterates() {
+        let lower = vec![-2.0, -1.0];
+        let upper = vec![0.5, 2.0];
+        let bounds = Bounds::new(lower.clone(), upper.clone()).unwrap();
+        let mut solver = LBFGSB::new(bounds, 10).unwrap();
+        let op = TestProblem::rosenbrock(2);
+        let mut op = OpWrapper::new(&op);
+        // the infeasible initial parameter vector is projected onto the bounds
+        let mut state = IterState::new(vec![-3.0, 1.0]);
+        let data = solver.init(&mut op, &state).unwrap().unwrap();
+        assert_eq!(data.get_param().unwrap(), vec![-2.0, 1.0]);
+        let mut iterates = vec![data];
+        let mut active = 0;
+        for _ in 0..200 {
+            let data = iterates.last().unwrap();
+            state.param(data.get_param().unwrap());
+            state.cost(data.get_cost().unwrap());
+            state.grad(data.get_grad().unwrap());
+            if solver.terminate(&state) != TerminationReason::NotTerminated {
+                break;
+            }
+            let data = solver.next_iter(&mut op, &state).unwrap();
+            active = data
+                .get_kv()
+                .kv
+                .iter()
+                .find(|(k, _)| *k == "active")
+                .map(|(_, v)| v.parse().unwrap())
+                .unwrap();
+            iterates.push(data);
+        }
+        for data in iterates.iter() {
+            let x = data.get_param().unwrap();
+            for ((x, l), u) in x.iter().zip(lower.iter()).zip(upper.iter()) {
+                assert!((*l..=*u).contains(x));
+            }
+        }
+        // only x_0 is at its bound at the solution
+        assert_eq!(active, 1);
+        assert!(iterates.len() < 200);
+    }
+}
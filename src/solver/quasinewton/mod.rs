@@ -9,6 +9,8 @@
 //!
 //! [BFGS](BFGS/struct.BFGS.html)
 //!
+//! [L-BFGS-B](lbfgsb/struct.LBFGSB.html)
+//!
 //! # References:
 //!
 //! [0] Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
@@ -16,8 +18,10 @@
 
 pub mod bfgs;
 pub mod dfp;
+pub mod lbfgsb;
 pub mod sr1;
 
 pub use self::bfgs::*;
 pub use self::dfp::*;
+pub use self::lbfgsb::*;
 pub use self::sr1::*;
@@ -15,10 +15,14 @@
 //! Science 13 May 1983, Vol. 220, Issue 4598, pp. 671-680
 //! DOI: 10.1126/science.220.4598.671  
 
+use crate::operator::Bounds;
 use crate::prelude::*;
 use rand::prelude::*;
 use rand_xorshift::XorShiftRng;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 /// Temperature functions for Simulated Annealing.
 ///
@@ -30,7 +34,7 @@ use serde::{Deserialize, Serialize};
 /// * `SATempFunc::Exponential`: `t_i = t_init * 0.95^i`
 // /// * `SATempFunc::Custom`: User provided temperature update function which must have the function
 // ///   signature `&Fn(init_temp: f64, iteration_number: u64) -> f64`
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
 pub enum SATempFunc {
     /// `t_i = t_init / i`
     TemperatureFast,
@@ -49,8 +53,104 @@ impl std::default::Default for SATempFunc {
     }
 }
 
+/// Outcome of a single move
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+enum SAMove {
+    /// Accepted move to a better parameter vector
+    AcceptedBetter,
+    /// Accepted move to a parameter vector which is not better
+    AcceptedWorse,
+    /// Rejected move
+    Rejected,
+}
+
+/// Acceptance statistics over a sliding window of the most recent moves
+#[derive(Clone, Serialize, Deserialize, Debug)]
+struct SAAcceptance {
+    /// length of the window
+    window: usize,
+    /// moves within the window
+    moves: VecDeque<SAMove>,
+    /// number of accepted moves to better parameter vectors within the window
+    accepted_better: u64,
+    /// number of accepted moves to parameter vectors which are not better within the window
+    accepted_worse: u64,
+    /// number of rejected moves within the window
+    rejected: u64,
+}
+
+impl SAAcceptance {
+    /// Constructor
+    fn new(window: usize) -> Self {
+        SAAcceptance {
+            window,
+            moves: VecDeque::with_capacity(window),
+            accepted_better: 0,
+            accepted_worse: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Add a move to the window, dropping the oldest one if the window is full
+    fn push(&mut self, m: SAMove) {
+        if self.moves.len() == self.window {
+            match self.moves.pop_front() {
+                Some(SAMove::AcceptedBetter) => self.accepted_better -= 1,
+                Some(SAMove::AcceptedWorse) => self.accepted_worse -= 1,
+                Some(SAMove::Rejected) => self.rejected -= 1,
+                None => {}
+            }
+        }
+        match m {
+            SAMove::AcceptedBetter => self.accepted_better += 1,
+            SAMove::AcceptedWorse => self.accepted_worse += 1,
+            SAMove::Rejected => self.rejected += 1,
+        }
+        self.moves.push_back(m);
+    }
+
+    /// Whether the window is full
+    fn is_full(&self) -> bool {
+        self.moves.len() == self.window
+    }
+
+    /// Fraction of accepted moves within the window (1 if the window is empty)
+    fn ratio(&self) -> f64 {
+        if self.moves.is_empty() {
+            1.0
+        } else {
+            (self.accepted_better + self.accepted_worse) as f64 / self.moves.len() as f64
+        }
+    }
+}
+
 /// Simulated Annealing
 ///
+/// The numbers of accepted moves to better parameter vectors, accepted moves to parameter vectors
+/// which are not better and rejected moves within a sliding window of the most recent
+/// `acceptance_window` iterations are reported as `acc_be`, `acc_wo` and `rej` via the KV of each
+/// iteration, together with the resulting acceptance ratio `acc_ratio`. With `freeze`, the
+/// optimization stops once the system has frozen, i.e. once the acceptance ratio over a full
+/// window has dropped below a threshold while the temperature is below a floor. Since no further
+/// improvement is to be expected, this is reported as `TargetToleranceReached` (unlike the stall
+/// limits, which report `AcceptedStallIterExceeded` and `BestStallIterExceeded`) and as `frozen`
+/// via the KV of each iteration.
+///
+/// The current parameter vector wanders by design. The solver therefore keeps track of the best
+/// parameter vector visited so far (reported as `best_c` via the KV of each iteration), which is
+/// available via `best_param` and `best_cost`. The `Executor` reports the best parameter vector
+/// visited as well; the last state of the chain, for instance to continue it, is available via
+/// `Stepper::state`.
+/// The iteration in which the best parameter vector was found is available via `best_iter` and
+/// reported as `best_iter` via the KV of each iteration; `best_time` is the time it took to find
+/// it. A best parameter vector found early in a long run indicates that the iteration budget is
+/// too large (or that the temperature drops too quickly). `stall_best` measures stalling relative
+/// to `best_iter`.
+///
+/// Neighbours are proposed by `ArgminOp::modify`. With bounds (set with `bounds` or taken from a
+/// bounded problem with `from_op`), proposals outside of them are treated like infeasible
+/// parameter vectors: they are rejected and counted in `infeas`.
+///
 /// # Example
 ///
 /// ```rust
@@ -64,7 +164,7 @@ impl std::default::Default for SATempFunc {
 /// Science 13 May 1983, Vol. 220, Issue 4598, pp. 671-680
 /// DOI: 10.1126/science.220.4598.671  
 #[derive(Serialize, Deserialize)]
-pub struct SimulatedAnnealing {
+pub struct SimulatedAnnealing<P> {
     /// Initial temperature
     init_temp: f64,
     /// which temperature function?
@@ -76,9 +176,7 @@ pub struct SimulatedAnnealing {
     stall_iter_accepted: u64,
     /// Stop if stall_iter_accepted exceedes this number
     stall_iter_accepted_limit: u64,
-    /// Iterations since the last best solution was found
-    stall_iter_best: u64,
-    /// Stop if stall_iter_best exceedes this number
+    /// Stop if the number of iterations since the last best solution was found exceedes this number
     stall_iter_best_limit: u64,
     /// Reanneal after this number of iterations is reached
     reanneal_fixed: u64,
@@ -96,9 +194,60 @@ pub struct SimulatedAnnealing {
     cur_temp: f64,
     /// random number generator
     rng: XorShiftRng,
+    /// Number of infeasible parameter vectors encountered
+    infeasible_count: u64,
+    /// Proposals outside of these bounds are rejected
+    bounds: Option<Bounds<P>>,
+    /// Acceptance statistics over the most recent moves
+    acceptance: SAAcceptance,
+    /// Stop if the acceptance ratio drops below this value ...
+    freeze_ratio: f64,
+    /// ... while the temperature is below this value
+    freeze_temp: f64,
+    /// best parameter vector visited so far
+    best_param: Option<P>,
+    /// cost function value of `best_param`
+    best_cost: f64,
+    /// number of iterations performed when `best_param` was found (0 for the initial parameter
+    /// vector)
+    best_iter: u64,
+    /// time since the first iteration when `best_param` was found (not serialized since it is
+    /// relative to `start`)
+    #[serde(skip)]
+    best_time: Option<Duration>,
+    /// number of iterations performed so far
+    iter: u64,
+    /// start of the first iteration
+    #[serde(skip)]
+    start: Option<Instant>,
+}
+
+/// Current time
+#[cfg(not(feature = "wasm"))]
+fn now() -> Option<Instant> {
+    Some(Instant::now())
 }
 
-impl SimulatedAnnealing {
+/// `Instant::now` panics on `wasm32-unknown-unknown`.
+#[cfg(feature = "wasm")]
+fn now() -> Option<Instant> {
+    None
+}
+
+/// Random number generator seeded from the system's entropy source
+#[cfg(not(feature = "wasm"))]
+fn default_rng() -> XorShiftRng {
+    XorShiftRng::from_entropy()
+}
+
+/// Random number generator with a fixed seed, since there is no entropy source on
+/// `wasm32-unknown-unknown`
+#[cfg(feature = "wasm")]
+fn default_rng() -> XorShiftRng {
+    XorShiftRng::seed_from_u64(0)
+}
+
+impl<P> SimulatedAnnealing<P> {
     /// Constructor
     ///
     /// Parameter:
@@ -117,7 +266,6 @@ impl SimulatedAnnealing {
                 temp_iter: 0u64,
                 stall_iter_accepted: 0u64,
                 stall_iter_accepted_limit: std::u64::MAX,
-                stall_iter_best: 0u64,
                 stall_iter_best_limit: std::u64::MAX,
                 reanneal_fixed: std::u64::MAX,
                 reanneal_iter_fixed: 0,
@@ -126,11 +274,50 @@ impl SimulatedAnnealing {
                 reanneal_best: std::u64::MAX,
                 reanneal_iter_best: 0,
                 cur_temp: init_temp,
-                rng: XorShiftRng::from_entropy(),
+                rng: default_rng(),
+                infeasible_count: 0,
+                bounds: None,
+                acceptance: SAAcceptance::new(100),
+                freeze_ratio: 0.0,
+                freeze_temp: 0.0,
+                best_param: None,
+                best_cost: std::f64::INFINITY,
+                best_iter: 0,
+                best_time: None,
+                iter: 0,
+                start: None,
             })
         }
     }
 
+    /// Constructor which takes the bounds from a bounded problem (see `bounds`)
+    ///
+    /// Parameter:
+    ///
+    /// * `op`: problem whose `bounds()` are used
+    /// * `init_temp`: initial temperature
+    pub fn from_op<O>(op: &O, init_temp: f64) -> Result<Self, Error>
+    where
+        O: ArgminBoundedOp<Param = P>,
+        P: ArgminElementwiseCmp,
+    {
+        Ok(SimulatedAnnealing::new(init_temp)?.bounds(op.bounds()?))
+    }
+
+    /// Reject proposed parameter vectors outside of `bounds`
+    pub fn bounds(mut self, bounds: Bounds<P>) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Seed the random number generator used in the acceptance function (by default it is seeded
+    /// from the system's entropy source, or with a fixed seed with the `wasm` feature). Together with a seeded random number generator in
+    /// `ArgminOp::modify` this makes runs reproducible.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = XorShiftRng::seed_from_u64(seed);
+        self
+    }
+
     /// Set temperature function to one of the options in `SATempFunc`.
     pub fn temp_func(mut self, temperature_func: SATempFunc) -> Self {
         self.temp_func = temperature_func;
@@ -149,6 +336,74 @@ impl SimulatedAnnealing {
         self
     }
 
+    /// Set the number of most recent iterations the acceptance statistics are computed over
+    /// (default: 100)
+    pub fn acceptance_window(mut self, window: usize) -> Result<Self, Error> {
+        if window == 0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "SimulatedAnnealing: acceptance window must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.acceptance = SAAcceptance::new(window);
+        Ok(self)
+    }
+
+    /// The optimization stops once the acceptance ratio over a full acceptance window is below
+    /// `ratio` while the temperature is below `temp` (disabled by default)
+    pub fn freeze(mut self, ratio: f64, temp: f64) -> Result<Self, Error> {
+        if ratio.is_nan() || ratio < 0.0 || ratio > 1.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "SimulatedAnnealing: freeze ratio must be in [0, 1].".to_string(),
+            }
+            .into());
+        }
+        if temp.is_nan() || temp <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "SimulatedAnnealing: freeze temperature must be > 0.".to_string(),
+            }
+            .into());
+        }
+        self.freeze_ratio = ratio;
+        self.freeze_temp = temp;
+        Ok(self)
+    }
+
+    /// Whether the system is frozen
+    fn frozen(&self) -> bool {
+        self.acceptance.is_full()
+            && self.cur_temp < self.freeze_temp
+            && self.acceptance.ratio() < self.freeze_ratio
+    }
+
+    /// Best parameter vector visited so far
+    pub fn best_param(&self) -> Option<&P> {
+        self.best_param.as_ref()
+    }
+
+    /// Cost function value of the best parameter vector visited so far
+    pub fn best_cost(&self) -> f64 {
+        self.best_cost
+    }
+
+    /// Number of iterations performed when the best parameter vector visited so far was found. The
+    /// initial parameter vector was found after 0 iterations, a parameter vector accepted in the
+    /// first iteration after 1 iteration, and so on.
+    pub fn best_iter(&self) -> u64 {
+        self.best_iter
+    }
+
+    /// Time between the start of the first iteration and finding the best parameter vector visited
+    /// so far (`None` before the first iteration and with the `wasm` feature)
+    pub fn best_time(&self) -> Option<Duration> {
+        self.best_time
+    }
+
+    /// Number of iterations since the best parameter vector visited so far was found
+    fn stall_iter_best(&self) -> u64 {
+        self.iter - self.best_iter
+    }
+
     /// Start reannealing after `iter` iterations
     pub fn reannealing_fixed(mut self, iter: u64) -> Self {
         self.reanneal_fixed = iter;
@@ -210,12 +465,6 @@ impl SimulatedAnnealing {
             self.reanneal_iter_accepted + 1
         };
 
-        self.stall_iter_best = if new_best {
-            0
-        } else {
-            self.stall_iter_best + 1
-        };
-
         self.reanneal_iter_best = if new_best {
             0
         } else {
@@ -224,9 +473,17 @@ impl SimulatedAnnealing {
     }
 }
 
-impl<O> Solver<O> for SimulatedAnnealing
+#[cfg(feature = "replay")]
+impl<P> crate::utils::ArgminSeed for SimulatedAnnealing<P> {
+    fn reseed(&mut self, seed: u64) {
+        self.rng = XorShiftRng::seed_from_u64(seed);
+    }
+}
+
+impl<O, P> Solver<O> for SimulatedAnnealing<P>
 where
-    O: ArgminOp<Output = f64>,
+    O: ArgminOp<Param = P, Output = f64>,
+    P: Clone + Serialize + DeserializeOwned + ArgminElementwiseCmp,
 {
     /// Perform one iteration of SA algorithm
     fn next_iter(
@@ -240,12 +497,40 @@ where
 
         let prev_param = state.get_param();
         let prev_cost = state.get_cost();
+        if self.start.is_none() {
+            self.start = now();
+        }
+        if self.best_param.is_none() && prev_cost.is_finite() {
+            self.best_param = Some(prev_param.clone());
+            self.best_cost = prev_cost;
+            self.best_iter = state.get_iter();
+            self.best_time = self.start.map(|start| start.elapsed());
+        }
+        self.iter = state.get_iter() + 1;
 
         // Make a move
         let new_param = op.modify(&prev_param, self.cur_temp)?;
 
-        // Evaluate cost function with new parameter vector
-        let new_cost = op.apply(&new_param)?;
+        // Evaluate cost function with new parameter vector. Infeasible parameter vectors and
+        // parameter vectors outside of the bounds are treated as having infinite cost and are
+        // therefore never accepted.
+        let in_bounds = self
+            .bounds
+            .as_ref()
+            .map(|bounds| bounds.contains(&new_param))
+            .unwrap_or(true);
+        let new_cost = if in_bounds {
+            op.try_apply(&new_param)?
+        } else {
+            None
+        };
+        let new_cost = match new_cost {
+            Some(cost) => cost,
+            None => {
+                self.infeasible_count += 1;
+                std::f64::INFINITY
+            }
+        };
 
         // Acceptance function
         //
@@ -262,8 +547,30 @@ where
         let accepted = (new_cost < state.get_prev_cost())
             || (1.0 / (1.0 + ((new_cost - state.get_prev_cost()) / self.cur_temp).exp()) > prob);
 
+        self.acceptance.push(if !accepted {
+            SAMove::Rejected
+        } else if new_cost < prev_cost {
+            SAMove::AcceptedBetter
+        } else {
+            SAMove::AcceptedWorse
+        });
+
+        // Keep track of the best parameter vector visited so far
+        let new_best = accepted && new_cost <= self.best_cost;
+        let (param, cost) = if accepted {
+            (new_param, new_cost)
+        } else {
+            (prev_param, prev_cost)
+        };
+        if new_best {
+            self.best_param = Some(param.clone());
+            self.best_cost = cost;
+            self.best_iter = self.iter;
+            self.best_time = self.start.map(|start| start.elapsed());
+        }
+
         // Update stall iter variables
-        self.update_stall_and_reanneal_iter(accepted, new_cost <= state.get_best_cost());
+        self.update_stall_and_reanneal_iter(accepted, new_best);
 
         let (r_fixed, r_accepted, r_best) = self.reanneal();
 
@@ -274,16 +581,13 @@ where
 
         self.update_temperature();
 
-        Ok(if accepted {
-            ArgminIterData::new().param(new_param).cost(new_cost)
-        } else {
-            ArgminIterData::new().param(prev_param).cost(prev_cost)
-        }
-        .kv(make_kv!(
+        Ok(ArgminIterData::new().param(param).cost(cost).kv(make_kv!(
             "t" => self.cur_temp;
-            "new_be" => new_cost <= state.get_best_cost();
+            "new_be" => new_best;
+            "best_c" => self.best_cost;
+            "best_iter" => self.best_iter;
             "acc" => accepted;
-            "st_i_be" => self.stall_iter_best;
+            "st_i_be" => self.stall_iter_best();
             "st_i_ac" => self.stall_iter_accepted;
             "ra_i_fi" => self.reanneal_iter_fixed;
             "ra_i_be" => self.reanneal_iter_best;
@@ -291,6 +595,12 @@ where
             "ra_fi" => r_fixed;
             "ra_be" => r_best;
             "ra_ac" => r_accepted;
+            "infeas" => self.infeasible_count;
+            "acc_be" => self.acceptance.accepted_better;
+            "acc_wo" => self.acceptance.accepted_worse;
+            "rej" => self.acceptance.rejected;
+            "acc_ratio" => self.acceptance.ratio();
+            "frozen" => self.frozen();
         )))
     }
 
@@ -298,9 +608,12 @@ where
         if self.stall_iter_accepted > self.stall_iter_accepted_limit {
             return TerminationReason::AcceptedStallIterExceeded;
         }
-        if self.stall_iter_best > self.stall_iter_best_limit {
+        if self.stall_iter_best() > self.stall_iter_best_limit {
             return TerminationReason::BestStallIterExceeded;
         }
+        if self.frozen() {
+            return TerminationReason::TargetToleranceReached;
+        }
         TerminationReason::NotTerminated
     }
 }
@@ -316,8 +629,490 @@ where
 mod tests {
     use super::*;
     use crate::send_sync_test;
+    use crate::stepper::Stepper;
+    use crate::testfunctions::sphere;
+    use std::sync::{Arc, Mutex};
 
     type Operator = MinimalNoOperator;
 
     send_sync_test!(sa, SimulatedAnnealing<Operator>);
+
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Sphere {
+        /// random number generator used in `modify`
+        rng: Arc<Mutex<XorShiftRng>>,
+    }
+
+    impl Sphere {
+        fn new(seed: u64) -> Self {
+            Sphere {
+                rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(seed))),
+            }
+        }
+    }
+
+    impl ArgminOp for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere(p))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            p.modify(
+                &mut *rng,
+                Some(&vec![-5.0; 3]),
+                Some(&vec![5.0; 3]),
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+        }
+    }
+
+    #[test]
+    fn test_sa_reproducible() {
+        let run = |seed: u64| {
+            let solver = SimulatedAnnealing::new(10.0).unwrap().seed(seed);
+            Executor::new(Sphere::new(seed), solver, vec![3.0, -2.0, 4.0])
+                .max_iters(200)
+                .run_fast()
+                .unwrap()
+        };
+        let (a, b) = (run(1), run(1));
+        assert_eq!(a.param, b.param);
+        assert!((a.cost - b.cost).abs() < std::f64::EPSILON);
+        assert_ne!(run(1).param, run(2).param);
+    }
+
+    /// Quadratic with its minimum at `(1e-6, 2e-6, 3e-6)` within the box `[0, 1e-5]^3`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct TinyQuadratic {
+        rng: Arc<Mutex<XorShiftRng>>,
+        perturbation: Perturbation,
+    }
+
+    impl ArgminOp for TinyQuadratic {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p.iter()
+                .enumerate()
+                .map(|(i, x)| (1e6 * x - (i + 1) as f64).powi(2))
+                .sum())
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            p.modify(
+                &mut *rng,
+                Some(&vec![0.0; 3]),
+                Some(&vec![1e-5; 3]),
+                self.perturbation,
+                Components::default(),
+                &Unconstrained,
+            )
+        }
+    }
+
+    #[test]
+    fn test_sa_relative_perturbation() {
+        let run = |perturbation: Perturbation| {
+            let op = TinyQuadratic {
+                rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(1))),
+                perturbation,
+            };
+            let solver = SimulatedAnnealing::new(0.1).unwrap().seed(1);
+            Executor::new(op, solver, vec![5e-6; 3])
+                .max_iters(3000)
+                .run_fast()
+                .unwrap()
+                .cost
+        };
+        // Steps of size O(1) are always clamped to the bounds, therefore only the corners of the
+        // box and the initial values are reachable. The best of these has cost 9.
+        assert!(run(Perturbation::default()) >= 9.0 - 1e-9);
+        assert!(run(Perturbation::GaussianRel(0.1)) < 0.1);
+    }
+
+    /// Sphere function in 100 dimensions which perturbs `components` per call of `modify`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct HighDimSphere {
+        rng: Arc<Mutex<XorShiftRng>>,
+        components: Components,
+    }
+
+    impl ArgminOp for HighDimSphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere(p))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            p.modify(
+                &mut *rng,
+                Some(&vec![-5.0; 100]),
+                Some(&vec![5.0; 100]),
+                Perturbation::Gaussian(0.2),
+                self.components,
+                &Unconstrained,
+            )
+        }
+    }
+
+    #[test]
+    fn test_sa_multiple_components() {
+        let run = |components: Components| {
+            let op = HighDimSphere {
+                rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(3))),
+                components,
+            };
+            let solver = SimulatedAnnealing::new(0.01).unwrap().seed(3);
+            Executor::new(op, solver, vec![4.0; 100])
+                .max_iters(1000)
+                .run_fast()
+                .unwrap()
+                .cost
+        };
+        // With one component per step each component is moved only about 10 times within the
+        // budget, with ten components about 100 times.
+        let single = run(Components::Count(1));
+        let multiple = run(Components::Count(10));
+        assert!(single > 800.0);
+        assert!(multiple < 0.5 * single);
+    }
+
+    #[test]
+    fn test_sa_acceptance_window() {
+        let mut acceptance = SAAcceptance::new(3);
+        assert!((acceptance.ratio() - 1.0).abs() < std::f64::EPSILON);
+        acceptance.push(SAMove::AcceptedBetter);
+        acceptance.push(SAMove::Rejected);
+        assert!(!acceptance.is_full());
+        assert!((acceptance.ratio() - 0.5).abs() < std::f64::EPSILON);
+        acceptance.push(SAMove::AcceptedWorse);
+        acceptance.push(SAMove::Rejected);
+        assert!(acceptance.is_full());
+        assert_eq!(acceptance.accepted_better, 0);
+        assert_eq!(acceptance.accepted_worse, 1);
+        assert_eq!(acceptance.rejected, 2);
+        assert!((acceptance.ratio() - 1.0 / 3.0).abs() < std::f64::EPSILON);
+
+        assert!(SimulatedAnnealing::<Vec<f64>>::new(1.0)
+            .unwrap()
+            .acceptance_window(0)
+            .is_err());
+        assert!(SimulatedAnnealing::new(1.0)
+            .unwrap()
+            .freeze(1.5, 1.0)
+            .is_err());
+        assert!(SimulatedAnnealing::new(1.0)
+            .unwrap()
+            .freeze(0.1, 0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_sa_acceptance_statistics() {
+        let op = Sphere::new(4);
+        let mut solver = SimulatedAnnealing::new(10.0)
+            .unwrap()
+            .seed(4)
+            .acceptance_window(50)
+            .unwrap();
+        let mut op = OpWrapper::new(&op);
+        let state = IterState::new(vec![3.0, -2.0, 4.0]);
+        let count = |kv: &ArgminKV, key: &str| -> u64 {
+            kv.kv
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.parse().unwrap())
+                .unwrap()
+        };
+        for i in 1..=120u64 {
+            let kv = solver.next_iter(&mut op, &state).unwrap().get_kv();
+            let total = count(&kv, "acc_be") + count(&kv, "acc_wo") + count(&kv, "rej");
+            assert_eq!(total, i.min(50));
+        }
+    }
+
+    #[test]
+    fn test_sa_freeze() {
+        let run = |max_iters: u64| {
+            let solver = SimulatedAnnealing::new(10.0)
+                .unwrap()
+                .seed(5)
+                .freeze(0.1, 1e-3)
+                .unwrap();
+            Executor::new(Sphere::new(5), solver, vec![3.0, -2.0, 4.0])
+                .max_iters(max_iters)
+                .run_fast()
+                .unwrap()
+        };
+        // Mid-anneal the temperature is still above the floor: `10 / 5001 > 1e-3`.
+        let res = run(5_000);
+        assert_eq!(res.termination_reason, TerminationReason::MaxItersReached);
+        // Below the floor hardly any move is accepted anymore.
+        let res = run(100_000);
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::TargetToleranceReached
+        );
+        assert!(res.termination_reason.is_success());
+        assert!(res.cost < 1e-2);
+    }
+
+    #[test]
+    fn test_sa_freeze_vs_stall_accepted() {
+        let run = |solver: SimulatedAnnealing<Vec<f64>>| {
+            let recorder = Arc::new(Recorder::default());
+            let res = Executor::new(Sphere::new(5), solver, vec![3.0, -2.0, 4.0])
+                .max_iters(100_000)
+                .add_logger(recorder.clone())
+                .run()
+                .unwrap();
+            let n = recorder.iters.lock().unwrap().len();
+            (res.termination_reason, recorder.get(n - 1, "frozen"))
+        };
+        let frozen = SimulatedAnnealing::new(10.0)
+            .unwrap()
+            .seed(5)
+            .freeze(0.1, 1e-3)
+            .unwrap();
+        assert_eq!(
+            run(frozen),
+            (
+                TerminationReason::TargetToleranceReached,
+                "true".to_string()
+            )
+        );
+        let stalled = SimulatedAnnealing::new(10.0)
+            .unwrap()
+            .seed(5)
+            .stall_accepted(20);
+        assert_eq!(
+            run(stalled),
+            (
+                TerminationReason::AcceptedStallIterExceeded,
+                "false".to_string()
+            )
+        );
+    }
+
+    /// `f(x) = x^2` which proposes `[1]` once and `[7]` afterwards, regardless of the current state
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Detour {
+        proposals: Arc<Mutex<u64>>,
+    }
+
+    impl ArgminOp for Detour {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p[0].powi(2))
+        }
+
+        fn modify(&self, _p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut proposals = self.proposals.lock().unwrap();
+            *proposals += 1;
+            Ok(if *proposals == 1 {
+                vec![1.0]
+            } else {
+                vec![7.0]
+            })
+        }
+    }
+
+    /// `f(x) = -x` on `[0, 1]`, whose proposals move `x` to the right by `0.25`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Drift;
+
+    impl ArgminOp for Drift {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(-p[0])
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] + 0.25])
+        }
+    }
+
+    impl ArgminBoundedOp for Drift {
+        fn lower_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![0.0])
+        }
+
+        fn upper_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![1.0])
+        }
+    }
+
+    #[test]
+    fn test_sa_bounds_from_op() {
+        // every proposal is an improvement, but only those up to the upper bound are feasible
+        let solver = SimulatedAnnealing::from_op(&Drift, 1.0).unwrap().seed(8);
+        let mut stepper = Stepper::new(Drift, solver, vec![0.0]).max_iters(10);
+        stepper.run().unwrap();
+        assert_eq!(stepper.best_param(), &vec![1.0]);
+        assert_eq!(stepper.solver().infeasible_count, 6);
+
+        let solver = SimulatedAnnealing::new(1.0).unwrap().seed(8);
+        let res = Executor::new(Drift, solver, vec![0.0])
+            .max_iters(10)
+            .run()
+            .unwrap();
+        assert_eq!(res.param, vec![2.5]);
+    }
+
+    fn detour() -> Detour {
+        Detour {
+            proposals: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    #[test]
+    fn test_sa_best_param() {
+        // At this temperature every worse move is accepted with probability 1/2: the chain moves
+        // on from `[1]` to the worse state `[7]`.
+        let solver = SimulatedAnnealing::new(1e300).unwrap().seed(6);
+        let mut stepper = Stepper::new(detour(), solver, vec![2.0]).max_iters(11);
+        stepper.run().unwrap();
+        assert_eq!(stepper.state().get_param(), vec![7.0]);
+        assert_eq!(stepper.solver().best_param(), Some(&vec![1.0]));
+        assert!((stepper.solver().best_cost() - 1.0).abs() < std::f64::EPSILON);
+
+        // the `Executor` reports the best parameter vector visited, not the last state
+        let solver = SimulatedAnnealing::new(1e300).unwrap().seed(6);
+        let res = Executor::new(detour(), solver, vec![2.0])
+            .max_iters(11)
+            .run()
+            .unwrap();
+        assert_eq!(res.param, vec![1.0]);
+        assert!((res.cost - 1.0).abs() < std::f64::EPSILON);
+    }
+
+    /// Records the KV of every iteration
+    #[derive(Default)]
+    struct Recorder {
+        iters: Mutex<Vec<ArgminKV>>,
+    }
+
+    impl Recorder {
+        fn get(&self, iter: usize, key: &str) -> String {
+            self.iters.lock().unwrap()[iter]
+                .kv
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        }
+    }
+
+    impl ArgminLog for Recorder {
+        fn log_info(&self, _msg: &str, _kv: &ArgminKV) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn log_iter(&self, kv: &ArgminKV) -> Result<(), Error> {
+            self.iters.lock().unwrap().push(kv.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sa_best_iter() {
+        let recorder = Arc::new(Recorder::default());
+        let solver = SimulatedAnnealing::new(10.0)
+            .unwrap()
+            .seed(3)
+            .stall_best(100);
+        let res = Executor::new(Sphere::new(3), solver, vec![3.0, -2.0, 4.0])
+            .max_iters(10_000)
+            .add_logger(recorder.clone())
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.termination_reason,
+            TerminationReason::BestStallIterExceeded
+        );
+        let n = recorder.iters.lock().unwrap().len();
+        // the iteration (counted from 1) of the last improvement as seen by the observer
+        let last_improvement = (0..n)
+            .filter(|&i| recorder.get(i, "new_be") == "true")
+            .last()
+            .unwrap()
+            + 1;
+        let best_iter: usize = recorder.get(n - 1, "best_iter").parse().unwrap();
+        assert_eq!(best_iter, last_improvement);
+        // found mid-run, and the run stopped once no better point was found for 100 iterations
+        assert!(best_iter > 1);
+        assert_eq!(n, best_iter + 101);
+        assert_eq!(recorder.get(n - 1, "st_i_be"), "101");
+    }
+
+    /// `f(x) = x^2` whose proposals halve `x`, i.e. every move is an improvement
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Halving;
+
+    impl ArgminOp for Halving {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p[0].powi(2))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] / 2.0])
+        }
+    }
+
+    #[test]
+    fn test_sa_best_iter_monotone() {
+        let mut solver = SimulatedAnnealing::new(1.0).unwrap().seed(4);
+        let op = Halving;
+        let mut op = OpWrapper::new(&op);
+        let mut state = IterState::new(vec![1.0]);
+        state.cost(1.0);
+        state.prev_cost(1.0);
+        assert_eq!(solver.best_iter(), 0);
+        assert!(solver.best_time().is_none());
+        for iter in 0..20 {
+            let data = solver.next_iter(&mut op, &state).unwrap();
+            state.prev_cost(state.get_cost());
+            state.param(data.get_param().unwrap());
+            state.cost(data.get_cost().unwrap());
+            state.increment_iter();
+            assert_eq!(solver.best_iter(), iter + 1);
+        }
+        assert_eq!(solver.best_iter(), state.get_iter());
+        #[cfg(not(feature = "wasm"))]
+        assert!(solver.best_time().is_some());
+
+        let recorder = Arc::new(Recorder::default());
+        let solver = SimulatedAnnealing::new(1.0).unwrap().seed(4);
+        Executor::new(Halving, solver, vec![1.0])
+            .max_iters(20)
+            .add_logger(recorder.clone())
+            .run()
+            .unwrap();
+        assert_eq!(recorder.iters.lock().unwrap().len(), 20);
+        assert_eq!(recorder.get(19, "best_iter"), "20");
+    }
 }
@@ -55,7 +55,7 @@ pub struct TrustRegion<R> {
     mk0: f64,
 }
 
-impl<R> TrustRegion<R> where {
+impl<R> TrustRegion<R> {
     /// Constructor
     pub fn new(subproblem: R) -> Self {
         TrustRegion {
@@ -68,21 +68,33 @@ impl<R> TrustRegion<R> where {
         }
     }
 
-    /// set radius
-    pub fn radius(mut self, radius: f64) -> Self {
+    /// Set the initial radius `0 < radius <= max_radius` (default: 1)
+    pub fn radius(mut self, radius: f64) -> Result<Self, Error> {
+        if !radius.is_finite() || radius <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "TrustRegion: radius must be in (0, max_radius].".to_string(),
+            }
+            .into());
+        }
         self.radius = radius;
-        self
+        Ok(self)
     }
 
-    /// Set maximum radius
-    pub fn max_radius(mut self, max_radius: f64) -> Self {
+    /// Set the maximum radius `max_radius > 0` (default: 100)
+    pub fn max_radius(mut self, max_radius: f64) -> Result<Self, Error> {
+        if max_radius.is_nan() || max_radius <= 0.0 {
+            return Err(ArgminError::InvalidParameter {
+                text: "TrustRegion: max_radius must be > 0.".to_string(),
+            }
+            .into());
+        }
         self.max_radius = max_radius;
-        self
+        Ok(self)
     }
 
-    /// Set eta
+    /// Set eta in `[0, 1/4)` (default: 0.125)
     pub fn eta(mut self, eta: f64) -> Result<Self, Error> {
-        if eta >= 0.25 || eta < 0.0 {
+        if eta.is_nan() || eta >= 0.25 || eta < 0.0 {
             return Err(ArgminError::InvalidParameter {
                 text: "TrustRegion: eta must be in [0, 1/4).".to_string(),
             }
@@ -116,6 +128,17 @@ where
         op: &mut OpWrapper<O>,
         state: &IterState<O>,
     ) -> Result<Option<ArgminIterData<O>>, Error> {
+        // `radius` and `max_radius` may be set in any order and are therefore checked here.
+        if self.radius > self.max_radius {
+            return Err(ArgminError::InvalidParameter {
+                text: format!(
+                    "TrustRegion: radius must be in (0, max_radius], but radius = {} and \
+                     max_radius = {}.",
+                    self.radius, self.max_radius
+                ),
+            }
+            .into());
+        }
         let param = state.get_param();
         let grad = op.gradient(&param)?;
         let hessian = op.hessian(&param)?;
@@ -191,8 +214,36 @@ mod tests {
     use super::*;
     use crate::send_sync_test;
     use crate::solver::trustregion::steihaug::Steihaug;
+    use crate::testfunctions::problem::TestProblem;
 
     type Operator = MinimalNoOperator;
 
     send_sync_test!(trustregion, TrustRegion<Operator, Steihaug<Operator>>);
+
+    #[test]
+    fn test_trustregion_invalid_parameters() {
+        let solver = || TrustRegion::new(Steihaug::<Vec<f64>>::new());
+        let message =
+            |res: Result<TrustRegion<Steihaug<Vec<f64>>>, Error>| res.err().unwrap().to_string();
+        assert!(message(solver().radius(0.0)).contains("radius"));
+        assert!(message(solver().radius(std::f64::INFINITY)).contains("radius"));
+        assert!(message(solver().max_radius(-1.0)).contains("max_radius"));
+        assert!(message(solver().eta(0.25)).contains("eta"));
+        // the radius may exceed the default maximum radius if that is raised afterwards
+        assert!(solver().radius(200.0).unwrap().max_radius(500.0).is_ok());
+    }
+
+    #[test]
+    fn test_trustregion_radius_exceeds_max_radius() {
+        let solver = TrustRegion::new(Steihaug::new())
+            .radius(10.0)
+            .unwrap()
+            .max_radius(5.0)
+            .unwrap();
+        let res = Executor::new(TestProblem::rosenbrock(2), solver, vec![-1.2, 1.0])
+            .max_iters(10)
+            .run_fast();
+        let e = res.err().unwrap().to_string();
+        assert!(e.contains("max_radius"), "{}", e);
+    }
 }
@@ -0,0 +1,427 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Step-by-step execution of solvers
+//!
+//! `Executor::run` performs all iterations at once. A `Stepper` performs one iteration per call to
+//! `step`, which allows to interleave the optimization with other work, for instance one
+//! iteration per frame of a simulation:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! # use argmin::stepper::Stepper;
+//! # use argmin::solver::neldermead::{NelderMead, SimplexStep};
+//! # use argmin::testfunctions::problem::TestProblem;
+//! # fn main() -> Result<(), Error> {
+//! let solver = NelderMead::from_point(vec![-1.2, 1.0], SimplexStep::default())?;
+//! let mut stepper = Stepper::new(TestProblem::rosenbrock(2), solver, vec![-1.2, 1.0])
+//!     .max_iters(1000);
+//! while !stepper.step()?.is_finished() {
+//!     // advance the simulation, inspect `stepper.state()`, ...
+//! }
+//! println!("{:?}: {}", stepper.termination_reason(), stepper.best_cost());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each step updates the `IterState` and the best parameter vector, notifies the loggers and
+//! checks the termination criteria (the ones of the solver, `max_iters` and `target_cost`). The
+//! iteration budget can be changed between steps with `set_max_iters`.
+//!
+//! Bounded problems (`ArgminBoundedOp`) do not need an initial parameter vector:
+//! `Stepper::new_bounded` draws a reproducible one from the bounds of the problem.
+
+use crate::operator::{check_init_param, random_init_param};
+use crate::prelude::*;
+use std::sync::Arc;
+
+/// Outcome of a call to `Stepper::step`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StepOutcome {
+    /// The iteration was performed and the run continues
+    Running,
+    /// The run has finished for the given reason. No iteration is performed by further calls to
+    /// `step`.
+    Finished(TerminationReason),
+}
+
+impl StepOutcome {
+    /// `true` if the run has finished
+    pub fn is_finished(&self) -> bool {
+        match *self {
+            StepOutcome::Running => false,
+            StepOutcome::Finished(_) => true,
+        }
+    }
+}
+
+/// Performs the iterations of a solver one at a time
+pub struct Stepper<O: ArgminOp, S> {
+    /// operator
+    op: OpWrapper<O>,
+    /// solver
+    solver: S,
+    /// current state
+    state: IterState<O>,
+    /// best parameter vector so far
+    best_param: O::Param,
+    /// cost function value of `best_param`
+    best_cost: f64,
+    /// maximum number of iterations
+    max_iters: u64,
+    /// stop once the cost function value is at or below this value
+    target_cost: f64,
+    /// loggers
+    loggers: Vec<Arc<dyn ArgminLog>>,
+    /// whether the solver has been initialized
+    initialized: bool,
+    /// reason why the run has finished
+    termination_reason: TerminationReason,
+}
+
+impl<O, S> Stepper<O, S>
+where
+    O: ArgminOp<Output = f64>,
+    S: Solver<O>,
+{
+    /// Constructor
+    pub fn new(op: O, solver: S, init_param: O::Param) -> Self {
+        Stepper {
+            op: OpWrapper::new(&op),
+            solver,
+            best_param: init_param.clone(),
+            state: IterState::new(init_param),
+            best_cost: std::f64::INFINITY,
+            max_iters: std::u64::MAX,
+            target_cost: std::f64::NEG_INFINITY,
+            loggers: vec![],
+            initialized: false,
+            termination_reason: TerminationReason::NotTerminated,
+        }
+    }
+
+    /// Set the maximum number of iterations (default: unlimited)
+    pub fn max_iters(mut self, max_iters: u64) -> Self {
+        self.max_iters = max_iters;
+        self
+    }
+
+    /// Change the maximum number of iterations, also between steps. The run finishes at the next
+    /// step if the new budget is already exhausted.
+    pub fn set_max_iters(&mut self, max_iters: u64) {
+        self.max_iters = max_iters;
+    }
+
+    /// Stop once the cost function value is at or below `target_cost` (default: `-inf`)
+    pub fn target_cost(mut self, target_cost: f64) -> Self {
+        self.target_cost = target_cost;
+        self
+    }
+
+    /// Attach a logger, which receives the KV of every iteration
+    pub fn add_logger(mut self, logger: Arc<dyn ArgminLog>) -> Self {
+        self.loggers.push(logger);
+        self
+    }
+
+    /// Current state
+    pub fn state(&self) -> &IterState<O> {
+        &self.state
+    }
+
+    /// Solver
+    pub fn solver(&self) -> &S {
+        &self.solver
+    }
+
+    /// Operator (with the evaluation counters)
+    pub fn operator(&self) -> &OpWrapper<O> {
+        &self.op
+    }
+
+    /// Best parameter vector so far
+    pub fn best_param(&self) -> &O::Param {
+        &self.best_param
+    }
+
+    /// Cost function value of the best parameter vector so far
+    pub fn best_cost(&self) -> f64 {
+        self.best_cost
+    }
+
+    /// Reason why the run has finished (`NotTerminated` while it is running)
+    pub fn termination_reason(&self) -> TerminationReason {
+        self.termination_reason
+    }
+
+    /// Updates the state with the output of `init` or `next_iter`. Returns whether a new best
+    /// parameter vector was found.
+    fn update(&mut self, data: &ArgminIterData<O>) -> Result<bool, Error> {
+        let param = data.get_param().unwrap_or_else(|| self.state.get_param());
+        let cost = match data.get_cost() {
+            Some(cost) => cost,
+            None => self.op.apply(&param)?,
+        };
+        if let Some(grad) = data.get_grad() {
+            self.state.grad(grad);
+        }
+        self.state.prev_cost(self.state.get_cost());
+        self.state.cost(cost);
+        let new_best = cost <= self.best_cost;
+        if new_best {
+            self.best_param = param.clone();
+            self.best_cost = cost;
+        }
+        self.state.param(param);
+        Ok(new_best)
+    }
+
+    fn finish(&mut self, reason: TerminationReason) -> StepOutcome {
+        self.termination_reason = reason;
+        StepOutcome::Finished(reason)
+    }
+
+    /// Perform one iteration (after initializing the solver at the first call), update the
+    /// state, notify the loggers and check the termination criteria.
+    pub fn step(&mut self) -> Result<StepOutcome, Error> {
+        if self.termination_reason != TerminationReason::NotTerminated {
+            return Ok(StepOutcome::Finished(self.termination_reason));
+        }
+        if !self.initialized {
+            self.initialized = true;
+            let cost = self.op.apply(&self.state.get_param())?;
+            self.state.cost(cost);
+            self.state.prev_cost(cost);
+            self.best_cost = cost;
+            if let Some(data) = self.solver.init(&mut self.op, &self.state)? {
+                self.update(&data)?;
+            }
+        }
+        if self.state.get_iter() >= self.max_iters {
+            return Ok(self.finish(TerminationReason::MaxItersReached));
+        }
+
+        let data = self.solver.next_iter(&mut self.op, &self.state)?;
+        let new_best = self.update(&data)?;
+        self.state.increment_iter();
+
+        if !self.loggers.is_empty() {
+            let mut kv = ArgminKV::new();
+            kv.push("iter", self.state.get_iter().to_string());
+            kv.push("cost", self.state.get_cost().to_string());
+            kv.push("best_cost", self.best_cost.to_string());
+            kv.push("new_best", new_best.to_string());
+            for (key, val) in data.get_kv().kv.iter() {
+                kv.push(*key, val.clone());
+            }
+            for logger in &self.loggers {
+                logger.log_iter(&kv)?;
+            }
+        }
+
+        let reason = match data.get_termination_reason() {
+            Some(reason) if reason != TerminationReason::NotTerminated => reason,
+            _ => self.solver.terminate(&self.state),
+        };
+        Ok(if reason != TerminationReason::NotTerminated {
+            self.finish(reason)
+        } else if self.best_cost <= self.target_cost {
+            self.finish(TerminationReason::TargetCostReached)
+        } else if self.state.get_iter() >= self.max_iters {
+            self.finish(TerminationReason::MaxItersReached)
+        } else {
+            StepOutcome::Running
+        })
+    }
+
+    /// Perform steps until the run has finished. Returns the termination reason.
+    pub fn run(&mut self) -> Result<TerminationReason, Error> {
+        loop {
+            if let StepOutcome::Finished(reason) = self.step()? {
+                return Ok(reason);
+            }
+        }
+    }
+}
+
+impl<O, S> Stepper<O, S>
+where
+    O: ArgminBoundedOp<Output = f64>,
+    O::Param: ArgminElementwiseCmp,
+    S: Solver<O>,
+{
+    /// Constructor for bounded problems. Returns an error if `init_param` violates the bounds of
+    /// `op` (see `check_init_param`).
+    pub fn new_checked(op: O, solver: S, init_param: O::Param) -> Result<Self, Error> {
+        check_init_param(&op, &init_param)?;
+        Ok(Stepper::new(op, solver, init_param))
+    }
+
+    /// Constructor for bounded problems. The initial parameter vector is drawn from the bounds of
+    /// `op` with a random number generator seeded with `seed` (see `random_init_param`). Returns
+    /// an error if the problem has no bounds.
+    pub fn new_bounded(op: O, solver: S, seed: u64) -> Result<Self, Error>
+    where
+        O::Param: ArgminParameter,
+    {
+        let init_param = random_init_param(&op, seed)?;
+        Ok(Stepper::new(op, solver, init_param))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::{ArmijoCondition, BacktrackingLineSearch};
+    use crate::solver::quasinewton::LBFGSB;
+    use crate::testfunctions::problem::TestProblem;
+    use serde::{Deserialize, Serialize};
+    use std::sync::Mutex;
+
+    type Descent = SteepestDescent<BacktrackingLineSearch<Vec<f64>, ArmijoCondition>>;
+
+    fn steepest_descent() -> Descent {
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap());
+        SteepestDescent::new(linesearch).unwrap()
+    }
+
+    /// Records the iteration numbers the loggers are notified of
+    #[derive(Default)]
+    struct Recorder {
+        iters: Mutex<Vec<String>>,
+    }
+
+    impl ArgminLog for Recorder {
+        fn log_info(&self, _msg: &str, _kv: &ArgminKV) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn log_iter(&self, kv: &ArgminKV) -> Result<(), Error> {
+            let iter = kv.kv.iter().find(|(k, _)| *k == "iter").unwrap();
+            self.iters.lock().unwrap().push(iter.1.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stepper_matches_executor() {
+        let problem = TestProblem::rosenbrock(2);
+        let init = vec![-1.2, 1.0];
+        let recorder = Arc::new(Recorder::default());
+        let mut stepper = Stepper::new(problem.clone(), steepest_descent(), init.clone())
+            .add_logger(recorder.clone());
+        for i in 0..10 {
+            assert_eq!(stepper.step().unwrap(), StepOutcome::Running);
+            // the state can be inspected between steps
+            assert_eq!(stepper.state().get_iter(), i + 1);
+        }
+
+        let res = Executor::new(problem, steepest_descent(), init)
+            .max_iters(10)
+            .run_fast()
+            .unwrap();
+        let bits = |p: &[f64]| p.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&stepper.state().get_param()), bits(&res.param));
+        assert_eq!(stepper.best_cost().to_bits(), res.cost.to_bits());
+        assert_eq!(
+            *recorder.iters.lock().unwrap(),
+            (1..=10).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_stepper_max_iters() {
+        let mut stepper =
+            Stepper::new(TestProblem::sphere(2), steepest_descent(), vec![1.0, 1.0]).max_iters(3);
+        assert_eq!(stepper.step().unwrap(), StepOutcome::Running);
+        assert_eq!(stepper.step().unwrap(), StepOutcome::Running);
+        assert_eq!(
+            stepper.step().unwrap(),
+            StepOutcome::Finished(TerminationReason::MaxItersReached)
+        );
+        // finished runs are not continued
+        assert!(stepper.step().unwrap().is_finished());
+        assert_eq!(stepper.state().get_iter(), 3);
+
+        // the budget can be changed between steps
+        let mut stepper =
+            Stepper::new(TestProblem::sphere(2), steepest_descent(), vec![1.0, 1.0]).max_iters(3);
+        stepper.step().unwrap();
+        stepper.set_max_iters(1);
+        assert_eq!(
+            stepper.step().unwrap(),
+            StepOutcome::Finished(TerminationReason::MaxItersReached)
+        );
+        assert_eq!(stepper.state().get_iter(), 1);
+    }
+
+    #[test]
+    fn test_stepper_target_cost() {
+        let problem = TestProblem::rosenbrock(2);
+        let mut stepper = Stepper::new(problem, steepest_descent(), vec![-1.2, 1.0])
+            .max_iters(100_000)
+            .target_cost(1.0);
+        let reason = stepper.run().unwrap();
+        assert_eq!(reason, TerminationReason::TargetCostReached);
+        assert_eq!(stepper.termination_reason(), reason);
+        assert!(stepper.best_cost() <= 1.0);
+        assert!(stepper.state().get_iter() < 100_000);
+    }
+
+    /// `f(x) = sum_i (x_i - 2)^2` on `[-1, 1]^2`, with its minimum at the corner `(1, 1)`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Corner;
+
+    impl ArgminOp for Corner {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p.iter().map(|x| (x - 2.0).powi(2)).sum())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(p.iter().map(|x| 2.0 * (x - 2.0)).collect())
+        }
+    }
+
+    impl ArgminBoundedOp for Corner {
+        fn lower_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![-1.0, -1.0])
+        }
+
+        fn upper_bound(&self) -> Option<Vec<f64>> {
+            Some(vec![1.0, 1.0])
+        }
+    }
+
+    fn lbfgsb() -> LBFGSB {
+        LBFGSB::new(Corner.bounds().unwrap(), 5).unwrap()
+    }
+
+    #[test]
+    fn test_stepper_new_bounded() {
+        let mut stepper = Stepper::new_bounded(Corner, lbfgsb(), 7)
+            .unwrap()
+            .max_iters(50);
+        let init = stepper.state().get_param();
+        assert!(Corner.bounds().unwrap().contains(&init));
+        stepper.run().unwrap();
+        assert!(stepper.best_param().iter().all(|x| (x - 1.0).abs() < 1e-8));
+
+        // same as a run from the drawn point given explicitly
+        let mut explicit = Stepper::new(Corner, lbfgsb(), init).max_iters(50);
+        explicit.run().unwrap();
+        assert_eq!(explicit.state().get_iter(), stepper.state().get_iter());
+        assert_eq!(explicit.best_param(), stepper.best_param());
+
+        assert!(Stepper::new_checked(Corner, lbfgsb(), vec![0.5, -0.5]).is_ok());
+        assert!(Stepper::new_checked(Corner, lbfgsb(), vec![0.5, 1.5]).is_err());
+    }
+}
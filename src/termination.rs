@@ -0,0 +1,97 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Classification of termination reasons
+//!
+//! A `TerminationReason` either means that the solver converged (for instance the target
+//! precision was reached) or that it was stopped before (the iteration budget was exhausted, the
+//! run was aborted or interrupted). `TerminationStatus` tells the two apart:
+//!
+//! ```rust
+//! # use argmin::prelude::*;
+//! assert!(TerminationReason::TargetPrecisionReached.is_success());
+//! assert!(!TerminationReason::MaxItersReached.is_success());
+//! assert_eq!(
+//!     TerminationReason::MaxItersReached.summary(),
+//!     "Stopped (MaxItersReached)"
+//! );
+//! ```
+
+use crate::prelude::*;
+
+/// Whether a run converged or was stopped
+pub trait TerminationStatus {
+    /// `true` if the solver converged, `false` if it was stopped or has not terminated yet
+    fn is_success(&self) -> bool;
+
+    /// Either `"converged"` or `"stopped"`
+    fn status(&self) -> &'static str {
+        if self.is_success() {
+            "converged"
+        } else {
+            "stopped"
+        }
+    }
+
+    /// Human readable summary such as `"Converged (TargetPrecisionReached)"`
+    fn summary(&self) -> String;
+}
+
+impl TerminationStatus for TerminationReason {
+    fn is_success(&self) -> bool {
+        matches!(
+            *self,
+            TerminationReason::TargetPrecisionReached
+                | TerminationReason::TargetCostReached
+                | TerminationReason::NoChangeInCost
+                | TerminationReason::LineSearchConditionMet
+                | TerminationReason::TargetToleranceReached
+        )
+    }
+
+    fn summary(&self) -> String {
+        if self.is_success() {
+            format!("Converged ({:?})", self)
+        } else {
+            format!("Stopped ({:?})", self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_termination_status() {
+        for reason in &[
+            TerminationReason::TargetPrecisionReached,
+            TerminationReason::TargetCostReached,
+            TerminationReason::NoChangeInCost,
+            TerminationReason::LineSearchConditionMet,
+            TerminationReason::TargetToleranceReached,
+        ] {
+            assert!(reason.is_success(), "{:?}", reason);
+            assert_eq!(reason.status(), "converged");
+        }
+        for reason in &[
+            TerminationReason::NotTerminated,
+            TerminationReason::MaxItersReached,
+            TerminationReason::AcceptedStallIterExceeded,
+            TerminationReason::BestStallIterExceeded,
+            TerminationReason::Aborted,
+        ] {
+            assert!(!reason.is_success(), "{:?}", reason);
+            assert_eq!(reason.status(), "stopped");
+        }
+        assert_eq!(
+            TerminationReason::TargetPrecisionReached.summary(),
+            "Converged (TargetPrecisionReached)"
+        );
+        assert_eq!(TerminationReason::Aborted.summary(), "Stopped (Aborted)");
+    }
+}
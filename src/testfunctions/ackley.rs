@@ -0,0 +1,127 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Ackley function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = - a * exp( -b \sqrt{\frac{1}{n}\sum_{i=1}^{n}x_i^2} ) -
+//! exp( \frac{1}{n} \sum_{i=1}^{n} \cos(c * x_i) ) + a + exp(1)`
+//!
+//! where `x_i \in [-32.768, 32.768]` and usually `a = 20`, `b = 0.2` and `c = 2 * pi`.
+//!
+//! The global minimum is at `f(x_1, x_2, ..., x_n) = f(0, 0, ..., 0) = 0`.
+//!
+//! The function is not differentiable at the origin. The derivative is defined to be zero there
+//! (which is the value of the gradient of the smooth second term, while the first term has a
+//! direction-dependent limit).
+//!
+//! # References:
+//!
+//! [0] David H. Ackley (1987). A connectionist machine for genetic hillclimbing. Kluwer
+//! Academic Publishers.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Ackley function with `a = 20`, `b = 0.2` and `c = 2 * pi`
+pub fn ackley<T: Float + FromPrimitive>(param: &[T]) -> T {
+    ackley_param(
+        param,
+        T::from_f64(20.0).unwrap(),
+        T::from_f64(0.2).unwrap(),
+        T::from_f64(2.0 * PI).unwrap(),
+    )
+}
+
+/// Ackley function with parameters `a`, `b` and `c`. Panics if `param` is empty.
+pub fn ackley_param<T: Float + FromPrimitive>(param: &[T], a: T, b: T, c: T) -> T {
+    assert!(!param.is_empty());
+    let n = T::from_usize(param.len()).unwrap();
+    let (sum_sq, sum_cos) = sums(param, c);
+    -a * (-b * (sum_sq / n).sqrt()).exp() - (sum_cos / n).exp() + a + T::one().exp()
+}
+
+/// Derivative of the Ackley function with `a = 20`, `b = 0.2` and `c = 2 * pi`
+pub fn ackley_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    ackley_param_derivative(
+        param,
+        T::from_f64(20.0).unwrap(),
+        T::from_f64(0.2).unwrap(),
+        T::from_f64(2.0 * PI).unwrap(),
+    )
+}
+
+/// Derivative of the Ackley function with parameters `a`, `b` and `c`. Returns zero at the
+/// origin. Panics if `param` is empty.
+pub fn ackley_param_derivative<T: Float + FromPrimitive>(param: &[T], a: T, b: T, c: T) -> Vec<T> {
+    assert!(!param.is_empty());
+    let n = T::from_usize(param.len()).unwrap();
+    let (sum_sq, sum_cos) = sums(param, c);
+    let norm = (sum_sq / n).sqrt();
+    // factor of x_i in the derivative of the first term
+    let f1 = if norm > T::zero() {
+        a * b * (-b * norm).exp() / (n * norm)
+    } else {
+        T::zero()
+    };
+    // factor of sin(c * x_i) in the derivative of the second term
+    let f2 = c * (sum_cos / n).exp() / n;
+    param.iter().map(|&x| f1 * x + f2 * (c * x).sin()).collect()
+}
+
+/// `sum_i x_i^2` and `sum_i cos(c * x_i)`
+fn sums<T: Float>(param: &[T], c: T) -> (T, T) {
+    param.iter().fold((T::zero(), T::zero()), |(sq, cs), &x| {
+        (sq + x.powi(2), cs + (c * x).cos())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_minimum() {
+        for n in 1..6 {
+            assert!(ackley(&vec![0.0f64; n]).abs() < 1e-12);
+        }
+        assert!(ackley(&[0.0f32; 3]).abs() < 1e-5);
+        // away from the origin the function is positive
+        assert!(ackley(&[1.0f64, -1.0]) > 3.0);
+    }
+
+    #[test]
+    fn test_derivative_at_origin() {
+        let g = ackley_derivative(&[0.0f64; 5]);
+        assert!(g.iter().all(|x| !x.is_nan() && x.abs() < std::f64::EPSILON));
+        let g = ackley_derivative(&[0.0f32; 2]);
+        assert!(g.iter().all(|x| !x.is_nan()));
+        // close to the origin the derivative is finite
+        let g = ackley_derivative(&[1e-300f64, 0.0]);
+        assert!(g.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let mut rng = XorShiftRng::seed_from_u64(416);
+        for n in &[1, 2, 5] {
+            let points: Vec<Vec<f64>> = (0..5)
+                .map(|_| (0..*n).map(|_| rng.gen_range(-5.0, 5.0)).collect())
+                .collect();
+            assert_gradient(ackley, ackley_derivative, &points);
+            assert_gradient(
+                |x| ackley_param(x, 10.0, 0.5, 1.0),
+                |x| ackley_param_derivative(x, 10.0, 0.5, 1.0),
+                &points,
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Beale function
+//!
+//! `f(x_1, x_2) = (1.5 - x_1 + x_1 * x_2)^2 + (2.25 - x_1 + x_1 * x_2^2)^2 +
+//! (2.625 - x_1 + x_1 * x_2^3)^2`
+//!
+//! where `x_i \in [-4.5, 4.5]`.
+//!
+//! The global minimum is at `f(x_1, x_2) = f(3, 0.5) = 0`.
+
+use num::{Float, FromPrimitive};
+
+/// Terms `t_k = c_k - x_1 + x_1 * x_2^k` for `k = 1, 2, 3`
+fn terms<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 3] {
+    let [x1, x2] = *param;
+    [
+        T::from_f64(1.5).unwrap() - x1 + x1 * x2,
+        T::from_f64(2.25).unwrap() - x1 + x1 * x2.powi(2),
+        T::from_f64(2.625).unwrap() - x1 + x1 * x2.powi(3),
+    ]
+}
+
+/// Beale function
+pub fn beale<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [t1, t2, t3] = terms(param);
+    t1.powi(2) + t2.powi(2) + t3.powi(2)
+}
+
+/// Derivative of the Beale function
+pub fn beale_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let [t1, t2, t3] = terms(param);
+    let n2 = T::from_f64(2.0).unwrap();
+    let n3 = T::from_f64(3.0).unwrap();
+    let one = T::one();
+    [
+        n2 * (t1 * (x2 - one) + t2 * (x2.powi(2) - one) + t3 * (x2.powi(3) - one)),
+        n2 * (t1 * x1 + t2 * n2 * x1 * x2 + t3 * n3 * x1 * x2.powi(2)),
+    ]
+}
+
+/// Hessian of the Beale function
+pub fn beale_hessian<T: Float + FromPrimitive>(param: &[T; 2]) -> [[T; 2]; 2] {
+    let [x1, x2] = *param;
+    let [t1, t2, t3] = terms(param);
+    let n2 = T::from_f64(2.0).unwrap();
+    let n3 = T::from_f64(3.0).unwrap();
+    let n6 = T::from_f64(6.0).unwrap();
+    let one = T::one();
+    // derivatives of the terms with respect to x_1 and x_2
+    let d1 = [x2 - one, x2.powi(2) - one, x2.powi(3) - one];
+    let d2 = [x1, n2 * x1 * x2, n3 * x1 * x2.powi(2)];
+    let h11 = n2 * (d1[0].powi(2) + d1[1].powi(2) + d1[2].powi(2));
+    let h12 = n2
+        * (d1[0] * d2[0]
+            + d1[1] * d2[1]
+            + d1[2] * d2[2]
+            + t1
+            + t2 * n2 * x2
+            + t3 * n3 * x2.powi(2));
+    let h22 =
+        n2 * (d2[0].powi(2) + d2[1].powi(2) + d2[2].powi(2) + t2 * n2 * x1 + t3 * n6 * x1 * x2);
+    [[h11, h12], [h12, h22]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!(beale(&[3.0f64, 0.5]).abs() < std::f64::EPSILON);
+        assert!(beale(&[3.0f32, 0.5]).abs() < std::f32::EPSILON);
+        let g = beale_derivative(&[3.0f64, 0.5]);
+        assert!(g[0].abs() < std::f64::EPSILON && g[1].abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        let points = vec![
+            vec![1.0, 1.0],
+            vec![-2.0, 0.5],
+            vec![4.0, -1.5],
+            vec![0.3, 2.1],
+        ];
+        assert_gradient(
+            |x| beale(&arr(x)),
+            |x| beale_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+        assert_hessian(
+            |x| beale_derivative(&arr(x)).to_vec(),
+            |x| beale_hessian(&arr(x)).iter().map(|r| r.to_vec()).collect(),
+            &points,
+        );
+    }
+}
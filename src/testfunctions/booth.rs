@@ -0,0 +1,76 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Booth function
+//!
+//! `f(x_1, x_2) = (x_1 + 2 * x_2 - 7)^2 + (2 * x_1 + x_2 - 5)^2`
+//!
+//! where `x_i \in [-10, 10]`.
+//!
+//! The global minimum is at `f(x_1, x_2) = f(1, 3) = 0`. The function is quadratic, therefore
+//! Newton's method converges in a single step.
+
+use num::{Float, FromPrimitive};
+
+/// Booth function
+pub fn booth<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    let n5 = T::from_f64(5.0).unwrap();
+    let n7 = T::from_f64(7.0).unwrap();
+    (x1 + n2 * x2 - n7).powi(2) + (n2 * x1 + x2 - n5).powi(2)
+}
+
+/// Derivative of the Booth function
+pub fn booth_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n8 = T::from_f64(8.0).unwrap();
+    let n10 = T::from_f64(10.0).unwrap();
+    [
+        n10 * x1 + n8 * x2 - T::from_f64(34.0).unwrap(),
+        n8 * x1 + n10 * x2 - T::from_f64(38.0).unwrap(),
+    ]
+}
+
+/// Hessian of the Booth function
+pub fn booth_hessian<T: Float + FromPrimitive>(_param: &[T; 2]) -> [[T; 2]; 2] {
+    let n8 = T::from_f64(8.0).unwrap();
+    let n10 = T::from_f64(10.0).unwrap();
+    [[n10, n8], [n8, n10]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!(booth(&[1.0f64, 3.0]).abs() < std::f64::EPSILON);
+        assert!(booth(&[1.0f32, 3.0]).abs() < std::f32::EPSILON);
+        assert_eq!(booth_derivative(&[1.0f64, 3.0]), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        let points = vec![vec![0.0, 0.0], vec![-7.0, 2.5], vec![9.0, -9.5]];
+        assert_gradient(
+            |x| booth(&arr(x)),
+            |x| booth_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+        assert_hessian(
+            |x| booth_derivative(&arr(x)).to_vec(),
+            |x| booth_hessian(&arr(x)).iter().map(|r| r.to_vec()).collect(),
+            &points,
+        );
+    }
+}
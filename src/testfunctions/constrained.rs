@@ -0,0 +1,501 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Constrained test problems
+//!
+//! Problems from the Hock-Schittkowski collection [0] in the form
+//!
+//! `min f(x)` subject to `g_i(x) <= 0`, `h_j(x) = 0` and `l <= x <= u`
+//!
+//! Note that Hock and Schittkowski state inequality constraints as `c_i(x) >= 0`, here they are
+//! given as `g_i(x) = -c_i(x) <= 0` to match `PenaltyOp`.
+//!
+//! * HS6: `f(x) = (1 - x_1)^2`, `h(x) = 10 (x_2 - x_1^2)`, optimum `f(1, 1) = 0`
+//! * HS7: `f(x) = ln(1 + x_1^2) - x_2`, `h(x) = (1 + x_1^2)^2 + x_2^2 - 4`, optimum
+//!   `f(0, sqrt(3)) = -sqrt(3)`
+//! * HS21: `f(x) = 0.01 x_1^2 + x_2^2 - 100`, `g(x) = 10 - 10 x_1 + x_2`, `2 <= x_1 <= 50`,
+//!   `-50 <= x_2 <= 50`, optimum `f(2, 0) = -99.96`
+//! * HS35: `f(x) = 9 - 8 x_1 - 6 x_2 - 4 x_3 + 2 x_1^2 + 2 x_2^2 + x_3^2 + 2 x_1 x_2 + 2 x_1 x_3`,
+//!   `g(x) = x_1 + x_2 + 2 x_3 - 3`, `0 <= x_i`, optimum `f(4/3, 7/9, 4/9) = 1/9`
+//! * HS71: `f(x) = x_1 x_4 (x_1 + x_2 + x_3) + x_3`, `g(x) = 25 - x_1 x_2 x_3 x_4`,
+//!   `h(x) = x_1^2 + x_2^2 + x_3^2 + x_4^2 - 40`, `1 <= x_i <= 5`, optimum
+//!   `f(1, 4.743, 3.8211, 1.3794) = 17.014`
+//!
+//! # References:
+//!
+//! [0] Willi Hock and Klaus Schittkowski (1981). Test Examples for Nonlinear Programming Codes.
+//! Lecture Notes in Economics and Mathematical Systems 187, Springer.
+
+use crate::operator::penalty::PenaltyOp;
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::INFINITY;
+
+/// Problems of the Hock-Schittkowski collection available as `ConstrainedProblem`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HockSchittkowski {
+    /// Problem 6
+    Hs6,
+    /// Problem 7
+    Hs7,
+    /// Problem 21
+    Hs21,
+    /// Problem 35
+    Hs35,
+    /// Problem 71
+    Hs71,
+}
+
+/// Constrained test problem together with its bounds, starting point and known optimum
+///
+/// The `ArgminOp` implementation only covers the objective function. `penalty_op` wraps the
+/// problem in a `PenaltyOp` which includes all constraints and bounds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConstrainedProblem {
+    /// problem
+    problem: HockSchittkowski,
+    /// lower bounds (`-inf` if unbounded)
+    lower: Vec<f64>,
+    /// upper bounds (`inf` if unbounded)
+    upper: Vec<f64>,
+    /// standard starting point
+    start: Vec<f64>,
+    /// minimizer
+    minimizer: Vec<f64>,
+    /// minimum
+    minimum: f64,
+}
+
+impl ConstrainedProblem {
+    /// Hock-Schittkowski problem 6
+    pub fn hs6() -> Self {
+        ConstrainedProblem {
+            problem: HockSchittkowski::Hs6,
+            lower: vec![-INFINITY; 2],
+            upper: vec![INFINITY; 2],
+            start: vec![-1.2, 1.0],
+            minimizer: vec![1.0, 1.0],
+            minimum: 0.0,
+        }
+    }
+
+    /// Hock-Schittkowski problem 7
+    pub fn hs7() -> Self {
+        ConstrainedProblem {
+            problem: HockSchittkowski::Hs7,
+            lower: vec![-INFINITY; 2],
+            upper: vec![INFINITY; 2],
+            start: vec![2.0, 2.0],
+            minimizer: vec![0.0, 3.0f64.sqrt()],
+            minimum: -(3.0f64.sqrt()),
+        }
+    }
+
+    /// Hock-Schittkowski problem 21
+    pub fn hs21() -> Self {
+        ConstrainedProblem {
+            problem: HockSchittkowski::Hs21,
+            lower: vec![2.0, -50.0],
+            upper: vec![50.0, 50.0],
+            start: vec![-1.0, -1.0],
+            minimizer: vec![2.0, 0.0],
+            minimum: -99.96,
+        }
+    }
+
+    /// Hock-Schittkowski problem 35
+    pub fn hs35() -> Self {
+        ConstrainedProblem {
+            problem: HockSchittkowski::Hs35,
+            lower: vec![0.0; 3],
+            upper: vec![INFINITY; 3],
+            start: vec![0.5; 3],
+            minimizer: vec![4.0 / 3.0, 7.0 / 9.0, 4.0 / 9.0],
+            minimum: 1.0 / 9.0,
+        }
+    }
+
+    /// Hock-Schittkowski problem 71
+    pub fn hs71() -> Self {
+        ConstrainedProblem {
+            problem: HockSchittkowski::Hs71,
+            lower: vec![1.0; 4],
+            upper: vec![5.0; 4],
+            start: vec![1.0, 5.0, 5.0, 1.0],
+            minimizer: vec![1.0, 4.742_999_637, 3.821_149_984, 1.379_408_293],
+            minimum: 17.014_017_289,
+        }
+    }
+
+    /// Returns the name of the problem
+    pub fn name(&self) -> String {
+        format!("{:?}", self.problem)
+    }
+
+    /// Returns the problem
+    pub fn problem(&self) -> HockSchittkowski {
+        self.problem
+    }
+
+    /// Returns the number of dimensions
+    pub fn dim(&self) -> usize {
+        self.start.len()
+    }
+
+    /// Returns the lower bounds (`-inf` if unbounded)
+    pub fn lower_bounds(&self) -> &[f64] {
+        &self.lower
+    }
+
+    /// Returns the upper bounds (`inf` if unbounded)
+    pub fn upper_bounds(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Returns the standard starting point
+    pub fn start(&self) -> &[f64] {
+        &self.start
+    }
+
+    /// Returns the minimizer
+    pub fn minimizer(&self) -> &[f64] {
+        &self.minimizer
+    }
+
+    /// Returns the minimum
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    /// Objective function
+    pub fn objective(&self, x: &[f64]) -> f64 {
+        match self.problem {
+            HockSchittkowski::Hs6 => (1.0 - x[0]).powi(2),
+            HockSchittkowski::Hs7 => x[0].powi(2).ln_1p() - x[1],
+            HockSchittkowski::Hs21 => 0.01 * x[0].powi(2) + x[1].powi(2) - 100.0,
+            HockSchittkowski::Hs35 => {
+                9.0 - 8.0 * x[0] - 6.0 * x[1] - 4.0 * x[2]
+                    + 2.0 * x[0].powi(2)
+                    + 2.0 * x[1].powi(2)
+                    + x[2].powi(2)
+                    + 2.0 * x[0] * x[1]
+                    + 2.0 * x[0] * x[2]
+            }
+            HockSchittkowski::Hs71 => x[0] * x[3] * (x[0] + x[1] + x[2]) + x[2],
+        }
+    }
+
+    /// Gradient of the objective function
+    pub fn objective_gradient(&self, x: &[f64]) -> Vec<f64> {
+        match self.problem {
+            HockSchittkowski::Hs6 => vec![-2.0 * (1.0 - x[0]), 0.0],
+            HockSchittkowski::Hs7 => vec![2.0 * x[0] / (1.0 + x[0].powi(2)), -1.0],
+            HockSchittkowski::Hs21 => vec![0.02 * x[0], 2.0 * x[1]],
+            HockSchittkowski::Hs35 => vec![
+                -8.0 + 4.0 * x[0] + 2.0 * x[1] + 2.0 * x[2],
+                -6.0 + 4.0 * x[1] + 2.0 * x[0],
+                -4.0 + 2.0 * x[2] + 2.0 * x[0],
+            ],
+            HockSchittkowski::Hs71 => vec![
+                x[3] * (2.0 * x[0] + x[1] + x[2]),
+                x[0] * x[3],
+                x[0] * x[3] + 1.0,
+                x[0] * (x[0] + x[1] + x[2]),
+            ],
+        }
+    }
+
+    /// Values of the inequality constraints `g_i(x) <= 0` (without bounds)
+    pub fn inequality(&self, x: &[f64]) -> Vec<f64> {
+        match self.problem {
+            HockSchittkowski::Hs6 | HockSchittkowski::Hs7 => vec![],
+            HockSchittkowski::Hs21 => vec![10.0 - 10.0 * x[0] + x[1]],
+            HockSchittkowski::Hs35 => vec![x[0] + x[1] + 2.0 * x[2] - 3.0],
+            HockSchittkowski::Hs71 => vec![25.0 - x[0] * x[1] * x[2] * x[3]],
+        }
+    }
+
+    /// Gradients of the inequality constraints (one row per constraint)
+    pub fn inequality_jacobian(&self, x: &[f64]) -> Vec<Vec<f64>> {
+        match self.problem {
+            HockSchittkowski::Hs6 | HockSchittkowski::Hs7 => vec![],
+            HockSchittkowski::Hs21 => vec![vec![-10.0, 1.0]],
+            HockSchittkowski::Hs35 => vec![vec![1.0, 1.0, 2.0]],
+            HockSchittkowski::Hs71 => vec![vec![
+                -x[1] * x[2] * x[3],
+                -x[0] * x[2] * x[3],
+                -x[0] * x[1] * x[3],
+                -x[0] * x[1] * x[2],
+            ]],
+        }
+    }
+
+    /// Values of the equality constraints `h_j(x) = 0`
+    pub fn equality(&self, x: &[f64]) -> Vec<f64> {
+        match self.problem {
+            HockSchittkowski::Hs6 => vec![10.0 * (x[1] - x[0].powi(2))],
+            HockSchittkowski::Hs7 => vec![(1.0 + x[0].powi(2)).powi(2) + x[1].powi(2) - 4.0],
+            HockSchittkowski::Hs21 | HockSchittkowski::Hs35 => vec![],
+            HockSchittkowski::Hs71 => vec![x.iter().map(|xi| xi.powi(2)).sum::<f64>() - 40.0],
+        }
+    }
+
+    /// Gradients of the equality constraints (one row per constraint)
+    pub fn equality_jacobian(&self, x: &[f64]) -> Vec<Vec<f64>> {
+        match self.problem {
+            HockSchittkowski::Hs6 => vec![vec![-20.0 * x[0], 10.0]],
+            HockSchittkowski::Hs7 => vec![vec![4.0 * x[0] * (1.0 + x[0].powi(2)), 2.0 * x[1]]],
+            HockSchittkowski::Hs21 | HockSchittkowski::Hs35 => vec![],
+            HockSchittkowski::Hs71 => vec![x.iter().map(|xi| 2.0 * xi).collect()],
+        }
+    }
+
+    /// Inequality constraints followed by the finite bounds, written as `x_i - u_i <= 0` and
+    /// `l_i - x_i <= 0` (upper before lower bound of each coordinate)
+    fn inequality_with_bounds(&self, x: &[f64]) -> Vec<f64> {
+        let mut out = self.inequality(x);
+        for (i, xi) in x.iter().enumerate() {
+            if self.upper[i].is_finite() {
+                out.push(xi - self.upper[i]);
+            }
+            if self.lower[i].is_finite() {
+                out.push(self.lower[i] - xi);
+            }
+        }
+        out
+    }
+
+    /// Jacobian of `inequality_with_bounds`
+    fn inequality_with_bounds_jacobian(&self, x: &[f64]) -> Vec<Vec<f64>> {
+        let mut out = self.inequality_jacobian(x);
+        let unit = |i: usize, v: f64| {
+            let mut e = vec![0.0; x.len()];
+            e[i] = v;
+            e
+        };
+        for i in 0..x.len() {
+            if self.upper[i].is_finite() {
+                out.push(unit(i, 1.0));
+            }
+            if self.lower[i].is_finite() {
+                out.push(unit(i, -1.0));
+            }
+        }
+        out
+    }
+
+    /// Maximum violation of all constraints and bounds at `x` (zero if `x` is feasible)
+    pub fn violation(&self, x: &[f64]) -> f64 {
+        let g = self
+            .inequality_with_bounds(x)
+            .into_iter()
+            .fold(0.0, f64::max);
+        self.equality(x).iter().fold(g, |acc, h| acc.max(h.abs()))
+    }
+
+    /// Wraps the problem in a `PenaltyOp` with penalty weight `weight` which contains all
+    /// constraints and finite bounds together with their gradients
+    pub fn penalty_op(&self, weight: f64) -> Result<PenaltyOp<ConstrainedProblem>, Error> {
+        let inequality_shift = vec![0.0; self.inequality_with_bounds(&self.start).len()];
+        let equality_shift = vec![0.0; self.equality(&self.start).len()];
+        self.shifted_penalty_op(weight, &inequality_shift, &equality_shift)
+    }
+
+    /// Like `penalty_op`, but the constraints are shifted: `g_i(x) + s_i <= 0` and
+    /// `h_j(x) + t_j = 0`. With `s_i = lambda_i / (2 * weight)` and `t_j = nu_j / (2 * weight)`,
+    /// the quadratic penalty equals the augmented Lagrangian with multipliers `lambda` and `nu` up
+    /// to a constant.
+    fn shifted_penalty_op(
+        &self,
+        weight: f64,
+        inequality_shift: &[f64],
+        equality_shift: &[f64],
+    ) -> Result<PenaltyOp<ConstrainedProblem>, Error> {
+        let mut op = PenaltyOp::new(self.clone(), weight)?;
+        for (i, &s) in inequality_shift.iter().enumerate() {
+            let (p1, p2) = (self.clone(), self.clone());
+            op = op.inequality_with_gradient(
+                move |x: &Vec<f64>| Ok(p1.inequality_with_bounds(x)[i] + s),
+                move |x: &Vec<f64>| Ok(p2.inequality_with_bounds_jacobian(x)[i].clone()),
+            );
+        }
+        for (j, &t) in equality_shift.iter().enumerate() {
+            let (p1, p2) = (self.clone(), self.clone());
+            op = op.equality_with_gradient(
+                move |x: &Vec<f64>| Ok(p1.equality(x)[j] + t),
+                move |x: &Vec<f64>| Ok(p2.equality_jacobian(x)[j].clone()),
+            );
+        }
+        Ok(op)
+    }
+}
+
+/// Returns all available Hock-Schittkowski problems
+pub fn all_hock_schittkowski() -> Vec<ConstrainedProblem> {
+    vec![
+        ConstrainedProblem::hs6(),
+        ConstrainedProblem::hs7(),
+        ConstrainedProblem::hs21(),
+        ConstrainedProblem::hs35(),
+        ConstrainedProblem::hs71(),
+    ]
+}
+
+impl ArgminOp for ConstrainedProblem {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = ();
+
+    fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+        Ok(self.objective(x))
+    }
+
+    fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        Ok(self.objective_gradient(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::landweber::Landweber;
+    use crate::testfunctions::fd::{assert_gradient, assert_jacobian};
+
+    send_sync_test!(constrained_problem, ConstrainedProblem);
+
+    #[test]
+    fn test_optima() {
+        for problem in all_hock_schittkowski() {
+            let x = problem.minimizer();
+            assert!(
+                (problem.objective(x) - problem.minimum()).abs() < 1e-7,
+                "{}",
+                problem.name()
+            );
+            assert!(problem.violation(x) < 1e-7, "{}", problem.name());
+            assert_eq!(problem.lower_bounds().len(), problem.dim());
+            assert_eq!(problem.upper_bounds().len(), problem.dim());
+        }
+        // the standard starting points of HS21 and HS71 are infeasible
+        assert!(ConstrainedProblem::hs21().violation(&[-1.0, -1.0]) > 2.9);
+        assert!(ConstrainedProblem::hs71().violation(&[1.0, 5.0, 5.0, 1.0]) > 11.9);
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        for problem in all_hock_schittkowski() {
+            let points: Vec<Vec<f64>> = vec![
+                problem.start().to_vec(),
+                problem.minimizer().to_vec(),
+                problem.start().iter().map(|x| 0.7 * x + 0.3).collect(),
+            ];
+            assert_gradient(
+                |x| problem.objective(x),
+                |x| problem.objective_gradient(x),
+                &points,
+            );
+            assert_jacobian(
+                |x| problem.inequality_with_bounds(x),
+                |x| problem.inequality_with_bounds_jacobian(x),
+                &points,
+            );
+            assert_jacobian(
+                |x| problem.equality(x),
+                |x| problem.equality_jacobian(x),
+                &points,
+            );
+        }
+    }
+
+    /// Step length of the Landweber iteration for penalty weight 1, number of inner iterations
+    /// and number of multiplier updates
+    fn settings(problem: HockSchittkowski) -> (f64, u64, usize) {
+        match problem {
+            HockSchittkowski::Hs6 => (5e-4, 20000, 10),
+            HockSchittkowski::Hs7 => (5e-4, 10000, 10),
+            HockSchittkowski::Hs21 | HockSchittkowski::Hs35 => (0.1, 2000, 20),
+            HockSchittkowski::Hs71 => (5e-4, 5000, 40),
+        }
+    }
+
+    #[test]
+    fn test_augmented_lagrangian() {
+        for problem in all_hock_schittkowski() {
+            let (omega, inner, outer) = settings(problem.problem());
+            // larger weights require smaller steps, the weight is therefore only increased for
+            // the well-conditioned problems
+            let weight = if omega > 0.01 { 10.0 } else { 1.0 };
+            let mut lambda = vec![0.0; problem.inequality_with_bounds(problem.start()).len()];
+            let mut nu = vec![0.0; problem.equality(problem.start()).len()];
+            let mut param = problem.start().to_vec();
+            for _ in 0..outer {
+                let shift = |m: &Vec<f64>| m.iter().map(|v| v / (2.0 * weight)).collect::<Vec<_>>();
+                let op = problem
+                    .shifted_penalty_op(weight, &shift(&lambda), &shift(&nu))
+                    .unwrap();
+                param = Executor::new(op, Landweber::new(omega / weight).unwrap(), param)
+                    .max_iters(inner)
+                    .run_fast()
+                    .unwrap()
+                    .param;
+                let g = problem.inequality_with_bounds(&param);
+                let h = problem.equality(&param);
+                for (l, gi) in lambda.iter_mut().zip(g.iter()) {
+                    *l = (*l + 2.0 * weight * gi).max(0.0);
+                }
+                for (n, hj) in nu.iter_mut().zip(h.iter()) {
+                    *n += 2.0 * weight * hj;
+                }
+            }
+            assert!(
+                (problem.objective(&param) - problem.minimum()).abs() < 1e-4,
+                "{}: {:?}",
+                problem.name(),
+                param
+            );
+            assert!(
+                problem.violation(&param) < 1e-6,
+                "{}: {:?}",
+                problem.name(),
+                param
+            );
+        }
+    }
+
+    #[test]
+    fn test_sequential_penalty() {
+        // the quadratic penalty only reaches a feasibility of O(1 / weight)
+        for (problem, c) in vec![
+            (ConstrainedProblem::hs7(), 5e-4),
+            (ConstrainedProblem::hs21(), 0.05),
+            (ConstrainedProblem::hs35(), 0.05),
+        ] {
+            let mut op = problem.penalty_op(1.0).unwrap();
+            let mut param = problem.start().to_vec();
+            let mut last_violation = std::f64::INFINITY;
+            for &weight in [1.0, 10.0, 100.0, 1000.0].iter() {
+                op.set_weight(weight).unwrap();
+                param = Executor::new(op.clone(), Landweber::new(c / weight).unwrap(), param)
+                    .max_iters(5000)
+                    .run_fast()
+                    .unwrap()
+                    .param;
+                let violation = problem.violation(&param);
+                assert!(violation < last_violation / 5.0, "{}", problem.name());
+                assert!(!op.is_feasible(&param, 1e-6).unwrap());
+                last_violation = violation;
+            }
+            assert!(last_violation < 5e-4, "{}", problem.name());
+            assert!(
+                (problem.objective(&param) - problem.minimum()).abs() < 1e-4,
+                "{}",
+                problem.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Cross-in-tray function
+//!
+//! `f(x_1, x_2) = -0.0001 * (|g(x_1, x_2)| + 1)^0.1`
+//!
+//! with `g(x_1, x_2) = sin(x_1) * sin(x_2) * exp(|100 - sqrt(x_1^2 + x_2^2) / pi|)`
+//!
+//! where `x_i \in [-10, 10]`.
+//!
+//! The four global minima are at `f(+-1.34941, +-1.34941) = -2.06261`.
+//!
+//! The function is not differentiable where `g` vanishes (`x_1 = k * pi` or `x_2 = k * pi`) and on
+//! the circle `sqrt(x_1^2 + x_2^2) = 100 * pi`. The derivative returns zero on the former and one
+//! of the one-sided derivatives on the latter.
+//!
+//! Inside the domain, `|g|` exceeds the range of `f32`, therefore the function is evaluated via
+//! `ln(|g|)`.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Coordinate of the four global minimizers `(+-x, +-x)`
+pub const CROSS_IN_TRAY_MINIMIZER: f64 = 1.349_406_617_153_910_7;
+
+/// Global minimum of the cross-in-tray function
+pub const CROSS_IN_TRAY_MINIMUM: f64 = -2.062_611_870_822_739;
+
+/// Cross-in-tray function
+pub fn cross_in_tray<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    match log_abs_g(param) {
+        Some(l) => -T::from_f64(0.0001).unwrap() * (T::from_f64(0.1).unwrap() * softplus(l)).exp(),
+        None => -T::from_f64(0.0001).unwrap(),
+    }
+}
+
+/// Derivative of the cross-in-tray function
+pub fn cross_in_tray_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let l = match log_abs_g(param) {
+        Some(l) => l,
+        None => return [T::zero(), T::zero()],
+    };
+    let pi = T::from_f64(PI).unwrap();
+    let f = cross_in_tray(param);
+    // `|g| / (|g| + 1)`
+    let w = T::one() / (T::one() + (-l).exp());
+    let r = x1.hypot(x2);
+    let s = (T::from_f64(100.0).unwrap() - r / pi).signum();
+    let c = T::from_f64(0.1).unwrap() * f * w;
+    [
+        c * (T::one() / x1.tan() - s * x1 / (r * pi)),
+        c * (T::one() / x2.tan() - s * x2 / (r * pi)),
+    ]
+}
+
+/// `ln(|g(x_1, x_2)|)`, or `None` if `g` vanishes
+fn log_abs_g<T: Float + FromPrimitive>(param: &[T; 2]) -> Option<T> {
+    let [x1, x2] = *param;
+    let pi = T::from_f64(PI).unwrap();
+    let s = (x1.sin() * x2.sin()).abs();
+    if s.is_zero() {
+        return None;
+    }
+    Some(s.ln() + (T::from_f64(100.0).unwrap() - x1.hypot(x2) / pi).abs())
+}
+
+/// `ln(exp(l) + 1)`
+fn softplus<T: Float>(l: T) -> T {
+    if l > T::zero() {
+        l + (-l).exp().ln_1p()
+    } else {
+        l.exp().ln_1p()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use serde::{Deserialize, Serialize};
+
+    const M: f64 = CROSS_IN_TRAY_MINIMIZER;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct CrossInTray {}
+
+    impl ArgminOp for CrossInTray {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(cross_in_tray(&arr(x)))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(cross_in_tray_derivative(&arr(x)).to_vec())
+        }
+    }
+
+    #[test]
+    fn test_minima() {
+        for m in &[[M, M], [-M, M], [M, -M], [-M, -M]] {
+            assert!((cross_in_tray(m) - CROSS_IN_TRAY_MINIMUM).abs() < 1e-14);
+            let g = cross_in_tray_derivative(m);
+            assert!(g[0].abs() < 1e-12 && g[1].abs() < 1e-12);
+            let m32 = [m[0] as f32, m[1] as f32];
+            assert!((f64::from(cross_in_tray(&m32)) - CROSS_IN_TRAY_MINIMUM).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_non_differentiable_set() {
+        for p in &[[0.0f64, 0.0], [0.0, 2.0], [-3.0, 0.0]] {
+            assert!((cross_in_tray(p) + 0.0001).abs() < std::f64::EPSILON);
+            assert!(cross_in_tray_derivative(p).iter().all(|g| g.is_zero()));
+        }
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![0.7, 2.1],
+            vec![-1.3, 0.4],
+            vec![5.0, -3.0],
+            vec![-8.0, 9.5],
+            vec![9.9, 9.9],
+        ];
+        assert_gradient(
+            |x| cross_in_tray(&arr(x)),
+            |x| cross_in_tray_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+
+    #[test]
+    fn test_multistart_finds_all_global_minima() {
+        let mut rng = XorShiftRng::seed_from_u64(429);
+        let results: Vec<Vec<f64>> = (0..40)
+            .map(|_| {
+                let init = vec![rng.gen_range(-3.0, 3.0), rng.gen_range(-3.0, 3.0)];
+                let solver = SteepestDescent::new(MoreThuenteLineSearch::new()).unwrap();
+                Executor::new(CrossInTray {}, solver, init)
+                    .max_iters(50)
+                    .run_fast()
+                    .unwrap()
+                    .param
+            })
+            .collect();
+        for m in &[[M, M], [-M, M], [M, -M], [-M, -M]] {
+            assert!(results
+                .iter()
+                .any(|r| (r[0] - m[0]).abs() < 1e-4 && (r[1] - m[1]).abs() < 1e-4));
+        }
+    }
+}
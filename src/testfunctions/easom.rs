@@ -0,0 +1,102 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Easom function
+//!
+//! `f(x_1, x_2) = - cos(x_1) * cos(x_2) * exp(-(x_1 - pi)^2 - (x_2 - pi)^2)`
+//!
+//! where `x_i \in [-100, 100]`.
+//!
+//! The global minimum is at `f(x_1, x_2) = f(pi, pi) = -1`. Away from the minimum the function
+//! is essentially flat; once the exponential underflows, both the function value and the gradient
+//! are exactly zero.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Easom function
+pub fn easom<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    -x1.cos() * x2.cos() * gaussian(param)
+}
+
+/// Derivative of the Easom function
+pub fn easom_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let e = gaussian(param);
+    // Return early to avoid computing `0 * (large)` terms far away from the minimum
+    if e.is_zero() {
+        return [T::zero(), T::zero()];
+    }
+    let pi = T::from_f64(PI).unwrap();
+    let n2 = T::from_f64(2.0).unwrap();
+    [
+        e * x2.cos() * (x1.sin() + n2 * (x1 - pi) * x1.cos()),
+        e * x1.cos() * (x2.sin() + n2 * (x2 - pi) * x2.cos()),
+    ]
+}
+
+/// `exp(-(x_1 - pi)^2 - (x_2 - pi)^2)`
+fn gaussian<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let pi = T::from_f64(PI).unwrap();
+    (-(x1 - pi).powi(2) - (x2 - pi).powi(2)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!((easom(&[PI, PI]) + 1.0).abs() < std::f64::EPSILON);
+        assert!((easom(&[std::f32::consts::PI, std::f32::consts::PI]) + 1.0).abs() < 1e-6);
+        let g = easom_derivative(&[PI, PI]);
+        assert!(g[0].abs() < 1e-15 && g[1].abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_flat_far_away() {
+        for p in &[
+            [100.0f64, 100.0],
+            [-100.0, 100.0],
+            [-100.0, -100.0],
+            [1e300, -1e300],
+        ] {
+            let f = easom(p);
+            let g = easom_derivative(p);
+            assert!(f.is_finite() && f.abs() < std::f64::EPSILON);
+            assert!(g
+                .iter()
+                .all(|gi| gi.is_finite() && gi.abs() < std::f64::EPSILON));
+        }
+        let g = easom_derivative(&[-60.0f32, 80.0]);
+        assert!(g.iter().all(|gi| gi.is_finite()));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![PI, PI],
+            vec![3.0, 3.5],
+            vec![2.0, 4.1],
+            vec![1.0, 1.0],
+            vec![5.0, -0.5],
+            vec![-20.0, 30.0],
+        ];
+        assert_gradient(
+            |x| easom(&arr(x)),
+            |x| easom_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Goldstein-Price function
+//!
+//! `f(x_1, x_2) = [1 + (x_1 + x_2 + 1)^2 * (19 - 14 * x_1 + 3 * x_1^2 - 14 * x_2 + 6 * x_1 * x_2
+//!                 + 3 * x_2^2)]
+//!              * [30 + (2 * x_1 - 3 * x_2)^2 * (18 - 32 * x_1 + 12 * x_1^2 + 48 * x_2
+//!                 - 36 * x_1 * x_2 + 27 * x_2^2)]`
+//!
+//! where `x_i \in [-2, 2]`.
+//!
+//! The global minimum is at `f(x_1, x_2) = f(0, -1) = 3`.
+
+use num::{Float, FromPrimitive};
+
+/// Goldstein-Price function
+pub fn goldstein_price<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let (a, _, _) = first_factor(param);
+    let (b, _, _) = second_factor(param);
+    a * b
+}
+
+/// Derivative of the Goldstein-Price function
+pub fn goldstein_price_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let (a, da1, da2) = first_factor(param);
+    let (b, db1, db2) = second_factor(param);
+    [da1 * b + a * db1, da2 * b + a * db2]
+}
+
+/// First factor `1 + s^2 * p` of the Goldstein-Price function and its partial derivatives
+fn first_factor<T: Float + FromPrimitive>(param: &[T; 2]) -> (T, T, T) {
+    let [x1, x2] = *param;
+    let f = |v: f64| T::from_f64(v).unwrap();
+    let s = x1 + x2 + T::one();
+    let p = f(19.0) - f(14.0) * x1 + f(3.0) * x1.powi(2) - f(14.0) * x2
+        + f(6.0) * x1 * x2
+        + f(3.0) * x2.powi(2);
+    // p is symmetric in x_1 and x_2, hence both partial derivatives coincide
+    let dp = f(-14.0) + f(6.0) * x1 + f(6.0) * x2;
+    let d = f(2.0) * s * p + s.powi(2) * dp;
+    (T::one() + s.powi(2) * p, d, d)
+}
+
+/// Second factor `30 + u^2 * q` of the Goldstein-Price function and its partial derivatives
+fn second_factor<T: Float + FromPrimitive>(param: &[T; 2]) -> (T, T, T) {
+    let [x1, x2] = *param;
+    let f = |v: f64| T::from_f64(v).unwrap();
+    let u = f(2.0) * x1 - f(3.0) * x2;
+    let q = f(18.0) - f(32.0) * x1 + f(12.0) * x1.powi(2) + f(48.0) * x2 - f(36.0) * x1 * x2
+        + f(27.0) * x2.powi(2);
+    let dq1 = f(-32.0) + f(24.0) * x1 - f(36.0) * x2;
+    let dq2 = f(48.0) - f(36.0) * x1 + f(54.0) * x2;
+    (
+        f(30.0) + u.powi(2) * q,
+        f(4.0) * u * q + u.powi(2) * dq1,
+        f(-6.0) * u * q + u.powi(2) * dq2,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!((goldstein_price(&[0.0f64, -1.0]) - 3.0).abs() < std::f64::EPSILON);
+        assert!((goldstein_price(&[0.0f32, -1.0]) - 3.0).abs() < std::f32::EPSILON);
+        let g = goldstein_price_derivative(&[0.0f64, -1.0]);
+        assert!(g[0].abs() < 1e-12 && g[1].abs() < 1e-12);
+        // (0, 0): (1 + 19) * (30 + 0)
+        assert!((goldstein_price(&[0.0f64, 0.0]) - 600.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![0.0, 0.0],
+            vec![-0.6, -0.4],
+            vec![1.2, 0.8],
+            vec![1.8, -1.9],
+            vec![-0.3, 1.1],
+        ];
+        assert_gradient(
+            |x| goldstein_price(&arr(x)),
+            |x| goldstein_price_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+}
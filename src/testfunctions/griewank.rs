@@ -0,0 +1,108 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Griewank function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = 1 + \sum_{i=1}^{n} x_i^2 / 4000 - \prod_{i=1}^{n} cos(x_i / sqrt(i))`
+//!
+//! where `x_i \in [-600, 600]`.
+//!
+//! The function has many regularly spaced local minima. The global minimum is at
+//! `f(x_1, x_2, ..., x_n) = f(0, 0, ..., 0) = 0`.
+
+use num::{Float, FromPrimitive};
+
+/// Griewank function
+pub fn griewank<T: Float + FromPrimitive>(param: &[T]) -> T {
+    let n4000 = T::from_f64(4000.0).unwrap();
+    let sum = param
+        .iter()
+        .fold(T::zero(), |acc, &x| acc + x.powi(2) / n4000);
+    let prod = param
+        .iter()
+        .enumerate()
+        .fold(T::one(), |acc, (i, &x)| acc * (x / sqrt_idx(i)).cos());
+    T::one() + sum - prod
+}
+
+/// Derivative of the Griewank function
+///
+/// The product of the cosines of all other coordinates is computed from prefix and suffix
+/// products instead of dividing the full product by `cos(x_i / sqrt(i))`, which may be zero.
+pub fn griewank_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    let n2000 = T::from_f64(2000.0).unwrap();
+    let cos: Vec<T> = param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| (x / sqrt_idx(i)).cos())
+        .collect();
+    // suffix[i] = \prod_{j > i} cos_j
+    let mut suffix = vec![T::one(); cos.len()];
+    for i in (1..cos.len()).rev() {
+        suffix[i - 1] = suffix[i] * cos[i];
+    }
+    let mut prefix = T::one();
+    param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let si = sqrt_idx(i);
+            let g = x / n2000 + (x / si).sin() / si * prefix * suffix[i];
+            prefix = prefix * cos[i];
+            g
+        })
+        .collect()
+}
+
+/// `sqrt(i + 1)` for the zero-based index `i`
+fn sqrt_idx<T: Float + FromPrimitive>(i: usize) -> T {
+    T::from_usize(i + 1).unwrap().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_minimum() {
+        assert!(griewank(&[0.0f64; 5]).abs() < std::f64::EPSILON);
+        assert!(griewank(&[0.0f32; 2]).abs() < std::f32::EPSILON);
+        assert!(griewank_derivative(&[0.0f64; 4])
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let mut rng = XorShiftRng::seed_from_u64(422);
+        for &n in &[2, 10] {
+            let points: Vec<Vec<f64>> = (0..4)
+                .map(|_| (0..n).map(|_| rng.gen_range(-600.0, 600.0)).collect())
+                .collect();
+            assert_gradient(griewank, griewank_derivative, &points);
+            let points: Vec<Vec<f64>> = (0..4)
+                .map(|_| (0..n).map(|_| rng.gen_range(-5.0, 5.0)).collect())
+                .collect();
+            assert_gradient(griewank, griewank_derivative, &points);
+        }
+    }
+
+    #[test]
+    fn test_derivative_with_vanishing_cosine() {
+        // cos(x_2 / sqrt(2)) = 0
+        let x = vec![1.3, PI / 2.0 * 2.0f64.sqrt(), -0.4];
+        let g = griewank_derivative(&x);
+        assert!(g.iter().all(|gi| gi.is_finite()));
+        assert_gradient(griewank, griewank_derivative, &[x]);
+    }
+}
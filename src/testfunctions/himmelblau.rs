@@ -0,0 +1,153 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Himmelblau's function
+//!
+//! `f(x_1, x_2) = (x_1^2 + x_2 - 11)^2 + (x_1 + x_2^2 - 7)^2`
+//!
+//! The function has four global minima with `f(x_1, x_2) = 0` at
+//!
+//! * `(x_1, x_2) = (3, 2)`
+//! * `(x_1, x_2) = (-2.805118, 3.131312)`
+//! * `(x_1, x_2) = (-3.779310, -3.283186)`
+//! * `(x_1, x_2) = (3.584428, -1.848126)`
+//!
+//! # References:
+//!
+//! [0] David M. Himmelblau (1972). Applied Nonlinear Programming. McGraw-Hill.
+
+use num::{Float, FromPrimitive};
+
+/// Himmelblau's function
+pub fn himmelblau<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let n7 = T::from_f64(7.0).unwrap();
+    let n11 = T::from_f64(11.0).unwrap();
+    (x1.powi(2) + x2 - n11).powi(2) + (x1 + x2.powi(2) - n7).powi(2)
+}
+
+/// Derivative of Himmelblau's function
+pub fn himmelblau_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    let n4 = T::from_f64(4.0).unwrap();
+    let n7 = T::from_f64(7.0).unwrap();
+    let n11 = T::from_f64(11.0).unwrap();
+    let a = x1.powi(2) + x2 - n11;
+    let b = x1 + x2.powi(2) - n7;
+    [n4 * x1 * a + n2 * b, n2 * a + n4 * x2 * b]
+}
+
+/// Hessian of Himmelblau's function
+pub fn himmelblau_hessian<T: Float + FromPrimitive>(param: &[T; 2]) -> [[T; 2]; 2] {
+    let [x1, x2] = *param;
+    let n4 = T::from_f64(4.0).unwrap();
+    let n12 = T::from_f64(12.0).unwrap();
+    let n26 = T::from_f64(26.0).unwrap();
+    let n42 = T::from_f64(42.0).unwrap();
+    let offdiag = n4 * (x1 + x2);
+    [
+        [n12 * x1.powi(2) + n4 * x2 - n42, offdiag],
+        [offdiag, n12 * x2.powi(2) + n4 * x1 - n26],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::solver::newton::Newton;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+    use serde::{Deserialize, Serialize};
+
+    const MINIMA: [[f64; 2]; 4] = [
+        [3.0, 2.0],
+        [-2.805_118, 3.131_312],
+        [-3.779_310, -3.283_186],
+        [3.584_428, -1.848_126],
+    ];
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minima() {
+        for m in MINIMA.iter() {
+            assert!(himmelblau(m) < 1e-10);
+            assert!(himmelblau(&[m[0] as f32, m[1] as f32]) < 1e-4);
+        }
+        assert!((himmelblau(&[0.0f64, 0.0]) - 170.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        let points = vec![
+            vec![0.0, 0.0],
+            vec![1.0, -2.0],
+            vec![-4.5, 3.3],
+            vec![2.2, 4.1],
+            vec![-0.3, -0.2],
+        ];
+        assert_gradient(
+            |x| himmelblau(&arr(x)),
+            |x| himmelblau_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+        assert_hessian(
+            |x| himmelblau_derivative(&arr(x)).to_vec(),
+            |x| {
+                himmelblau_hessian(&arr(x))
+                    .iter()
+                    .map(|r| r.to_vec())
+                    .collect()
+            },
+            &points,
+        );
+        for m in MINIMA.iter() {
+            let g = himmelblau_derivative(m);
+            assert!(g[0].abs() < 1e-3 && g[1].abs() < 1e-3);
+        }
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Himmelblau {}
+
+    impl ArgminOp for Himmelblau {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(himmelblau(&arr(p)))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(himmelblau_derivative(&arr(p)).to_vec())
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(himmelblau_hessian(&arr(p))
+                .iter()
+                .map(|r| r.to_vec())
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_newton_finds_all_minima() {
+        for m in MINIMA.iter() {
+            let init = vec![m[0] + 0.2, m[1] - 0.2];
+            let res = Executor::new(Himmelblau {}, Newton::new(), init)
+                .max_iters(20)
+                .run_fast()
+                .unwrap();
+            assert!((res.param[0] - m[0]).abs() < 1e-5);
+            assert!((res.param[1] - m[1]).abs() < 1e-5);
+        }
+    }
+}
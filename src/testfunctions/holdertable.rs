@@ -0,0 +1,96 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Holder table function
+//!
+//! `f(x_1, x_2) = -|sin(x_1) * cos(x_2) * exp(|1 - sqrt(x_1^2 + x_2^2) / pi|)|`
+//!
+//! where `x_i \in [-10, 10]`.
+//!
+//! The four global minima are at `f(+-8.05502, +-9.66459) = -19.2085`.
+//!
+//! The function is not differentiable where the term inside the absolute value vanishes
+//! (`x_1 = k * pi` or `x_2 = pi / 2 + k * pi`) and on the circle `sqrt(x_1^2 + x_2^2) = pi`. The
+//! derivative returns one of the one-sided derivatives there (and zero at the origin).
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Global minimizers of the Holder table function
+pub const HOLDER_TABLE_MINIMIZERS: [[f64; 2]; 4] = [
+    [8.055_023_475_736_563, 9.664_590_019_241_272],
+    [-8.055_023_475_736_563, 9.664_590_019_241_272],
+    [8.055_023_475_736_563, -9.664_590_019_241_272],
+    [-8.055_023_475_736_563, -9.664_590_019_241_272],
+];
+
+/// Global minimum of the Holder table function
+pub const HOLDER_TABLE_MINIMUM: f64 = -19.208_502_567_886_732;
+
+/// Holder table function
+pub fn holder_table<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let pi = T::from_f64(PI).unwrap();
+    -(x1.sin() * x2.cos() * (T::one() - x1.hypot(x2) / pi).abs().exp()).abs()
+}
+
+/// Derivative of the Holder table function
+pub fn holder_table_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let r = x1.hypot(x2);
+    if r.is_zero() {
+        return [T::zero(), T::zero()];
+    }
+    let pi = T::from_f64(PI).unwrap();
+    let e = (T::one() - r / pi).abs().exp();
+    let h = x1.sin() * x2.cos() * e;
+    // derivative of `|1 - r / pi|` is `-s * x_i / (r * pi)`
+    let s = (T::one() - r / pi).signum();
+    let sh = h.signum();
+    [
+        -sh * (x1.cos() * x2.cos() * e - h * s * x1 / (r * pi)),
+        -sh * (-x1.sin() * x2.sin() * e - h * s * x2 / (r * pi)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minima() {
+        for m in &HOLDER_TABLE_MINIMIZERS {
+            assert!((holder_table(m) - HOLDER_TABLE_MINIMUM).abs() < 1e-13);
+            let g = holder_table_derivative(m);
+            assert!(g[0].abs() < 1e-12 && g[1].abs() < 1e-12);
+        }
+        assert!(holder_table_derivative(&[0.0f64, 0.0])
+            .iter()
+            .all(|g| g.is_zero()));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![0.7, 2.1],
+            vec![-1.3, 0.4],
+            vec![5.0, -3.0],
+            vec![-8.0, 9.5],
+            vec![9.9, 9.9],
+        ];
+        assert_gradient(
+            |x| holder_table(&arr(x)),
+            |x| holder_table_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Nonlinear least squares problems
+//!
+//! Test problems in residual form `f(x) = 1/2 * ||r(x)||^2` for Gauss-Newton type methods. Each
+//! problem provides the residuals `r(x)` and the Jacobian `J(x)` with `J_ij = d r_i / d x_j`.
+//!
+//! * Powell's singular function: `r(x) = (x_1 + 10 x_2, sqrt(5) (x_3 - x_4), (x_2 - 2 x_3)^2,
+//!   sqrt(10) (x_1 - x_4)^2)`. The minimizer is `x = (0, 0, 0, 0)` with `||r|| = 0`; the
+//!   Jacobian is singular there. The standard starting point is `(3, -1, 0, 1)`.
+//! * Beale's problem: `r_i(x) = c_i - x_1 (1 - x_2^i)` with `c = (1.5, 2.25, 2.625)`. The minimizer
+//!   is `x = (3, 0.5)` with `||r|| = 0`. The standard starting point is `(1, 1)`.
+//! * Exponential decay fit: `r_i(x) = x_1 exp(-x_2 t_i) - y_i` for data generated by
+//!   `exponential_decay_data`. The minimizer is the pair `(a, b)` the data was generated with and
+//!   `||r|| = 0`.
+//!
+//! # References:
+//!
+//! [0] Jorge J. Moré, Burton S. Garbow, Kenneth E. Hillstrom (1981). Testing Unconstrained
+//! Optimization Software. ACM Transactions on Mathematical Software 7 (1): 17–41.
+
+use num::{Float, FromPrimitive};
+
+/// Standard starting point of Powell's singular function
+pub const POWELL_SINGULAR_START: [f64; 4] = [3.0, -1.0, 0.0, 1.0];
+
+/// Standard starting point of Beale's problem
+pub const BEALE_RESIDUALS_START: [f64; 2] = [1.0, 1.0];
+
+/// Residuals of Powell's singular function
+pub fn powell_singular_residuals<T: Float + FromPrimitive>(param: &[T; 4]) -> [T; 4] {
+    let [x1, x2, x3, x4] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    [
+        x1 + T::from_f64(10.0).unwrap() * x2,
+        T::from_f64(5.0).unwrap().sqrt() * (x3 - x4),
+        (x2 - n2 * x3).powi(2),
+        T::from_f64(10.0).unwrap().sqrt() * (x1 - x4).powi(2),
+    ]
+}
+
+/// Jacobian of the residuals of Powell's singular function
+pub fn powell_singular_jacobian<T: Float + FromPrimitive>(param: &[T; 4]) -> [[T; 4]; 4] {
+    let [x1, x2, x3, x4] = *param;
+    let zero = T::zero();
+    let n2 = T::from_f64(2.0).unwrap();
+    let s5 = T::from_f64(5.0).unwrap().sqrt();
+    let d3 = n2 * (x2 - n2 * x3);
+    let d4 = n2 * T::from_f64(10.0).unwrap().sqrt() * (x1 - x4);
+    [
+        [T::one(), T::from_f64(10.0).unwrap(), zero, zero],
+        [zero, zero, s5, -s5],
+        [zero, d3, -n2 * d3, zero],
+        [d4, zero, zero, -d4],
+    ]
+}
+
+/// Residuals of Beale's problem
+pub fn beale_residuals<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 3] {
+    let [x1, x2] = *param;
+    let one = T::one();
+    [
+        T::from_f64(1.5).unwrap() - x1 * (one - x2),
+        T::from_f64(2.25).unwrap() - x1 * (one - x2.powi(2)),
+        T::from_f64(2.625).unwrap() - x1 * (one - x2.powi(3)),
+    ]
+}
+
+/// Jacobian of the residuals of Beale's problem
+pub fn beale_jacobian<T: Float + FromPrimitive>(param: &[T; 2]) -> [[T; 2]; 3] {
+    let [x1, x2] = *param;
+    let one = T::one();
+    let n2 = T::from_f64(2.0).unwrap();
+    let n3 = T::from_f64(3.0).unwrap();
+    [
+        [x2 - one, x1],
+        [x2.powi(2) - one, n2 * x1 * x2],
+        [x2.powi(3) - one, n3 * x1 * x2.powi(2)],
+    ]
+}
+
+/// Generates `n` samples `(t_i, y_i)` of `y = a * exp(-b * t)` at `t_i = i / 2`
+pub fn exponential_decay_data<T: Float + FromPrimitive>(a: T, b: T, n: usize) -> (Vec<T>, Vec<T>) {
+    let t: Vec<T> = (0..n)
+        .map(|i| T::from_usize(i).unwrap() / T::from_f64(2.0).unwrap())
+        .collect();
+    let y = t.iter().map(|&ti| a * (-b * ti).exp()).collect();
+    (t, y)
+}
+
+/// Residuals `x_1 * exp(-x_2 * t_i) - y_i` of the exponential decay fit
+///
+/// Panics if `t` and `y` differ in length.
+pub fn exponential_decay_residuals<T: Float + FromPrimitive>(
+    param: &[T; 2],
+    t: &[T],
+    y: &[T],
+) -> Vec<T> {
+    assert_eq!(
+        t.len(),
+        y.len(),
+        "exponential_decay_residuals: t and y differ in length"
+    );
+    let [a, b] = *param;
+    t.iter()
+        .zip(y.iter())
+        .map(|(&ti, &yi)| a * (-b * ti).exp() - yi)
+        .collect()
+}
+
+/// Jacobian of the residuals of the exponential decay fit (one row per sample)
+pub fn exponential_decay_jacobian<T: Float + FromPrimitive>(
+    param: &[T; 2],
+    t: &[T],
+) -> Vec<[T; 2]> {
+    let [a, b] = *param;
+    t.iter()
+        .map(|&ti| {
+            let e = (-b * ti).exp();
+            [e, -a * ti * e]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_jacobian;
+
+    fn norm(r: &[f64]) -> f64 {
+        r.iter().map(|ri| ri.powi(2)).sum::<f64>().sqrt()
+    }
+
+    #[test]
+    fn test_powell_singular() {
+        assert!(norm(&powell_singular_residuals(&[0.0; 4])) < std::f64::EPSILON);
+        // ||r(x_0)||^2 = 215 at the standard starting point
+        let r = powell_singular_residuals(&POWELL_SINGULAR_START);
+        assert!((norm(&r).powi(2) - 215.0).abs() < 1e-12);
+        // the Jacobian at the minimizer has rank 2
+        let j = powell_singular_jacobian(&[0.0f64; 4]);
+        assert!(j[2]
+            .iter()
+            .chain(j[3].iter())
+            .all(|x| x.abs() < std::f64::EPSILON));
+        let points = vec![
+            POWELL_SINGULAR_START.to_vec(),
+            vec![0.5, -0.2, 1.3, -0.7],
+            vec![1e-3, 2e-3, -1e-3, 3e-3],
+        ];
+        assert_jacobian(
+            |x| powell_singular_residuals(&[x[0], x[1], x[2], x[3]]).to_vec(),
+            |x| {
+                powell_singular_jacobian(&[x[0], x[1], x[2], x[3]])
+                    .iter()
+                    .map(|r| r.to_vec())
+                    .collect()
+            },
+            &points,
+        );
+    }
+
+    #[test]
+    fn test_beale() {
+        assert!(norm(&beale_residuals(&[3.0, 0.5])) < std::f64::EPSILON);
+        // consistent with the scalar Beale function
+        let x = [1.3, -0.4];
+        let r = beale_residuals(&x);
+        assert!((norm(&r).powi(2) - crate::testfunctions::beale(&x)).abs() < 1e-12);
+        let points = vec![
+            BEALE_RESIDUALS_START.to_vec(),
+            vec![3.0, 0.5],
+            vec![-2.0, 1.7],
+        ];
+        assert_jacobian(
+            |x| beale_residuals(&[x[0], x[1]]).to_vec(),
+            |x| {
+                beale_jacobian(&[x[0], x[1]])
+                    .iter()
+                    .map(|r| r.to_vec())
+                    .collect()
+            },
+            &points,
+        );
+    }
+
+    #[test]
+    fn test_exponential_decay() {
+        let (t, y) = exponential_decay_data(2.5, 0.7, 10);
+        assert_eq!(t.len(), 10);
+        assert!((t[3] - 1.5).abs() < std::f64::EPSILON);
+        assert!(norm(&exponential_decay_residuals(&[2.5, 0.7], &t, &y)) < 1e-15);
+        assert!(norm(&exponential_decay_residuals(&[2.0, 0.7], &t, &y)) > 0.1);
+        let points = vec![vec![2.5, 0.7], vec![1.0, 0.1], vec![-3.0, 1.5]];
+        assert_jacobian(
+            |x| exponential_decay_residuals(&[x[0], x[1]], &t, &y),
+            |x| {
+                exponential_decay_jacobian(&[x[0], x[1]], &t)
+                    .iter()
+                    .map(|r| r.to_vec())
+                    .collect()
+            },
+            &points,
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Levy function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = sin^2(pi * w_1)
+//!     + \sum_{i=1}^{n-1} (w_i - 1)^2 * [1 + 10 * sin^2(pi * w_i + 1)]
+//!     + (w_n - 1)^2 * [1 + sin^2(2 * pi * w_n)]`
+//!
+//! where `w_i = 1 + (x_i - 1) / 4` and `x_i \in [-10, 10]`.
+//!
+//! The global minimum is at `f(x_1, x_2, ..., x_n) = f(1, 1, ..., 1) = 0`.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Levy function
+///
+/// Panics if `param` is empty.
+pub fn levy<T: Float + FromPrimitive>(param: &[T]) -> T {
+    assert!(!param.is_empty(), "levy: param must not be empty");
+    let one = T::one();
+    let n2 = T::from_f64(2.0).unwrap();
+    let n10 = T::from_f64(10.0).unwrap();
+    let pi = T::from_f64(PI).unwrap();
+    let w: Vec<T> = param.iter().map(|&x| w_of(x)).collect();
+    let (&wn, rest) = w.split_last().unwrap();
+    (pi * w[0]).sin().powi(2)
+        + rest
+            .iter()
+            .map(|&wi| (wi - one).powi(2) * (one + n10 * (pi * wi + one).sin().powi(2)))
+            .fold(T::zero(), |acc, x| acc + x)
+        + (wn - one).powi(2) * (one + (n2 * pi * wn).sin().powi(2))
+}
+
+/// Derivative of the Levy function
+///
+/// Panics if `param` is empty.
+pub fn levy_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    assert!(
+        !param.is_empty(),
+        "levy_derivative: param must not be empty"
+    );
+    let one = T::one();
+    let n2 = T::from_f64(2.0).unwrap();
+    let n10 = T::from_f64(10.0).unwrap();
+    let pi = T::from_f64(PI).unwrap();
+    // dw_i / dx_i
+    let dw = T::from_f64(0.25).unwrap();
+    let n = param.len();
+    let mut grad: Vec<T> = param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let w = w_of(x);
+            if i < n - 1 {
+                let s = pi * w + one;
+                n2 * (w - one) * (one + n10 * s.sin().powi(2))
+                    + (w - one).powi(2) * n10 * pi * (n2 * s).sin()
+            } else {
+                let s = n2 * pi * w;
+                n2 * (w - one) * (one + s.sin().powi(2))
+                    + (w - one).powi(2) * n2 * pi * (n2 * s).sin()
+            }
+        })
+        .collect();
+    grad[0] = grad[0] + pi * (n2 * pi * w_of(param[0])).sin();
+    grad.iter().map(|&g| g * dw).collect()
+}
+
+/// `w = 1 + (x - 1) / 4`
+fn w_of<T: Float + FromPrimitive>(x: T) -> T {
+    T::one() + (x - T::one()) / T::from_f64(4.0).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_minimum() {
+        assert!(levy(&[1.0f64; 4]).abs() < std::f64::EPSILON);
+        assert!(levy(&[1.0f32; 3]).abs() < std::f32::EPSILON);
+        assert!(levy(&[1.0f64]).abs() < std::f64::EPSILON);
+        assert!(levy_derivative(&[1.0f64; 5])
+            .iter()
+            .all(|g| g.abs() < 1e-15));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences_4d() {
+        let mut rng = XorShiftRng::seed_from_u64(418);
+        let points: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..4).map(|_| rng.gen_range(-10.0, 10.0)).collect())
+            .collect();
+        assert_gradient(levy, levy_derivative, &points);
+        assert_gradient(levy, levy_derivative, &[vec![-3.5], vec![2.0, 0.0]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty() {
+        levy::<f64>(&[]);
+    }
+}
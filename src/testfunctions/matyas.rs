@@ -0,0 +1,70 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Matyas function
+//!
+//! `f(x_1, x_2) = 0.26 * (x_1^2 + x_2^2) - 0.48 * x_1 * x_2`
+//!
+//! where `x_i \in [-10, 10]`.
+//!
+//! The global minimum is at `f(x_1, x_2) = f(0, 0) = 0`.
+
+use num::{Float, FromPrimitive};
+
+/// Matyas function
+pub fn matyas<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    T::from_f64(0.26).unwrap() * (x1.powi(2) + x2.powi(2)) - T::from_f64(0.48).unwrap() * x1 * x2
+}
+
+/// Derivative of the Matyas function
+pub fn matyas_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let a = T::from_f64(0.52).unwrap();
+    let b = T::from_f64(0.48).unwrap();
+    [a * x1 - b * x2, a * x2 - b * x1]
+}
+
+/// Hessian of the Matyas function
+pub fn matyas_hessian<T: Float + FromPrimitive>(_param: &[T; 2]) -> [[T; 2]; 2] {
+    let a = T::from_f64(0.52).unwrap();
+    let b = T::from_f64(0.48).unwrap();
+    [[a, -b], [-b, a]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!(matyas(&[0.0f64, 0.0]).abs() < std::f64::EPSILON);
+        assert!(matyas(&[0.0f32, 0.0]).abs() < std::f32::EPSILON);
+        // the valley along x_1 = x_2 is very flat
+        assert!((matyas(&[1.0f64, 1.0]) - 0.04).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        let points = vec![vec![1.0, 1.0], vec![-3.0, 7.5], vec![9.0, -0.5]];
+        assert_gradient(
+            |x| matyas(&arr(x)),
+            |x| matyas_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+        assert_hessian(
+            |x| matyas_derivative(&arr(x)).to_vec(),
+            |x| matyas_hessian(&arr(x)).iter().map(|r| r.to_vec()).collect(),
+            &points,
+        );
+    }
+}
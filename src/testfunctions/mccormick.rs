@@ -0,0 +1,97 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # McCormick function
+//!
+//! `f(x_1, x_2) = sin(x_1 + x_2) + (x_1 - x_2)^2 - 1.5 * x_1 + 2.5 * x_2 + 1`
+//!
+//! where `x_1 \in [-1.5, 4]` and `x_2 \in [-3, 4]`.
+//!
+//! The global minimum on this domain is at `f(x_1, x_2) = f(1/2 - pi/3, -1/2 - pi/3) =
+//! -sqrt(3)/2 - pi/3 ≈ -1.9133`. Outside of the domain the function is unbounded below.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Lower bounds of the standard search domain
+pub const MCCORMICK_LOWER_BOUNDS: [f64; 2] = [-1.5, -3.0];
+
+/// Upper bounds of the standard search domain
+pub const MCCORMICK_UPPER_BOUNDS: [f64; 2] = [4.0, 4.0];
+
+/// Global minimizer on the standard search domain
+pub const MCCORMICK_MINIMIZER: [f64; 2] = [0.5 - PI / 3.0, -0.5 - PI / 3.0];
+
+/// Global minimum on the standard search domain
+pub const MCCORMICK_MINIMUM: f64 = -1.913_222_954_981_036;
+
+/// McCormick function
+pub fn mccormick<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    (x1 + x2).sin() + (x1 - x2).powi(2) - T::from_f64(1.5).unwrap() * x1
+        + T::from_f64(2.5).unwrap() * x2
+        + T::one()
+}
+
+/// Derivative of the McCormick function
+pub fn mccormick_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let c = (x1 + x2).cos();
+    let d = T::from_f64(2.0).unwrap() * (x1 - x2);
+    [
+        c + d - T::from_f64(1.5).unwrap(),
+        c - d + T::from_f64(2.5).unwrap(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[test]
+    fn test_minimum() {
+        assert!((mccormick(&MCCORMICK_MINIMIZER) - MCCORMICK_MINIMUM).abs() < 1e-14);
+        let g = mccormick_derivative(&MCCORMICK_MINIMIZER);
+        assert!(g[0].abs() < 1e-14 && g[1].abs() < 1e-14);
+        let feasible = MCCORMICK_MINIMIZER
+            .iter()
+            .zip(
+                MCCORMICK_LOWER_BOUNDS
+                    .iter()
+                    .zip(MCCORMICK_UPPER_BOUNDS.iter()),
+            )
+            .all(|(x, (l, u))| l <= x && x <= u);
+        assert!(feasible);
+        // the corners of the domain are worse than the minimum
+        for &x1 in &[MCCORMICK_LOWER_BOUNDS[0], MCCORMICK_UPPER_BOUNDS[0]] {
+            for &x2 in &[MCCORMICK_LOWER_BOUNDS[1], MCCORMICK_UPPER_BOUNDS[1]] {
+                assert!(mccormick(&[x1, x2]) > MCCORMICK_MINIMUM);
+            }
+        }
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![0.0, 0.0],
+            vec![-1.5, -3.0],
+            vec![4.0, 4.0],
+            vec![2.2, -1.3],
+            vec![-0.7, 3.1],
+        ];
+        assert_gradient(
+            |x| mccormick(&arr(x)),
+            |x| mccormick_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Michalewicz function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = - \sum_{i=1}^{n} sin(x_i) * sin^{2m}(i * x_i^2 / pi)`
+//!
+//! where `x_i \in [0, pi]`. The steepness `m` controls how narrow the valleys are and therefore
+//! how difficult the function is; the usual choice is `m = 10`. For non-integer `m` the power is
+//! applied to `|sin(i * x_i^2 / pi)|`.
+//!
+//! For `m = 10` the global minima are approximately
+//!
+//! * `-1.8013` for `n = 2` at `(2.20, 1.57)`
+//! * `-4.687658` for `n = 5`
+//! * `-9.66015` for `n = 10`
+//!
+//! (see `MICHALEWICZ_MINIMUM_2D`, `MICHALEWICZ_MINIMUM_5D` and `MICHALEWICZ_MINIMUM_10D`).
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Global minimum in 2 dimensions for `m = 10`
+pub const MICHALEWICZ_MINIMUM_2D: f64 = -1.801_303_410_098_550_8;
+
+/// Global minimum in 5 dimensions for `m = 10`
+pub const MICHALEWICZ_MINIMUM_5D: f64 = -4.687_658_179_088_144;
+
+/// Global minimum in 10 dimensions for `m = 10`
+pub const MICHALEWICZ_MINIMUM_10D: f64 = -9.660_151_715_641_337;
+
+/// Michalewicz function with steepness `m`
+pub fn michalewicz<T: Float + FromPrimitive>(param: &[T], m: T) -> T {
+    let two_m = T::from_f64(2.0).unwrap() * m;
+    param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| -x.sin() * inner(i, x).sin().abs().powf(two_m))
+        .fold(T::zero(), |acc, x| acc + x)
+}
+
+/// Derivative of the Michalewicz function with steepness `m`
+///
+/// The function is differentiable for `m > 1/2`. The power of the sine is computed as
+/// `|s|^(2m - 1) * sign(s)`, which is zero (and not `0 * inf`) where `s` vanishes, in particular
+/// at `x_i = 0`.
+pub fn michalewicz_derivative<T: Float + FromPrimitive>(param: &[T], m: T) -> Vec<T> {
+    let n2 = T::from_f64(2.0).unwrap();
+    let two_m = n2 * m;
+    let pi = T::from_f64(PI).unwrap();
+    param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let u = inner(i, x);
+            let s = u.sin();
+            // d/dx_i (i * x_i^2 / pi)
+            let du = n2 * T::from_usize(i + 1).unwrap() * x / pi;
+            let ds_pow = if s.is_zero() {
+                T::zero()
+            } else {
+                two_m * s.abs().powf(two_m - T::one()) * s.signum() * u.cos() * du
+            };
+            -(x.cos() * s.abs().powf(two_m) + x.sin() * ds_pow)
+        })
+        .collect()
+}
+
+/// `i * x_i^2 / pi` for the zero-based index `i`
+fn inner<T: Float + FromPrimitive>(i: usize, x: T) -> T {
+    T::from_usize(i + 1).unwrap() * x.powi(2) / T::from_f64(PI).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use serde::{Deserialize, Serialize};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_minima() {
+        assert!(
+            (michalewicz(&[2.202_906f64, 1.570_796], 10.0) - MICHALEWICZ_MINIMUM_2D).abs() < 1e-9
+        );
+        // the function is separable, the minimizers of the first coordinates do not change
+        let x5 = [2.202_906, 1.570_796, 1.284_992, 1.923_058, 1.720_470];
+        assert!((michalewicz(&x5, 10.0) - MICHALEWICZ_MINIMUM_5D).abs() < 1e-9);
+        let x10 = [
+            2.202_906, 1.570_796, 1.284_992, 1.923_058, 1.720_470, 1.570_796, 1.454_414, 1.756_087,
+            1.655_717, 1.570_796,
+        ];
+        assert!((michalewicz(&x10, 10.0) - MICHALEWICZ_MINIMUM_10D).abs() < 1e-9);
+        let g = michalewicz_derivative(&x5, 10.0);
+        assert!(g.iter().all(|gi| gi.abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let mut rng = XorShiftRng::seed_from_u64(424);
+        let points: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..5).map(|_| rng.gen_range(0.0, PI)).collect())
+            .collect();
+        for &m in &[1.0, 2.5, 10.0] {
+            assert_gradient(
+                |x| michalewicz(x, m),
+                |x| michalewicz_derivative(x, m),
+                &points,
+            );
+        }
+    }
+
+    #[test]
+    fn test_derivative_at_zero() {
+        for &m in &[0.75, 1.0, 10.0] {
+            let g = michalewicz_derivative(&[0.0f64, 1.0, 0.0], m);
+            assert!(g.iter().all(|gi| gi.is_finite()));
+            assert!(g[0].abs() < std::f64::EPSILON && g[2].abs() < std::f64::EPSILON);
+            let g = michalewicz_derivative(&[0.0f32, 0.0], m as f32);
+            assert!(g.iter().all(|gi| gi.is_finite()));
+        }
+    }
+
+    /// Michalewicz function with `m = 10` in 5D, perturbed by single Gaussian steps
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Michalewicz {
+        rng: Arc<Mutex<XorShiftRng>>,
+    }
+
+    impl ArgminOp for Michalewicz {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(michalewicz(p, 10.0))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            p.modify(
+                &mut *rng,
+                Some(&vec![0.0; 5]),
+                Some(&vec![PI; 5]),
+                Perturbation::Gaussian(0.3),
+                Components::default(),
+                &Unconstrained,
+            )
+        }
+    }
+
+    #[test]
+    fn test_sa_5d() {
+        let op = Michalewicz {
+            rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(424))),
+        };
+        let solver = SimulatedAnnealing::new(1.0).unwrap().seed(424);
+        let res = Executor::new(op, solver, vec![1.5; 5])
+            .max_iters(20000)
+            .run_fast()
+            .unwrap();
+        assert!(res.cost < MICHALEWICZ_MINIMUM_5D + 0.1);
+    }
+}
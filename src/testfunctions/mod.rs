@@ -0,0 +1,285 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Testfunctions
+//!
+//! Reexport of `argmin-testfunctions`, complemented by test functions with analytic derivatives
+//! (and Hessians where useful) which are defined in this crate. The functions defined here take
+//! precedence over functions of the same name in `argmin-testfunctions`.
+//!
+//! All functions defined in this crate are generic over the float type (`T: Float +
+//! FromPrimitive`), therefore they can be used with `f32` as well as `f64` parameter vectors.
+//! Constants are converted with `T::from_f64`. Functions which are only available via the
+//! reexport of `argmin-testfunctions` work on `f64` only.
+//!
+//! * [Ackley](ackley/index.html)
+//! * [Beale](beale/index.html)
+//! * [Booth](booth/index.html)
+//! * [Constrained problems (Hock-Schittkowski)](constrained/index.html)
+//! * [Cross-in-tray](crossintray/index.html)
+//! * [Easom](easom/index.html)
+//! * [Goldstein-Price](goldsteinprice/index.html)
+//! * [Griewank](griewank/index.html)
+//! * [Himmelblau](himmelblau/index.html)
+//! * [Holder table](holdertable/index.html)
+//! * [Least squares problems](least_squares/index.html)
+//! * [Levy](levy/index.html)
+//! * [Matyas](matyas/index.html)
+//! * [McCormick](mccormick/index.html)
+//! * [Michalewicz](michalewicz/index.html)
+//! * [Rastrigin](rastrigin/index.html)
+//! * [Rosenbrock](rosenbrock/index.html)
+//! * [Schaffer N.2 and N.4](schaffer/index.html)
+//! * [Six-hump camel](sixhumpcamel/index.html)
+//! * [Sphere](sphere/index.html)
+//! * [Styblinski-Tang](styblinskitang/index.html)
+//! * [Zakharov](zakharov/index.html)
+//!
+//! [Test problems](problem/index.html) bundle a function with its derivatives, search domain,
+//! standard starting point and known optimum and can be passed to solvers directly.
+
+pub use argmin_testfunctions::*;
+
+/// Ackley function
+pub mod ackley;
+/// Beale function
+pub mod beale;
+/// Booth function
+pub mod booth;
+/// Constrained test problems from the Hock-Schittkowski collection
+pub mod constrained;
+/// Cross-in-tray function
+pub mod crossintray;
+/// Easom function
+pub mod easom;
+/// Goldstein-Price function
+pub mod goldsteinprice;
+/// Griewank function
+pub mod griewank;
+/// Himmelblau's function
+pub mod himmelblau;
+/// Holder table function
+pub mod holdertable;
+/// Nonlinear least squares problems in residual form
+pub mod least_squares;
+/// Levy function
+pub mod levy;
+/// Matyas function
+pub mod matyas;
+/// McCormick function
+pub mod mccormick;
+/// Michalewicz function
+pub mod michalewicz;
+/// Test problems with known optima
+pub mod problem;
+/// Rastrigin function
+pub mod rastrigin;
+/// Rosenbrock function
+pub mod rosenbrock;
+/// Schaffer functions N.2 and N.4
+pub mod schaffer;
+/// Six-hump camel function
+pub mod sixhumpcamel;
+/// Sphere function
+pub mod sphere;
+/// Styblinski-Tang function
+pub mod styblinskitang;
+/// Zakharov function
+pub mod zakharov;
+
+pub use self::ackley::{ackley, ackley_derivative, ackley_param, ackley_param_derivative};
+pub use self::beale::{beale, beale_derivative, beale_hessian};
+pub use self::booth::{booth, booth_derivative, booth_hessian};
+pub use self::crossintray::{
+    cross_in_tray, cross_in_tray_derivative, CROSS_IN_TRAY_MINIMIZER, CROSS_IN_TRAY_MINIMUM,
+};
+pub use self::easom::{easom, easom_derivative};
+pub use self::goldsteinprice::{goldstein_price, goldstein_price_derivative};
+pub use self::griewank::{griewank, griewank_derivative};
+pub use self::himmelblau::{himmelblau, himmelblau_derivative, himmelblau_hessian};
+pub use self::holdertable::{
+    holder_table, holder_table_derivative, HOLDER_TABLE_MINIMIZERS, HOLDER_TABLE_MINIMUM,
+};
+pub use self::levy::{levy, levy_derivative};
+pub use self::matyas::{matyas, matyas_derivative, matyas_hessian};
+pub use self::mccormick::{
+    mccormick, mccormick_derivative, MCCORMICK_LOWER_BOUNDS, MCCORMICK_MINIMIZER,
+    MCCORMICK_MINIMUM, MCCORMICK_UPPER_BOUNDS,
+};
+pub use self::michalewicz::{
+    michalewicz, michalewicz_derivative, MICHALEWICZ_MINIMUM_10D, MICHALEWICZ_MINIMUM_2D,
+    MICHALEWICZ_MINIMUM_5D,
+};
+pub use self::rastrigin::{rastrigin, rastrigin_a, rastrigin_a_derivative, rastrigin_derivative};
+pub use self::rosenbrock::{
+    rosenbrock, rosenbrock_2d, rosenbrock_2d_derivative, rosenbrock_2d_hessian,
+    rosenbrock_derivative, rosenbrock_hessian,
+};
+pub use self::schaffer::{
+    schaffer_n2, schaffer_n2_derivative, schaffer_n4, schaffer_n4_derivative,
+    SCHAFFER_N4_MINIMIZER, SCHAFFER_N4_MINIMUM,
+};
+pub use self::sixhumpcamel::{
+    sixhump_camel, sixhump_camel_derivative, SIXHUMP_CAMEL_LOWER_BOUNDS, SIXHUMP_CAMEL_MINIMIZERS,
+    SIXHUMP_CAMEL_MINIMUM, SIXHUMP_CAMEL_UPPER_BOUNDS,
+};
+pub use self::sphere::{
+    sphere, sphere_derivative, sphere_hessian, sphere_shifted, sphere_shifted_derivative,
+};
+pub use self::styblinskitang::{
+    styblinski_tang, styblinski_tang_derivative, STYBLINSKI_TANG_MINIMIZER, STYBLINSKI_TANG_MINIMUM,
+};
+pub use self::zakharov::{zakharov, zakharov_derivative};
+
+/// Helpers for the tests of the test functions
+#[cfg(test)]
+pub(crate) mod fd {
+    use crate::prelude::*;
+    use crate::utils::check_jacobian;
+
+    /// Asserts that `grad` matches central differences of `func` at all `points`
+    pub fn assert_gradient<F, G>(func: F, grad: G, points: &[Vec<f64>])
+    where
+        F: Fn(&[f64]) -> f64,
+        G: Fn(&[f64]) -> Vec<f64>,
+    {
+        let report = check_jacobian(
+            |x: &Vec<f64>| -> Result<Vec<f64>, Error> { Ok(vec![func(x)]) },
+            |x: &Vec<f64>| -> Result<Vec<Vec<f64>>, Error> { Ok(vec![grad(x)]) },
+            points,
+            1e-6,
+            1e-7,
+        )
+        .unwrap();
+        assert!(report.passed(), "{}", report);
+    }
+
+    /// Asserts that `hessian` matches central differences of `grad` at all `points`
+    pub fn assert_hessian<G, H>(grad: G, hessian: H, points: &[Vec<f64>])
+    where
+        G: Fn(&[f64]) -> Vec<f64>,
+        H: Fn(&[f64]) -> Vec<Vec<f64>>,
+    {
+        assert_jacobian(grad, hessian, points);
+    }
+
+    /// Asserts that `jacobian` matches central differences of `residuals` at all `points`
+    pub fn assert_jacobian<R, J>(residuals: R, jacobian: J, points: &[Vec<f64>])
+    where
+        R: Fn(&[f64]) -> Vec<f64>,
+        J: Fn(&[f64]) -> Vec<Vec<f64>>,
+    {
+        let report = check_jacobian(
+            |x: &Vec<f64>| -> Result<Vec<f64>, Error> { Ok(residuals(x)) },
+            |x: &Vec<f64>| -> Result<Vec<Vec<f64>>, Error> { Ok(jacobian(x)) },
+            points,
+            1e-6,
+            1e-7,
+        )
+        .unwrap();
+        assert!(report.passed(), "{}", report);
+    }
+}
+
+/// Evaluates every test function of this module for `f32` and `f64`
+#[cfg(test)]
+mod generic_tests {
+    use super::*;
+    use num::{Float, FromPrimitive};
+
+    /// Evaluates all functions and derivatives at fixed points. This only compiles if none of them
+    /// is restricted to `f64`.
+    fn evaluate_all<T: Float + FromPrimitive>() -> Vec<T> {
+        let c = |v: f64| T::from_f64(v).unwrap();
+        let p2 = [c(0.7), c(-1.3)];
+        let pn = [c(0.7), c(-1.3), c(0.4), c(1.1)];
+        let mut out = vec![
+            ackley(&pn),
+            beale(&p2),
+            booth(&p2),
+            cross_in_tray(&p2),
+            easom(&[c(3.0), c(3.5)]),
+            goldstein_price(&[c(0.2), c(-0.6)]),
+            griewank(&pn),
+            himmelblau(&p2),
+            holder_table(&p2),
+            levy(&pn),
+            matyas(&p2),
+            mccormick(&p2),
+            michalewicz(&pn, c(10.0)),
+            rastrigin(&pn),
+            rosenbrock(&pn, c(1.0), c(100.0)),
+            rosenbrock_2d(&p2, c(1.0), c(100.0)),
+            schaffer_n2(&p2),
+            schaffer_n4(&p2),
+            sixhump_camel(&p2),
+            sphere(&pn),
+            sphere_shifted(&pn, &[c(1.0); 4]),
+            styblinski_tang(&pn),
+            zakharov(&pn),
+        ];
+        out.extend(ackley_derivative(&pn));
+        out.extend(&beale_derivative(&p2));
+        out.extend(&booth_derivative(&p2));
+        out.extend(&cross_in_tray_derivative(&p2));
+        out.extend(&easom_derivative(&[c(3.0), c(3.5)]));
+        out.extend(&goldstein_price_derivative(&[c(0.2), c(-0.6)]));
+        out.extend(griewank_derivative(&pn));
+        out.extend(&himmelblau_derivative(&p2));
+        out.extend(&holder_table_derivative(&p2));
+        out.extend(levy_derivative(&pn));
+        out.extend(&matyas_derivative(&p2));
+        out.extend(&mccormick_derivative(&p2));
+        out.extend(michalewicz_derivative(&pn, c(10.0)));
+        out.extend(rastrigin_derivative(&pn));
+        out.extend(rosenbrock_derivative(&pn, c(1.0), c(100.0)));
+        out.extend(&schaffer_n2_derivative(&p2));
+        out.extend(&schaffer_n4_derivative(&p2));
+        out.extend(&sixhump_camel_derivative(&p2));
+        out.extend(sphere_derivative(&pn));
+        out.extend(sphere_shifted_derivative(&pn, &[c(1.0); 4]));
+        out.extend(styblinski_tang_derivative(&pn));
+        out.extend(zakharov_derivative(&pn));
+        for h in beale_hessian(&p2)
+            .iter()
+            .chain(booth_hessian(&p2).iter())
+            .chain(himmelblau_hessian(&p2).iter())
+            .chain(matyas_hessian(&p2).iter())
+        {
+            out.extend(h);
+        }
+        for h in rosenbrock_hessian(&pn, c(1.0), c(100.0))
+            .iter()
+            .chain(sphere_hessian(&pn).iter())
+        {
+            out.extend(h);
+        }
+        out.extend(rosenbrock_2d_hessian(&p2, c(1.0), c(100.0)));
+        out.extend(&least_squares::powell_singular_residuals(&pn));
+        out.extend(&least_squares::beale_residuals(&p2));
+        out
+    }
+
+    #[test]
+    fn test_f32_matches_f64() {
+        let single = evaluate_all::<f32>();
+        let double = evaluate_all::<f64>();
+        assert_eq!(single.len(), double.len());
+        for (i, (s, d)) in single.iter().zip(double.iter()).enumerate() {
+            let s = f64::from(*s);
+            assert!(s.is_finite(), "entry {} is not finite", i);
+            // f32 has about 7 significant digits
+            assert!(
+                (s - d).abs() <= 1e-4 * d.abs().max(1.0),
+                "entry {}: f32 {} vs f64 {}",
+                i,
+                s,
+                d
+            );
+        }
+    }
+}
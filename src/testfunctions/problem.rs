@@ -0,0 +1,530 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Test problems
+//!
+//! A `TestProblem` bundles a test function with its analytic derivatives, the recommended search
+//! domain, a standard starting point and the known global minimizer(s) and minimum. It implements
+//! `ArgminOp` (with `Param = Vec<f64>`), therefore it can be handed to any solver directly:
+//!
+//! ```rust
+//! # extern crate argmin;
+//! # use argmin::prelude::*;
+//! # use argmin::solver::newton::Newton;
+//! # use argmin::testfunctions::problem::TestProblem;
+//! let problem = TestProblem::booth();
+//! let res = Executor::new(problem.clone(), Newton::new(), problem.start().to_vec())
+//!     .max_iters(1)
+//!     .run_fast()
+//!     .unwrap();
+//! assert!(problem.is_solution(&res.param));
+//! ```
+//!
+//! `all_unconstrained_2d()` and `scalable(dim)` return collections of problems for benchmarks.
+
+use crate::prelude::*;
+use crate::testfunctions::*;
+use serde::{Deserialize, Serialize};
+
+/// Benchmark functions available as `TestProblem`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Benchmark {
+    /// Ackley function
+    Ackley,
+    /// Beale function
+    Beale,
+    /// Booth function
+    Booth,
+    /// Goldstein-Price function
+    GoldsteinPrice,
+    /// Griewank function
+    Griewank,
+    /// Himmelblau's function
+    Himmelblau,
+    /// Levy function
+    Levy,
+    /// Matyas function
+    Matyas,
+    /// Rastrigin function
+    Rastrigin,
+    /// Rosenbrock function with `a = 1` and `b = 100`
+    Rosenbrock,
+    /// Six-hump camel function
+    SixHumpCamel,
+    /// Sphere function
+    Sphere,
+    /// Styblinski-Tang function
+    StyblinskiTang,
+    /// Zakharov function
+    Zakharov,
+}
+
+/// Test function together with its domain, starting point and known optimum
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestProblem {
+    /// benchmark function
+    benchmark: Benchmark,
+    /// lower bounds of the search domain
+    lower: Vec<f64>,
+    /// upper bounds of the search domain
+    upper: Vec<f64>,
+    /// standard starting point
+    start: Vec<f64>,
+    /// global minimizers
+    minimizers: Vec<Vec<f64>>,
+    /// global minimum
+    minimum: f64,
+    /// maximal distance (maximum norm) of a solution to one of the minimizers
+    tol: f64,
+}
+
+impl TestProblem {
+    fn new(
+        benchmark: Benchmark,
+        (lower, upper): (f64, f64),
+        start: Vec<f64>,
+        minimizers: Vec<Vec<f64>>,
+        minimum: f64,
+    ) -> Self {
+        let dim = start.len();
+        TestProblem {
+            benchmark,
+            lower: vec![lower; dim],
+            upper: vec![upper; dim],
+            start,
+            minimizers,
+            minimum,
+            tol: 1e-5,
+        }
+    }
+
+    /// Beale function
+    pub fn beale() -> Self {
+        TestProblem::new(
+            Benchmark::Beale,
+            (-4.5, 4.5),
+            vec![1.0, 1.0],
+            vec![vec![3.0, 0.5]],
+            0.0,
+        )
+    }
+
+    /// Booth function
+    pub fn booth() -> Self {
+        TestProblem::new(
+            Benchmark::Booth,
+            (-10.0, 10.0),
+            vec![0.0, 0.0],
+            vec![vec![1.0, 3.0]],
+            0.0,
+        )
+    }
+
+    /// Goldstein-Price function
+    pub fn goldstein_price() -> Self {
+        TestProblem::new(
+            Benchmark::GoldsteinPrice,
+            (-2.0, 2.0),
+            vec![0.5, -0.5],
+            vec![vec![0.0, -1.0]],
+            3.0,
+        )
+    }
+
+    /// Himmelblau's function
+    pub fn himmelblau() -> Self {
+        TestProblem::new(
+            Benchmark::Himmelblau,
+            (-5.0, 5.0),
+            vec![0.0, 0.0],
+            vec![
+                vec![3.0, 2.0],
+                vec![-2.805_118_086_952_745, 3.131_312_518_250_573],
+                vec![-3.779_310_253_377_747, -3.283_185_991_286_169_6],
+                vec![3.584_428_340_330_491_7, -1.848_126_526_964_403_4],
+            ],
+            0.0,
+        )
+    }
+
+    /// Matyas function
+    pub fn matyas() -> Self {
+        TestProblem::new(
+            Benchmark::Matyas,
+            (-10.0, 10.0),
+            vec![1.0, -0.5],
+            vec![vec![0.0, 0.0]],
+            0.0,
+        )
+    }
+
+    /// Six-hump camel function
+    pub fn sixhump_camel() -> Self {
+        let mut problem = TestProblem::new(
+            Benchmark::SixHumpCamel,
+            (0.0, 0.0),
+            vec![0.5, -0.5],
+            SIXHUMP_CAMEL_MINIMIZERS
+                .iter()
+                .map(|m| m.to_vec())
+                .collect(),
+            SIXHUMP_CAMEL_MINIMUM,
+        );
+        problem.lower = SIXHUMP_CAMEL_LOWER_BOUNDS.to_vec();
+        problem.upper = SIXHUMP_CAMEL_UPPER_BOUNDS.to_vec();
+        problem
+    }
+
+    /// Ackley function in `dim` dimensions
+    pub fn ackley(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Ackley,
+            (-32.768, 32.768),
+            vec![2.0; dim],
+            vec![vec![0.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Griewank function in `dim` dimensions
+    pub fn griewank(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Griewank,
+            (-600.0, 600.0),
+            vec![100.0; dim],
+            vec![vec![0.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Levy function in `dim` dimensions
+    pub fn levy(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Levy,
+            (-10.0, 10.0),
+            vec![-5.0; dim],
+            vec![vec![1.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Rastrigin function in `dim` dimensions
+    pub fn rastrigin(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Rastrigin,
+            (-5.12, 5.12),
+            vec![2.5; dim],
+            vec![vec![0.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Rosenbrock function (`a = 1`, `b = 100`) in `dim` dimensions, starting at
+    /// `(-1.2, 1, -1.2, 1, ...)`
+    pub fn rosenbrock(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Rosenbrock,
+            (-5.0, 10.0),
+            (0..dim)
+                .map(|i| if i % 2 == 0 { -1.2 } else { 1.0 })
+                .collect(),
+            vec![vec![1.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Sphere function in `dim` dimensions
+    pub fn sphere(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Sphere,
+            (-5.12, 5.12),
+            vec![1.0; dim],
+            vec![vec![0.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Styblinski-Tang function in `dim` dimensions
+    pub fn styblinski_tang(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::StyblinskiTang,
+            (-5.0, 5.0),
+            vec![0.0; dim],
+            vec![vec![STYBLINSKI_TANG_MINIMIZER; dim]],
+            dim as f64 * STYBLINSKI_TANG_MINIMUM,
+        )
+    }
+
+    /// Zakharov function in `dim` dimensions
+    pub fn zakharov(dim: usize) -> Self {
+        TestProblem::new(
+            Benchmark::Zakharov,
+            (-5.0, 10.0),
+            vec![1.0; dim],
+            vec![vec![0.0; dim]],
+            0.0,
+        )
+    }
+
+    /// Set the tolerance used by `is_solution` (default: `1e-5`)
+    pub fn tolerance(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Returns the name of the benchmark function
+    pub fn name(&self) -> String {
+        format!("{:?} ({}D)", self.benchmark, self.dim())
+    }
+
+    /// Returns the benchmark function
+    pub fn benchmark(&self) -> Benchmark {
+        self.benchmark
+    }
+
+    /// Returns the number of dimensions
+    pub fn dim(&self) -> usize {
+        self.start.len()
+    }
+
+    /// Returns the lower bounds of the search domain
+    pub fn lower_bounds(&self) -> &[f64] {
+        &self.lower
+    }
+
+    /// Returns the upper bounds of the search domain
+    pub fn upper_bounds(&self) -> &[f64] {
+        &self.upper
+    }
+
+    /// Returns the standard starting point
+    pub fn start(&self) -> &[f64] {
+        &self.start
+    }
+
+    /// Returns the global minimizers
+    pub fn minimizers(&self) -> &[Vec<f64>] {
+        &self.minimizers
+    }
+
+    /// Returns the global minimum
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    /// Returns whether an analytic Hessian is available
+    pub fn has_hessian(&self) -> bool {
+        match self.benchmark {
+            Benchmark::Beale
+            | Benchmark::Booth
+            | Benchmark::Himmelblau
+            | Benchmark::Matyas
+            | Benchmark::Rosenbrock
+            | Benchmark::Sphere => true,
+            _ => false,
+        }
+    }
+
+    /// Returns whether `param` is within the tolerance (maximum norm) of one of the minimizers
+    pub fn is_solution(&self, param: &[f64]) -> bool {
+        self.minimizers.iter().any(|m| {
+            m.iter()
+                .zip(param.iter())
+                .all(|(a, b)| (a - b).abs() <= self.tol)
+        })
+    }
+}
+
+/// Returns all two-dimensional problems which are commonly used without constraints
+pub fn all_unconstrained_2d() -> Vec<TestProblem> {
+    vec![
+        TestProblem::beale(),
+        TestProblem::booth(),
+        TestProblem::goldstein_price(),
+        TestProblem::himmelblau(),
+        TestProblem::matyas(),
+        TestProblem::rosenbrock(2),
+        TestProblem::sixhump_camel(),
+    ]
+}
+
+/// Returns all problems which are defined for an arbitrary number of dimensions
+pub fn scalable(dim: usize) -> Vec<TestProblem> {
+    vec![
+        TestProblem::ackley(dim),
+        TestProblem::griewank(dim),
+        TestProblem::levy(dim),
+        TestProblem::rastrigin(dim),
+        TestProblem::rosenbrock(dim),
+        TestProblem::sphere(dim),
+        TestProblem::styblinski_tang(dim),
+        TestProblem::zakharov(dim),
+    ]
+}
+
+fn arr(p: &[f64]) -> [f64; 2] {
+    [p[0], p[1]]
+}
+
+fn to_vec(h: [[f64; 2]; 2]) -> Vec<Vec<f64>> {
+    h.iter().map(|r| r.to_vec()).collect()
+}
+
+impl ArgminOp for TestProblem {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = Vec<Vec<f64>>;
+
+    fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+        Ok(match self.benchmark {
+            Benchmark::Ackley => ackley(p),
+            Benchmark::Beale => beale(&arr(p)),
+            Benchmark::Booth => booth(&arr(p)),
+            Benchmark::GoldsteinPrice => goldstein_price(&arr(p)),
+            Benchmark::Griewank => griewank(p),
+            Benchmark::Himmelblau => himmelblau(&arr(p)),
+            Benchmark::Levy => levy(p),
+            Benchmark::Matyas => matyas(&arr(p)),
+            Benchmark::Rastrigin => rastrigin(p),
+            Benchmark::Rosenbrock => rosenbrock(p, 1.0, 100.0),
+            Benchmark::SixHumpCamel => sixhump_camel(&arr(p)),
+            Benchmark::Sphere => sphere(p),
+            Benchmark::StyblinskiTang => styblinski_tang(p),
+            Benchmark::Zakharov => zakharov(p),
+        })
+    }
+
+    fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        Ok(match self.benchmark {
+            Benchmark::Ackley => ackley_derivative(p),
+            Benchmark::Beale => beale_derivative(&arr(p)).to_vec(),
+            Benchmark::Booth => booth_derivative(&arr(p)).to_vec(),
+            Benchmark::GoldsteinPrice => goldstein_price_derivative(&arr(p)).to_vec(),
+            Benchmark::Griewank => griewank_derivative(p),
+            Benchmark::Himmelblau => himmelblau_derivative(&arr(p)).to_vec(),
+            Benchmark::Levy => levy_derivative(p),
+            Benchmark::Matyas => matyas_derivative(&arr(p)).to_vec(),
+            Benchmark::Rastrigin => rastrigin_derivative(p),
+            Benchmark::Rosenbrock => rosenbrock_derivative(p, 1.0, 100.0),
+            Benchmark::SixHumpCamel => sixhump_camel_derivative(&arr(p)).to_vec(),
+            Benchmark::Sphere => sphere_derivative(p),
+            Benchmark::StyblinskiTang => styblinski_tang_derivative(p),
+            Benchmark::Zakharov => zakharov_derivative(p),
+        })
+    }
+
+    fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+        Ok(match self.benchmark {
+            Benchmark::Beale => to_vec(beale_hessian(&arr(p))),
+            Benchmark::Booth => to_vec(booth_hessian(&arr(p))),
+            Benchmark::Himmelblau => to_vec(himmelblau_hessian(&arr(p))),
+            Benchmark::Matyas => to_vec(matyas_hessian(&arr(p))),
+            Benchmark::Rosenbrock => rosenbrock_hessian(p, 1.0, 100.0),
+            Benchmark::Sphere => sphere_hessian(p),
+            _ => {
+                return Err(ArgminError::NotImplemented {
+                    text: format!("TestProblem: no analytic Hessian for {:?}", self.benchmark),
+                }
+                .into())
+            }
+        })
+    }
+}
+
+/// Test problems with a seeded random `modify`, shared by the tests of the solvers, pipelines
+/// and run configurations which use simulated annealing
+#[cfg(test)]
+pub(crate) mod annealable {
+    use super::*;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use std::sync::{Arc, Mutex};
+
+    /// `TestProblem` whose `modify` adds uniform noise in `[-scale * extent, scale * extent)`,
+    /// with `extent` capped at 1
+    #[derive(Clone, Serialize, Deserialize)]
+    pub(crate) struct Annealable {
+        problem: TestProblem,
+        scale: f64,
+        rng: Arc<Mutex<XorShiftRng>>,
+    }
+
+    impl Annealable {
+        /// Constructor
+        pub(crate) fn new(problem: TestProblem, scale: f64, seed: u64) -> Self {
+            Annealable {
+                problem,
+                scale,
+                rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(seed))),
+            }
+        }
+    }
+
+    impl ArgminOp for Annealable {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            self.problem.apply(p)
+        }
+
+        fn modify(&self, p: &Vec<f64>, extent: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            let scale = extent.min(1.0) * self.scale;
+            Ok(p.iter().map(|x| x + rng.gen_range(-scale, scale)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+
+    send_sync_test!(test_problem, TestProblem);
+
+    #[test]
+    fn test_minima() {
+        for problem in all_unconstrained_2d().iter().chain(scalable(5).iter()) {
+            assert_eq!(problem.lower_bounds().len(), problem.dim());
+            assert_eq!(problem.upper_bounds().len(), problem.dim());
+            for m in problem.minimizers() {
+                let cost = problem.apply(m).unwrap();
+                assert!(
+                    (cost - problem.minimum()).abs() < 1e-9,
+                    "{}: {} vs {}",
+                    problem.name(),
+                    cost,
+                    problem.minimum()
+                );
+                assert!(problem.is_solution(m));
+                assert!(problem.gradient(m).unwrap().iter().all(|g| g.abs() < 1e-6));
+                assert!(m
+                    .iter()
+                    .zip(problem.lower_bounds().iter().zip(problem.upper_bounds()))
+                    .all(|(x, (l, u))| l <= x && x <= u));
+            }
+            assert_eq!(
+                problem.hessian(&problem.start().to_vec()).is_ok(),
+                problem.has_hessian()
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_solution() {
+        let problem = TestProblem::booth();
+        assert!(problem.is_solution(&[1.0 + 1e-6, 3.0]));
+        assert!(!problem.is_solution(&[1.0 + 1e-4, 3.0]));
+        assert!(problem
+            .clone()
+            .tolerance(1e-3)
+            .is_solution(&[1.0 + 1e-4, 3.0]));
+        assert_eq!(problem.name(), "Booth (2D)");
+    }
+}
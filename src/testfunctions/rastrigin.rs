@@ -0,0 +1,99 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Rastrigin function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = a * n + \sum_{i=1}^{n} [x_i^2 - a * cos(2 * pi * x_i)]`
+//!
+//! where `x_i \in [-5.12, 5.12]` and `a = 10` by default.
+//!
+//! The global minimum is at `f(x_1, x_2, ..., x_n) = f(0, 0, ..., 0) = 0`.
+//!
+//! # References:
+//!
+//! [0] L. A. Rastrigin (1974). Systems of extremal control. Nauka, Moscow.
+
+use num::{Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Rastrigin function with `a = 10`
+pub fn rastrigin<T: Float + FromPrimitive>(param: &[T]) -> T {
+    rastrigin_a(param, T::from_f64(10.0).unwrap())
+}
+
+/// Rastrigin function with parameter `a`
+pub fn rastrigin_a<T: Float + FromPrimitive>(param: &[T], a: T) -> T {
+    let two_pi = T::from_f64(2.0 * PI).unwrap();
+    a * T::from_usize(param.len()).unwrap()
+        + param
+            .iter()
+            .map(|&x| x.powi(2) - a * (two_pi * x).cos())
+            .fold(T::zero(), |acc, x| acc + x)
+}
+
+/// Derivative of the Rastrigin function with `a = 10`
+pub fn rastrigin_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    rastrigin_a_derivative(param, T::from_f64(10.0).unwrap())
+}
+
+/// Derivative of the Rastrigin function with parameter `a`
+pub fn rastrigin_a_derivative<T: Float + FromPrimitive>(param: &[T], a: T) -> Vec<T> {
+    let two = T::from_f64(2.0).unwrap();
+    let two_pi = T::from_f64(2.0 * PI).unwrap();
+    param
+        .iter()
+        .map(|&x| two * x + two_pi * a * (two_pi * x).sin())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_origin() {
+        assert!(rastrigin(&[0.0f64; 2]).abs() < std::f64::EPSILON);
+        assert!(rastrigin(&[0.0f32; 7]).abs() < std::f32::EPSILON);
+        assert!(rastrigin_derivative(&[0.0f64; 3])
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+    }
+
+    #[test]
+    fn test_hand_computed_2d() {
+        // 20 + (0.25 - 10 cos(pi)) + (1 - 10 cos(2 pi))
+        assert!((rastrigin(&[0.5f64, 1.0]) - 21.25).abs() < 1e-12);
+        // 2 x_i + 20 pi sin(2 pi x_i)
+        let g = rastrigin_derivative(&[0.5f64, 1.0]);
+        assert!((g[0] - 1.0).abs() < 1e-12);
+        assert!((g[1] - 2.0).abs() < 1e-12);
+        let g = rastrigin_derivative(&[0.25f64, -0.25]);
+        assert!((g[0] - (0.5 + 20.0 * PI)).abs() < 1e-12);
+        assert!((g[1] + (0.5 + 20.0 * PI)).abs() < 1e-12);
+        // a = 1: 2 + (1 - cos(2 pi)) + (0.0625 - cos(pi / 2))
+        assert!((rastrigin_a(&[1.0f64, 0.25], 1.0) - 2.0625).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_derivative_finite_differences_5d() {
+        let mut rng = XorShiftRng::seed_from_u64(415);
+        let points: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..5).map(|_| rng.gen_range(-5.12, 5.12)).collect())
+            .collect();
+        assert_gradient(rastrigin, rastrigin_derivative, &points);
+        assert_gradient(
+            |x| rastrigin_a(x, 3.0),
+            |x| rastrigin_a_derivative(x, 3.0),
+            &points,
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Rosenbrock function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = \sum_{i=1}^{n-1} [(a - x_i)^2 + b * (x_{i+1} - x_i^2)^2]`
+//!
+//! where `x_i \in (-\infty, \infty)`. The parameters a and b usually are: `a = 1` and `b = 100`.
+//!
+//! In two dimensions, the global minimum is at `f(x_1, x_2) = f(a, a^2) = 0`. In more dimensions,
+//! a zero of `f` requires `x_i = a` and `x_{i+1} = x_i^2` for all `i`, which is only possible for
+//! `a = 1` (global minimum `f(1, 1, ..., 1) = 0`) and `a = 0` (`f(0, 0, ..., 0) = 0`). For other
+//! values of `a` the minimum is positive and has no closed form.
+//!
+//! All functions take slices; a `Vec` can be passed directly and an `ndarray::Array1` via
+//! `as_slice()`. The `*_2d` functions are kept as thin wrappers for two-dimensional problems.
+//!
+//! # References:
+//!
+//! [0] H. H. Rosenbrock (1960). An automatic method for finding the greatest or least value of a
+//! function. The Computer Journal 3 (3): 175–184.
+
+use num::{Float, FromPrimitive};
+
+/// Rosenbrock function
+///
+/// Panics if `param` has less than two elements.
+pub fn rosenbrock<T: Float + FromPrimitive>(param: &[T], a: T, b: T) -> T {
+    assert!(
+        param.len() >= 2,
+        "rosenbrock: param needs at least two elements"
+    );
+    param
+        .iter()
+        .zip(param.iter().skip(1))
+        .map(|(&xi, &xi1)| (a - xi).powi(2) + b * (xi1 - xi.powi(2)).powi(2))
+        .fold(T::zero(), |acc, x| acc + x)
+}
+
+/// Derivative of the Rosenbrock function
+///
+/// Panics if `param` has less than two elements.
+pub fn rosenbrock_derivative<T: Float + FromPrimitive>(param: &[T], a: T, b: T) -> Vec<T> {
+    assert!(
+        param.len() >= 2,
+        "rosenbrock_derivative: param needs at least two elements"
+    );
+    let n = param.len();
+    let n2 = T::from_f64(2.0).unwrap();
+    let n4 = T::from_f64(4.0).unwrap();
+    let mut grad = vec![T::zero(); n];
+    for (i, w) in param.windows(2).enumerate() {
+        let (xi, xi1) = (w[0], w[1]);
+        let r = xi1 - xi.powi(2);
+        grad[i] = grad[i] - n2 * (a - xi) - n4 * b * xi * r;
+        grad[i + 1] = grad[i + 1] + n2 * b * r;
+    }
+    grad
+}
+
+/// Hessian of the Rosenbrock function
+///
+/// The Hessian is tridiagonal; it is returned as a dense matrix (`Vec` of rows).
+///
+/// Panics if `param` has less than two elements.
+pub fn rosenbrock_hessian<T: Float + FromPrimitive>(param: &[T], _a: T, b: T) -> Vec<Vec<T>> {
+    assert!(
+        param.len() >= 2,
+        "rosenbrock_hessian: param needs at least two elements"
+    );
+    let n = param.len();
+    let n2 = T::from_f64(2.0).unwrap();
+    let n4 = T::from_f64(4.0).unwrap();
+    let n12 = T::from_f64(12.0).unwrap();
+    let mut hessian = vec![vec![T::zero(); n]; n];
+    for (i, w) in param.windows(2).enumerate() {
+        let (xi, xi1) = (w[0], w[1]);
+        hessian[i][i] = hessian[i][i] + n2 + n12 * b * xi.powi(2) - n4 * b * xi1;
+        hessian[i][i + 1] = -n4 * b * xi;
+        hessian[i + 1][i] = -n4 * b * xi;
+        hessian[i + 1][i + 1] = hessian[i + 1][i + 1] + n2 * b;
+    }
+    hessian
+}
+
+/// Two-dimensional Rosenbrock function
+///
+/// Panics if `param` does not have exactly two elements.
+pub fn rosenbrock_2d<T: Float + FromPrimitive>(param: &[T], a: T, b: T) -> T {
+    assert_eq!(param.len(), 2, "rosenbrock_2d: param needs two elements");
+    rosenbrock(param, a, b)
+}
+
+/// Derivative of the two-dimensional Rosenbrock function
+///
+/// Panics if `param` does not have exactly two elements.
+pub fn rosenbrock_2d_derivative<T: Float + FromPrimitive>(param: &[T], a: T, b: T) -> Vec<T> {
+    assert_eq!(
+        param.len(),
+        2,
+        "rosenbrock_2d_derivative: param needs two elements"
+    );
+    rosenbrock_derivative(param, a, b)
+}
+
+/// Hessian of the two-dimensional Rosenbrock function
+///
+/// Returns the entries in row-major order: `[h_11, h_12, h_21, h_22]`.
+///
+/// Panics if `param` does not have exactly two elements.
+pub fn rosenbrock_2d_hessian<T: Float + FromPrimitive>(param: &[T], a: T, b: T) -> Vec<T> {
+    assert_eq!(
+        param.len(),
+        2,
+        "rosenbrock_2d_hessian: param needs two elements"
+    );
+    rosenbrock_hessian(param, a, b).concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_minimum() {
+        assert!(rosenbrock(&[1.0f64; 5], 1.0, 100.0).abs() < std::f64::EPSILON);
+        assert!(rosenbrock(&[1.0f32; 20], 1.0, 100.0).abs() < std::f32::EPSILON);
+        assert!(rosenbrock_derivative(&[1.0f64; 5], 1.0, 100.0)
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+        // a = 2 in two dimensions: (2, 4)
+        assert!(rosenbrock(&[2.0f64, 4.0], 2.0, 100.0).abs() < std::f64::EPSILON);
+        assert!(rosenbrock_derivative(&[2.0f64, 4.0], 2.0, 100.0)
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+        // a = 2 in three dimensions: (2, 4, 16) is not a minimizer, since (a - x_2)^2 = 4
+        assert!((rosenbrock(&[2.0f64, 4.0, 16.0], 2.0, 100.0) - 4.0).abs() < std::f64::EPSILON);
+        assert!(rosenbrock_derivative(&[2.0f64, 4.0, 16.0], 2.0, 100.0)
+            .iter()
+            .any(|g| g.abs() > 1.0));
+    }
+
+    #[test]
+    fn test_2d() {
+        // (1 - 0)^2 + 100 * (1 - 0)^2
+        assert!((rosenbrock_2d(&[0.0f64, 1.0], 1.0, 100.0) - 101.0).abs() < std::f64::EPSILON);
+        let x = [-1.2f64, 1.0];
+        let g = rosenbrock_2d_derivative(&x, 1.0, 100.0);
+        assert!((g[0] + 215.6).abs() < 1e-10);
+        assert!((g[1] + 88.0).abs() < 1e-10);
+        let h = rosenbrock_2d_hessian(&x, 1.0, 100.0);
+        let expected = [1330.0, 480.0, 480.0, 200.0];
+        for (hi, ei) in h.iter().zip(expected.iter()) {
+            assert!((hi - ei).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences_5d() {
+        let mut rng = XorShiftRng::seed_from_u64(423);
+        let points: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..5).map(|_| rng.gen_range(-2.0, 2.0)).collect())
+            .collect();
+        assert_gradient(
+            |x| rosenbrock(x, 1.0, 100.0),
+            |x| rosenbrock_derivative(x, 1.0, 100.0),
+            &points,
+        );
+        assert_hessian(
+            |x| rosenbrock_derivative(x, 1.0, 100.0),
+            |x| rosenbrock_hessian(x, 1.0, 100.0),
+            &points,
+        );
+        assert_gradient(
+            |x| rosenbrock(x, 0.5, 3.0),
+            |x| rosenbrock_derivative(x, 0.5, 3.0),
+            &points,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_1d() {
+        rosenbrock(&[1.0f64], 1.0, 100.0);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Schaffer functions N.2 and N.4
+//!
+//! Schaffer function N.2:
+//!
+//! `f(x_1, x_2) = 0.5 + (sin^2(x_1^2 - x_2^2) - 0.5) / (1 + 0.001 * (x_1^2 + x_2^2))^2`
+//!
+//! The global minimum is at `f(x_1, x_2) = f(0, 0) = 0`.
+//!
+//! Schaffer function N.4:
+//!
+//! `f(x_1, x_2) = 0.5 + (cos^2(sin(|x_1^2 - x_2^2|)) - 0.5) / (1 + 0.001 * (x_1^2 + x_2^2))^2`
+//!
+//! The global minimum is at `f(x_1, x_2) = f(0, ±1.25313) = f(±1.25313, 0) ≈ 0.292579` (see
+//! `SCHAFFER_N4_MINIMIZER` and `SCHAFFER_N4_MINIMUM`).
+//!
+//! For both functions `x_i \in [-100, 100]`. Both oscillate with a frequency which grows
+//! linearly with the distance from the origin.
+
+use num::{Float, FromPrimitive};
+
+/// Nonzero coordinate of the global minimizers of Schaffer function N.4
+pub const SCHAFFER_N4_MINIMIZER: f64 = 1.253_131_831_463_733;
+
+/// Global minimum of Schaffer function N.4
+pub const SCHAFFER_N4_MINIMUM: f64 = 0.292_578_632_035_980_5;
+
+/// Schaffer function N.2
+pub fn schaffer_n2<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let half = T::from_f64(0.5).unwrap();
+    half + ((x1.powi(2) - x2.powi(2)).sin().powi(2) - half) / denom(param).powi(2)
+}
+
+/// Derivative of Schaffer function N.2
+pub fn schaffer_n2_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    let a = x1.powi(2) - x2.powi(2);
+    // d/da sin^2(a) = sin(2a); avoids cancellation of 2 * sin(a) * cos(a) for small a
+    let dnum = (n2 * a).sin();
+    let num = a.sin().powi(2) - T::from_f64(0.5).unwrap();
+    combine(param, num, dnum)
+}
+
+/// Schaffer function N.4
+pub fn schaffer_n4<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let half = T::from_f64(0.5).unwrap();
+    half + ((x1.powi(2) - x2.powi(2)).abs().sin().cos().powi(2) - half) / denom(param).powi(2)
+}
+
+/// Derivative of Schaffer function N.4
+///
+/// Although `|x_1^2 - x_2^2|` has a kink on the diagonals, the function is differentiable there
+/// because the derivative of `cos^2(sin(t))` vanishes at `t = 0`.
+pub fn schaffer_n4_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    let a = x1.powi(2) - x2.powi(2);
+    let s = a.abs().sin();
+    // d/da cos^2(sin(|a|)) = -sin(2 sin(|a|)) * cos(|a|) * sign(a)
+    let sign = if a < T::zero() { -T::one() } else { T::one() };
+    let dnum = -(n2 * s).sin() * a.abs().cos() * sign;
+    let num = s.cos().powi(2) - T::from_f64(0.5).unwrap();
+    combine(param, num, dnum)
+}
+
+/// `1 + 0.001 * (x_1^2 + x_2^2)`
+fn denom<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    T::one() + T::from_f64(0.001).unwrap() * (x1.powi(2) + x2.powi(2))
+}
+
+/// Gradient of `0.5 + num(a) / denom^2` with `a = x_1^2 - x_2^2`, given `num` and `d num / d a`
+fn combine<T: Float + FromPrimitive>(param: &[T; 2], num: T, dnum: T) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n2 = T::from_f64(2.0).unwrap();
+    let d = denom(param);
+    let d2 = d.powi(2);
+    // d/dx_i of denom^-2 is -2 * denom^-3 * 0.002 * x_i
+    let dd = T::from_f64(0.004).unwrap() * num / (d2 * d);
+    [
+        dnum * n2 * x1 / d2 - dd * x1,
+        -dnum * n2 * x2 / d2 - dd * x2,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    /// Points at several radii including very close to the origin
+    fn points() -> Vec<Vec<f64>> {
+        let mut points = vec![vec![1e-4, -2e-4], vec![0.05, 0.02]];
+        for &r in &[0.5, 1.3, 4.0, 15.0, 60.0] {
+            for &phi in &[0.3f64, 2.0, 4.4] {
+                points.push(vec![r * phi.cos(), r * phi.sin()]);
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_minima() {
+        assert!(schaffer_n2(&[0.0f64, 0.0]).abs() < std::f64::EPSILON);
+        assert!(schaffer_n2(&[0.0f32, 0.0]).abs() < std::f32::EPSILON);
+        let g = schaffer_n2_derivative(&[0.0f64, 0.0]);
+        assert!(g[0].abs() < std::f64::EPSILON && g[1].abs() < std::f64::EPSILON);
+        for m in &[
+            [0.0, SCHAFFER_N4_MINIMIZER],
+            [0.0, -SCHAFFER_N4_MINIMIZER],
+            [SCHAFFER_N4_MINIMIZER, 0.0],
+            [-SCHAFFER_N4_MINIMIZER, 0.0],
+        ] {
+            assert!((schaffer_n4(m) - SCHAFFER_N4_MINIMUM).abs() < 1e-14);
+            let g = schaffer_n4_derivative(m);
+            assert!(g[0].abs() < 1e-10 && g[1].abs() < 1e-10);
+        }
+        // the origin is a local maximum of N.4
+        assert!((schaffer_n4(&[0.0f64, 0.0]) - 1.0).abs() < std::f64::EPSILON);
+    }
+
+    #[test]
+    fn test_n2_derivative_finite_differences() {
+        assert_gradient(
+            |x| schaffer_n2(&arr(x)),
+            |x| schaffer_n2_derivative(&arr(x)).to_vec(),
+            &points(),
+        );
+    }
+
+    #[test]
+    fn test_n4_derivative_finite_differences() {
+        assert_gradient(
+            |x| schaffer_n4(&arr(x)),
+            |x| schaffer_n4_derivative(&arr(x)).to_vec(),
+            &points(),
+        );
+    }
+
+    #[test]
+    fn test_n4_derivative_on_diagonal() {
+        let points: Vec<Vec<f64>> = [0.7, -3.0, 20.0]
+            .iter()
+            .flat_map(|&t| vec![vec![t, t], vec![t, -t]])
+            .collect();
+        assert_gradient(
+            |x| schaffer_n4(&arr(x)),
+            |x| schaffer_n4_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+        let g = schaffer_n4_derivative(&[0.0f64, 0.0]);
+        assert!(g[0].abs() < std::f64::EPSILON && g[1].abs() < std::f64::EPSILON);
+    }
+}
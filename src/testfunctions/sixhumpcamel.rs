@@ -0,0 +1,135 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Six-hump camel function
+//!
+//! `f(x_1, x_2) = (4 - 2.1 * x_1^2 + x_1^4 / 3) * x_1^2 + x_1 * x_2 + (-4 + 4 * x_2^2) * x_2^2`
+//!
+//! where `x_1 \in [-3, 3]` and `x_2 \in [-2, 2]`.
+//!
+//! The function has six local minima, two of which are global:
+//! `f(x_1, x_2) = f(0.0898, -0.7126) = f(-0.0898, 0.7126) ≈ -1.0316`.
+
+use num::{Float, FromPrimitive};
+
+/// Lower bounds of the standard search domain
+pub const SIXHUMP_CAMEL_LOWER_BOUNDS: [f64; 2] = [-3.0, -2.0];
+
+/// Upper bounds of the standard search domain
+pub const SIXHUMP_CAMEL_UPPER_BOUNDS: [f64; 2] = [3.0, 2.0];
+
+/// The two global minimizers
+pub const SIXHUMP_CAMEL_MINIMIZERS: [[f64; 2]; 2] = [
+    [0.089_842_013_100_318_07, -0.712_656_403_020_739_6],
+    [-0.089_842_013_100_318_07, 0.712_656_403_020_739_6],
+];
+
+/// Global minimum
+pub const SIXHUMP_CAMEL_MINIMUM: f64 = -1.031_628_453_489_877_4;
+
+/// Six-hump camel function
+pub fn sixhump_camel<T: Float + FromPrimitive>(param: &[T; 2]) -> T {
+    let [x1, x2] = *param;
+    let n4 = T::from_f64(4.0).unwrap();
+    (n4 - T::from_f64(2.1).unwrap() * x1.powi(2) + x1.powi(4) / T::from_f64(3.0).unwrap())
+        * x1.powi(2)
+        + x1 * x2
+        + (-n4 + n4 * x2.powi(2)) * x2.powi(2)
+}
+
+/// Derivative of the six-hump camel function
+pub fn sixhump_camel_derivative<T: Float + FromPrimitive>(param: &[T; 2]) -> [T; 2] {
+    let [x1, x2] = *param;
+    let n8 = T::from_f64(8.0).unwrap();
+    [
+        n8 * x1 - T::from_f64(8.4).unwrap() * x1.powi(3)
+            + T::from_f64(2.0).unwrap() * x1.powi(5)
+            + x2,
+        x1 - n8 * x2 + T::from_f64(16.0).unwrap() * x2.powi(3),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::solver::gradientdescent::SteepestDescent;
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+    use serde::{Deserialize, Serialize};
+
+    fn arr(x: &[f64]) -> [f64; 2] {
+        [x[0], x[1]]
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct SixHumpCamel {}
+
+    impl ArgminOp for SixHumpCamel {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, x: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sixhump_camel(&arr(x)))
+        }
+
+        fn gradient(&self, x: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sixhump_camel_derivative(&arr(x)).to_vec())
+        }
+    }
+
+    #[test]
+    fn test_minima() {
+        for m in &SIXHUMP_CAMEL_MINIMIZERS {
+            assert!((sixhump_camel(m) - SIXHUMP_CAMEL_MINIMUM).abs() < 1e-14);
+            let g = sixhump_camel_derivative(m);
+            assert!(g[0].abs() < 1e-12 && g[1].abs() < 1e-12);
+        }
+        // one of the four non-global local minima
+        assert!(sixhump_camel(&[1.7036f64, -0.7961]) > SIXHUMP_CAMEL_MINIMUM + 0.5);
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let points = vec![
+            vec![0.0, 0.0],
+            vec![-3.0, -2.0],
+            vec![3.0, 2.0],
+            vec![1.1, -0.4],
+            vec![-2.3, 1.7],
+        ];
+        assert_gradient(
+            |x| sixhump_camel(&arr(x)),
+            |x| sixhump_camel_derivative(&arr(x)).to_vec(),
+            &points,
+        );
+    }
+
+    #[test]
+    fn test_multistart_finds_both_global_minima() {
+        let mut rng = XorShiftRng::seed_from_u64(420);
+        let results: Vec<Vec<f64>> = (0..10)
+            .map(|_| {
+                let init = vec![rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+                let solver = SteepestDescent::new(MoreThuenteLineSearch::new()).unwrap();
+                Executor::new(SixHumpCamel {}, solver, init)
+                    .max_iters(40)
+                    .run_fast()
+                    .unwrap()
+                    .param
+            })
+            .collect();
+        for m in &SIXHUMP_CAMEL_MINIMIZERS {
+            assert!(results
+                .iter()
+                .any(|r| (r[0] - m[0]).abs() < 1e-4 && (r[1] - m[1]).abs() < 1e-4));
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Sphere function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = \sum_{i=1}^{n} x_i^2`
+//!
+//! The global minimum is at `f(x_1, x_2, ..., x_n) = f(0, 0, ..., 0) = 0`.
+//!
+//! The shifted variant `f(x) = \sum_{i=1}^{n} (x_i - c_i)^2` has its minimum at the center `c`.
+//! Newton's method converges in a single step on both.
+
+use num::{Float, FromPrimitive};
+
+/// Sphere function
+pub fn sphere<T: Float + FromPrimitive>(param: &[T]) -> T {
+    param.iter().fold(T::zero(), |acc, &x| acc + x.powi(2))
+}
+
+/// Derivative of the sphere function
+pub fn sphere_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    let n2 = T::from_f64(2.0).unwrap();
+    param.iter().map(|&x| n2 * x).collect()
+}
+
+/// Hessian of the sphere function (two times the identity)
+pub fn sphere_hessian<T: Float + FromPrimitive>(param: &[T]) -> Vec<Vec<T>> {
+    let n2 = T::from_f64(2.0).unwrap();
+    let n = param.len();
+    (0..n)
+        .map(|i| {
+            let mut row = vec![T::zero(); n];
+            row[i] = n2;
+            row
+        })
+        .collect()
+}
+
+/// Sphere function shifted to `center`
+///
+/// Panics if `param` and `center` differ in length.
+pub fn sphere_shifted<T: Float + FromPrimitive>(param: &[T], center: &[T]) -> T {
+    assert_eq!(
+        param.len(),
+        center.len(),
+        "sphere_shifted: param and center differ in length"
+    );
+    param
+        .iter()
+        .zip(center.iter())
+        .fold(T::zero(), |acc, (&x, &c)| acc + (x - c).powi(2))
+}
+
+/// Derivative of the sphere function shifted to `center`
+///
+/// The Hessian equals `sphere_hessian`.
+///
+/// Panics if `param` and `center` differ in length.
+pub fn sphere_shifted_derivative<T: Float + FromPrimitive>(param: &[T], center: &[T]) -> Vec<T> {
+    assert_eq!(
+        param.len(),
+        center.len(),
+        "sphere_shifted_derivative: param and center differ in length"
+    );
+    let n2 = T::from_f64(2.0).unwrap();
+    param
+        .iter()
+        .zip(center.iter())
+        .map(|(&x, &c)| n2 * (x - c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::{assert_gradient, assert_hessian};
+
+    #[test]
+    fn test_minimum() {
+        assert!(sphere(&[0.0f64; 4]).abs() < std::f64::EPSILON);
+        assert!(sphere(&[0.0f32; 2]).abs() < std::f32::EPSILON);
+        assert!((sphere(&[1.0f64, -2.0, 3.0]) - 14.0).abs() < std::f64::EPSILON);
+        let c = [1.0f64, -2.0, 3.0];
+        assert!(sphere_shifted(&c, &c).abs() < std::f64::EPSILON);
+        assert!(sphere_shifted_derivative(&c, &c)
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+    }
+
+    #[test]
+    fn test_derivatives_finite_differences() {
+        let points = vec![vec![0.3, -1.2, 4.0], vec![-7.0, 0.0, 2.5]];
+        let center = [1.0, 2.0, -3.0];
+        assert_gradient(sphere, sphere_derivative, &points);
+        assert_hessian(sphere_derivative, sphere_hessian, &points);
+        assert_gradient(
+            |x| sphere_shifted(x, &center),
+            |x| sphere_shifted_derivative(x, &center),
+            &points,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_shifted_length_mismatch() {
+        sphere_shifted(&[1.0f64, 2.0], &[0.0]);
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Styblinski-Tang function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = 1/2 * \sum_{i=1}^{n} (x_i^4 - 16 * x_i^2 + 5 * x_i)`
+//!
+//! where `x_i \in [-5, 5]`.
+//!
+//! The global minimum is at `f(x_1, x_2, ..., x_n) = f(-2.903534, ..., -2.903534) =
+//! -39.16617 * n` (see `STYBLINSKI_TANG_MINIMIZER` and `STYBLINSKI_TANG_MINIMUM`).
+
+use num::{Float, FromPrimitive};
+
+/// Coordinate of the global minimizer, the smaller root of `4 x^3 - 32 x + 5`
+pub const STYBLINSKI_TANG_MINIMIZER: f64 = -2.903_534_027_771_177;
+
+/// Minimum value per dimension
+pub const STYBLINSKI_TANG_MINIMUM: f64 = -39.166_165_703_771_41;
+
+/// Styblinski-Tang function
+pub fn styblinski_tang<T: Float + FromPrimitive>(param: &[T]) -> T {
+    let n5 = T::from_f64(5.0).unwrap();
+    let n16 = T::from_f64(16.0).unwrap();
+    T::from_f64(0.5).unwrap()
+        * param
+            .iter()
+            .map(|&x| x.powi(4) - n16 * x.powi(2) + n5 * x)
+            .fold(T::zero(), |acc, x| acc + x)
+}
+
+/// Derivative of the Styblinski-Tang function
+pub fn styblinski_tang_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    let n2 = T::from_f64(2.0).unwrap();
+    let n16 = T::from_f64(16.0).unwrap();
+    let n2_5 = T::from_f64(2.5).unwrap();
+    param
+        .iter()
+        .map(|&x| n2 * x.powi(3) - n16 * x + n2_5)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_minimum() {
+        let x = [STYBLINSKI_TANG_MINIMIZER; 3];
+        assert!((styblinski_tang(&x) - 3.0 * STYBLINSKI_TANG_MINIMUM).abs() < 1e-12);
+        assert!(styblinski_tang_derivative(&x)
+            .iter()
+            .all(|g| g.abs() < 1e-12));
+        let x = [STYBLINSKI_TANG_MINIMIZER as f32; 2];
+        assert!((styblinski_tang(&x) - 2.0 * STYBLINSKI_TANG_MINIMUM as f32).abs() < 1e-4);
+        // the local minimum at x_i ≈ 2.7468 is worse
+        assert!(styblinski_tang(&[2.7468f64]) > STYBLINSKI_TANG_MINIMUM + 10.0);
+    }
+
+    #[test]
+    fn test_derivative_finite_differences_4d() {
+        let mut rng = XorShiftRng::seed_from_u64(418);
+        let points: Vec<Vec<f64>> = (0..5)
+            .map(|_| (0..4).map(|_| rng.gen_range(-5.0, 5.0)).collect())
+            .collect();
+        assert_gradient(styblinski_tang, styblinski_tang_derivative, &points);
+    }
+}
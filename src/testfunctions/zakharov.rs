@@ -0,0 +1,76 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Zakharov function
+//!
+//! In n dimensions:
+//!
+//! `f(x_1, x_2, ..., x_n) = \sum_{i=1}^{n} x_i^2 + s^2 + s^4`
+//!
+//! with `s = \sum_{i=1}^{n} 0.5 * i * x_i` and `x_i \in [-5, 10]`.
+//!
+//! The function is convex and has its global minimum at
+//! `f(x_1, x_2, ..., x_n) = f(0, 0, ..., 0) = 0`.
+
+use num::{Float, FromPrimitive};
+
+/// Zakharov function
+pub fn zakharov<T: Float + FromPrimitive>(param: &[T]) -> T {
+    let s = weighted_sum(param);
+    param.iter().fold(T::zero(), |acc, &x| acc + x.powi(2)) + s.powi(2) + s.powi(4)
+}
+
+/// Derivative of the Zakharov function
+pub fn zakharov_derivative<T: Float + FromPrimitive>(param: &[T]) -> Vec<T> {
+    let n2 = T::from_f64(2.0).unwrap();
+    let s = weighted_sum(param);
+    // d/dx_i (s^2 + s^4) = 0.5 * i * (2 * s + 4 * s^3) = i * (s + 2 * s^3)
+    let ds = s + n2 * s.powi(3);
+    param
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| n2 * x + T::from_usize(i + 1).unwrap() * ds)
+        .collect()
+}
+
+/// `s = \sum_{i=1}^{n} 0.5 * i * x_i`
+fn weighted_sum<T: Float + FromPrimitive>(param: &[T]) -> T {
+    let half = T::from_f64(0.5).unwrap();
+    param.iter().enumerate().fold(T::zero(), |acc, (i, &x)| {
+        acc + half * T::from_usize(i + 1).unwrap() * x
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::fd::assert_gradient;
+    use rand::prelude::*;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_minimum() {
+        assert!(zakharov(&[0.0f64; 5]).abs() < std::f64::EPSILON);
+        assert!(zakharov(&[0.0f32; 2]).abs() < std::f32::EPSILON);
+        assert!(zakharov_derivative(&[0.0f64; 4])
+            .iter()
+            .all(|g| g.abs() < std::f64::EPSILON));
+        // x = (1, 1): 2 + 1.5^2 + 1.5^4
+        assert!((zakharov(&[1.0f64, 1.0]) - 9.3125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_derivative_finite_differences() {
+        let mut rng = XorShiftRng::seed_from_u64(422);
+        for &n in &[2, 10] {
+            let points: Vec<Vec<f64>> = (0..4)
+                .map(|_| (0..n).map(|_| rng.gen_range(-1.0, 1.0)).collect())
+                .collect();
+            assert_gradient(zakharov, zakharov_derivative, &points);
+        }
+    }
+}
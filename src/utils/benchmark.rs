@@ -0,0 +1,547 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Benchmarks
+//!
+//! `run_benchmark` runs several solvers on several `TestProblem`s with a common budget of cost
+//! function evaluations and records the best cost found after each evaluation. The resulting
+//! `BenchmarkReport` can be serialized, exported to (and read from) CSV and summarized via data
+//! profiles [0], i.e. the fraction of problems solved to a tolerance as a function of the
+//! (relative) budget.
+//!
+//! ```
+//! # use argmin::prelude::*;
+//! # use argmin::solver::neldermead::{NelderMead, SimplexStep};
+//! # use argmin::testfunctions::problem::TestProblem;
+//! # use argmin::utils::{run_benchmark, Budget, SolverFactory};
+//! # fn main() -> Result<(), Error> {
+//! let problems = vec![TestProblem::sphere(2), TestProblem::rosenbrock(2)];
+//! let solvers = vec![SolverFactory::new("nelder-mead", |problem, _seed| {
+//!     NelderMead::from_point(problem.start().to_vec(), SimplexStep::default())
+//! })?];
+//! let report = run_benchmark(&problems, &solvers, Budget::new(500));
+//! for profile in report.data_profile(1e-3, &[10.0, 100.0]) {
+//!     println!("{}: {:?}", profile.solver, profile.solved);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # References:
+//!
+//! [0] Jorge J. Moré and Stefan M. Wild (2009). Benchmarking Derivative-Free Optimization
+//! Algorithms. SIAM Journal on Optimization 20(1), 172-191.
+
+use crate::prelude::*;
+use crate::testfunctions::problem::TestProblem;
+use rand::prelude::*;
+use rand_xorshift::XorShiftRng;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Error returned by `BenchmarkOp::apply` once the budget of evaluations is exhausted
+#[derive(Clone, Debug, Default, PartialEq)]
+struct BudgetExhausted {}
+
+impl std::fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Budget of cost function evaluations exhausted")
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// Budget of each run of a benchmark
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    /// maximum number of cost function evaluations
+    evaluations: u64,
+    /// maximum number of iterations
+    iterations: u64,
+    /// base seed
+    seed: u64,
+}
+
+impl Budget {
+    /// Budget of `evaluations` cost function evaluations per run
+    pub fn new(evaluations: u64) -> Self {
+        Budget {
+            evaluations,
+            iterations: std::u64::MAX,
+            seed: 0,
+        }
+    }
+
+    /// Set the maximum number of iterations per run (default: unlimited)
+    pub fn max_iters(mut self, iterations: u64) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Set the base seed (default: 0). All runs on the `i`-th problem use the seed `seed + i`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Operator which is optimized in a benchmark run.
+///
+/// Wraps a `TestProblem`, records the best cost after each evaluation of `apply` and fails with
+/// an error once the budget of evaluations is exhausted (which ends the run). `modify` perturbs a
+/// single component by a uniformly distributed value from `[-1, 1]` within the domain of the
+/// problem, using a random number generator seeded with the seed of the run.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BenchmarkOp {
+    /// test problem
+    problem: TestProblem,
+    /// maximum number of evaluations
+    max_evals: u64,
+    /// best cost after each evaluation
+    #[serde(skip)]
+    trace: Arc<Mutex<Vec<f64>>>,
+    /// random number generator used in `modify`
+    rng: Arc<Mutex<XorShiftRng>>,
+}
+
+impl BenchmarkOp {
+    fn new(problem: TestProblem, max_evals: u64, seed: u64) -> Self {
+        BenchmarkOp {
+            problem,
+            max_evals,
+            trace: Arc::new(Mutex::new(vec![])),
+            rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Return the test problem
+    pub fn problem(&self) -> &TestProblem {
+        &self.problem
+    }
+}
+
+impl ArgminOp for BenchmarkOp {
+    type Param = Vec<f64>;
+    type Output = f64;
+    type Hessian = Vec<Vec<f64>>;
+
+    fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+        let mut trace = self.trace.lock().unwrap();
+        if trace.len() as u64 >= self.max_evals {
+            return Err(BudgetExhausted {}.into());
+        }
+        let cost = self.problem.apply(p)?;
+        // `min` ignores `NaN`s
+        let best = trace.last().map_or(cost, |best| best.min(cost));
+        trace.push(best);
+        Ok(cost)
+    }
+
+    fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+        self.problem.gradient(p)
+    }
+
+    fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+        self.problem.hessian(p)
+    }
+
+    fn modify(&self, p: &Vec<f64>, _extent: f64) -> Result<Vec<f64>, Error> {
+        let mut rng = self.rng.lock().unwrap();
+        p.modify(
+            &mut *rng,
+            Some(&self.problem.lower_bounds().to_vec()),
+            Some(&self.problem.upper_bounds().to_vec()),
+            Perturbation::default(),
+            Components::default(),
+            &Unconstrained,
+        )
+    }
+}
+
+/// Runs a solver on a `BenchmarkOp` with the given seed and maximum number of iterations
+type RunFn = dyn Fn(BenchmarkOp, u64, u64) -> Result<(), Error>;
+
+/// Named constructor of a solver which is used in a benchmark
+pub struct SolverFactory {
+    /// name of the solver
+    name: String,
+    /// runs the solver
+    run: Box<RunFn>,
+}
+
+impl SolverFactory {
+    /// Constructor
+    ///
+    /// Parameters:
+    ///
+    /// * `name`: name of the solver, which must not contain commas or line breaks
+    /// * `make`: constructs the solver for a test problem and the seed of the run. The solver
+    ///   starts from the standard starting point of the problem.
+    pub fn new<S, F>(name: &str, make: F) -> Result<Self, Error>
+    where
+        S: Solver<BenchmarkOp> + 'static,
+        F: Fn(&TestProblem, u64) -> Result<S, Error> + 'static,
+    {
+        if name.contains(',') || name.contains('\n') || name.contains('\r') {
+            return Err(ArgminError::InvalidParameter {
+                text: "SolverFactory: name must not contain commas or line breaks.".to_string(),
+            }
+            .into());
+        }
+        let run = move |op: BenchmarkOp, seed: u64, iterations: u64| -> Result<(), Error> {
+            let solver = make(op.problem(), seed)?;
+            let init = op.problem().start().to_vec();
+            Executor::new(op, solver, init)
+                .max_iters(iterations)
+                .run_fast()?;
+            Ok(())
+        };
+        Ok(SolverFactory {
+            name: name.to_string(),
+            run: Box::new(run),
+        })
+    }
+
+    /// Return the name of the solver
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Run of a single solver on a single problem
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    /// name of the problem
+    pub problem: String,
+    /// number of dimensions of the problem
+    pub dim: usize,
+    /// global minimum of the problem
+    pub minimum: f64,
+    /// name of the solver
+    pub solver: String,
+    /// seed of the run
+    pub seed: u64,
+    /// cost at the starting point
+    pub initial_cost: f64,
+    /// best cost after each evaluation
+    pub trace: Vec<f64>,
+    /// error which ended the run (other than the exhausted budget). Not included in the CSV
+    /// export.
+    pub error: Option<String>,
+}
+
+impl BenchmarkRun {
+    /// Number of cost function evaluations
+    pub fn evaluations(&self) -> usize {
+        self.trace.len()
+    }
+
+    /// Best cost found (infinity if the cost function was never evaluated)
+    pub fn best_cost(&self) -> f64 {
+        self.trace.last().cloned().unwrap_or(std::f64::INFINITY)
+    }
+
+    /// Number of evaluations after which the convergence test `f <= f_min + tol * (f_0 - f_min)`
+    /// of [0] is satisfied, where `f_min` is the global minimum of the problem and `f_0` the cost
+    /// at the starting point. Returns `None` if the run never satisfied the test.
+    pub fn evaluations_to_solve(&self, tol: f64) -> Option<usize> {
+        let target = self.minimum + tol * (self.initial_cost - self.minimum);
+        self.trace.iter().position(|c| *c <= target).map(|i| i + 1)
+    }
+}
+
+/// Data profile of a solver: fraction of problems solved within `budgets[i] * (dim + 1)`
+/// evaluations
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DataProfile {
+    /// name of the solver
+    pub solver: String,
+    /// budgets in units of simplex gradients (`dim + 1` evaluations)
+    pub budgets: Vec<f64>,
+    /// fraction of problems solved within each budget
+    pub solved: Vec<f64>,
+}
+
+/// Results of `run_benchmark`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// one run per problem and solver, ordered by problem first
+    runs: Vec<BenchmarkRun>,
+}
+
+const CSV_HEADER: &str = "problem,dim,minimum,solver,seed,initial_cost,evaluation,best_cost";
+
+fn csv_error(line: usize) -> Error {
+    ArgminError::InvalidParameter {
+        text: format!("BenchmarkReport: invalid CSV in line {}.", line + 1),
+    }
+    .into()
+}
+
+impl BenchmarkReport {
+    /// Return all runs
+    pub fn runs(&self) -> &[BenchmarkRun] {
+        &self.runs
+    }
+
+    /// Names of the problems in the order of the benchmark
+    pub fn problems(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for run in self.runs.iter() {
+            if !names.contains(&run.problem.as_str()) {
+                names.push(&run.problem);
+            }
+        }
+        names
+    }
+
+    /// Names of the solvers in the order of the benchmark
+    pub fn solvers(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for run in self.runs.iter() {
+            if !names.contains(&run.solver.as_str()) {
+                names.push(&run.solver);
+            }
+        }
+        names
+    }
+
+    /// Return the run of `solver` on `problem`
+    pub fn run(&self, problem: &str, solver: &str) -> Option<&BenchmarkRun> {
+        self.runs
+            .iter()
+            .find(|r| r.problem == problem && r.solver == solver)
+    }
+
+    /// Data profiles of all solvers for the convergence test with tolerance `tol` (see
+    /// `BenchmarkRun::evaluations_to_solve`). The budgets are given in units of `dim + 1`
+    /// evaluations.
+    pub fn data_profile(&self, tol: f64, budgets: &[f64]) -> Vec<DataProfile> {
+        let problems = self.problems();
+        self.solvers()
+            .into_iter()
+            .map(|solver| {
+                let solved = budgets
+                    .iter()
+                    .map(|budget| {
+                        let count = problems
+                            .iter()
+                            .filter_map(|problem| self.run(problem, solver))
+                            .filter(|run| match run.evaluations_to_solve(tol) {
+                                Some(evals) => evals as f64 <= budget * (run.dim + 1) as f64,
+                                None => false,
+                            })
+                            .count();
+                        count as f64 / problems.len() as f64
+                    })
+                    .collect();
+                DataProfile {
+                    solver: solver.to_string(),
+                    budgets: budgets.to_vec(),
+                    solved,
+                }
+            })
+            .collect()
+    }
+
+    /// Export the traces as CSV with one line per evaluation (a run without evaluations is
+    /// exported as a single line with evaluation 0). Errors are not exported.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for run in self.runs.iter() {
+            let prefix = format!(
+                "{},{},{:?},{},{},{:?}",
+                run.problem, run.dim, run.minimum, run.solver, run.seed, run.initial_cost
+            );
+            if run.trace.is_empty() {
+                csv.push_str(&format!("{},0,{:?}\n", prefix, std::f64::INFINITY));
+            }
+            for (i, cost) in run.trace.iter().enumerate() {
+                csv.push_str(&format!("{},{},{:?}\n", prefix, i + 1, cost));
+            }
+        }
+        csv
+    }
+
+    /// Read a report which was exported via `to_csv`
+    pub fn from_csv(csv: &str) -> Result<Self, Error> {
+        let mut lines = csv.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header == CSV_HEADER => {}
+            _ => return Err(csv_error(0)),
+        }
+        let mut runs: Vec<BenchmarkRun> = vec![];
+        for (i, line) in lines {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 8 {
+                return Err(csv_error(i));
+            }
+            let evaluation: usize = fields[6].parse().map_err(|_| csv_error(i))?;
+            let cost: f64 = fields[7].parse().map_err(|_| csv_error(i))?;
+            if evaluation > 1 {
+                // continuation of the previous run
+                match runs.last_mut() {
+                    Some(ref mut run)
+                        if run.problem == fields[0]
+                            && run.solver == fields[3]
+                            && run.trace.len() + 1 == evaluation =>
+                    {
+                        run.trace.push(cost);
+                        continue;
+                    }
+                    _ => return Err(csv_error(i)),
+                }
+            }
+            runs.push(BenchmarkRun {
+                problem: fields[0].to_string(),
+                dim: fields[1].parse().map_err(|_| csv_error(i))?,
+                minimum: fields[2].parse().map_err(|_| csv_error(i))?,
+                solver: fields[3].to_string(),
+                seed: fields[4].parse().map_err(|_| csv_error(i))?,
+                initial_cost: fields[5].parse().map_err(|_| csv_error(i))?,
+                trace: if evaluation == 1 { vec![cost] } else { vec![] },
+                error: None,
+            });
+        }
+        Ok(BenchmarkReport { runs })
+    }
+}
+
+/// Run every solver on every problem with the given budget. The runs are deterministic as long as
+/// the solvers only use the seed they are constructed with and the `modify` method of
+/// `BenchmarkOp` as sources of randomness.
+pub fn run_benchmark(
+    problems: &[TestProblem],
+    solvers: &[SolverFactory],
+    budget: Budget,
+) -> BenchmarkReport {
+    let mut runs = vec![];
+    for (i, problem) in problems.iter().enumerate() {
+        let seed = budget.seed.wrapping_add(i as u64);
+        let initial_cost = problem
+            .apply(&problem.start().to_vec())
+            .unwrap_or(std::f64::NAN);
+        for solver in solvers.iter() {
+            let op = BenchmarkOp::new(problem.clone(), budget.evaluations, seed);
+            let trace = op.trace.clone();
+            let error = match (solver.run)(op, seed, budget.iterations) {
+                Err(ref e) if e.downcast_ref::<BudgetExhausted>().is_some() => None,
+                Err(e) => Some(e.to_string()),
+                Ok(()) => None,
+            };
+            let trace = trace.lock().unwrap().clone();
+            runs.push(BenchmarkRun {
+                problem: problem.name(),
+                dim: problem.dim(),
+                minimum: problem.minimum(),
+                solver: solver.name.clone(),
+                seed,
+                initial_cost,
+                trace,
+                error,
+            });
+        }
+    }
+    BenchmarkReport { runs }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::solver::neldermead::{NelderMead, SimplexStep};
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+
+    send_sync_test!(benchmark_op, BenchmarkOp);
+
+    fn solvers() -> Vec<SolverFactory> {
+        vec![
+            SolverFactory::new("nelder-mead", |problem, _seed| {
+                NelderMead::from_point(problem.start().to_vec(), SimplexStep::default())
+            })
+            .unwrap(),
+            SolverFactory::new("simulated annealing", |_problem, seed| {
+                Ok(SimulatedAnnealing::<Vec<f64>>::new(10.0)?.seed(seed))
+            })
+            .unwrap(),
+        ]
+    }
+
+    fn problems() -> Vec<TestProblem> {
+        vec![
+            TestProblem::sphere(2),
+            TestProblem::booth(),
+            TestProblem::rosenbrock(2),
+        ]
+    }
+
+    #[test]
+    fn test_benchmark_report() {
+        let report = run_benchmark(&problems(), &solvers(), Budget::new(200).seed(3));
+        assert_eq!(report.runs().len(), 6);
+        assert_eq!(report.problems().len(), 3);
+        assert_eq!(report.solvers(), vec!["nelder-mead", "simulated annealing"]);
+        for run in report.runs() {
+            assert!(run.error.is_none(), "{:?}", run.error);
+            assert!(run.evaluations() > 0 && run.evaluations() <= 200);
+            for window in run.trace.windows(2) {
+                assert!(window[1] <= window[0]);
+            }
+        }
+        for problem in report.problems() {
+            // the starting point is a vertex of the initial simplex
+            let nm = report.run(problem, "nelder-mead").unwrap();
+            assert!(nm.best_cost() <= nm.initial_cost);
+        }
+        // simulated annealing does not terminate on its own and uses the whole budget
+        let sa = report.run("Sphere (2D)", "simulated annealing").unwrap();
+        assert_eq!(sa.evaluations(), 200);
+
+        let profiles = report.data_profile(1e-3, &[1.0, 10.0, 100.0]);
+        assert_eq!(profiles.len(), 2);
+        for profile in profiles.iter() {
+            assert_eq!(profile.solved.len(), 3);
+            assert!(profile.solved.iter().all(|s| (0.0..=1.0).contains(s)));
+            assert!(profile.solved.windows(2).all(|w| w[0] <= w[1]));
+        }
+        // Nelder-Mead solves the sphere within 100 simplex gradients
+        assert!(profiles[0].solved[2] > 0.0);
+    }
+
+    #[test]
+    fn test_benchmark_deterministic() {
+        let a = run_benchmark(&problems(), &solvers(), Budget::new(100).seed(7));
+        let b = run_benchmark(&problems(), &solvers(), Budget::new(100).seed(7));
+        assert_eq!(a, b);
+        assert_eq!(a.runs()[1].seed, 7);
+        assert_eq!(a.runs()[5].seed, 9);
+    }
+
+    #[test]
+    fn test_benchmark_csv_round_trip() {
+        let report = run_benchmark(&problems(), &solvers(), Budget::new(50).max_iters(20));
+        let csv = report.to_csv();
+        assert_eq!(BenchmarkReport::from_csv(&csv).unwrap(), report);
+        assert!(BenchmarkReport::from_csv("nonsense").is_err());
+        // a missing evaluation
+        let broken: Vec<&str> = csv
+            .lines()
+            .enumerate()
+            .filter(|(i, _)| *i != 2)
+            .map(|(_, line)| line)
+            .collect();
+        assert!(BenchmarkReport::from_csv(&broken.join("\n")).is_err());
+    }
+
+    #[test]
+    fn test_solver_factory_name() {
+        let factory = SolverFactory::new("a,b", |problem, _seed| {
+            NelderMead::from_point(problem.start().to_vec(), SimplexStep::default())
+        });
+        assert!(factory.is_err());
+    }
+}
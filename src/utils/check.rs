@@ -0,0 +1,459 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Derivative checks
+//!
+//! Compares hand-derived gradients, Hessians and Jacobians with central differences at a number of
+//! user-supplied points. A component passes if either its absolute error is below `abs_tol` or its
+//! relative error is below `rel_tol` at every point.
+//!
+//! ```
+//! # use argmin::prelude::*;
+//! # use argmin::utils::check_gradient;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Clone, Default, Serialize, Deserialize)]
+//! struct Square {}
+//!
+//! impl ArgminOp for Square {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!     type Hessian = Vec<Vec<f64>>;
+//!
+//!     fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+//!         Ok(p.iter().map(|x| x * x).sum())
+//!     }
+//!
+//!     fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+//!         Ok(p.iter().map(|x| 2.0 * x).collect())
+//!     }
+//! }
+//!
+//! let report = check_gradient(&Square {}, &[vec![1.0, 2.0]], 1e-6, 1e-8).unwrap();
+//! assert!(report.passed());
+//! println!("{}", report);
+//! ```
+
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Result of the check of a single component of a derivative. If the component failed at any of
+/// the points, the worst failing point is reported, otherwise the point with the largest relative
+/// error.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentCheck {
+    /// Row (gradient: component; Hessian and Jacobian: row)
+    pub row: usize,
+    /// Column (only for Hessians and Jacobians)
+    pub col: Option<usize>,
+    /// Index of the reported point in the list of points
+    pub point: usize,
+    /// Analytic derivative
+    pub analytic: f64,
+    /// Finite difference approximation
+    pub numeric: f64,
+    /// Absolute error
+    pub abs_error: f64,
+    /// Relative error
+    pub rel_error: f64,
+    /// Whether the component passed at all points
+    pub passed: bool,
+}
+
+impl ComponentCheck {
+    fn new(row: usize, col: Option<usize>, point: usize, analytic: f64, numeric: f64) -> Self {
+        let abs_error = (analytic - numeric).abs();
+        let scale = analytic.abs().max(numeric.abs());
+        let rel_error = if analytic.is_nan() || numeric.is_nan() {
+            std::f64::NAN
+        } else if scale > 0.0 {
+            abs_error / scale
+        } else {
+            0.0
+        };
+        ComponentCheck {
+            row,
+            col,
+            point,
+            analytic,
+            numeric,
+            abs_error,
+            rel_error,
+            passed: true,
+        }
+    }
+
+    fn check(&mut self, rel_tol: f64, abs_tol: f64) {
+        // NaNs always fail
+        self.passed = self.abs_error <= abs_tol || self.rel_error <= rel_tol;
+    }
+}
+
+/// Report of a derivative check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GradCheckReport {
+    /// Which derivative was checked
+    pub kind: String,
+    /// Relative tolerance
+    pub rel_tol: f64,
+    /// Absolute tolerance
+    pub abs_tol: f64,
+    /// Results per component
+    pub components: Vec<ComponentCheck>,
+}
+
+impl GradCheckReport {
+    fn new(kind: &str, rel_tol: f64, abs_tol: f64) -> Self {
+        GradCheckReport {
+            kind: kind.to_string(),
+            rel_tol,
+            abs_tol,
+            components: vec![],
+        }
+    }
+
+    /// Merge the check of one component at one point into the report
+    fn add(&mut self, mut entry: ComponentCheck) {
+        entry.check(self.rel_tol, self.abs_tol);
+        let existing = self
+            .components
+            .iter_mut()
+            .find(|c| c.row == entry.row && c.col == entry.col);
+        match existing {
+            None => self.components.push(entry),
+            Some(c) => {
+                let worse = match (c.passed, entry.passed) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    _ => entry.rel_error > c.rel_error || entry.rel_error.is_nan(),
+                };
+                if worse {
+                    *c = entry;
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if all components passed at all points
+    pub fn passed(&self) -> bool {
+        self.components.iter().all(|c| c.passed)
+    }
+
+    /// Components which failed
+    pub fn failures(&self) -> Vec<&ComponentCheck> {
+        self.components.iter().filter(|c| !c.passed).collect()
+    }
+
+    /// Largest relative error over all components and points
+    pub fn worst_rel_error(&self) -> f64 {
+        self.components
+            .iter()
+            .map(|c| c.rel_error)
+            .fold(0.0, |acc, e| if e.is_nan() { e } else { acc.max(e) })
+    }
+}
+
+impl fmt::Display for GradCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let failures = self.failures().len();
+        if failures == 0 {
+            writeln!(f, "{} check: passed", self.kind)?;
+        } else {
+            writeln!(
+                f,
+                "{} check: FAILED ({} of {} components)",
+                self.kind,
+                failures,
+                self.components.len()
+            )?;
+        }
+        writeln!(
+            f,
+            "{:>10} {:>6} {:>14} {:>14} {:>11} {:>11}  status",
+            "component", "point", "analytic", "numeric", "abs. error", "rel. error"
+        )?;
+        for c in self.components.iter() {
+            let component = match c.col {
+                Some(col) => format!("[{}, {}]", c.row, col),
+                None => format!("[{}]", c.row),
+            };
+            writeln!(
+                f,
+                "{:>10} {:>6} {:>14.6e} {:>14.6e} {:>11.3e} {:>11.3e}  {}",
+                component,
+                c.point,
+                c.analytic,
+                c.numeric,
+                c.abs_error,
+                c.rel_error,
+                if c.passed { "ok" } else { "FAILED" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Step size for central differences
+fn step(x: f64) -> f64 {
+    std::f64::EPSILON.cbrt() * x.abs().max(1.0)
+}
+
+/// Central difference approximation of the derivatives of the vector-valued function `f`. Returns
+/// `out[i][j] = d f_i / d x_j`.
+fn central_jacobian<F>(x: &[f64], f: F) -> Result<Vec<Vec<f64>>, Error>
+where
+    F: Fn(&Vec<f64>) -> Result<Vec<f64>, Error>,
+{
+    let mut columns = Vec::with_capacity(x.len());
+    let mut xp = x.to_vec();
+    for j in 0..x.len() {
+        let h = step(x[j]);
+        xp[j] = x[j] + h;
+        let fp = f(&xp)?;
+        xp[j] = x[j] - h;
+        let fm = f(&xp)?;
+        xp[j] = x[j];
+        columns.push(
+            fp.iter()
+                .zip(fm.iter())
+                .map(|(a, b)| (a - b) / (2.0 * h))
+                .collect::<Vec<f64>>(),
+        );
+    }
+    let rows = columns.first().map(|c| c.len()).unwrap_or(0);
+    Ok((0..rows)
+        .map(|i| columns.iter().map(|c| c[i]).collect())
+        .collect())
+}
+
+fn dimension_error(kind: &str, expected: usize, got: usize) -> Error {
+    ArgminError::ConditionViolated {
+        text: format!(
+            "check_{}: expected {} components, got {}.",
+            kind, expected, got
+        ),
+    }
+    .into()
+}
+
+/// Compares `op.gradient` with central differences of `op.apply` at all `points`.
+pub fn check_gradient<O>(
+    op: &O,
+    points: &[Vec<f64>],
+    rel_tol: f64,
+    abs_tol: f64,
+) -> Result<GradCheckReport, Error>
+where
+    O: ArgminOp<Param = Vec<f64>, Output = f64>,
+{
+    let mut report = GradCheckReport::new("Gradient", rel_tol, abs_tol);
+    for (k, x) in points.iter().enumerate() {
+        let grad = op.gradient(x)?;
+        if grad.len() != x.len() {
+            return Err(dimension_error("gradient", x.len(), grad.len()));
+        }
+        let numeric = central_jacobian(x, |p| Ok(vec![op.apply(p)?]))?;
+        for (i, g) in grad.iter().enumerate() {
+            report.add(ComponentCheck::new(i, None, k, *g, numeric[0][i]));
+        }
+    }
+    Ok(report)
+}
+
+/// Compares `op.hessian` with central differences of `op.gradient` at all `points`.
+pub fn check_hessian<O>(
+    op: &O,
+    points: &[Vec<f64>],
+    rel_tol: f64,
+    abs_tol: f64,
+) -> Result<GradCheckReport, Error>
+where
+    O: ArgminOp<Param = Vec<f64>, Hessian = Vec<Vec<f64>>>,
+{
+    let mut report = GradCheckReport::new("Hessian", rel_tol, abs_tol);
+    for (k, x) in points.iter().enumerate() {
+        let hessian = op.hessian(x)?;
+        if hessian.len() != x.len() {
+            return Err(dimension_error("hessian", x.len(), hessian.len()));
+        }
+        let numeric = central_jacobian(x, |p| op.gradient(p))?;
+        for (i, row) in hessian.iter().enumerate() {
+            if row.len() != x.len() {
+                return Err(dimension_error("hessian", x.len(), row.len()));
+            }
+            for (j, h) in row.iter().enumerate() {
+                report.add(ComponentCheck::new(i, Some(j), k, *h, numeric[i][j]));
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Compares the Jacobian `jacobian` (`jacobian(x)[i][j] = d r_i / d x_j`) of the residual function
+/// `residuals` with central differences at all `points`.
+pub fn check_jacobian<R, J>(
+    residuals: R,
+    jacobian: J,
+    points: &[Vec<f64>],
+    rel_tol: f64,
+    abs_tol: f64,
+) -> Result<GradCheckReport, Error>
+where
+    R: Fn(&Vec<f64>) -> Result<Vec<f64>, Error>,
+    J: Fn(&Vec<f64>) -> Result<Vec<Vec<f64>>, Error>,
+{
+    let mut report = GradCheckReport::new("Jacobian", rel_tol, abs_tol);
+    for (k, x) in points.iter().enumerate() {
+        let jac = jacobian(x)?;
+        let numeric = central_jacobian(x, &residuals)?;
+        if jac.len() != numeric.len() {
+            return Err(dimension_error("jacobian", numeric.len(), jac.len()));
+        }
+        for (i, row) in jac.iter().enumerate() {
+            if row.len() != x.len() {
+                return Err(dimension_error("jacobian", x.len(), row.len()));
+            }
+            for (j, v) in row.iter().enumerate() {
+                report.add(ComponentCheck::new(i, Some(j), k, *v, numeric[i][j]));
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative, rosenbrock_2d_hessian};
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Rosenbrock {
+        /// flip the sign of the second gradient component
+        sign_error: bool,
+    }
+
+    impl ArgminOp for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(rosenbrock_2d(p, 1.0, 100.0))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            let mut g = rosenbrock_2d_derivative(p, 1.0, 100.0);
+            if self.sign_error {
+                g[1] = -g[1];
+            }
+            Ok(g)
+        }
+
+        fn hessian(&self, p: &Vec<f64>) -> Result<Vec<Vec<f64>>, Error> {
+            let h = rosenbrock_2d_hessian(p, 1.0, 100.0);
+            Ok(vec![vec![h[0], h[1]], vec![h[2], h[3]]])
+        }
+    }
+
+    fn points() -> Vec<Vec<f64>> {
+        vec![vec![-1.2, 1.0], vec![0.5, -0.3], vec![2.0, 3.0]]
+    }
+
+    #[test]
+    fn test_correct_gradient() {
+        let report =
+            check_gradient(&Rosenbrock { sign_error: false }, &points(), 1e-6, 1e-8).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.components.len(), 2);
+    }
+
+    #[test]
+    fn test_gradient_sign_error() {
+        let report =
+            check_gradient(&Rosenbrock { sign_error: true }, &points(), 1e-6, 1e-8).unwrap();
+        assert!(!report.passed());
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].row, 1);
+        assert!(format!("{}", report).contains("FAILED"));
+    }
+
+    #[test]
+    fn test_hessian() {
+        let report =
+            check_hessian(&Rosenbrock { sign_error: false }, &points(), 1e-6, 1e-6).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.components.len(), 4);
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let residuals = |x: &Vec<f64>| Ok(vec![x[0].powi(2) - x[1], x[0].exp() * x[1], x[1]]);
+        let jacobian = |x: &Vec<f64>| {
+            Ok(vec![
+                vec![2.0 * x[0], -1.0],
+                vec![x[0].exp() * x[1], x[0].exp()],
+                vec![0.0, 1.0],
+            ])
+        };
+        let report = check_jacobian(residuals, jacobian, &points(), 1e-6, 1e-8).unwrap();
+        assert!(report.passed());
+        assert_eq!(report.components.len(), 6);
+
+        let wrong = |x: &Vec<f64>| {
+            Ok(vec![
+                vec![2.0 * x[0], -1.0],
+                vec![x[0].exp(), x[0].exp()],
+                vec![0.0, 1.0],
+            ])
+        };
+        let report = check_jacobian(residuals, wrong, &points(), 1e-6, 1e-8).unwrap();
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].row, failures[0].col), (1, Some(0)));
+    }
+
+    /// Undefined for `x_0 < 0`: both the cost function and the gradient are `NaN` there
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Sqrt {}
+
+    impl ArgminOp for Sqrt {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(p[0].sqrt())
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(vec![0.5 / p[0].sqrt()])
+        }
+    }
+
+    #[test]
+    fn test_gradient_nan() {
+        let report = check_gradient(&Sqrt {}, &[vec![4.0]], 1e-6, 1e-8).unwrap();
+        assert!(report.passed());
+
+        // analytic and numeric derivative are both `NaN`
+        let report = check_gradient(&Sqrt {}, &[vec![4.0], vec![-1.0]], 1e-6, 1e-8).unwrap();
+        assert!(!report.passed());
+        let failures = report.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].point, 1);
+        assert!(failures[0].analytic.is_nan() && failures[0].numeric.is_nan());
+        assert!(failures[0].rel_error.is_nan());
+    }
+
+    #[test]
+    fn test_wrong_dimension() {
+        let residuals = |x: &Vec<f64>| Ok(vec![x[0]]);
+        let jacobian = |_x: &Vec<f64>| Ok(vec![vec![1.0]]);
+        assert!(check_jacobian(residuals, jacobian, &points(), 1e-6, 1e-8).is_err());
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Comparison of results
+//!
+//! `compare_results` collects the results of several (named) runs on the same problem into a
+//! `ComparisonReport`, which marks the winner by cost and the winner by number of evaluations.
+//! The report can be printed as a table or serialized.
+
+use crate::utils::ranking::{cmp_cost, ArgminRanked};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// A single row of a `ComparisonReport`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonEntry {
+    /// name of the run
+    pub name: String,
+    /// cost
+    pub cost: f64,
+    /// number of evaluations, if known
+    pub evaluations: Option<u64>,
+}
+
+/// Comparison of the results of several runs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// one entry per run, in the order given to `compare_results`
+    entries: Vec<ComparisonEntry>,
+    /// index of the entry with the lowest cost
+    winner_by_cost: Option<usize>,
+    /// index of the entry with the fewest evaluations
+    winner_by_evaluations: Option<usize>,
+}
+
+impl ComparisonReport {
+    /// Return all entries
+    pub fn entries(&self) -> &[ComparisonEntry] {
+        &self.entries
+    }
+
+    /// Name of the run with the lowest cost (`NaN` costs never win). The first one wins ties.
+    pub fn winner_by_cost(&self) -> Option<&str> {
+        self.winner_by_cost.map(|i| self.entries[i].name.as_str())
+    }
+
+    /// Name of the run with the fewest evaluations among those which report the number of
+    /// evaluations. The first one wins ties.
+    pub fn winner_by_evaluations(&self) -> Option<&str> {
+        self.winner_by_evaluations
+            .map(|i| self.entries[i].name.as_str())
+    }
+}
+
+/// Compare the results of several named runs
+pub fn compare_results<R: ArgminRanked>(results: &[(&str, &R)]) -> ComparisonReport {
+    let entries: Vec<ComparisonEntry> = results
+        .iter()
+        .map(|(name, res)| ComparisonEntry {
+            name: name.to_string(),
+            cost: res.ranking_cost(),
+            evaluations: res.evaluations(),
+        })
+        .collect();
+    let winner_by_cost = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| !e.cost.is_nan())
+        .fold(None, |best: Option<(usize, f64)>, (i, e)| match best {
+            Some((_, c)) if cmp_cost(e.cost, c) != Ordering::Less => best,
+            _ => Some((i, e.cost)),
+        })
+        .map(|(i, _)| i);
+    let winner_by_evaluations = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| e.evaluations.map(|n| (i, n)))
+        .fold(None, |best: Option<(usize, u64)>, (i, n)| match best {
+            Some((_, m)) if n >= m => best,
+            _ => Some((i, n)),
+        })
+        .map(|(i, _)| i);
+    ComparisonReport {
+        entries,
+        winner_by_cost,
+        winner_by_evaluations,
+    }
+}
+
+impl std::fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let width = self
+            .entries
+            .iter()
+            .map(|e| e.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        writeln!(
+            f,
+            "{:width$}  {:>24}  {:>12}",
+            "solver",
+            "cost",
+            "evaluations",
+            width = width
+        )?;
+        for (i, e) in self.entries.iter().enumerate() {
+            let cost_mark = if self.winner_by_cost == Some(i) {
+                "*"
+            } else {
+                " "
+            };
+            let eval_mark = if self.winner_by_evaluations == Some(i) {
+                "*"
+            } else {
+                " "
+            };
+            let evals = e
+                .evaluations
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            writeln!(
+                f,
+                "{:width$}  {:>23e}{}  {:>11}{}",
+                e.name,
+                e.cost,
+                cost_mark,
+                evals,
+                eval_mark,
+                width = width
+            )?;
+        }
+        write!(f, "(* marks the winner)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::testfunctions::{sphere, sphere_derivative};
+
+    send_sync_test!(comparison_report, ComparisonReport);
+
+    struct Run {
+        cost: f64,
+        evals: Option<u64>,
+    }
+
+    impl ArgminRanked for Run {
+        fn ranking_cost(&self) -> f64 {
+            self.cost
+        }
+
+        fn evaluations(&self) -> Option<u64> {
+            self.evals
+        }
+    }
+
+    #[test]
+    fn test_winners() {
+        let a = Run {
+            cost: 1e-3,
+            evals: Some(50),
+        };
+        let b = Run {
+            cost: 1e-8,
+            evals: Some(400),
+        };
+        let c = Run {
+            cost: std::f64::NAN,
+            evals: Some(10),
+        };
+        let d = Run {
+            cost: 1e-8,
+            evals: None,
+        };
+        let report = compare_results(&[("a", &a), ("b", &b), ("c", &c), ("d", &d)]);
+        assert_eq!(report.winner_by_cost(), Some("b"));
+        assert_eq!(report.winner_by_evaluations(), Some("c"));
+        assert_eq!(report.entries().len(), 4);
+        assert_eq!(report.entries()[3].evaluations, None);
+        let table = format!("{}", report);
+        assert_eq!(table.lines().count(), 6);
+        assert!(table.lines().nth(2).unwrap().contains('*'));
+    }
+
+    #[test]
+    fn test_empty_and_nan() {
+        let report = compare_results::<Run>(&[]);
+        assert_eq!(report.winner_by_cost(), None);
+        assert_eq!(report.winner_by_evaluations(), None);
+        let nan = Run {
+            cost: std::f64::NAN,
+            evals: None,
+        };
+        let report = compare_results(&[("nan", &nan)]);
+        assert_eq!(report.winner_by_cost(), None);
+        assert_eq!(report.winner_by_evaluations(), None);
+    }
+
+    #[test]
+    fn test_landweber_step_sizes() {
+        use crate::prelude::*;
+        use crate::solver::landweber::Landweber;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Clone, Default, Serialize, Deserialize)]
+        struct Square {}
+
+        impl ArgminOp for Square {
+            type Param = Vec<f64>;
+            type Output = f64;
+            type Hessian = ();
+
+            fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+                Ok(sphere(p))
+            }
+
+            fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+                Ok(sphere_derivative(p))
+            }
+        }
+
+        let run = |omega: f64| {
+            Executor::new(Square {}, Landweber::new(omega).unwrap(), vec![1.0, 2.0])
+                .max_iters(10)
+                .run_fast()
+                .unwrap()
+        };
+        let (slow, fast) = (run(0.01), run(0.4));
+        let report = compare_results(&[("slow", &slow), ("fast", &fast)]);
+        assert_eq!(report.winner_by_cost(), Some("fast"));
+        assert_eq!(report.winner_by_evaluations(), None);
+    }
+}
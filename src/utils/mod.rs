@@ -0,0 +1,35 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Utilities
+//!
+//! * [Benchmarks](benchmark/index.html) (`benchmark` feature)
+//! * [Derivative checks](check/index.html)
+//! * [Ranking of results](ranking/index.html)
+//! * [Comparison of results](comparison/index.html)
+//! * [Deterministic replay of runs](replay/index.html) (`replay` feature)
+
+/// Benchmarks of solvers on the test problems
+#[cfg(feature = "benchmark")]
+pub mod benchmark;
+/// Comparison of analytic derivatives with finite differences
+pub mod check;
+/// Comparison report of several runs
+pub mod comparison;
+/// Total order on results which handles `NaN` costs
+pub mod ranking;
+/// Recording and replay of runs
+#[cfg(feature = "replay")]
+pub mod replay;
+
+#[cfg(feature = "benchmark")]
+pub use self::benchmark::*;
+pub use self::check::*;
+pub use self::comparison::*;
+pub use self::ranking::*;
+#[cfg(feature = "replay")]
+pub use self::replay::*;
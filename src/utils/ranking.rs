@@ -0,0 +1,196 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Ranking of results
+//!
+//! Picking the best out of several results (for instance from multi-start runs) by comparing
+//! their costs with `partial_cmp` fails as soon as a cost is `NaN`. `ByCost` wraps a result and
+//! provides a total order in which `NaN` costs are worse than any other cost, such that
+//! `iter().min()` returns the best result.
+//!
+//! Ties are broken by the number of evaluations, if the result provides it (fewer is better).
+
+use crate::prelude::*;
+use std::cmp::Ordering;
+
+/// Results which can be ranked by their cost
+pub trait ArgminRanked {
+    /// Cost of the result
+    fn ranking_cost(&self) -> f64;
+
+    /// Number of evaluations needed to obtain the result, if known
+    fn evaluations(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns `true` if `self` is strictly better than `other`
+    fn better_than(&self, other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        rank(self, other) == Ordering::Less
+    }
+}
+
+impl<O: ArgminOp> ArgminRanked for ArgminResult<O> {
+    fn ranking_cost(&self) -> f64 {
+        self.cost
+    }
+}
+
+/// Total order of costs in which `NaN` is the worst cost and all `NaN`s are equal
+pub fn cmp_cost(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+/// Order by cost, then by number of evaluations (unknown counts are treated as equal)
+fn rank<R: ArgminRanked>(a: &R, b: &R) -> Ordering {
+    cmp_cost(a.ranking_cost(), b.ranking_cost()).then_with(|| {
+        match (a.evaluations(), b.evaluations()) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            _ => Ordering::Equal,
+        }
+    })
+}
+
+/// Wrapper which orders results by cost (`NaN` is worst), ties broken by fewer evaluations
+#[derive(Clone, Debug)]
+pub struct ByCost<R>(pub R);
+
+impl<R: ArgminRanked> PartialEq for ByCost<R> {
+    fn eq(&self, other: &Self) -> bool {
+        rank(&self.0, &other.0) == Ordering::Equal
+    }
+}
+
+impl<R: ArgminRanked> Eq for ByCost<R> {}
+
+impl<R: ArgminRanked> PartialOrd for ByCost<R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: ArgminRanked> Ord for ByCost<R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        rank(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::send_sync_test;
+    use crate::testfunctions::{sphere, sphere_derivative};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug)]
+    struct Run {
+        name: &'static str,
+        cost: f64,
+        evals: Option<u64>,
+    }
+
+    impl ArgminRanked for Run {
+        fn ranking_cost(&self) -> f64 {
+            self.cost
+        }
+
+        fn evaluations(&self) -> Option<u64> {
+            self.evals
+        }
+    }
+
+    fn run(name: &'static str, cost: f64, evals: Option<u64>) -> Run {
+        Run { name, cost, evals }
+    }
+
+    send_sync_test!(by_cost, ByCost<f64>);
+
+    #[test]
+    fn test_nan_is_worst() {
+        let nan = run("nan", std::f64::NAN, None);
+        let finite = run("finite", 1e300, None);
+        assert!(finite.better_than(&nan));
+        assert!(!nan.better_than(&finite));
+        assert!(ByCost(nan.clone()) > ByCost(finite.clone()));
+        let runs = vec![
+            nan.clone(),
+            finite.clone(),
+            run("inf", std::f64::INFINITY, None),
+        ];
+        let best = runs.into_iter().map(ByCost).min().unwrap();
+        assert_eq!(best.0.name, "finite");
+        let worst = vec![finite, nan].into_iter().map(ByCost).max().unwrap();
+        assert_eq!(worst.0.name, "nan");
+    }
+
+    #[test]
+    fn test_both_nan() {
+        let a = run("a", std::f64::NAN, None);
+        let b = run("b", std::f64::NAN, None);
+        assert!(!a.better_than(&b) && !b.better_than(&a));
+        assert!(ByCost(a.clone()) == ByCost(b));
+        assert!(ByCost(a.clone()) == ByCost(a));
+        assert_eq!(cmp_cost(std::f64::NAN, std::f64::NAN), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_tie_breaking() {
+        let a = run("a", 1.0, Some(100));
+        let b = run("b", 1.0, Some(50));
+        let c = run("c", 2.0, Some(1));
+        assert!(b.better_than(&a));
+        let best = vec![a.clone(), b, c].into_iter().map(ByCost).min().unwrap();
+        assert_eq!(best.0.name, "b");
+        // unknown counts do not break ties
+        let d = run("d", 1.0, None);
+        assert!(ByCost(a) == ByCost(d));
+        // NaN costs are worst regardless of the number of evaluations
+        assert!(run("e", 5.0, Some(1000)).better_than(&run("f", std::f64::NAN, Some(1))));
+    }
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Square {}
+
+    impl ArgminOp for Square {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere(p))
+        }
+
+        fn gradient(&self, p: &Vec<f64>) -> Result<Vec<f64>, Error> {
+            Ok(sphere_derivative(p))
+        }
+    }
+
+    #[test]
+    fn test_results() {
+        use crate::solver::landweber::Landweber;
+        let results: Vec<ArgminResult<Square>> = [1, 20, 5]
+            .iter()
+            .map(|iters| {
+                Executor::new(Square {}, Landweber::new(0.1).unwrap(), vec![1.0, -1.0])
+                    .max_iters(*iters)
+                    .run_fast()
+                    .unwrap()
+            })
+            .collect();
+        assert!(results[1].better_than(&results[0]));
+        let best = results.into_iter().map(ByCost).min().unwrap();
+        // 2 * (1 - 2 * 0.1)^40
+        assert!(best.0.cost < 1e-3);
+    }
+}
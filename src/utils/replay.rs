@@ -0,0 +1,356 @@
+// Copyright 2018 Stefan Kroboth
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Deterministic replay of runs (`replay` feature)
+//!
+//! A rare bad run of a stochastic solver can only be debugged if it can be reproduced exactly.
+//! `record` runs a seeded solver and writes everything needed to repeat the run to a compact
+//! binary file:
+//!
+//! * the seed and the (serialized) solver before the first iteration,
+//! * the type name of the operator and a hash of its serialized state,
+//! * the initial parameter vector and the iteration budget,
+//! * for every iteration, a hash of the serialized solver at the start of the iteration and the
+//!   cost function value of the accepted iterate.
+//!
+//! `replay` repeats the run with the stored seed and solver on a given operator and compares it
+//! with the recording. The first iteration which differs is reported as `ReplayDivergence`. Since
+//! the state of the solver (including its random number generator) is compared as well,
+//! divergences are detected before they show in the cost function values.
+//!
+//! The runs are driven by a `Stepper` instead of an `Executor`, such that the solver can be
+//! hashed before every iteration. They end when the solver terminates or the iteration budget is
+//! exhausted.
+//! Randomness of the operator (for instance in `modify`) has to be seeded by the operator itself;
+//! an operator whose state differs from the recorded one is rejected.
+
+use crate::prelude::*;
+use crate::stepper::Stepper;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Solvers whose randomness is determined by a seed
+pub trait ArgminSeed {
+    /// Reseed the random number generator(s) of the solver
+    fn reseed(&mut self, seed: u64);
+}
+
+/// Everything needed to replay a run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayLog {
+    /// seed of the solver
+    seed: u64,
+    /// type name of the solver
+    solver_name: String,
+    /// serialized solver before seeding
+    solver: Vec<u8>,
+    /// type name of the operator
+    op_name: String,
+    /// hash of the serialized operator
+    op_hash: u64,
+    /// serialized initial parameter vector
+    init_param: Vec<u8>,
+    /// iteration budget
+    max_iters: u64,
+    /// hash of the serialized solver at the start of each iteration
+    states: Vec<u64>,
+    /// cost function value after each iteration
+    costs: Vec<f64>,
+}
+
+impl ReplayLog {
+    /// Read a recording
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(bincode::deserialize_from(file)?)
+    }
+
+    /// Write the recording
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(bincode::serialize_into(file, self)?)
+    }
+
+    /// Seed of the solver
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Cost function values after each iteration
+    pub fn costs(&self) -> &[f64] {
+        &self.costs
+    }
+
+    /// Number of recorded iterations
+    pub fn iters(&self) -> u64 {
+        self.costs.len() as u64
+    }
+}
+
+/// First iteration in which a replayed run differs from its recording
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayDivergence {
+    /// iteration (counted from 0)
+    pub iter: u64,
+    /// description of the difference
+    pub reason: String,
+}
+
+impl std::fmt::Display for ReplayDivergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Replay diverged in iteration {}: {}",
+            self.iter, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ReplayDivergence {}
+
+/// FNV-1a hash, which (unlike `DefaultHasher`) is stable across Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn hash<T: Serialize>(value: &T) -> Result<u64, Error> {
+    Ok(fnv1a(&bincode::serialize(value)?))
+}
+
+/// Runs `solver` for at most `max_iters` iterations and returns the hash of the solver at the
+/// start of each iteration and the cost function value after each iteration
+fn run<O, S>(
+    op: &O,
+    solver: S,
+    init_param: O::Param,
+    max_iters: u64,
+) -> Result<(Vec<u64>, Vec<f64>), Error>
+where
+    O: ArgminOp<Output = f64>,
+    S: Solver<O> + Serialize,
+{
+    let mut stepper = Stepper::new(op.clone(), solver, init_param).max_iters(max_iters);
+    let (mut states, mut costs) = (vec![], vec![]);
+    while stepper.termination_reason() == TerminationReason::NotTerminated {
+        let state = hash(stepper.solver())?;
+        let iter = stepper.state().get_iter();
+        stepper.step()?;
+        if stepper.state().get_iter() > iter {
+            states.push(state);
+            costs.push(stepper.state().get_cost());
+        }
+    }
+    Ok((states, costs))
+}
+
+/// Runs `solver`, seeded with `seed`, on `op` from `init_param` for at most `max_iters`
+/// iterations and writes the recording to `path`. Returns the recording.
+pub fn record<O, S, P>(
+    path: P,
+    op: &O,
+    solver: S,
+    init_param: O::Param,
+    seed: u64,
+    max_iters: u64,
+) -> Result<ReplayLog, Error>
+where
+    O: ArgminOp<Output = f64> + Serialize,
+    S: Solver<O> + ArgminSeed + Serialize,
+    P: AsRef<Path>,
+{
+    let mut log = ReplayLog {
+        seed,
+        solver_name: std::any::type_name::<S>().to_string(),
+        solver: bincode::serialize(&solver)?,
+        op_name: std::any::type_name::<O>().to_string(),
+        op_hash: hash(op)?,
+        init_param: bincode::serialize(&init_param)?,
+        max_iters,
+        states: vec![],
+        costs: vec![],
+    };
+    let mut solver = solver;
+    solver.reseed(seed);
+    let (states, costs) = run(op, solver, init_param, max_iters)?;
+    log.states = states;
+    log.costs = costs;
+    log.save(path)?;
+    Ok(log)
+}
+
+/// Replays the recording at `path` on `op`. The solver type `S` has to be the recorded one.
+/// Returns the recording if the replayed run is identical, a `ReplayDivergence` naming the first
+/// iteration which differs otherwise.
+pub fn replay<O, S, P>(path: P, op: &O) -> Result<ReplayLog, Error>
+where
+    O: ArgminOp<Output = f64> + Serialize,
+    S: Solver<O> + ArgminSeed + Serialize + DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let log = ReplayLog::load(path)?;
+    let mismatch = |what: &str, recorded: &str, given: &str| -> Error {
+        ArgminError::InvalidParameter {
+            text: format!(
+                "replay: {} differs from the recording (recorded: {}, given: {}).",
+                what, recorded, given
+            ),
+        }
+        .into()
+    };
+    let solver_name = std::any::type_name::<S>();
+    if log.solver_name != solver_name {
+        return Err(mismatch("solver", &log.solver_name, solver_name));
+    }
+    let op_name = std::any::type_name::<O>();
+    if log.op_name != op_name {
+        return Err(mismatch("operator", &log.op_name, op_name));
+    }
+    let op_hash = hash(op)?;
+    if log.op_hash != op_hash {
+        return Err(mismatch(
+            "state of the operator",
+            &format!("{:016x}", log.op_hash),
+            &format!("{:016x}", op_hash),
+        ));
+    }
+
+    let mut solver: S = bincode::deserialize(&log.solver)?;
+    solver.reseed(log.seed);
+    let init_param: O::Param = bincode::deserialize(&log.init_param)?;
+    let (states, costs) = run(op, solver, init_param, log.max_iters)?;
+
+    let diverged = |iter: usize, reason: String| -> Error {
+        ReplayDivergence {
+            iter: iter as u64,
+            reason,
+        }
+        .into()
+    };
+    for (iter, (state, recorded)) in states.iter().zip(log.states.iter()).enumerate() {
+        if state != recorded {
+            return Err(diverged(iter, "state of the solver differs".to_string()));
+        }
+        let (cost, recorded) = (costs[iter], log.costs[iter]);
+        if cost.to_bits() != recorded.to_bits() {
+            return Err(diverged(
+                iter,
+                format!("cost {} differs from recorded cost {}", cost, recorded),
+            ));
+        }
+    }
+    if costs.len() != log.costs.len() {
+        let iter = costs.len().min(log.costs.len());
+        return Err(diverged(
+            iter,
+            format!(
+                "terminated after {} iterations instead of {}",
+                costs.len(),
+                log.costs.len()
+            ),
+        ));
+    }
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+    use crate::testfunctions::sphere;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+    use std::sync::{Arc, Mutex};
+
+    /// Sphere with a seeded random `modify`
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Sphere {
+        rng: Arc<Mutex<XorShiftRng>>,
+    }
+
+    impl Sphere {
+        fn new(seed: u64) -> Self {
+            Sphere {
+                rng: Arc::new(Mutex::new(XorShiftRng::seed_from_u64(seed))),
+            }
+        }
+    }
+
+    impl ArgminOp for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+        type Hessian = ();
+
+        fn apply(&self, p: &Vec<f64>) -> Result<f64, Error> {
+            Ok(sphere(p))
+        }
+
+        fn modify(&self, p: &Vec<f64>, _temp: f64) -> Result<Vec<f64>, Error> {
+            let mut rng = self.rng.lock().unwrap();
+            p.modify(
+                &mut *rng,
+                None,
+                None,
+                Perturbation::default(),
+                Components::default(),
+                &Unconstrained,
+            )
+        }
+    }
+
+    type Annealing = SimulatedAnnealing<Vec<f64>>;
+
+    fn path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("argmin_replay_{}.bin", name))
+    }
+
+    fn record_sa(path: &Path) -> ReplayLog {
+        let solver = SimulatedAnnealing::new(10.0).unwrap();
+        record(path, &Sphere::new(1), solver, vec![3.0, -2.0, 4.0], 42, 300).unwrap()
+    }
+
+    #[test]
+    fn test_replay_identical() {
+        let path = path("identical");
+        let log = record_sa(&path);
+        assert_eq!(log.iters(), 300);
+        assert_eq!(log.seed(), 42);
+        let replayed = replay::<_, Annealing, _>(&path, &Sphere::new(1)).unwrap();
+        assert_eq!(replayed.costs(), log.costs());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_corrupted_seed() {
+        let path = path("corrupted_seed");
+        let mut log = record_sa(&path);
+        log.seed += 1;
+        log.save(&path).unwrap();
+        let err = replay::<_, Annealing, _>(&path, &Sphere::new(1))
+            .err()
+            .unwrap();
+        let divergence = err.downcast_ref::<ReplayDivergence>().unwrap();
+        assert_eq!(divergence.iter, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_different_operator() {
+        let path = path("different_operator");
+        record_sa(&path);
+        // the random number generator of the operator is seeded differently
+        let err = replay::<_, Annealing, _>(&path, &Sphere::new(2))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("state of the operator"), "{}", err);
+        std::fs::remove_file(&path).unwrap();
+    }
+}